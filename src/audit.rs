@@ -0,0 +1,195 @@
+//! ## Audit logging
+//!
+//! With the `audit` feature a client can report every insert, update, upsert and delete it sends to
+//! an [`AuditSink`], once the response arrived. The [`AuditEvent`] names the table and operation,
+//! the filter of the request, a SHA-256 digest of its body, the response status and the latency.
+//! Writes going through RPC functions, like [batches](crate::batch), aren't reported.
+//!
+//! ### Usage
+//! [`AuditTable`] inserts every event into a table, see [`AUDIT_LOG_MIGRATION_SQL`] for the columns
+//! it writes:
+//! ```rust,ignore
+//! use supabase_rs::audit::AuditTable;
+//!
+//! let client = SupabaseClient::new(url, key)?;
+//! let client = client.clone().with_audit_sink(AuditTable::new(client, "audit_log"));
+//!
+//! // reported to `audit_log` once PostgREST answered
+//! client.delete_from("sessions").eq("user_id", 42).execute().await?;
+//! ```
+//!
+//! The sink is awaited before the write returns, so a slow sink slows down every write. Sinks
+//! shipping events elsewhere can hand them to a channel instead.
+
+#![cfg(feature = "audit")]
+
+use crate::errors::ErrorTypes;
+use crate::SupabaseClient;
+
+use reqwest::{Request, Response};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The operations reported to the audit sink.
+const AUDITED_OPERATIONS: &[&str] = &["insert", "update", "upsert", "delete"];
+
+/// Creates the table [`AuditTable`] writes to, run it once as a migration.
+///
+/// Row level security is enabled without policies, so only the service role can read and write it.
+pub const AUDIT_LOG_MIGRATION_SQL: &str = r#"
+create table if not exists public.audit_log (
+  id bigint generated always as identity primary key,
+  table_name text not null,
+  operation text not null,
+  filter text,
+  payload_digest text,
+  status integer,
+  latency_ms bigint not null,
+  created_at timestamptz not null default now()
+);
+
+alter table public.audit_log enable row level security;
+"#;
+
+/// A write sent by a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    /// The table written to.
+    pub table: String,
+    /// `insert`, `update`, `upsert` or `delete`.
+    pub operation: String,
+    /// The query string selecting the rows, e.g. `id=eq.1`, `None` when the request has none.
+    pub filter: Option<String>,
+    /// The hex encoded SHA-256 digest of the request body, `None` when it has no body.
+    pub payload_digest: Option<String>,
+    /// The status of the response, `None` when the request failed without one.
+    pub status: Option<u16>,
+    /// The time from sending the request until its response arrived.
+    pub latency: Duration,
+}
+
+/// The future returned by [`AuditSink::record`].
+pub type AuditFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Receives the writes of a `SupabaseClient`.
+pub trait AuditSink: Debug + Send + Sync {
+    /// Records a write. Failures can't fail the write anymore, so the sink has to handle them.
+    fn record(&self, event: AuditEvent) -> AuditFuture<'_>;
+}
+
+impl SupabaseClient {
+    /// Reports every insert, update, upsert and delete of the client and its clones to `sink`.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the audit sink.
+    pub fn with_audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit_sink = Some(Arc::new(sink));
+        self
+    }
+}
+
+/// Writes every event as a row of a table.
+#[derive(Debug, Clone)]
+pub struct AuditTable {
+    client: SupabaseClient,
+    table_name: String,
+}
+
+impl AuditTable {
+    /// Creates a sink inserting into `table_name` with `client`, whose own audit sink is dropped so
+    /// the inserts into the audit table aren't audited themselves.
+    pub fn new(client: SupabaseClient, table_name: &str) -> Self {
+        let mut client: SupabaseClient = client;
+        client.audit_sink = None;
+
+        AuditTable {
+            client,
+            table_name: table_name.to_string(),
+        }
+    }
+}
+
+impl AuditSink for AuditTable {
+    fn record(&self, event: AuditEvent) -> AuditFuture<'_> {
+        Box::pin(async move {
+            let row = json!({
+                "table_name": event.table,
+                "operation": event.operation,
+                "filter": event.filter,
+                "payload_digest": event.payload_digest,
+                "status": event.status,
+                "latency_ms": event.latency.as_millis() as u64,
+            });
+
+            let result: Result<(), String> = self
+                .client
+                .insert_without_defined_key(&self.table_name, row)
+                .await;
+            #[cfg(feature = "tracing")]
+            if let Err(error) = result {
+                tracing::warn!(error = %error, table = %self.table_name, "audit event was not stored");
+            }
+            #[cfg(not(feature = "tracing"))]
+            let _ = result;
+        })
+    }
+}
+
+/// A write on its way, reported to the sink once its response arrived.
+pub(crate) struct PendingAudit {
+    sink: Arc<dyn AuditSink>,
+    event: AuditEvent,
+    started: Instant,
+}
+
+impl PendingAudit {
+    /// Starts timing a request, when it is a write and the client has a sink.
+    pub(crate) fn start(
+        sink: Option<&Arc<dyn AuditSink>>,
+        table_name: &str,
+        operation: &str,
+        request: &Request,
+    ) -> Option<Self> {
+        let sink: Arc<dyn AuditSink> = sink?.clone();
+        if !AUDITED_OPERATIONS.contains(&operation) {
+            return None;
+        }
+
+        let payload_digest: Option<String> =
+            request.body().and_then(|body| body.as_bytes()).map(|body| {
+                Sha256::digest(body)
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect()
+            });
+
+        Some(PendingAudit {
+            sink,
+            event: AuditEvent {
+                table: table_name.to_string(),
+                operation: operation.to_string(),
+                filter: request.url().query().map(str::to_string),
+                payload_digest,
+                status: None,
+                latency: Duration::ZERO,
+            },
+            started: Instant::now(),
+        })
+    }
+
+    /// Reports the write with the outcome of its request.
+    pub(crate) async fn finish(mut self, result: &Result<Response, ErrorTypes>) {
+        self.event.status = result
+            .as_ref()
+            .ok()
+            .map(|response| response.status().as_u16());
+        self.event.latency = self.started.elapsed();
+
+        self.sink.record(self.event).await;
+    }
+}
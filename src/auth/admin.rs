@@ -0,0 +1,493 @@
+//! ## Admin Auth
+//!
+//! Service role user management on `/auth/v1/admin`, for back-office tooling. The client has to be
+//! created with the service role key, never use it in a client application.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde_json::json;
+//! use supabase_rs::auth::admin::{
+//!     AdminUserAttributes, GenerateLinkParams, GenerateLinkType, InviteOptions,
+//! };
+//!
+//! let admin = client.auth_admin();
+//!
+//! let user = admin
+//!     .create_user(
+//!         AdminUserAttributes::new()
+//!             .email("jane@example.com")
+//!             .password("correct horse battery staple")
+//!             .email_confirm(true)
+//!             .user_metadata(json!({"name": "Jane"})),
+//!     )
+//!     .await?;
+//!
+//! // walk every user, 50 at a time
+//! let mut page: u32 = 1;
+//! loop {
+//!     let users = admin.list_users(page, 50).await?;
+//!     // ...
+//!     match users.next_page {
+//!         Some(next) => page = next,
+//!         None => break,
+//!     }
+//! }
+//!
+//! admin.ban_user(&user.id, "24h").await?;
+//!
+//! let invited = admin
+//!     .invite_user_by_email("john@example.com", InviteOptions::new().redirect_to("https://example.com/onboarding"))
+//!     .await?;
+//!
+//! let link = admin
+//!     .generate_link(GenerateLinkParams::new(GenerateLinkType::Recovery, "jane@example.com"))
+//!     .await?;
+//! println!("{}", link.action_link);
+//! ```
+
+use crate::auth::types::User;
+use crate::auth::{parse, send, with_redirect};
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use reqwest::{Method, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The attributes of a user created or updated by an admin, unset attributes are left untouched.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AdminUserAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Marks the email as confirmed, so no confirmation email is sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_confirm: Option<bool>,
+    /// Marks the phone number as confirmed, so no confirmation SMS is sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_confirm: Option<bool>,
+    /// Metadata the user can read and update themselves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_metadata: Option<Value>,
+    /// Metadata only the service role can update, e.g. roles or plans.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_metadata: Option<Value>,
+    /// How long the user is banned, e.g. `24h` or `876000h`, `none` lifts the ban.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ban_duration: Option<String>,
+}
+
+impl AdminUserAttributes {
+    /// Creates empty attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the email address.
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    /// Sets the phone number.
+    pub fn phone(mut self, phone: &str) -> Self {
+        self.phone = Some(phone.to_string());
+        self
+    }
+
+    /// Sets the password.
+    pub fn password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Sets whether the email address is confirmed.
+    pub fn email_confirm(mut self, confirmed: bool) -> Self {
+        self.email_confirm = Some(confirmed);
+        self
+    }
+
+    /// Sets whether the phone number is confirmed.
+    pub fn phone_confirm(mut self, confirmed: bool) -> Self {
+        self.phone_confirm = Some(confirmed);
+        self
+    }
+
+    /// Sets the user metadata.
+    pub fn user_metadata(mut self, metadata: Value) -> Self {
+        self.user_metadata = Some(metadata);
+        self
+    }
+
+    /// Sets the app metadata.
+    pub fn app_metadata(mut self, metadata: Value) -> Self {
+        self.app_metadata = Some(metadata);
+        self
+    }
+
+    /// Bans the user for a duration like `24h`, `none` lifts the ban.
+    pub fn ban_duration(mut self, duration: &str) -> Self {
+        self.ban_duration = Some(duration.to_string());
+        self
+    }
+}
+
+/// A page of users returned by [`AdminAuthClient::list_users`].
+#[derive(Debug, Clone, Default)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    /// The number of users in the project, when the server reports it.
+    pub total: Option<u64>,
+    /// The next page to request, `None` on the last page.
+    pub next_page: Option<u32>,
+}
+
+/// The options of [`AdminAuthClient::invite_user_by_email`].
+#[derive(Debug, Clone, Default)]
+pub struct InviteOptions {
+    /// Metadata of the invited user, stored as their `user_metadata`.
+    pub data: Option<Value>,
+    /// Where the invite link sends the user, the site URL of the project when `None`.
+    pub redirect_to: Option<String>,
+}
+
+impl InviteOptions {
+    /// Creates options without metadata or redirect.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the user metadata.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets where the invite link sends the user, one of the redirect URLs of the project.
+    pub fn redirect_to(mut self, redirect_to: &str) -> Self {
+        self.redirect_to = Some(redirect_to.to_string());
+        self
+    }
+}
+
+/// The kind of link generated by [`AdminAuthClient::generate_link`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerateLinkType {
+    Signup,
+    Invite,
+    MagicLink,
+    Recovery,
+    EmailChangeCurrent,
+    EmailChangeNew,
+}
+
+impl GenerateLinkType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            GenerateLinkType::Signup => "signup",
+            GenerateLinkType::Invite => "invite",
+            GenerateLinkType::MagicLink => "magiclink",
+            GenerateLinkType::Recovery => "recovery",
+            GenerateLinkType::EmailChangeCurrent => "email_change_current",
+            GenerateLinkType::EmailChangeNew => "email_change_new",
+        }
+    }
+}
+
+/// The parameters of [`AdminAuthClient::generate_link`].
+#[derive(Debug, Clone)]
+pub struct GenerateLinkParams {
+    pub link_type: GenerateLinkType,
+    pub email: String,
+    /// The password of the user, required for `signup` links.
+    pub password: Option<String>,
+    /// The new email address, required for email change links.
+    pub new_email: Option<String>,
+    /// User metadata stored on `signup` and `invite`.
+    pub data: Option<Value>,
+    /// Where the link sends the user after verifying.
+    pub redirect_to: Option<String>,
+}
+
+impl GenerateLinkParams {
+    /// Creates the parameters of a link for `email`.
+    pub fn new(link_type: GenerateLinkType, email: &str) -> Self {
+        GenerateLinkParams {
+            link_type,
+            email: email.to_string(),
+            password: None,
+            new_email: None,
+            data: None,
+            redirect_to: None,
+        }
+    }
+
+    /// Sets the password of a `signup` link.
+    pub fn password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Sets the new email address of an email change link.
+    pub fn new_email(mut self, new_email: &str) -> Self {
+        self.new_email = Some(new_email.to_string());
+        self
+    }
+
+    /// Sets the user metadata of a `signup` or `invite` link.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets where the link sends the user after verifying.
+    pub fn redirect_to(mut self, redirect_to: &str) -> Self {
+        self.redirect_to = Some(redirect_to.to_string());
+        self
+    }
+
+    /// The JSON body sent to `/admin/generate_link`.
+    ///
+    /// Fails when a parameter the link type needs is missing, the password of a `signup` link or
+    /// the new email address of an email change link.
+    pub(crate) fn body(&self) -> Result<Value> {
+        let missing: Option<&str> = match self.link_type {
+            GenerateLinkType::Signup if self.password.is_none() => Some("password"),
+            GenerateLinkType::EmailChangeCurrent | GenerateLinkType::EmailChangeNew
+                if self.new_email.is_none() =>
+            {
+                Some("new_email")
+            }
+            _ => None,
+        };
+        if let Some(missing) = missing {
+            return Err(ErrorTypes::InvalidParameters(format!(
+                "{} links need a {}",
+                self.link_type.as_str(),
+                missing
+            )));
+        }
+
+        let mut body: Value = serde_json::json!({
+            "type": self.link_type.as_str(),
+            "email": self.email,
+        });
+        if let Some(password) = &self.password {
+            body["password"] = Value::from(password.as_str());
+        }
+        if let Some(new_email) = &self.new_email {
+            body["new_email"] = Value::from(new_email.as_str());
+        }
+        if let Some(data) = &self.data {
+            body["data"] = data.clone();
+        }
+        if let Some(redirect_to) = &self.redirect_to {
+            body["redirect_to"] = Value::from(redirect_to.as_str());
+        }
+        Ok(body)
+    }
+}
+
+/// A link generated by [`AdminAuthClient::generate_link`], to send with your own email provider.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GeneratedLink {
+    /// The link the user opens to verify.
+    pub action_link: String,
+    /// The one time password matching the link.
+    pub email_otp: String,
+    /// The hashed token, for building a link to your own verification endpoint.
+    pub hashed_token: String,
+    pub redirect_to: String,
+    pub verification_type: String,
+    /// The user the link belongs to, created for `signup` and `invite` links.
+    #[serde(skip)]
+    pub user: User,
+}
+
+/// A client for the admin Auth API, created by [`SupabaseClient::auth_admin`].
+#[derive(Debug, Clone)]
+pub struct AdminAuthClient {
+    client: SupabaseClient,
+}
+
+impl SupabaseClient {
+    /// Returns a client for managing users, the client has to use the service role key.
+    pub fn auth_admin(&self) -> AdminAuthClient {
+        AdminAuthClient {
+            client: self.clone(),
+        }
+    }
+}
+
+impl AdminAuthClient {
+    /// Lists the users of the project.
+    ///
+    /// # Arguments
+    /// * `page` - The page to fetch, starting at `1`.
+    /// * `per_page` - The number of users per page.
+    pub async fn list_users(&self, page: u32, per_page: u32) -> Result<UserPage> {
+        let path: String = format!("admin/users?page={}&per_page={}", page, per_page);
+        let response: Response = self.send(Method::GET, &path, None, "list_users").await?;
+
+        let total: Option<u64> = response
+            .headers()
+            .get("x-total-count")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        let body: Value = response.json().await?;
+        let users: Vec<User> = parse(body["users"].clone())?;
+
+        let next_page: Option<u32> = next_page(page, per_page, users.len(), total);
+        Ok(UserPage {
+            users,
+            total,
+            next_page,
+        })
+    }
+
+    /// Fetches a user by id.
+    pub async fn get_user_by_id(&self, user_id: &str) -> Result<User> {
+        let path: String = format!("admin/users/{}", user_id);
+        let response: Response = self.send(Method::GET, &path, None, "get_user").await?;
+        Ok(response.json().await?)
+    }
+
+    /// Creates a user, set [`AdminUserAttributes::email_confirm`] to skip the confirmation email.
+    pub async fn create_user(&self, attributes: AdminUserAttributes) -> Result<User> {
+        let body: Value = attributes_body(&attributes)?;
+        let response: Response = self
+            .send(Method::POST, "admin/users", Some(body), "create_user")
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Updates a user, e.g. their metadata or ban.
+    pub async fn update_user_by_id(
+        &self,
+        user_id: &str,
+        attributes: AdminUserAttributes,
+    ) -> Result<User> {
+        let path: String = format!("admin/users/{}", user_id);
+        let body: Value = attributes_body(&attributes)?;
+        let response: Response = self
+            .send(Method::PUT, &path, Some(body), "update_user")
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Bans a user for a duration like `24h`, the user can't sign in or refresh their session.
+    pub async fn ban_user(&self, user_id: &str, duration: &str) -> Result<User> {
+        self.update_user_by_id(user_id, AdminUserAttributes::new().ban_duration(duration))
+            .await
+    }
+
+    /// Lifts the ban of a user.
+    pub async fn unban_user(&self, user_id: &str) -> Result<User> {
+        self.ban_user(user_id, "none").await
+    }
+
+    /// Deletes a user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user.
+    /// * `soft_delete` - Keeps the user row and obfuscates its personal data instead of removing it.
+    pub async fn delete_user(&self, user_id: &str, soft_delete: bool) -> Result<()> {
+        let path: String = format!("admin/users/{}", user_id);
+        let body: Value = serde_json::json!({ "should_soft_delete": soft_delete });
+        self.send(Method::DELETE, &path, Some(body), "delete_user")
+            .await?;
+        Ok(())
+    }
+
+    /// Invites a user by email, creating the user and sending them the invite email of the project.
+    ///
+    /// Auth doesn't return the link it sends, use [`generate_link`](Self::generate_link) with
+    /// [`GenerateLinkType::Invite`] to send the invite with your own email provider instead.
+    ///
+    /// # Returns
+    /// The invited user, whose `invited_at` is set.
+    pub async fn invite_user_by_email(&self, email: &str, options: InviteOptions) -> Result<User> {
+        let mut body: Value = serde_json::json!({ "email": email });
+        if let Some(data) = options.data {
+            body["data"] = data;
+        }
+        let path: String = with_redirect("invite", options.redirect_to.as_deref());
+        let response: Response = self
+            .send(Method::POST, &path, Some(body), "invite_user_by_email")
+            .await?;
+        parse(response.json().await?)
+    }
+
+    /// Generates a signup, invite, magic link, recovery or email change link without sending an email.
+    ///
+    /// # Returns
+    /// The action link and its one-time password and hashed token, to deliver with your own email
+    /// provider, or [`ErrorTypes::InvalidParameters`] when `params` misses what the link type needs.
+    pub async fn generate_link(&self, params: GenerateLinkParams) -> Result<GeneratedLink> {
+        let body: Value = params.body()?;
+        let response: Response = self
+            .send(
+                Method::POST,
+                "admin/generate_link",
+                Some(body),
+                "generate_link",
+            )
+            .await?;
+        parse_generated_link(response.json().await?)
+    }
+
+    /// Sends a request to `/auth/v1/{path}` with the service role key.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        operation: &str,
+    ) -> Result<Response> {
+        let key: &str = &self.client.api_key;
+        send(&self.client, method, path, key, body, operation).await
+    }
+}
+
+/// Serializes the attributes of a create or update request.
+fn attributes_body(attributes: &AdminUserAttributes) -> Result<Value> {
+    serde_json::to_value(attributes).map_err(|error| ErrorTypes::RequestFailed(error.to_string()))
+}
+
+/// The page after `page`, or `None` when it would be empty.
+///
+/// Without a total count a full page is assumed to have a successor.
+pub(crate) fn next_page(
+    page: u32,
+    per_page: u32,
+    returned: usize,
+    total: Option<u64>,
+) -> Option<u32> {
+    let has_more: bool = match total {
+        Some(total) => (page as u64) * (per_page as u64) < total,
+        None => returned > 0 && returned as u64 >= per_page as u64,
+    };
+    has_more.then_some(page + 1)
+}
+
+/// Splits a `generate_link` response, which holds the user and the link properties side by side.
+///
+/// Older GoTrue versions nest the link under `properties`, both shapes are accepted.
+pub(crate) fn parse_generated_link(body: Value) -> Result<GeneratedLink> {
+    let properties: Value = match body.get("properties") {
+        Some(properties) => properties.clone(),
+        None => body.clone(),
+    };
+    let user: Value = match body.get("user") {
+        Some(user) => user.clone(),
+        None => body,
+    };
+
+    let mut link: GeneratedLink = parse(properties)?;
+    link.user = parse(user)?;
+    Ok(link)
+}
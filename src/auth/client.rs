@@ -0,0 +1,471 @@
+//! ## Auth client
+//!
+//! Signs users in on `/auth/v1` and keeps their [`Session`] in a [`SessionStore`], which the other
+//! user-scoped calls (like [`mfa`](crate::auth::mfa)) authenticate with. Clones of an `AuthClient`
+//! share the store.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::auth::{AuthClient, SignInOptions, SignOutScope, SignUpOptions};
+//!
+//! let auth = AuthClient::new(supabase_url, anon_key)?;
+//! auth.sign_up("jane@example.com", "hunter22", SignUpOptions::new().data(json!({"name": "Jane"})))
+//!     .await?;
+//! let session = auth.sign_in_with_password("jane@example.com", "hunter22").await?;
+//!
+//! // with the token of the captcha the user solved, when the project enabled bot protection
+//! let options = SignInOptions::new().captcha_token(&captcha_token);
+//! let session = auth.sign_in_with_password_with("jane@example.com", "hunter22", options).await?;
+//!
+//! // or with the ID token of a native Sign in with Apple or Google
+//! let session = auth.sign_in_with_id_token("apple", &id_token, Some(&raw_nonce)).await?;
+//!
+//! // or sharing the connection pool of a client
+//! let auth = supabase_client.auth();
+//!
+//! // whose requests are sent as the user too once it keeps the session
+//! let supabase_client = supabase_client.with_session_store(MemorySessionStore::new());
+//! let auth = supabase_client.auth();
+//!
+//! // later, before the access token expires
+//! let session = auth.refresh_session().await?;
+//!
+//! auth.sign_out().await?;
+//!
+//! // or only on this device
+//! auth.sign_out_with_scope(SignOutScope::Local).await?;
+//! ```
+
+use crate::auth::events::{AuthEvent, AuthListeners};
+use crate::auth::jwt::JwtVerifier;
+use crate::auth::phone::SignUp;
+use crate::auth::session::{MemorySessionStore, SessionStore};
+use crate::auth::types::{Session, User};
+use crate::auth::{parse, send, with_captcha, with_redirect};
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The sessions of the user a sign out ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignOutScope {
+    /// Every session of the user, on every device.
+    #[default]
+    Global,
+    /// Only the current session.
+    Local,
+    /// Every session except the current one, which stays signed in.
+    Others,
+}
+
+impl SignOutScope {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SignOutScope::Global => "global",
+            SignOutScope::Local => "local",
+            SignOutScope::Others => "others",
+        }
+    }
+}
+
+/// The attributes a user updates themselves, unset attributes are left untouched.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UserAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// The code of a reauthentication, when the project asks for one to change the password.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Metadata the user can read and update themselves, stored as the `user_metadata`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl UserAttributes {
+    /// Creates empty attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the email address.
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    /// Sets the phone number.
+    pub fn phone(mut self, phone: &str) -> Self {
+        self.phone = Some(phone.to_string());
+        self
+    }
+
+    /// Sets the password.
+    pub fn password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Sets the code of a reauthentication.
+    pub fn nonce(mut self, nonce: &str) -> Self {
+        self.nonce = Some(nonce.to_string());
+        self
+    }
+
+    /// Sets the user metadata.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// The options of an email sign up.
+#[derive(Debug, Clone, Default)]
+pub struct SignUpOptions {
+    /// Metadata of the new user, stored as their `user_metadata`.
+    pub data: Option<Value>,
+    /// The token of a solved hCaptcha or Turnstile challenge, when the project enabled bot protection.
+    pub captcha_token: Option<String>,
+    /// Where the confirmation link sends the user, the site URL of the project when `None`.
+    pub redirect_to: Option<String>,
+}
+
+impl SignUpOptions {
+    /// Creates options without metadata or captcha token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the user metadata.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets the captcha token.
+    pub fn captcha_token(mut self, captcha_token: &str) -> Self {
+        self.captcha_token = Some(captcha_token.to_string());
+        self
+    }
+
+    /// Sets where the confirmation link sends the user, one of the redirect URLs of the project.
+    pub fn redirect_to(mut self, redirect_to: &str) -> Self {
+        self.redirect_to = Some(redirect_to.to_string());
+        self
+    }
+}
+
+/// The options of a password sign in.
+#[derive(Debug, Clone, Default)]
+pub struct SignInOptions {
+    /// The token of a solved hCaptcha or Turnstile challenge, when the project enabled bot protection.
+    pub captcha_token: Option<String>,
+}
+
+impl SignInOptions {
+    /// Creates options without captcha token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the captcha token.
+    pub fn captcha_token(mut self, captcha_token: &str) -> Self {
+        self.captcha_token = Some(captcha_token.to_string());
+        self
+    }
+}
+
+impl SupabaseClient {
+    /// Creates an [`AuthClient`] for the project of this client, sharing its connection pool.
+    ///
+    /// The session stays with the `AuthClient`, unless this client was given a store with
+    /// [`with_session_store`](Self::with_session_store). Then once a user signed in, the REST, RPC,
+    /// edge function and storage requests of this client and its clones are sent with the access
+    /// token of the user instead of the API key, so they pass the row level security policies as
+    /// that user. After signing out they use the API key again.
+    pub fn auth(&self) -> AuthClient {
+        let session_store: Arc<dyn SessionStore> = match &self.session_store {
+            Some(store) => store.clone(),
+            None => Arc::new(MemorySessionStore::new()),
+        };
+        let mut client: SupabaseClient = self.clone();
+        client.session_store = Some(session_store.clone());
+
+        AuthClient {
+            client,
+            session_store,
+            jwt: JwtVerifier::default(),
+            listeners: AuthListeners::default(),
+            code_verifier: Arc::default(),
+        }
+    }
+
+    /// Keeps the session of the user signed in with [`auth`](Self::auth) in `store`, picking up the
+    /// session it already holds, and sends the requests of this client and its clones as that user.
+    ///
+    /// Without a store, e.g. [`MemorySessionStore`], the requests of a client shared between the
+    /// users of a server keep the API key whoever signs in.
+    pub fn with_session_store(mut self, store: impl SessionStore + 'static) -> Self {
+        self.session_store = Some(Arc::new(store));
+        self
+    }
+}
+
+/// Replaces the API key bearer of a request with the access token of the session in `store`.
+pub(crate) fn authorize_session(
+    store: Option<&Arc<dyn SessionStore>>,
+    api_key: &str,
+    headers: &mut HeaderMap,
+) {
+    let Some(session) = store.and_then(|store| store.get()) else {
+        return;
+    };
+    let sends_api_key: bool = headers
+        .get(AUTHORIZATION)
+        .is_some_and(|bearer| bearer.as_bytes() == format!("Bearer {}", api_key).as_bytes());
+    if !sends_api_key {
+        return;
+    }
+    if let Ok(bearer) = HeaderValue::from_str(&format!("Bearer {}", session.access_token)) {
+        headers.insert(AUTHORIZATION, bearer);
+    }
+}
+
+/// A client for the user-facing Auth API, holding the session of the signed in user.
+#[derive(Debug, Clone)]
+pub struct AuthClient {
+    pub(crate) client: SupabaseClient,
+    session_store: Arc<dyn SessionStore>,
+    pub(crate) jwt: JwtVerifier,
+    pub(crate) listeners: AuthListeners,
+    /// The PKCE verifier of the sign in started with `authorize`.
+    pub(crate) code_verifier: Arc<Mutex<Option<String>>>,
+}
+
+impl AuthClient {
+    /// Creates an `AuthClient` for a project, keeping sessions in memory.
+    ///
+    /// # Arguments
+    /// * `supabase_url` - The URL of the project.
+    /// * `anon_key` - The anon (public) key of the project.
+    pub fn new(supabase_url: String, anon_key: String) -> Result<Self> {
+        Ok(SupabaseClient::new(supabase_url, anon_key)?.auth())
+    }
+
+    /// Keeps sessions in `store`, picking up the session it already holds.
+    ///
+    /// Only the requests of this `AuthClient` follow the new store, set the store of a
+    /// [`SupabaseClient`] with [`SupabaseClient::with_session_store`] to share it with its requests.
+    pub fn with_session_store(mut self, store: impl SessionStore + 'static) -> Self {
+        let store: Arc<dyn SessionStore> = Arc::new(store);
+        self.client.session_store = Some(store.clone());
+        self.session_store = store;
+        self
+    }
+
+    /// Signs a user up with their email and a password.
+    ///
+    /// # Arguments
+    /// * `email` - The email address of the new user.
+    /// * `password` - The password of the new user.
+    /// * `options` - The metadata of the user and the captcha token, see [`SignUpOptions`].
+    ///
+    /// # Returns
+    /// The session when the project doesn't ask to confirm new users, which is kept, or the
+    /// unconfirmed user otherwise, who confirms with the link sent to them.
+    pub async fn sign_up(
+        &self,
+        email: &str,
+        password: &str,
+        options: SignUpOptions,
+    ) -> Result<SignUp> {
+        let mut body: Value = json!({ "email": email, "password": password });
+        if let Some(data) = options.data {
+            body["data"] = data;
+        }
+        let body: Value = with_captcha(body, options.captcha_token.as_deref());
+        let path: String = with_redirect("signup", options.redirect_to.as_deref());
+        let key: &str = &self.client.api_key;
+        let response = send(
+            &self.client,
+            Method::POST,
+            &path,
+            key,
+            Some(body),
+            "sign_up",
+        )
+        .await?;
+        self.sign_up_response(response.json().await?)
+    }
+
+    /// Signs a user in with their email and password, and keeps the session.
+    pub async fn sign_in_with_password(&self, email: &str, password: &str) -> Result<Session> {
+        self.sign_in_with_password_with(email, password, SignInOptions::default())
+            .await
+    }
+
+    /// Signs a user in with their email and password and the given `options`, e.g. the captcha
+    /// token of a project with bot protection, and keeps the session.
+    pub async fn sign_in_with_password_with(
+        &self,
+        email: &str,
+        password: &str,
+        options: SignInOptions,
+    ) -> Result<Session> {
+        let body: Value = json!({ "email": email, "password": password });
+        let body: Value = with_captcha(body, options.captcha_token.as_deref());
+        self.grant("password", body, "sign_in").await
+    }
+
+    /// Signs a user in with an OpenID Connect ID token, and keeps the session. This is how the
+    /// native Sign in with Apple and Google flows of mobile and desktop apps end.
+    ///
+    /// # Arguments
+    /// * `provider` - The provider that issued the token, e.g. `apple` or `google`.
+    /// * `id_token` - The ID token returned by the provider.
+    /// * `nonce` - The raw nonce, when the token was requested with its SHA-256 hash, as Apple does.
+    pub async fn sign_in_with_id_token(
+        &self,
+        provider: &str,
+        id_token: &str,
+        nonce: Option<&str>,
+    ) -> Result<Session> {
+        let mut body: Value = json!({ "provider": provider, "id_token": id_token });
+        if let Some(nonce) = nonce {
+            body["nonce"] = json!(nonce);
+        }
+        self.grant("id_token", body, "sign_in_with_id_token").await
+    }
+
+    /// Exchanges the refresh token of the current session for a new session.
+    pub async fn refresh_session(&self) -> Result<Session> {
+        let refresh_token: String = self
+            .session()
+            .map(|session| session.refresh_token)
+            .ok_or(ErrorTypes::MissingSession)?;
+        let body: Value = json!({ "refresh_token": refresh_token });
+        self.grant("refresh_token", body, "refresh_session").await
+    }
+
+    /// Signs the user out of all their sessions, revoking their refresh tokens and removing the
+    /// stored session. See [`sign_out_with_scope`](Self::sign_out_with_scope).
+    pub async fn sign_out(&self) -> Result<()> {
+        self.sign_out_with_scope(SignOutScope::Global).await
+    }
+
+    /// Signs the user out of the sessions in `scope`, revoking their refresh tokens.
+    ///
+    /// The stored session is removed unless the scope is [`SignOutScope::Others`], even when the
+    /// server rejects the request, e.g. because the access token already expired.
+    pub async fn sign_out_with_scope(&self, scope: SignOutScope) -> Result<()> {
+        let path: String = format!("logout?scope={}", scope.as_str());
+        let result = match self.session() {
+            Some(_) => self.send(Method::POST, &path, None, "sign_out").await,
+            None => return Ok(()),
+        };
+        if scope == SignOutScope::Others {
+            return result.map(|_| ());
+        }
+        self.session_store.clear()?;
+        self.listeners.emit(AuthEvent::SignedOut, None);
+        result.map(|_| ())
+    }
+
+    /// Fetches the signed in user, including their identities and MFA factors.
+    pub async fn get_user(&self) -> Result<User> {
+        let response = self.send(Method::GET, "user", None, "get_user").await?;
+        parse(response.json().await?)
+    }
+
+    /// Updates the signed in user, and the user of the stored session.
+    ///
+    /// A new email or phone number is only changed once confirmed with the code or link Auth sends.
+    pub async fn update_user(&self, attributes: UserAttributes) -> Result<User> {
+        let body: Value = serde_json::to_value(&attributes)
+            .map_err(|error| ErrorTypes::RequestFailed(error.to_string()))?;
+        let response = self
+            .send(Method::PUT, "user", Some(body), "update_user")
+            .await?;
+        let user: User = parse(response.json().await?)?;
+
+        if let Some(mut session) = self.session() {
+            session.user = user.clone();
+            self.store_session(session, AuthEvent::UserUpdated)?;
+        }
+        Ok(user)
+    }
+
+    /// Returns the current session, if a user is signed in.
+    pub fn session(&self) -> Option<Session> {
+        self.session_store.get()
+    }
+
+    /// Replaces the current session, e.g. with one received from another client.
+    pub fn set_session(&self, session: Session) -> Result<()> {
+        self.store_session(session, AuthEvent::SignedIn).map(|_| ())
+    }
+
+    /// Keeps a session returned by the server, filling in `expires_at` when the server left it out,
+    /// and tells the callbacks about the `event`.
+    pub(crate) fn store_session(&self, mut session: Session, event: AuthEvent) -> Result<Session> {
+        if session.expires_at.is_none() {
+            let now: u64 = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default();
+            session.expires_at = Some(now + session.expires_in);
+        }
+        self.session_store.set(&session)?;
+        self.listeners.emit(event, Some(&session));
+        Ok(session)
+    }
+
+    /// Exchanges a grant for a session on `/token`, and keeps the session.
+    pub(crate) async fn grant(
+        &self,
+        grant_type: &str,
+        body: Value,
+        operation: &str,
+    ) -> Result<Session> {
+        let path: String = format!("token?grant_type={}", grant_type);
+        let key: &str = &self.client.api_key;
+        let response = send(
+            &self.client,
+            Method::POST,
+            &path,
+            key,
+            Some(body),
+            operation,
+        )
+        .await?;
+        let event: AuthEvent = match grant_type {
+            "refresh_token" => AuthEvent::TokenRefreshed,
+            _ => AuthEvent::SignedIn,
+        };
+        self.store_session(parse(response.json().await?)?, event)
+    }
+
+    /// Sends a request authenticated with the access token of the current session.
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        operation: &str,
+    ) -> Result<reqwest::Response> {
+        let access_token: String = self
+            .session()
+            .map(|session| session.access_token)
+            .ok_or(ErrorTypes::MissingSession)?;
+        send(&self.client, method, path, &access_token, body, operation).await
+    }
+}
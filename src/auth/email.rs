@@ -0,0 +1,128 @@
+//! ## Email auth
+//!
+//! Passwordless sign ins and password recovery for the user of an [`AuthClient`], with links sent by
+//! email. The links send the user to the `redirect_to` URL given, or to the site URL of the project,
+//! which completes the sign in with the tokens or code it receives.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::auth::email::MagicLinkOptions;
+//!
+//! let options = MagicLinkOptions::new()
+//!     .redirect_to("https://example.com/welcome")
+//!     .data(json!({"referrer": "newsletter"}));
+//! auth.send_magic_link("jane@example.com", options).await?;
+//!
+//! auth.reset_password_for_email("jane@example.com", Some("https://example.com/reset")).await?;
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::{send, with_captcha, with_redirect};
+use crate::errors::Result;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// The options of a magic link.
+#[derive(Debug, Clone)]
+pub struct MagicLinkOptions {
+    /// Where the link sends the user, the site URL of the project when `None`.
+    pub redirect_to: Option<String>,
+    /// Metadata of the user signed up by the link, stored as their `user_metadata`.
+    pub data: Option<Value>,
+    /// Whether a user is signed up when no user has the email yet, `true` by default.
+    pub create_user: bool,
+    /// The token of a solved hCaptcha or Turnstile challenge, when the project enabled bot protection.
+    pub captcha_token: Option<String>,
+}
+
+impl Default for MagicLinkOptions {
+    fn default() -> Self {
+        Self {
+            redirect_to: None,
+            data: None,
+            create_user: true,
+            captcha_token: None,
+        }
+    }
+}
+
+impl MagicLinkOptions {
+    /// Creates options signing up new users, without metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets where the link sends the user, one of the redirect URLs of the project.
+    pub fn redirect_to(mut self, redirect_to: &str) -> Self {
+        self.redirect_to = Some(redirect_to.to_string());
+        self
+    }
+
+    /// Sets the metadata of a new user.
+    pub fn data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets whether a user is signed up when no user has the email yet.
+    pub fn create_user(mut self, create_user: bool) -> Self {
+        self.create_user = create_user;
+        self
+    }
+
+    /// Sets the captcha token.
+    pub fn captcha_token(mut self, captcha_token: &str) -> Self {
+        self.captcha_token = Some(captcha_token.to_string());
+        self
+    }
+}
+
+impl AuthClient {
+    /// Sends a magic link to an email address, signing the user in once they follow it.
+    pub async fn send_magic_link(&self, email: &str, options: MagicLinkOptions) -> Result<()> {
+        let mut body: Value = json!({ "email": email, "create_user": options.create_user });
+        if let Some(data) = options.data {
+            body["data"] = data;
+        }
+        let body: Value = with_captcha(body, options.captcha_token.as_deref());
+        let path: String = with_redirect("otp", options.redirect_to.as_deref());
+        let key: &str = &self.client.api_key;
+        send(
+            &self.client,
+            Method::POST,
+            &path,
+            key,
+            Some(body),
+            "send_magic_link",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Sends a password recovery link to an email address. The link signs the user in, who then
+    /// sets a new password with [`update_user`](Self::update_user).
+    ///
+    /// # Arguments
+    /// * `email` - The email address of the user.
+    /// * `redirect_to` - Where the link sends the user, e.g. the page asking for the new password.
+    pub async fn reset_password_for_email(
+        &self,
+        email: &str,
+        redirect_to: Option<&str>,
+    ) -> Result<()> {
+        let body: Value = json!({ "email": email });
+        let path: String = with_redirect("recover", redirect_to);
+        let key: &str = &self.client.api_key;
+        send(
+            &self.client,
+            Method::POST,
+            &path,
+            key,
+            Some(body),
+            "reset_password_for_email",
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! ## Auth events
+//!
+//! An [`AuthClient`] tells the callbacks registered with
+//! [`on_auth_state_change`](AuthClient::on_auth_state_change) whenever its session changes, so the
+//! components depending on the session, like a client sending the access token of the user or a
+//! cache of their data, follow sign ins, refreshes and sign outs without polling. Clones of an
+//! `AuthClient` share their callbacks.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::auth::events::AuthEvent;
+//!
+//! let subscription = auth.on_auth_state_change(|event, session| match event {
+//!     AuthEvent::SignedIn | AuthEvent::TokenRefreshed => {
+//!         println!("token expires at {:?}", session.and_then(|session| session.expires_at));
+//!     }
+//!     AuthEvent::SignedOut => println!("signed out"),
+//!     _ => {}
+//! });
+//!
+//! // later
+//! subscription.unsubscribe();
+//! ```
+//!
+//! Callbacks run on the task changing the session, right after the new session was stored, so they
+//! should hand longer work to a task of their own.
+
+use crate::auth::client::AuthClient;
+use crate::auth::types::Session;
+
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+/// A change of the session of an [`AuthClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthEvent {
+    /// A user signed in, or a session was set with `set_session`.
+    SignedIn,
+    /// The user signed out, the callbacks receive no session.
+    SignedOut,
+    /// The session was refreshed with its refresh token.
+    TokenRefreshed,
+    /// The user of the session was updated, e.g. their email or metadata.
+    UserUpdated,
+    /// An MFA challenge was verified, raising the session to `aal2`.
+    MfaChallengeVerified,
+}
+
+impl AuthEvent {
+    /// The name supabase-js uses for the event, e.g. `SIGNED_IN`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthEvent::SignedIn => "SIGNED_IN",
+            AuthEvent::SignedOut => "SIGNED_OUT",
+            AuthEvent::TokenRefreshed => "TOKEN_REFRESHED",
+            AuthEvent::UserUpdated => "USER_UPDATED",
+            AuthEvent::MfaChallengeVerified => "MFA_CHALLENGE_VERIFIED",
+        }
+    }
+}
+
+/// A callback receiving the changes of a session.
+type AuthCallback = Arc<dyn Fn(AuthEvent, Option<&Session>) + Send + Sync>;
+
+/// The callbacks of an `AuthClient` and its clones.
+#[derive(Clone, Default)]
+pub(crate) struct AuthListeners {
+    next_id: Arc<AtomicU64>,
+    callbacks: Arc<Mutex<Vec<(u64, AuthCallback)>>>,
+}
+
+impl Debug for AuthListeners {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthListeners")
+            .field("callbacks", &lock(&self.callbacks).len())
+            .finish()
+    }
+}
+
+impl AuthListeners {
+    /// Calls every callback with `event` and the session after it.
+    pub(crate) fn emit(&self, event: AuthEvent, session: Option<&Session>) {
+        let callbacks: Vec<AuthCallback> = lock(&self.callbacks)
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(event, session);
+        }
+    }
+}
+
+/// A callback registered with [`AuthClient::on_auth_state_change`].
+///
+/// Dropping the subscription keeps the callback, [`unsubscribe`](Self::unsubscribe) removes it.
+#[derive(Debug)]
+pub struct AuthSubscription {
+    id: u64,
+    callbacks: Weak<Mutex<Vec<(u64, AuthCallback)>>>,
+}
+
+impl AuthSubscription {
+    /// Removes the callback, it isn't called for later changes.
+    pub fn unsubscribe(self) {
+        if let Some(callbacks) = self.callbacks.upgrade() {
+            lock(&callbacks).retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+impl AuthClient {
+    /// Calls `callback` with every change of the session, and the session after it.
+    ///
+    /// # Returns
+    /// The subscription removing the callback again.
+    pub fn on_auth_state_change<F>(&self, callback: F) -> AuthSubscription
+    where
+        F: Fn(AuthEvent, Option<&Session>) + Send + Sync + 'static,
+    {
+        let id: u64 = self.listeners.next_id.fetch_add(1, Ordering::Relaxed);
+        lock(&self.listeners.callbacks).push((id, Arc::new(callback)));
+
+        AuthSubscription {
+            id,
+            callbacks: Arc::downgrade(&self.listeners.callbacks),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,61 @@
+//! ## Identities
+//!
+//! The login methods of the user signed in on an [`AuthClient`], e.g. their email and the OAuth
+//! providers they connected. Manual linking has to be enabled for the project to link providers.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! // send the user to the provider, who returns them to the site URL of the project
+//! let url: String = auth.link_identity("github").await?;
+//!
+//! let identities: Vec<Identity> = auth.get_user_identities().await?;
+//! for identity in &identities {
+//!     println!("{} {}", identity.provider, identity.identity_id);
+//! }
+//!
+//! // a user keeps at least one identity
+//! auth.unlink_identity(&identities[1].identity_id).await?;
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::types::Identity;
+use crate::errors::{ErrorTypes, Result};
+
+use reqwest::Method;
+use serde_json::Value;
+
+impl AuthClient {
+    /// Lists the identities of the signed in user.
+    pub async fn get_user_identities(&self) -> Result<Vec<Identity>> {
+        Ok(self.get_user().await?.identities)
+    }
+
+    /// Starts linking an OAuth provider to the signed in user.
+    ///
+    /// # Arguments
+    /// * `provider` - The provider to link, e.g. `github` or `google`.
+    ///
+    /// # Returns
+    /// The URL of the provider to send the user to. Once they return, the provider is one of their
+    /// identities.
+    pub async fn link_identity(&self, provider: &str) -> Result<String> {
+        let path: String = format!(
+            "user/identities/authorize?provider={}&skip_http_redirect=true",
+            provider
+        );
+        let response = self.send(Method::GET, &path, None, "link_identity").await?;
+        let body: Value = response.json().await?;
+        body["url"].as_str().map(str::to_string).ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse("no url to link the identity".to_string())
+        })
+    }
+
+    /// Unlinks an identity from the signed in user, by its `identity_id`. Auth refuses to unlink the
+    /// last identity of a user.
+    pub async fn unlink_identity(&self, identity_id: &str) -> Result<()> {
+        let path: String = format!("user/identities/{}", identity_id);
+        self.send(Method::DELETE, &path, None, "unlink_identity")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+//! ## JWT verification
+//!
+//! Verifies Supabase access tokens locally, so middlewares can authenticate requests without calling
+//! the Auth API every time.
+//!
+//! - Tokens signed with the legacy shared secret (`HS256`) are checked against the project JWT secret,
+//!   set with [`AuthClient::with_jwt_secret`].
+//! - Tokens signed with asymmetric signing keys (`ES256`, `RS256`) are checked against the public keys
+//!   served on `/auth/v1/.well-known/jwks.json`. The keys are fetched once and cached, and fetched again
+//!   when a token names a key that isn't cached yet (at most once a minute), so key rotations are picked up.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let auth = AuthClient::new(supabase_url, anon_key)?.with_jwt_secret(&jwt_secret);
+//!
+//! // e.g. in an axum extractor
+//! let claims: Claims = auth.verify_jwt(bearer_token).await?;
+//! println!("request by {} ({})", claims.sub, claims.role);
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::send;
+use crate::errors::{ErrorTypes, Result};
+
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Header, Validation};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// The audience of tokens issued to signed in users.
+const AUTHENTICATED_AUDIENCE: &str = "authenticated";
+
+/// The minimum time between two JWKS fetches triggered by unknown key ids.
+const JWKS_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The claims of a Supabase access token.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Claims {
+    /// The id of the user.
+    pub sub: String,
+    pub aud: String,
+    pub iss: Option<String>,
+    /// The expiry, as a Unix timestamp.
+    pub exp: u64,
+    pub iat: Option<u64>,
+    /// The Postgres role of the requests made with the token, e.g. `authenticated`.
+    pub role: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// The assurance level, `aal2` once an MFA factor was verified.
+    pub aal: Option<String>,
+    pub session_id: Option<String>,
+    pub is_anonymous: bool,
+    pub app_metadata: Value,
+    pub user_metadata: Value,
+}
+
+/// The keys tokens are verified with, shared between clones of an `AuthClient`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JwtVerifier {
+    pub(crate) secret: Option<String>,
+    jwks: Arc<RwLock<Option<(JwkSet, Instant)>>>,
+}
+
+impl JwtVerifier {
+    /// Returns the cached key with the given id.
+    fn cached_key(&self, key_id: &str) -> Option<Jwk> {
+        let jwks = self
+            .jwks
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        jwks.as_ref()
+            .and_then(|(jwks, _)| jwks.find(key_id).cloned())
+    }
+
+    /// Whether the key set may be fetched again.
+    fn may_refetch(&self) -> bool {
+        let jwks = self
+            .jwks
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match jwks.as_ref() {
+            Some((_, fetched_at)) => fetched_at.elapsed() >= JWKS_REFETCH_INTERVAL,
+            None => true,
+        }
+    }
+
+    pub(crate) fn cache(&self, jwks: JwkSet) {
+        *self
+            .jwks
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((jwks, Instant::now()));
+    }
+}
+
+impl AuthClient {
+    /// Verifies tokens signed with the legacy shared secret against `secret`, the JWT secret of the project.
+    pub fn with_jwt_secret(mut self, secret: &str) -> Self {
+        self.jwt.secret = Some(secret.to_string());
+        self
+    }
+
+    /// Verifies the signature, expiry and audience of an access token without calling the Auth API.
+    ///
+    /// # Returns
+    /// The claims of the token, or [`ErrorTypes::InvalidJwt`] when it can't be trusted.
+    pub async fn verify_jwt(&self, token: &str) -> Result<Claims> {
+        let header: Header = decode_header(token).map_err(invalid)?;
+
+        let key: DecodingKey = match header.alg {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret: &str = self.jwt.secret.as_deref().ok_or_else(|| {
+                    ErrorTypes::InvalidJwt(
+                        "the token is signed with the JWT secret, set it with `with_jwt_secret`"
+                            .to_string(),
+                    )
+                })?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            _ => {
+                let key_id: String = header
+                    .kid
+                    .clone()
+                    .ok_or_else(|| ErrorTypes::InvalidJwt("the token has no key id".to_string()))?;
+                let jwk: Jwk = self.signing_key(&key_id).await?;
+                DecodingKey::from_jwk(&jwk).map_err(invalid)?
+            }
+        };
+
+        decode_claims(token, &key, header.alg)
+    }
+
+    /// Returns the public key with the given id, fetching the key set when it isn't cached.
+    async fn signing_key(&self, key_id: &str) -> Result<Jwk> {
+        if let Some(jwk) = self.jwt.cached_key(key_id) {
+            return Ok(jwk);
+        }
+        if self.jwt.may_refetch() {
+            let key: &str = &self.client.api_key;
+            let response = send(
+                &self.client,
+                Method::GET,
+                ".well-known/jwks.json",
+                key,
+                None,
+                "jwks",
+            )
+            .await?;
+            self.jwt.cache(response.json().await?);
+        }
+
+        self.jwt
+            .cached_key(key_id)
+            .ok_or_else(|| ErrorTypes::InvalidJwt(format!("unknown signing key `{}`", key_id)))
+    }
+}
+
+/// Checks the signature, expiry and audience of a token and returns its claims.
+pub(crate) fn decode_claims(
+    token: &str,
+    key: &DecodingKey,
+    algorithm: Algorithm,
+) -> Result<Claims> {
+    let mut validation: Validation = Validation::new(algorithm);
+    validation.set_audience(&[AUTHENTICATED_AUDIENCE]);
+
+    decode::<Claims>(token, key, &validation)
+        .map(|data| data.claims)
+        .map_err(invalid)
+}
+
+fn invalid(error: jsonwebtoken::errors::Error) -> ErrorTypes {
+    ErrorTypes::InvalidJwt(error.to_string())
+}
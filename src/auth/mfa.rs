@@ -0,0 +1,134 @@
+//! ## Multi-factor authentication
+//!
+//! TOTP enrollment and verification on `/auth/v1/factors`, for the user signed in on an [`AuthClient`].
+//!
+//! A factor is enrolled unverified, the user adds the QR code to their authenticator app and proves it
+//! works by verifying a first challenge. From then on every sign in needs a verified challenge to reach
+//! the `aal2` assurance level.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let enrollment = auth.mfa_enroll(Some("Phone"), None).await?;
+//! // show enrollment.totp.qr_code (an SVG data URL) to the user, then ask for the code
+//!
+//! let challenge = auth.mfa_challenge(&enrollment.id).await?;
+//! let session = auth.mfa_verify(&enrollment.id, &challenge.id, &code).await?;
+//!
+//! for factor in auth.list_factors().await? {
+//!     println!("{} {:?}", factor.id, factor.status);
+//! }
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::events::AuthEvent;
+use crate::auth::parse;
+use crate::auth::types::{Challenge, Factor, Session};
+use crate::errors::Result;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// A TOTP factor that was just enrolled, with the secret to add to an authenticator app.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TotpEnrollment {
+    /// The id of the new, still unverified, factor.
+    pub id: String,
+    #[serde(rename = "type")]
+    pub factor_type: String,
+    pub friendly_name: Option<String>,
+    pub totp: Totp,
+}
+
+/// The secret of a TOTP factor, only returned on enrollment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Totp {
+    /// An SVG of the QR code as a data URL.
+    pub qr_code: String,
+    /// The secret, for entering it manually.
+    pub secret: String,
+    /// The `otpauth://` URI encoded in the QR code.
+    pub uri: String,
+}
+
+impl AuthClient {
+    /// Enrolls a new TOTP factor for the signed in user.
+    ///
+    /// # Arguments
+    /// * `friendly_name` - A name telling the factors of a user apart, e.g. `Phone`.
+    /// * `issuer` - The issuer shown in the authenticator app, the project URL by default.
+    pub async fn mfa_enroll(
+        &self,
+        friendly_name: Option<&str>,
+        issuer: Option<&str>,
+    ) -> Result<TotpEnrollment> {
+        let body: Value = enroll_body(friendly_name, issuer);
+        let response = self
+            .send(Method::POST, "factors", Some(body), "mfa_enroll")
+            .await?;
+        parse(response.json().await?)
+    }
+
+    /// Creates a challenge for a factor, to be answered with [`mfa_verify`](Self::mfa_verify).
+    pub async fn mfa_challenge(&self, factor_id: &str) -> Result<Challenge> {
+        let path: String = format!("factors/{}/challenge", factor_id);
+        let response = self
+            .send(Method::POST, &path, None, "mfa_challenge")
+            .await?;
+        parse(response.json().await?)
+    }
+
+    /// Verifies a challenge with the code from the authenticator app.
+    ///
+    /// The first verification marks the factor as verified. The returned `aal2` session replaces the
+    /// current one.
+    pub async fn mfa_verify(
+        &self,
+        factor_id: &str,
+        challenge_id: &str,
+        code: &str,
+    ) -> Result<Session> {
+        let path: String = format!("factors/{}/verify", factor_id);
+        let body: Value = json!({ "challenge_id": challenge_id, "code": code });
+        let response = self
+            .send(Method::POST, &path, Some(body), "mfa_verify")
+            .await?;
+        self.store_session(
+            parse(response.json().await?)?,
+            AuthEvent::MfaChallengeVerified,
+        )
+    }
+
+    /// Creates a challenge and verifies it right away, for flows that ask for the code up front.
+    pub async fn mfa_challenge_and_verify(&self, factor_id: &str, code: &str) -> Result<Session> {
+        let challenge: Challenge = self.mfa_challenge(factor_id).await?;
+        self.mfa_verify(factor_id, &challenge.id, code).await
+    }
+
+    /// Removes a factor. Removing a verified factor requires an `aal2` session.
+    pub async fn mfa_unenroll(&self, factor_id: &str) -> Result<()> {
+        let path: String = format!("factors/{}", factor_id);
+        self.send(Method::DELETE, &path, None, "mfa_unenroll")
+            .await?;
+        Ok(())
+    }
+
+    /// Lists the factors of the signed in user, verified or not.
+    pub async fn list_factors(&self) -> Result<Vec<Factor>> {
+        Ok(self.get_user().await?.factors)
+    }
+}
+
+/// The body of a TOTP enrollment.
+pub(crate) fn enroll_body(friendly_name: Option<&str>, issuer: Option<&str>) -> Value {
+    let mut body: Value = json!({ "factor_type": "totp" });
+    if let Some(friendly_name) = friendly_name {
+        body["friendly_name"] = Value::from(friendly_name);
+    }
+    if let Some(issuer) = issuer {
+        body["issuer"] = Value::from(issuer);
+    }
+    body
+}
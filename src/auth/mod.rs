@@ -0,0 +1,122 @@
+//! ## Auth
+//!
+//! Clients for Supabase Auth (GoTrue) on `/auth/v1`. Enabled with the `auth` feature.
+//!
+//! - [`client`]: The [`AuthClient`] signing users in and holding their session.
+//! - [`email`]: Magic links and password recovery emails.
+//! - [`phone`]: Sign ups and sign ins with a phone number and SMS or WhatsApp codes.
+//! - [`sso`]: Enterprise SAML sign ins by email domain or identity provider.
+//! - [`session`]: Where the session is kept, in memory, in a file or in a custom [`SessionStore`].
+//! - [`events`]: Callbacks following the sign ins, refreshes and sign outs of an [`AuthClient`].
+//! - [`pkce`]: OAuth sign ins with PKCE, and the verifiers and challenges they use.
+//! - [`identities`]: Linking and unlinking the OAuth providers of the signed in user.
+//! - [`jwt`]: Local verification of access tokens, for server-side middlewares.
+//! - [`mfa`]: TOTP multi-factor enrollment, challenges and verification for the signed in user.
+//! - [`admin`]: Service role user management, see [`SupabaseClient::auth_admin`].
+//! - [`types`]: The users, sessions and factors returned by the Auth API.
+#![cfg(feature = "auth")]
+
+pub mod admin;
+pub mod client;
+pub mod email;
+pub mod events;
+pub mod identities;
+pub mod jwt;
+pub mod mfa;
+pub mod phone;
+pub mod pkce;
+pub mod session;
+pub mod sso;
+pub mod types;
+
+pub use admin::AdminAuthClient;
+pub use client::{AuthClient, SignInOptions, SignOutScope, SignUpOptions, UserAttributes};
+pub use email::MagicLinkOptions;
+pub use events::{AuthEvent, AuthSubscription};
+pub use jwt::Claims;
+pub use phone::{SignUp, SmsChannel};
+pub use pkce::Pkce;
+pub use session::{FileSessionStore, MemorySessionStore, SessionStore};
+pub use types::{Challenge, Factor, Identity, Session, User};
+
+use crate::errors::{ErrorTypes, Result};
+use crate::request::Headers;
+use crate::SupabaseClient;
+
+use reqwest::{Method, Response};
+use serde_json::Value;
+
+/// Sends a request to `/auth/v1/{path}`.
+///
+/// # Arguments
+/// * `client` - The client whose URL, key and connection pool are used.
+/// * `method` - The HTTP method.
+/// * `path` - The path below `/auth/v1`, including any query string.
+/// * `token` - The bearer token, the API key for anonymous and admin calls.
+/// * `body` - An optional JSON body.
+/// * `operation` - The operation name, used to label metrics.
+///
+/// # Returns
+/// The successful response, or the error matching the status and body, see
+/// [`ErrorTypes::from_auth_response`].
+pub(crate) async fn send(
+    client: &SupabaseClient,
+    method: Method,
+    path: &str,
+    token: &str,
+    body: Option<Value>,
+    operation: &str,
+) -> Result<Response> {
+    let endpoint: String = format!("{}/auth/v1/{}", client.url, path);
+    // Auth requests pick their bearer themselves, e.g. the service role key of admin calls
+    let mut client: SupabaseClient = client.clone();
+    client.session_store = None;
+
+    let mut request = client.client.request(method, &endpoint);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    let headers: Headers = Headers::with_defaults(&client.api_key, token);
+    for (key, value) in headers.get_headers() {
+        request = request.header(key, value);
+    }
+
+    let response: Response = client.dispatch("auth", operation, request).await?;
+
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    Err(ErrorTypes::from_auth_response(response).await)
+}
+
+/// Deserializes a JSON body of the Auth API.
+pub(crate) fn parse<T: serde::de::DeserializeOwned>(body: Value) -> Result<T> {
+    serde_json::from_value(body).map_err(|error| ErrorTypes::RequestFailed(error.to_string()))
+}
+
+/// Adds the captcha token to a request body, which Auth reads from `gotrue_meta_security`.
+pub(crate) fn with_captcha(mut body: Value, captcha_token: Option<&str>) -> Value {
+    if let Some(captcha_token) = captcha_token {
+        body["gotrue_meta_security"] = serde_json::json!({ "captcha_token": captcha_token });
+    }
+    body
+}
+
+/// Adds the `redirect_to` query parameter of the emailed links to a path, percent-encoded.
+pub(crate) fn with_redirect(path: &str, redirect_to: Option<&str>) -> String {
+    let Some(redirect_to) = redirect_to else {
+        return path.to_string();
+    };
+    let mut encoded: String = String::with_capacity(redirect_to.len());
+    for byte in redirect_to.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    let separator: char = if path.contains('?') { '&' } else { '?' };
+    format!("{}{}redirect_to={}", path, separator, encoded)
+}
@@ -0,0 +1,165 @@
+//! ## Phone auth
+//!
+//! Sign ups and sign ins with a phone number, for the user of an [`AuthClient`]. The codes are sent
+//! by the SMS provider configured for the project, as a text message or over WhatsApp.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::auth::phone::{SignUp, SmsChannel};
+//!
+//! // with a password, confirming the number once
+//! match auth.sign_up_with_phone("+31612345678", "hunter22", SmsChannel::Sms).await? {
+//!     SignUp::Session(session) => println!("signed in as {}", session.user.id),
+//!     SignUp::Unconfirmed(_) => {
+//!         // ask for the code sent to the user
+//!         auth.verify_sms_otp("+31612345678", &code).await?;
+//!     }
+//! }
+//! let session = auth.sign_in_with_phone_password("+31612345678", "hunter22").await?;
+//!
+//! // or without a password, with a new code for every sign in
+//! auth.send_sms_otp("+31612345678", SmsChannel::Whatsapp, true).await?;
+//! let session = auth.verify_sms_otp("+31612345678", &code).await?;
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::events::AuthEvent;
+use crate::auth::types::{Session, User};
+use crate::auth::{parse, send};
+use crate::errors::Result;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// How a one-time code is sent to a phone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmsChannel {
+    /// A text message, supported by every SMS provider.
+    #[default]
+    Sms,
+    /// A WhatsApp message, only supported by Twilio and Twilio Verify.
+    Whatsapp,
+}
+
+impl SmsChannel {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SmsChannel::Sms => "sms",
+            SmsChannel::Whatsapp => "whatsapp",
+        }
+    }
+}
+
+/// The outcome of a sign up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignUp {
+    /// Signed in right away, the project doesn't ask to confirm new users.
+    Session(Session),
+    /// Waiting for the user to confirm with the code or link sent to them.
+    Unconfirmed(User),
+}
+
+impl AuthClient {
+    /// Signs a user up with their phone number and a password.
+    ///
+    /// # Arguments
+    /// * `phone` - The phone number in E.164 format, e.g. `+31612345678`.
+    /// * `password` - The password of the new user.
+    /// * `channel` - How the confirmation code is sent.
+    ///
+    /// # Returns
+    /// The session when the project doesn't ask to confirm new users, which is kept, or the
+    /// unconfirmed user otherwise, confirmed with [`verify_sms_otp`](Self::verify_sms_otp).
+    pub async fn sign_up_with_phone(
+        &self,
+        phone: &str,
+        password: &str,
+        channel: SmsChannel,
+    ) -> Result<SignUp> {
+        let body: Value =
+            json!({ "phone": phone, "password": password, "channel": channel.as_str() });
+        let key: &str = &self.client.api_key;
+        let response = send(
+            &self.client,
+            Method::POST,
+            "signup",
+            key,
+            Some(body),
+            "sign_up",
+        )
+        .await?;
+        self.sign_up_response(response.json().await?)
+    }
+
+    /// Signs a user in with their phone number and password, and keeps the session.
+    pub async fn sign_in_with_phone_password(
+        &self,
+        phone: &str,
+        password: &str,
+    ) -> Result<Session> {
+        let body: Value = json!({ "phone": phone, "password": password });
+        self.grant("password", body, "sign_in").await
+    }
+
+    /// Sends a one-time code to a phone, to sign in with [`verify_sms_otp`](Self::verify_sms_otp).
+    ///
+    /// # Arguments
+    /// * `phone` - The phone number in E.164 format, e.g. `+31612345678`.
+    /// * `channel` - How the code is sent.
+    /// * `create_user` - Whether a user is signed up when no user has the number yet.
+    pub async fn send_sms_otp(
+        &self,
+        phone: &str,
+        channel: SmsChannel,
+        create_user: bool,
+    ) -> Result<()> {
+        let body: Value = json!({
+            "phone": phone,
+            "channel": channel.as_str(),
+            "create_user": create_user,
+        });
+        let key: &str = &self.client.api_key;
+        send(
+            &self.client,
+            Method::POST,
+            "otp",
+            key,
+            Some(body),
+            "send_otp",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Verifies a code sent to a phone, by a sign up or [`send_sms_otp`](Self::send_sms_otp), and
+    /// keeps the session.
+    pub async fn verify_sms_otp(&self, phone: &str, code: &str) -> Result<Session> {
+        let body: Value = json!({ "type": "sms", "phone": phone, "token": code });
+        let key: &str = &self.client.api_key;
+        let response = send(
+            &self.client,
+            Method::POST,
+            "verify",
+            key,
+            Some(body),
+            "verify_otp",
+        )
+        .await?;
+        self.store_session(parse(response.json().await?)?, AuthEvent::SignedIn)
+    }
+
+    /// Keeps the session of a sign up, or returns the user waiting for confirmation.
+    pub(crate) fn sign_up_response(&self, body: Value) -> Result<SignUp> {
+        if body.get("access_token").is_some() {
+            return self
+                .store_session(parse(body)?, AuthEvent::SignedIn)
+                .map(SignUp::Session);
+        }
+        // the user is returned on its own, some versions of Auth nest it under `user`
+        let user: Value = match body.get("user") {
+            Some(user) if user.is_object() => user.clone(),
+            _ => body,
+        };
+        parse(user).map(SignUp::Unconfirmed)
+    }
+}
@@ -0,0 +1,144 @@
+//! ## PKCE
+//!
+//! Proof Key for Code Exchange (RFC 7636), which OAuth and magic link sign ins use to prove that the
+//! client exchanging an auth code is the one that started the sign in. [`AuthClient::authorize`]
+//! keeps the verifier of the sign in it starts, and
+//! [`exchange_code_for_session`](AuthClient::exchange_code_for_session) sends it along with the code
+//! the provider redirects back with.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! // send the user to the provider, who redirects them back with `?code=...`
+//! let url: String = auth.authorize("github", Some("https://example.com/callback"))?;
+//!
+//! // in the callback
+//! let session = auth.exchange_code_for_session(&code).await?;
+//! ```
+//!
+//! A server handling the callback in another process keeps the verifier itself, e.g. in a cookie:
+//! ```rust,ignore
+//! use supabase_rs::auth::pkce::Pkce;
+//!
+//! let pkce = Pkce::new();
+//! // pass `pkce.challenge` as the `code_challenge`, with `code_challenge_method=s256`
+//! let session = auth.exchange_code_with_verifier(&code, &pkce.verifier).await?;
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::types::Session;
+use crate::errors::{ErrorTypes, Result};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use reqwest::Url;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, MutexGuard};
+
+/// A code verifier and its S256 challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pkce {
+    /// The secret sent when exchanging the code, 86 characters.
+    pub verifier: String,
+    /// The SHA-256 hash of the verifier, sent when starting the sign in.
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// Generates a new verifier and its challenge.
+    pub fn new() -> Self {
+        let verifier: String = generate_code_verifier();
+        let challenge: String = code_challenge(&verifier);
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fills `length` bytes from the random number generator of the operating system.
+pub fn secure_random(length: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0; length];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Generates a code verifier, 64 random bytes encoded as unpadded base64url.
+pub fn generate_code_verifier() -> String {
+    URL_SAFE_NO_PAD.encode(secure_random(64))
+}
+
+/// The S256 challenge of a verifier, its SHA-256 hash encoded as unpadded base64url.
+pub fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+impl AuthClient {
+    /// Starts an OAuth sign in with PKCE, keeping the verifier for
+    /// [`exchange_code_for_session`](Self::exchange_code_for_session).
+    ///
+    /// Starting another sign in replaces the verifier, only the latest sign in can be completed.
+    ///
+    /// # Arguments
+    /// * `provider` - The provider to sign in with, e.g. `github` or `google`.
+    /// * `redirect_to` - Where the provider sends the user back to, the site URL of the project when
+    ///   `None`. It has to be one of the redirect URLs of the project.
+    ///
+    /// # Returns
+    /// The URL of the provider to send the user to.
+    pub fn authorize(&self, provider: &str, redirect_to: Option<&str>) -> Result<String> {
+        let endpoint: String = format!("{}/auth/v1/authorize", self.client.url);
+        let mut url: Url = Url::parse(&endpoint)
+            .map_err(|error| ErrorTypes::InvalidParameters(error.to_string()))?;
+        let pkce: Pkce = self.start_pkce();
+
+        url.query_pairs_mut()
+            .append_pair("provider", provider)
+            .append_pair("code_challenge", &pkce.challenge)
+            .append_pair("code_challenge_method", "s256");
+        if let Some(redirect_to) = redirect_to {
+            url.query_pairs_mut()
+                .append_pair("redirect_to", redirect_to);
+        }
+        Ok(url.to_string())
+    }
+
+    /// Generates the PKCE pair of a new sign in, keeping its verifier in place of the one of an
+    /// earlier sign in.
+    pub(crate) fn start_pkce(&self) -> Pkce {
+        let pkce: Pkce = Pkce::new();
+        *lock(&self.code_verifier) = Some(pkce.verifier.clone());
+        pkce
+    }
+
+    /// Completes the sign in started with [`authorize`](Self::authorize), exchanging the `code` the
+    /// provider redirected back with for a session, which is kept.
+    ///
+    /// The verifier is used once, exchanging a code again needs a new sign in.
+    pub async fn exchange_code_for_session(&self, code: &str) -> Result<Session> {
+        let verifier: String = lock(&self.code_verifier).take().ok_or_else(|| {
+            ErrorTypes::InvalidParameters("no code verifier, start with authorize".to_string())
+        })?;
+        self.exchange_code_with_verifier(code, &verifier).await
+    }
+
+    /// Exchanges an auth code for a session with a verifier kept by the caller, and keeps the session.
+    pub async fn exchange_code_with_verifier(&self, code: &str, verifier: &str) -> Result<Session> {
+        let body: Value = json!({ "auth_code": code, "code_verifier": verifier });
+        self.grant("pkce", body, "exchange_code_for_session").await
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
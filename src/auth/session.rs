@@ -0,0 +1,122 @@
+//! ## Session persistence
+//!
+//! An [`AuthClient`](crate::auth::AuthClient) keeps the session of the signed in user in a
+//! [`SessionStore`]. The default [`MemorySessionStore`] forgets it when the process exits, CLI tools and
+//! desktop apps can use [`FileSessionStore`] or their own store (e.g. the OS keychain) to stay signed in
+//! across restarts.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::auth::session::FileSessionStore;
+//! use supabase_rs::auth::AuthClient;
+//!
+//! let auth = AuthClient::new(supabase_url, anon_key)?
+//!     .with_session_store(FileSessionStore::new(config_dir.join("session.json")));
+//!
+//! if auth.session().is_none() {
+//!     auth.sign_in_with_password(&email, &password).await?;
+//! }
+//! ```
+
+use crate::auth::types::Session;
+
+use std::fmt::Debug;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Where an `AuthClient` keeps its session.
+pub trait SessionStore: Debug + Send + Sync {
+    /// Returns the stored session, `None` when there is none or it can't be read.
+    fn get(&self) -> Option<Session>;
+
+    /// Stores a session, replacing the previous one.
+    fn set(&self, session: &Session) -> std::io::Result<()>;
+
+    /// Removes the stored session.
+    fn clear(&self) -> std::io::Result<()>;
+}
+
+/// Keeps the session in memory, the default store.
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    session: RwLock<Option<Session>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn get(&self) -> Option<Session> {
+        self.session
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    fn set(&self, session: &Session) -> std::io::Result<()> {
+        *self
+            .session
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(session.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> std::io::Result<()> {
+        *self
+            .session
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        Ok(())
+    }
+}
+
+/// Keeps the session in a JSON file.
+///
+/// The file holds the refresh token, so on Unix it is only readable by the current user.
+#[derive(Debug, Clone)]
+pub struct FileSessionStore {
+    path: PathBuf,
+}
+
+impl FileSessionStore {
+    /// Creates a store keeping the session in `path`, the file is created on the first sign in.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileSessionStore { path: path.into() }
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn get(&self) -> Option<Session> {
+        let contents: String = std::fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn set(&self, session: &Session) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // write next to the file and rename, so a crash never leaves half a session behind
+        let temporary: PathBuf = self.path.with_extension("tmp");
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file: std::fs::File = options.open(&temporary)?;
+        file.write_all(&serde_json::to_vec(session)?)?;
+        file.sync_all()?;
+        std::fs::rename(&temporary, &self.path)
+    }
+
+    fn clear(&self) -> std::io::Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
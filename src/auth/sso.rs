@@ -0,0 +1,82 @@
+//! ## SSO
+//!
+//! Enterprise single sign-on with SAML 2.0 identity providers registered for the project, found by
+//! the email domain of the user or by the id of the provider. The sign in uses PKCE like
+//! [`authorize`](AuthClient::authorize), and completes with
+//! [`exchange_code_for_session`](AuthClient::exchange_code_for_session).
+//!
+//! ### Usage
+//! ```rust,ignore
+//! // send the user to their identity provider, who returns them with `?code=...`
+//! let url: String = auth.sign_in_with_sso("acme.com", Some("https://example.com/callback")).await?;
+//!
+//! // in the callback
+//! let session = auth.exchange_code_for_session(&code).await?;
+//! ```
+
+use crate::auth::client::AuthClient;
+use crate::auth::pkce::Pkce;
+use crate::auth::send;
+use crate::errors::{ErrorTypes, Result};
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+impl AuthClient {
+    /// Starts a SAML sign in with the identity provider of a domain, or with a provider by its id.
+    ///
+    /// # Arguments
+    /// * `domain_or_provider_id` - The email domain registered for the provider, e.g. `acme.com`,
+    ///   or the UUID of the provider.
+    /// * `redirect_to` - Where the user is sent after signing in, the site URL of the project when
+    ///   `None`.
+    ///
+    /// # Returns
+    /// The URL of the identity provider to send the user to.
+    pub async fn sign_in_with_sso(
+        &self,
+        domain_or_provider_id: &str,
+        redirect_to: Option<&str>,
+    ) -> Result<String> {
+        let pkce: Pkce = self.start_pkce();
+        let mut body: Value = json!({
+            "skip_http_redirect": true,
+            "code_challenge": pkce.challenge,
+            "code_challenge_method": "s256",
+        });
+        let target: &str = if is_uuid(domain_or_provider_id) {
+            "provider_id"
+        } else {
+            "domain"
+        };
+        body[target] = json!(domain_or_provider_id);
+        if let Some(redirect_to) = redirect_to {
+            body["redirect_to"] = json!(redirect_to);
+        }
+
+        let key: &str = &self.client.api_key;
+        let response = send(
+            &self.client,
+            Method::POST,
+            "sso",
+            key,
+            Some(body),
+            "sign_in_with_sso",
+        )
+        .await?;
+        let body: Value = response.json().await?;
+        body["url"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ErrorTypes::UnexpectedResponse("no url to sign in with".to_string()))
+    }
+}
+
+/// Whether `value` is a hyphenated UUID, the format of SSO provider ids.
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(index, character)| match index {
+            8 | 13 | 18 | 23 => character == '-',
+            _ => character.is_ascii_hexdigit(),
+        })
+}
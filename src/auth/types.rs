@@ -0,0 +1,93 @@
+//! ## Auth types
+//!
+//! The models returned by the Auth API. Timestamps are kept as the RFC 3339 strings GoTrue sends,
+//! except for session and challenge expiries which are Unix timestamps.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A user of the project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct User {
+    pub id: String,
+    pub aud: String,
+    pub role: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub email_confirmed_at: Option<String>,
+    pub phone_confirmed_at: Option<String>,
+    pub confirmed_at: Option<String>,
+    pub invited_at: Option<String>,
+    pub last_sign_in_at: Option<String>,
+    pub banned_until: Option<String>,
+    pub app_metadata: Value,
+    pub user_metadata: Value,
+    pub identities: Vec<Identity>,
+    /// The MFA factors of the user, verified or not.
+    pub factors: Vec<Factor>,
+    pub is_anonymous: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// A login method of a user, e.g. their email or a linked OAuth provider.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Identity {
+    pub identity_id: String,
+    pub id: String,
+    pub user_id: String,
+    pub provider: String,
+    pub email: Option<String>,
+    pub identity_data: Value,
+    pub last_sign_in_at: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// The session of a signed in user.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub access_token: String,
+    pub token_type: String,
+    /// The lifetime of the access token in seconds.
+    pub expires_in: u64,
+    /// When the access token expires, as a Unix timestamp.
+    pub expires_at: Option<u64>,
+    pub refresh_token: String,
+    pub user: User,
+}
+
+/// The verification status of an MFA factor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FactorStatus {
+    /// Enrolled, but no challenge was verified yet, so it isn't used for sign in.
+    #[default]
+    Unverified,
+    Verified,
+}
+
+/// An MFA factor of a user.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Factor {
+    pub id: String,
+    pub friendly_name: Option<String>,
+    /// The kind of factor, e.g. `totp`.
+    pub factor_type: String,
+    pub status: FactorStatus,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// A challenge of an MFA factor, answered with a code through `mfa_verify`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Challenge {
+    pub id: String,
+    /// When the challenge expires, as a Unix timestamp.
+    pub expires_at: u64,
+}
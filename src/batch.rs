@@ -0,0 +1,269 @@
+//! ## Batches
+//!
+//! PostgREST runs every request in a transaction of its own, so separate writes can't be committed
+//! together. A [`Batch`] collects inserts, upserts, updates and deletes and sends them in a single
+//! call to the `supabase_rs_batch` function, which applies them in one transaction: either every
+//! operation is applied, or none is. The function has to be created once, see
+//! [`BATCH_MIGRATION_SQL`]. It runs with the privileges of the caller, so row level security still
+//! applies.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde_json::json;
+//!
+//! let affected: Vec<u64> = client
+//!     .batch()
+//!     .insert("orders", json!({"id": 1, "user_id": 7, "total": 25}))
+//!     .update("users", &[("id", "7")], json!({"last_order_id": 1}))
+//!     .delete("carts", &[("user_id", "7")])
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! Updates and deletes change the rows whose columns equal every filter, compared as text. The
+//! tables are those of the schema of the client, `public` by default.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Creates the function [`Batch::execute`] calls, run it once as a migration.
+pub const BATCH_MIGRATION_SQL: &str = r#"
+create or replace function public.supabase_rs_batch(operations jsonb)
+returns jsonb
+language plpgsql
+security invoker
+set search_path = ''
+as $$
+declare
+  operation jsonb;
+  target regclass;
+  columns text;
+  conflict text;
+  assignments text;
+  conditions text;
+  affected bigint;
+  results jsonb := '[]'::jsonb;
+begin
+  for operation in select value from jsonb_array_elements(operations) loop
+    target := format('%I.%I', coalesce(operation->>'schema', 'public'), operation->>'table')::regclass;
+
+    select coalesce(string_agg(format('%I::text = %L', key, value), ' and '), 'false')
+    into conditions
+    from jsonb_each_text(coalesce(operation->'filters', '{}'::jsonb));
+
+    if operation->>'op' in ('insert', 'upsert') then
+      select string_agg(format('%I', key), ', '),
+             string_agg(format('%I = excluded.%I', key, key), ', ')
+      into columns, assignments
+      from (select distinct jsonb_object_keys(value) as key from jsonb_array_elements(operation->'rows')) as keys;
+
+      conflict := '';
+      if operation->>'op' = 'upsert' then
+        select format(' on conflict (%s) do update set %s', string_agg(format('%I', value), ', '), assignments)
+        into conflict
+        from jsonb_array_elements_text(operation->'on_conflict');
+      end if;
+
+      execute format(
+        'insert into %s (%s) select %s from jsonb_populate_recordset(null::%s, $1)%s',
+        target, columns, columns, target, conflict
+      ) using operation->'rows';
+    elsif operation->>'op' = 'update' then
+      select string_agg(format('%I = (jsonb_populate_record(null::%s, $1)).%I', key, target, key), ', ')
+      into assignments
+      from jsonb_object_keys(operation->'values') as key;
+
+      execute format('update %s set %s where %s', target, assignments, conditions)
+      using operation->'values';
+    elsif operation->>'op' = 'delete' then
+      execute format('delete from %s where %s', target, conditions);
+    else
+      raise exception 'unknown batch operation: %', operation->>'op';
+    end if;
+
+    get diagnostics affected = row_count;
+    results := results || to_jsonb(affected);
+  end loop;
+
+  return results;
+end;
+$$;
+
+revoke execute on function public.supabase_rs_batch(jsonb) from public;
+grant execute on function public.supabase_rs_batch(jsonb) to anon, authenticated, service_role;
+"#;
+
+/// Writes applied together in one transaction, created by [`SupabaseClient::batch`].
+#[derive(Debug, Clone)]
+pub struct Batch {
+    client: SupabaseClient,
+    operations: Vec<std::result::Result<Value, String>>,
+}
+
+impl SupabaseClient {
+    /// Starts a batch of writes which are applied all together, or not at all.
+    ///
+    /// Requires the function of [`BATCH_MIGRATION_SQL`].
+    pub fn batch(&self) -> Batch {
+        Batch {
+            client: self.clone(),
+            operations: Vec::new(),
+        }
+    }
+}
+
+impl Batch {
+    /// Inserts a row or a list of rows into `table_name`.
+    pub fn insert<T: Serialize>(mut self, table_name: &str, rows: T) -> Self {
+        let operation = batch_rows(table_name, rows)
+            .map(|rows| json!({"op": "insert", "table": table_name, "rows": rows}));
+        self.operations.push(operation);
+        self
+    }
+
+    /// Inserts a row or a list of rows into `table_name`, updating the rows that conflict on the
+    /// `on_conflict` columns.
+    pub fn upsert<T: Serialize>(mut self, table_name: &str, rows: T, on_conflict: &[&str]) -> Self {
+        let operation = match on_conflict.is_empty() {
+            true => Err(format!(
+                "Upsert into `{}` has no conflict columns",
+                table_name
+            )),
+            false => batch_rows(table_name, rows).map(|rows| {
+                json!({
+                    "op": "upsert",
+                    "table": table_name,
+                    "rows": rows,
+                    "on_conflict": on_conflict,
+                })
+            }),
+        };
+        self.operations.push(operation);
+        self
+    }
+
+    /// Sets `values`, a JSON object of columns, on the rows of `table_name` matching every filter.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `filters` - Column and value pairs the rows have to equal, at least one.
+    /// * `values` - The columns to change and their new values.
+    pub fn update<T: Serialize>(
+        mut self,
+        table_name: &str,
+        filters: &[(&str, &str)],
+        values: T,
+    ) -> Self {
+        let operation = batch_filters("update", table_name, filters).and_then(|filters| {
+            let values: Value = serde_json::to_value(values).map_err(|error| error.to_string())?;
+            if !values.is_object() {
+                return Err(format!("Update of `{}` is not a JSON object", table_name));
+            }
+            Ok(json!({
+                "op": "update",
+                "table": table_name,
+                "filters": filters,
+                "values": values,
+            }))
+        });
+        self.operations.push(operation);
+        self
+    }
+
+    /// Deletes the rows of `table_name` matching every filter.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `filters` - Column and value pairs the rows have to equal, at least one.
+    pub fn delete(mut self, table_name: &str, filters: &[(&str, &str)]) -> Self {
+        let operation = batch_filters("delete", table_name, filters)
+            .map(|filters| json!({"op": "delete", "table": table_name, "filters": filters}));
+        self.operations.push(operation);
+        self
+    }
+
+    /// The number of operations in the batch.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Whether the batch has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies every operation in one transaction.
+    ///
+    /// # Returns
+    /// The number of rows each operation affected, in the order they were added. When any operation
+    /// fails nothing is applied, and the error of the failed operation is returned.
+    pub async fn execute(self) -> Result<Vec<u64>> {
+        if self.operations.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut operations: Vec<Value> = self
+            .operations
+            .into_iter()
+            .collect::<std::result::Result<_, _>>()
+            .map_err(ErrorTypes::InvalidParameters)?;
+        if let Some(schema) = self.client.schema() {
+            for operation in &mut operations {
+                operation["schema"] = json!(schema);
+            }
+        }
+
+        // the function lives in `public`, the tables in the schema of the client
+        let result: Value = self
+            .client
+            .rpc("supabase_rs_batch", json!({ "operations": operations }))
+            .schema("public")
+            .execute()
+            .await?;
+
+        // the writes went through a function, so the cached selects of their tables are stale
+        #[cfg(feature = "cache")]
+        for operation in &operations {
+            if let Some(table) = operation["table"].as_str() {
+                self.client.invalidate_cache(table);
+            }
+        }
+
+        serde_json::from_value(result)
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string()))
+    }
+}
+
+/// Serializes a row or a list of rows into a list of JSON objects.
+fn batch_rows<T: Serialize>(table_name: &str, rows: T) -> std::result::Result<Value, String> {
+    let rows: Vec<Value> = match serde_json::to_value(rows).map_err(|error| error.to_string())? {
+        Value::Array(rows) => rows,
+        row => vec![row],
+    };
+    if rows.is_empty() || !rows.iter().all(Value::is_object) {
+        return Err(format!("Rows of `{}` have to be JSON objects", table_name));
+    }
+    Ok(Value::Array(rows))
+}
+
+/// Turns the filters of an update or delete into a JSON object, refusing to change a whole table.
+fn batch_filters(
+    operation: &str,
+    table_name: &str,
+    filters: &[(&str, &str)],
+) -> std::result::Result<Value, String> {
+    if filters.is_empty() {
+        return Err(format!(
+            "Refusing to {} every row of `{}` in a batch, add a filter",
+            operation, table_name
+        ));
+    }
+    Ok(Value::Object(
+        filters
+            .iter()
+            .map(|(column, value)| (column.to_string(), Value::from(*value)))
+            .collect(),
+    ))
+}
@@ -0,0 +1,107 @@
+//! `cargo supabase-types`, generates Rust types from the schema of a Supabase project.
+//!
+//! ```text
+//! cargo install supabase_rs --features type_gen
+//! cargo supabase-types --output src/supabase_types.rs --schema public
+//! ```
+//!
+//! The URL and key default to the `SUPABASE_URL` and `SUPABASE_KEY` environment variables, which
+//! are also read from a `.env` file. Without `--output` the source is printed to stdout.
+
+use supabase_rs::type_gen::{generate_supabase_types_source, TypeGenOptions};
+use supabase_rs::SupabaseClient;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Generates Rust types from the schema of a Supabase project.
+
+Usage: cargo supabase-types [OPTIONS]
+
+Options:
+      --url <URL>          The project URL [default: $SUPABASE_URL]
+      --key <KEY>          The API key [default: $SUPABASE_KEY]
+      --schema <SCHEMA>    The schema to generate, the default exposed schema when left out
+  -o, --output <FILE>      The file to write to, stdout when left out
+      --derive-to-schema   Derives `utoipa::ToSchema` on the generated structs
+  -h, --help               Prints this help";
+
+/// The parsed command line.
+struct Args {
+    url: Option<String>,
+    key: Option<String>,
+    output: Option<String>,
+    options: TypeGenOptions,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed: Args = Args {
+        url: None,
+        key: None,
+        output: None,
+        options: TypeGenOptions::new(),
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or(format!("{} needs a value", flag));
+        match arg.as_str() {
+            "--url" => parsed.url = Some(value(&arg)?),
+            "--key" => parsed.key = Some(value(&arg)?),
+            "--schema" => parsed.options = parsed.options.schema(&value(&arg)?),
+            "-o" | "--output" => parsed.output = Some(value(&arg)?),
+            "--derive-to-schema" => parsed.options = parsed.options.derive_to_schema(true),
+            "-h" | "--help" => return Ok(None),
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    Ok(Some(parsed))
+}
+
+fn run() -> Result<(), String> {
+    // cargo passes the name of the subcommand as the first argument
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("supabase-types") {
+        args.next();
+    }
+    let Some(args) = parse_args(args)? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    dotenv::dotenv().ok();
+    let url: String = match args.url {
+        Some(url) => url,
+        None => std::env::var("SUPABASE_URL").map_err(|_| "pass --url or set SUPABASE_URL")?,
+    };
+    let key: String = match args.key {
+        Some(key) => key,
+        None => std::env::var("SUPABASE_KEY").map_err(|_| "pass --key or set SUPABASE_KEY")?,
+    };
+    let client: SupabaseClient = SupabaseClient::new(url, key).map_err(|e| e.to_string())?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+    let source: String =
+        runtime.block_on(generate_supabase_types_source(&client, &args.options))?;
+
+    match args.output {
+        Some(output) => std::fs::write(output, source).map_err(|e| e.to_string()),
+        None => {
+            print!("{}", source);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
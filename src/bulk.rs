@@ -0,0 +1,278 @@
+//! ## Chunked bulk operations
+//!
+//! `bulk_insert` sends all rows in one request, which PostgREST rejects once the body outgrows its
+//! limit, and a single bad row fails every other row with it. [`SupabaseClient::bulk_insert_chunked`]
+//! splits the rows into chunks of [`BulkOptions::chunk_size`] rows instead, sends up to
+//! [`BulkOptions::parallelism`] of them at once, and returns a [`BulkReport`] telling which chunks
+//! made it in and why the others didn't.
+//!
+//! Chunks are separate requests, so a failed chunk doesn't roll back the ones that succeeded. By
+//! default no new chunk is sent after the first failure, [`BulkOptions::continue_on_error`] sends
+//! the remaining chunks anyway.
+//!
+//! A chunk the server never received, because the connection was refused, the
+//! [circuit](crate::circuit_breaker) was open or the request was rejected with `429` or `503`, is
+//! sent again up to [`BulkOptions::max_retries`] times. Timeouts and dropped connections aren't
+//! retried, the rows of the chunk may have been written.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::bulk::{BulkOptions, BulkReport};
+//!
+//! let report: BulkReport = client
+//!     .bulk_insert_chunked("events", events, BulkOptions::new().chunk_size(500).parallelism(4))
+//!     .await?;
+//!
+//! for failure in &report.failed {
+//!     eprintln!("rows {:?} failed: {}", failure.chunk.rows, failure.error);
+//! }
+//! ```
+
+use crate::errors::{ErrorTypes, RestError};
+use crate::query::Returning;
+use crate::SupabaseClient;
+
+use futures_util::{stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Options of the chunked bulk operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkOptions {
+    /// The number of rows per request, 1000 by default.
+    pub chunk_size: usize,
+    /// The number of chunks sent at once, 1 by default.
+    pub parallelism: usize,
+    /// Whether the remaining chunks are still sent after a chunk failed, `false` by default.
+    pub continue_on_error: bool,
+    /// How often a chunk the server never received is sent again, 0 by default.
+    pub max_retries: u32,
+    /// The wait before the first retry of a chunk, doubled for every further retry, 500ms by
+    /// default. A `Retry-After` sent by the server is waited for instead.
+    pub retry_backoff: Duration,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BulkOptions {
+    pub fn new() -> Self {
+        BulkOptions {
+            chunk_size: 1000,
+            parallelism: 1,
+            continue_on_error: false,
+            max_retries: 0,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+
+    /// Sets the number of rows per request, at least 1.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets the number of chunks sent at once, at least 1.
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Sends the remaining chunks after a chunk failed.
+    pub fn continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// Sets how often a chunk the server never received is sent again.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the wait before the first retry of a chunk.
+    pub fn retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// The wait before the retry after `retries` earlier ones, or the wait the server asked for.
+    fn retry_delay(&self, retries: u32, error: &RestError) -> Duration {
+        error
+            .as_error_types()
+            .and_then(ErrorTypes::retry_after)
+            .unwrap_or_else(|| {
+                self.retry_backoff
+                    .saturating_mul(2_u32.saturating_pow(retries))
+            })
+    }
+}
+
+/// A chunk of the rows of a bulk operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkChunk {
+    /// The position of the chunk, starting at 0.
+    pub index: usize,
+    /// The positions of its rows in the rows passed in.
+    pub rows: Range<usize>,
+}
+
+/// A chunk that failed, with the reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkFailure {
+    pub chunk: BulkChunk,
+    pub error: String,
+}
+
+/// What happened to the chunks of a bulk operation, each list ordered by chunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkReport {
+    /// The chunks whose rows were written.
+    pub succeeded: Vec<BulkChunk>,
+    /// The chunks that were rejected or couldn't be sent.
+    pub failed: Vec<BulkFailure>,
+    /// The chunks that weren't sent because an earlier chunk failed.
+    pub skipped: Vec<BulkChunk>,
+    /// How often chunks were sent again after the server didn't receive them.
+    pub retries: u32,
+}
+
+impl BulkReport {
+    /// Whether every chunk was written.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+
+    /// The number of rows written.
+    pub fn rows_written(&self) -> usize {
+        self.succeeded.iter().map(|chunk| chunk.rows.len()).sum()
+    }
+}
+
+impl SupabaseClient {
+    /// Inserts rows in chunks, see the [module docs](crate::bulk).
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `rows` - The rows to insert, each serializing to a JSON object.
+    /// * `options` - The chunk size, parallelism and whether to continue after a failure.
+    ///
+    /// # Returns
+    /// The report of every chunk, or a `RestError` when the rows can't be serialized, in which
+    /// case nothing was sent.
+    pub async fn bulk_insert_chunked<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: Vec<T>,
+        options: BulkOptions,
+    ) -> Result<BulkReport, RestError> {
+        let rows: Vec<Value> = rows
+            .into_iter()
+            .map(serde_json::to_value)
+            .collect::<Result<_, _>>()
+            .map_err(|error| error.to_string())?;
+
+        Ok(self
+            .run_chunks(&rows, options, |chunk: &[Value]| async move {
+                self.insert_with_returning(table_name, chunk, Returning::Minimal)
+                    .await
+                    .map(|_| ())
+            })
+            .await)
+    }
+
+    /// Sends the chunks of `rows` with `send`, keeping up to `parallelism` of them in flight and
+    /// sending the ones the server never received again.
+    pub(crate) async fn run_chunks<'a, F, Fut>(
+        &self,
+        rows: &'a [Value],
+        options: BulkOptions,
+        send: F,
+    ) -> BulkReport
+    where
+        F: Fn(&'a [Value]) -> Fut + Sync,
+        Fut: Future<Output = Result<(), RestError>> + Send + 'a,
+    {
+        let send: &F = &send;
+        let mut report: BulkReport = BulkReport::default();
+        let mut chunks = rows
+            .chunks(options.chunk_size)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let start: usize = index * options.chunk_size;
+                (
+                    BulkChunk {
+                        index,
+                        rows: start..start + chunk.len(),
+                    },
+                    chunk,
+                )
+            });
+
+        // no chunk is taken from `chunks` once stopped, the ones left are reported as skipped
+        let stopped: AtomicBool = AtomicBool::new(false);
+        let pending = std::iter::from_fn(|| {
+            if stopped.load(Ordering::SeqCst) {
+                return None;
+            }
+            chunks.next()
+        });
+        let mut outcomes = stream::iter(pending)
+            .map(|(chunk, rows)| async move {
+                let mut retries: u32 = 0;
+                loop {
+                    match send(rows).await {
+                        Err(error) if retries < options.max_retries && !was_received(&error) => {
+                            tokio::time::sleep(options.retry_delay(retries, &error)).await;
+                            retries += 1;
+                        }
+                        result => return (chunk, retries, result),
+                    }
+                }
+            })
+            .buffer_unordered(options.parallelism.max(1));
+
+        while let Some((chunk, retries, result)) = outcomes.next().await {
+            report.retries += retries;
+            match result {
+                Ok(()) => report.succeeded.push(chunk),
+                Err(error) => {
+                    if !options.continue_on_error {
+                        stopped.store(true, Ordering::SeqCst);
+                    }
+                    report.failed.push(BulkFailure {
+                        chunk,
+                        error: error.to_string(),
+                    });
+                }
+            }
+        }
+        drop(outcomes);
+
+        report.skipped = chunks.map(|(chunk, _)| chunk).collect();
+        report.succeeded.sort_by_key(|chunk| chunk.index);
+        report.failed.sort_by_key(|failure| failure.chunk.index);
+        report
+    }
+}
+
+/// Whether the server may have received the request that failed with `error`, so sending it again
+/// could write its rows twice.
+fn was_received(error: &RestError) -> bool {
+    match error.as_error_types() {
+        Some(ErrorTypes::ReqwestError(error)) => !error.is_connect(),
+        Some(
+            ErrorTypes::CircuitOpen { .. }
+            | ErrorTypes::RateLimited { .. }
+            | ErrorTypes::ServiceUnavailable { .. },
+        ) => false,
+        _ => true,
+    }
+}
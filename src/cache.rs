@@ -0,0 +1,238 @@
+//! ## Response cache
+//!
+//! An opt-in cache for the rows of selects, for reference data that is read far more often than it
+//! changes. Selects are cached by table, query string, preferences and the bearer they are sent with,
+//! for the TTL of the cache or of the query, so clones acting as another user or role never share
+//! rows. Any write through the same client (or its clones) to a table drops the cached selects
+//! of that table, when it is sent and again once it is answered, so a select answered while the
+//! write was in flight isn't served after it.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use supabase_rs::cache::CacheConfig;
+//!
+//! let client = SupabaseClient::new(url, key)?.with_cache(CacheConfig {
+//!     ttl: Duration::from_secs(30),
+//!     max_entries: 1_000,
+//! });
+//!
+//! // served from the cache for 30 seconds
+//! let countries = client.select("countries").execute().await?;
+//! // cached longer, or not at all
+//! let currencies = client.select("currencies").cache_ttl(Duration::from_secs(600)).execute().await?;
+//! let balances = client.select("balances").no_cache().execute().await?;
+//! ```
+//!
+//! Only `execute` is cached, counts and CSV exports always reach the database.
+
+#![cfg(feature = "cache")]
+
+use crate::query::QueryBuilder;
+use crate::SupabaseClient;
+
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// The configuration of the response cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// How long the rows of a select are served from the cache, unless the query sets its own TTL.
+    pub ttl: Duration,
+    /// The maximum number of cached selects, the ones expiring first are dropped beyond it.
+    pub max_entries: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            ttl: Duration::from_secs(60),
+            max_entries: 1_000,
+        }
+    }
+}
+
+/// How a query uses the response cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Cached for the TTL of the cache.
+    #[default]
+    Default,
+    /// Cached for the given TTL.
+    Ttl(Duration),
+    /// Always sent to the database, and not cached.
+    Bypass,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    table: String,
+    rows: Vec<Value>,
+    expires_at: Instant,
+}
+
+/// The cached selects, shared between clones of a `SupabaseClient`.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    config: CacheConfig,
+    entries: Arc<Mutex<HashMap<String, CacheEntry>>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        ResponseCache {
+            config,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the rows cached under `key`, unless they expired.
+    pub(crate) fn get(&self, key: &str) -> Option<Vec<Value>> {
+        let mut entries = self.lock();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.rows.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches the rows of a select on `table`, for `ttl` or the TTL of the cache.
+    pub(crate) fn insert(&self, key: String, table: &str, rows: Vec<Value>, ttl: Option<Duration>) {
+        if self.config.max_entries == 0 {
+            return;
+        }
+
+        let now: Instant = Instant::now();
+        let mut entries = self.lock();
+        entries.retain(|_, entry| entry.expires_at > now);
+        while entries.len() >= self.config.max_entries && !entries.contains_key(&key) {
+            let first_expiring: Option<String> = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone());
+            match first_expiring {
+                Some(first_expiring) => entries.remove(&first_expiring),
+                None => break,
+            };
+        }
+
+        entries.insert(
+            key,
+            CacheEntry {
+                table: table.to_string(),
+                rows,
+                expires_at: now + ttl.unwrap_or(self.config.ttl),
+            },
+        );
+    }
+
+    /// Drops the cached selects of `table`.
+    pub(crate) fn invalidate(&self, table: &str) {
+        self.lock().retain(|_, entry| entry.table != table);
+    }
+
+    /// Drops every cached select.
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// The number of cached selects, including expired ones not dropped yet.
+    pub(crate) fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CacheEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl SupabaseClient {
+    /// Caches the rows of selects, see [`cache`](crate::cache).
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the cache, shared with its clones.
+    pub fn with_cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(ResponseCache::new(config));
+        self
+    }
+
+    /// Drops the cached selects of `table`, e.g. after it was changed by another service.
+    pub fn invalidate_cache(&self, table: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(table);
+        }
+    }
+
+    /// Drops every cached select.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+}
+
+impl QueryBuilder {
+    /// Caches the rows of this select for `ttl` instead of the TTL of the cache.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_policy = CachePolicy::Ttl(ttl);
+        self
+    }
+
+    /// Sends this select to the database even when it is cached, and doesn't cache its rows.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn no_cache(mut self) -> Self {
+        self.cache_policy = CachePolicy::Bypass;
+        self
+    }
+
+    /// The cache and the key of this select, when it uses the cache.
+    pub(crate) fn cache_key(&self, query_string: &str) -> Option<(&ResponseCache, String)> {
+        let cache: &ResponseCache = self.client.cache.as_ref()?;
+        if self.cache_policy == CachePolicy::Bypass {
+            return None;
+        }
+
+        let mut preferences: Vec<String> = self
+            .preferences(None)
+            .get_headers()
+            .into_iter()
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .collect();
+        preferences.sort();
+
+        // the rows depend on who asks, the bearer itself isn't kept in memory any longer
+        let mut bearer = DefaultHasher::new();
+        self.client.bearer().hash(&mut bearer);
+        Some((
+            cache,
+            format!(
+                "{}?{}|{}|{:016x}",
+                self.table_name,
+                query_string,
+                preferences.join(","),
+                bearer.finish()
+            ),
+        ))
+    }
+
+    /// The TTL of this select, `None` for the TTL of the cache.
+    pub(crate) fn cache_ttl_override(&self) -> Option<Duration> {
+        match self.cache_policy {
+            CachePolicy::Ttl(ttl) => Some(ttl),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! ## Circuit breaker
+//!
+//! An optional circuit breaker that makes dependent services fail fast while Supabase is having
+//! an incident, instead of piling up requests that are going to time out anyway.
+//!
+//! The breaker keeps a separate state per host:
+//! - **Closed**: requests flow normally. Consecutive `5xx` responses and transport errors
+//!   (timeouts, refused connections) are counted.
+//! - **Open**: after `failure_threshold` consecutive failures every request to that host is
+//!   rejected immediately with [`ErrorTypes::CircuitOpen`] for `open_duration`.
+//! - **Half-open**: once `open_duration` has passed, up to `half_open_max_probes` requests are let
+//!   through. A successful probe closes the circuit, a failed one opens it again.
+//!
+//! A request holds a [`CircuitPermit`] while it is in flight. A probe that is dropped before its
+//! response arrived, e.g. on a timeout or cancellation, frees its slot for the next probe.
+//!
+//! ### Usage
+//! ```
+//! # use supabase_rs::SupabaseClient;
+//! use supabase_rs::circuit_breaker::CircuitBreakerConfig;
+//! use std::time::Duration;
+//!
+//! let client = SupabaseClient::new(
+//!     "https://your-project.supabase.co".to_string(),
+//!     "your-secret-key".to_string(),
+//! )
+//! .unwrap()
+//! .with_circuit_breaker(CircuitBreakerConfig {
+//!     failure_threshold: 5,
+//!     open_duration: Duration::from_secs(30),
+//!     half_open_max_probes: 1,
+//! });
+//! ```
+
+use crate::errors::ErrorTypes;
+use crate::SupabaseClient;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Configuration for the [`CircuitBreaker`].
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// The amount of consecutive failures after which the circuit opens.
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before probes are allowed.
+    pub open_duration: Duration,
+    /// The amount of concurrent probe requests allowed while half-open.
+    pub half_open_max_probes: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+            half_open_max_probes: 1,
+        }
+    }
+}
+
+/// The state of the circuit for a single host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests are rejected without being sent.
+    Open,
+    /// A limited amount of probe requests is allowed through.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct HostCircuit {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probes_in_flight: u32,
+}
+
+impl HostCircuit {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            probes_in_flight: 0,
+        }
+    }
+}
+
+/// A per-host circuit breaker shared between all clones of a `SupabaseClient`.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    hosts: Arc<Mutex<HashMap<String, HostCircuit>>>,
+}
+
+impl CircuitBreaker {
+    /// Creates a new circuit breaker with every host starting out closed.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            hosts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn hosts(&self) -> MutexGuard<'_, HashMap<String, HostCircuit>> {
+        self.hosts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the current state of the circuit for `host`.
+    pub fn state(&self, host: &str) -> CircuitState {
+        let hosts = self.hosts();
+        match hosts.get(host) {
+            Some(circuit) => match (circuit.state, circuit.opened_at) {
+                (CircuitState::Open, Some(opened_at))
+                    if opened_at.elapsed() >= self.config.open_duration =>
+                {
+                    CircuitState::HalfOpen
+                }
+                (state, _) => state,
+            },
+            None => CircuitState::Closed,
+        }
+    }
+
+    /// Checks whether a request to `host` may be sent.
+    ///
+    /// # Returns
+    /// The permit of the request, to record its outcome with. Dropping it unrecorded frees the probe
+    /// slot it took while half-open.
+    ///
+    /// # Errors
+    /// Returns [`ErrorTypes::CircuitOpen`] while the circuit is open, or while half-open and all
+    /// probe slots are taken.
+    pub fn try_acquire(&self, host: &str) -> Result<CircuitPermit, ErrorTypes> {
+        let mut hosts = self.hosts();
+        let circuit: &mut HostCircuit = hosts
+            .entry(host.to_string())
+            .or_insert_with(HostCircuit::new);
+
+        if circuit.state == CircuitState::Open {
+            let elapsed: Duration = circuit.opened_at.map(|at| at.elapsed()).unwrap_or_default();
+            if elapsed < self.config.open_duration {
+                return Err(ErrorTypes::CircuitOpen {
+                    host: host.to_string(),
+                    retry_in: self.config.open_duration - elapsed,
+                });
+            }
+            circuit.state = CircuitState::HalfOpen;
+            circuit.probes_in_flight = 0;
+        }
+
+        let mut probe: Option<Option<Instant>> = None;
+        if circuit.state == CircuitState::HalfOpen {
+            if circuit.probes_in_flight >= self.config.half_open_max_probes {
+                return Err(ErrorTypes::CircuitOpen {
+                    host: host.to_string(),
+                    retry_in: Duration::ZERO,
+                });
+            }
+            circuit.probes_in_flight += 1;
+            probe = Some(circuit.opened_at);
+        }
+
+        Ok(CircuitPermit {
+            breaker: self.clone(),
+            host: host.to_string(),
+            probe,
+        })
+    }
+
+    /// Records a healthy response from `host`, closing the circuit.
+    pub fn record_success(&self, host: &str) {
+        let mut hosts = self.hosts();
+        if let Some(circuit) = hosts.get_mut(host) {
+            *circuit = HostCircuit::new();
+        }
+    }
+
+    /// Records a failed request to `host`, opening the circuit when the threshold is reached.
+    pub fn record_failure(&self, host: &str) {
+        let mut hosts = self.hosts();
+        let circuit: &mut HostCircuit = hosts
+            .entry(host.to_string())
+            .or_insert_with(HostCircuit::new);
+
+        circuit.consecutive_failures += 1;
+        if circuit.state == CircuitState::HalfOpen
+            || circuit.consecutive_failures >= self.config.failure_threshold
+        {
+            circuit.state = CircuitState::Open;
+            circuit.opened_at = Some(Instant::now());
+            circuit.probes_in_flight = 0;
+        }
+    }
+}
+
+/// A request let through by [`CircuitBreaker::try_acquire`].
+///
+/// Recording the outcome with [`success`](Self::success) or [`failure`](Self::failure) consumes the
+/// permit. A probe dropped unrecorded frees its slot without changing the state of the circuit.
+#[derive(Debug)]
+#[must_use = "dropping the permit frees its probe slot right away"]
+pub struct CircuitPermit {
+    breaker: CircuitBreaker,
+    host: String,
+    /// When the circuit whose probe slot the permit took was opened, `None` for requests sent while
+    /// the circuit was closed.
+    probe: Option<Option<Instant>>,
+}
+
+impl CircuitPermit {
+    /// Records a healthy response, closing the circuit.
+    pub fn success(mut self) {
+        self.probe = None;
+        self.breaker.record_success(&self.host);
+    }
+
+    /// Records a failed request, opening the circuit when the threshold is reached.
+    pub fn failure(mut self) {
+        self.probe = None;
+        self.breaker.record_failure(&self.host);
+    }
+}
+
+impl Drop for CircuitPermit {
+    fn drop(&mut self) {
+        let Some(opened_at) = self.probe else {
+            return;
+        };
+        let mut hosts = self.breaker.hosts();
+        // the circuit may have closed or opened again since, with slots of its own
+        if let Some(circuit) = hosts.get_mut(&self.host) {
+            if circuit.state == CircuitState::HalfOpen && circuit.opened_at == opened_at {
+                circuit.probes_in_flight = circuit.probes_in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+impl SupabaseClient {
+    /// Enables a circuit breaker for every request sent by this client and its clones.
+    ///
+    /// # Arguments
+    /// * `config` - Thresholds and timings for the circuit breaker.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the circuit breaker attached.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(CircuitBreaker::new(config));
+        self
+    }
+}
@@ -0,0 +1,165 @@
+//! ## Scheduled jobs
+//!
+//! Manages `pg_cron` jobs from Rust. The `cron` schema isn't exposed through PostgREST, so the
+//! helpers call a small set of wrapper functions which have to be created once, see
+//! [`CRON_MIGRATION_SQL`]. The wrappers can only be executed with the service role key.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let job_id: i64 = client
+//!     .cron()
+//!     .schedule("nightly-cleanup", "0 3 * * *", "delete from sessions where expired")
+//!     .await?;
+//!
+//! let jobs: Vec<CronJob> = client.cron().list().await?;
+//!
+//! client.cron().unschedule("nightly-cleanup").await?;
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Creates the wrapper functions the [`Cron`] helpers call, run it once as a migration.
+pub const CRON_MIGRATION_SQL: &str = r#"
+create extension if not exists pg_cron;
+
+create or replace function public.supabase_rs_cron_schedule(job_name text, schedule text, command text)
+returns bigint
+language sql
+security definer
+set search_path = ''
+as $$ select cron.schedule(job_name, schedule, command) $$;
+
+create or replace function public.supabase_rs_cron_unschedule(job_name text)
+returns boolean
+language sql
+security definer
+set search_path = ''
+as $$ select cron.unschedule(job_name) $$;
+
+create or replace function public.supabase_rs_cron_jobs()
+returns setof cron.job
+language sql
+security definer
+set search_path = ''
+as $$ select * from cron.job order by jobid $$;
+
+revoke execute on function public.supabase_rs_cron_schedule(text, text, text) from public, anon, authenticated;
+revoke execute on function public.supabase_rs_cron_unschedule(text) from public, anon, authenticated;
+revoke execute on function public.supabase_rs_cron_jobs() from public, anon, authenticated;
+grant execute on function public.supabase_rs_cron_schedule(text, text, text) to service_role;
+grant execute on function public.supabase_rs_cron_unschedule(text) to service_role;
+grant execute on function public.supabase_rs_cron_jobs() to service_role;
+"#;
+
+/// A scheduled job as stored in `cron.job`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CronJob {
+    /// The id of the job.
+    pub jobid: i64,
+    /// The name the job was scheduled with, unnamed jobs have none.
+    pub jobname: Option<String>,
+    /// The cron expression, e.g. `0 3 * * *`.
+    pub schedule: String,
+    /// The SQL command the job runs.
+    pub command: String,
+    /// Whether the job is currently scheduled to run.
+    pub active: bool,
+    /// The database the job runs in.
+    pub database: String,
+    /// The role the job runs as.
+    pub username: String,
+}
+
+/// A client for `pg_cron` jobs, created by [`SupabaseClient::cron`].
+#[derive(Debug, Clone)]
+pub struct Cron {
+    client: SupabaseClient,
+}
+
+impl SupabaseClient {
+    /// Returns a client for managing `pg_cron` scheduled jobs.
+    ///
+    /// Requires the service role key and the functions of [`CRON_MIGRATION_SQL`].
+    pub fn cron(&self) -> Cron {
+        Cron {
+            client: self.clone(),
+        }
+    }
+}
+
+impl Cron {
+    /// Schedules a job, replacing an existing job with the same name.
+    ///
+    /// # Arguments
+    /// * `job_name` - The unique name of the job.
+    /// * `schedule` - A cron expression, e.g. `*/5 * * * *`, or an interval like `30 seconds`.
+    /// * `command` - The SQL command to run.
+    ///
+    /// # Returns
+    /// The id of the scheduled job.
+    pub async fn schedule(&self, job_name: &str, schedule: &str, command: &str) -> Result<i64> {
+        let result: Value = self
+            .client
+            .rpc(
+                "supabase_rs_cron_schedule",
+                json!({
+                    "job_name": job_name,
+                    "schedule": schedule,
+                    "command": command,
+                }),
+            )
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_i64().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a job id, got {}", result))
+        })
+    }
+
+    /// Removes a job by name.
+    ///
+    /// # Returns
+    /// `true` once the job is removed. `cron.unschedule` raises an error for a name no job has,
+    /// which is returned as the error of the call rather than `false`.
+    pub async fn unschedule(&self, job_name: &str) -> Result<bool> {
+        let result: Value = self
+            .client
+            .rpc(
+                "supabase_rs_cron_unschedule",
+                json!({ "job_name": job_name }),
+            )
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_bool().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a boolean, got {}", result))
+        })
+    }
+
+    /// Lists all scheduled jobs, ordered by id.
+    pub async fn list(&self) -> Result<Vec<CronJob>> {
+        let result: Value = self
+            .client
+            .rpc("supabase_rs_cron_jobs", json!({}))
+            .schema("public")
+            .execute()
+            .await?;
+
+        parse_jobs(result)
+    }
+}
+
+/// Parses the `cron.job` rows returned by `supabase_rs_cron_jobs`.
+pub fn parse_jobs(rows: Value) -> Result<Vec<CronJob>> {
+    match rows {
+        Value::Null => Ok(Vec::new()),
+        rows => serde_json::from_value(rows)
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string())),
+    }
+}
@@ -6,6 +6,8 @@
 //! ## Usage
 //!     
 
+use crate::errors::RestError;
+use crate::success::write_error;
 use crate::SupabaseClient;
 use reqwest::Response;
 use serde_json::json;
@@ -20,7 +22,8 @@ impl SupabaseClient {
     ///
     /// # Returns
     /// This method returns a `Result<(), String>`. On success, it returns `Ok(())`, and on failure, it returns
-    /// `Err(String)` with an error message.
+    /// `Err(String)` with an error message. [`delete_from`](Self::delete_from) returns a
+    /// [`RestError`] instead.
     ///
     /// # Examples
     /// ```
@@ -57,25 +60,27 @@ impl SupabaseClient {
         let body: serde_json::Value = json!({}); // this is temporary, will be used for more complex queries
 
         // Send the delete request and handle the response
-        let response: Response = match self
-            .client
-            .delete(&endpoint)
-            .header("apikey", &self.api_key)
-            .header("Authorization", &format!("Bearer {}", &self.api_key))
-            .header("Content-Type", "application/json")
-            .body(body.to_string())
-            .send()
+        let response: Response = self
+            .dispatch(
+                table_name,
+                "delete",
+                self.content_profile(
+                    self.client
+                        .delete(&endpoint)
+                        .header("apikey", &self.api_key)
+                        .header("Authorization", &format!("Bearer {}", &self.api_key))
+                        .header("Content-Type", "application/json")
+                        .body(body.to_string()),
+                ),
+            )
             .await
-        {
-            Ok(response) => response,
-            Err(error) => return Err(error.to_string()),
-        };
+            .map_err(RestError::from)?;
 
         // Check the HTTP status code of the response
         if response.status().is_success() {
             Ok(())
         } else {
-            Err(response.status().to_string())
+            Err(write_error(response).await.into())
         }
     }
 }
@@ -1,8 +1,227 @@
 //! ## Error handling
 //!
 //! This module provides error handling utilities for the Supabase client.
+//!
+//! ### One error type for the whole SDK
+//! The REST methods fail with a `String` or a [`RestError`], auth, storage and realtime with
+//! [`ErrorTypes`] and the GraphQL client with an `anyhow::Error`. All of them convert into
+//! [`Error`], whose [`kind`](Error::kind) tells failures apart without matching messages.
+//!
+//! The methods of the first releases, e.g. `execute`, `insert` and `update`, keep failing with
+//! the `String` message. Their typed siblings, e.g. [`fetch`](crate::query::QueryBuilder::fetch)
+//! and `insert_with_returning`, fail with a [`RestError`]: it derefs to the same message and
+//! converts into a `String`, and keeps the [`ErrorTypes`] of the failed request.
+//! ```rust,ignore
+//! use supabase_rs::{Error, ErrorKind};
+//!
+//! async fn sync(client: &SupabaseClient, auth: &AuthClient) -> Result<(), Error> {
+//!     let session = auth.sign_in_with_password(email, password).await?;
+//!     let rows = client.select("users").execute().await?;
+//!     Ok(())
+//! }
+//!
+//! match sync(&client, &auth).await {
+//!     Err(error) if error.kind() == ErrorKind::RateLimited => { /* back off */ }
+//!     Err(error) => return Err(error),
+//!     Ok(()) => {}
+//! }
+//! ```
+//!
+//! ### Request ids
+//! Every request carries an `X-Request-Id`, and the error message of a response the server rejected
+//! ends with it, e.g. `... (request id: 2f6c...)`. [`RestError::request_id`] returns it, to quote
+//! in a support ticket or to find the request in the API logs, and [`request_id`] reads it back
+//! out of the `String` message.
+//! ```rust,ignore
+//! if let Err(error) = client.select("users").fetch().await {
+//!     tracing::error!(request_id = ?error.request_id(), "{}", error);
+//! }
+//! ```
+
+use crate::request::headers::HeadersTypes;
+
+use anyhow::Error as AnyError;
+use reqwest::header::RETRY_AFTER;
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::Error;
+/// The error body PostgREST responds with, e.g. `{"code":"PGRST202","message":"Could not find the function ..."}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostgrestError {
+    /// A PostgREST (`PGRST...`) or Postgres (SQLSTATE) error code.
+    pub code: Option<String>,
+    pub message: String,
+    pub details: Option<String>,
+    pub hint: Option<String>,
+}
+
+/// The broad cause of a [`PostgrestError`], to react on without matching error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostgrestErrorKind {
+    /// No function (or table) matches the name and parameters, `PGRST202`/`PGRST205`/`42883`.
+    NotFound,
+    /// Several function overloads match the parameters, `PGRST203`.
+    AmbiguousFunction,
+    /// A single row was requested, but none was returned, `PGRST116`.
+    NoRows,
+    /// A single row was requested, but several were returned, `PGRST116`.
+    MultipleRows,
+    /// The role may not execute the function or access the data, `42501` or a `401`/`403` status.
+    PermissionDenied,
+    /// A parameter has the wrong type or format, `22P02` and other `22` data exceptions.
+    InvalidParameters,
+    /// A constraint was violated, `23xxx`.
+    ConstraintViolation,
+    /// The function raised an exception, `P0001`.
+    RaisedException,
+    /// Any other error.
+    Other,
+}
+
+impl PostgrestError {
+    /// Classifies the error by its code, using the status for errors without one.
+    pub fn kind(&self, status: u16) -> PostgrestErrorKind {
+        let code: &str = self.code.as_deref().unwrap_or_default();
+        match code {
+            "PGRST202" | "PGRST205" | "42883" => PostgrestErrorKind::NotFound,
+            "PGRST203" => PostgrestErrorKind::AmbiguousFunction,
+            "PGRST116" => {
+                let no_rows: bool = self
+                    .details
+                    .as_deref()
+                    .is_some_and(|details| details.contains(" 0 rows"));
+                if no_rows {
+                    PostgrestErrorKind::NoRows
+                } else {
+                    PostgrestErrorKind::MultipleRows
+                }
+            }
+            "42501" => PostgrestErrorKind::PermissionDenied,
+            "P0001" => PostgrestErrorKind::RaisedException,
+            _ if code.starts_with("22") => PostgrestErrorKind::InvalidParameters,
+            _ if code.starts_with("23") => PostgrestErrorKind::ConstraintViolation,
+            _ if status == 401 || status == 403 => PostgrestErrorKind::PermissionDenied,
+            _ if status == 404 => PostgrestErrorKind::NotFound,
+            _ => PostgrestErrorKind::Other,
+        }
+    }
+}
+
+impl Display for PostgrestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(code) = &self.code {
+            write!(f, "[{}] ", code)?;
+        }
+        f.write_str(&self.message)?;
+        if let Some(details) = &self.details {
+            write!(f, " ({})", details)?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, ", hint: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// The error body Auth (GoTrue) responds with, e.g.
+/// `{"code":400,"error_code":"invalid_credentials","msg":"Invalid login credentials"}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthApiError {
+    /// The error code of Auth, e.g. `invalid_credentials`, or the OAuth error of older versions,
+    /// e.g. `invalid_grant`.
+    pub error_code: Option<String>,
+    pub message: String,
+    /// Why a password was rejected as weak, e.g. `length`, `characters` or `pwned`.
+    pub weak_password_reasons: Vec<String>,
+}
+
+/// The cause of an [`AuthApiError`] a user can act on, to show a matching message without
+/// matching error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthErrorKind {
+    /// The password doesn't meet the requirements of the project, `weak_password`, see
+    /// [`AuthApiError::weak_password_reasons`].
+    WeakPassword,
+    /// Too many emails were sent to the address, `over_email_send_rate_limit`.
+    EmailRateLimited,
+    /// Too many text messages were sent to the number, `over_sms_send_rate_limit`.
+    SmsRateLimited,
+    /// Too many requests came from the client, `over_request_rate_limit` or a `429` status.
+    RequestRateLimited,
+    /// The user signs in before confirming their email address, `email_not_confirmed`.
+    EmailNotConfirmed,
+    /// The user signs in before confirming their phone number, `phone_not_confirmed`.
+    PhoneNotConfirmed,
+    /// A user with the email or phone number exists already, `user_already_exists`,
+    /// `email_exists` or `phone_exists`.
+    UserAlreadyExists,
+    /// The email, phone number or password is wrong, `invalid_credentials`.
+    InvalidCredentials,
+    /// Any other error.
+    Other,
+}
+
+impl AuthApiError {
+    /// Classifies the error by its code, using the status for errors without one.
+    pub fn kind(&self, status: u16) -> AuthErrorKind {
+        match self.error_code.as_deref().unwrap_or_default() {
+            "weak_password" => AuthErrorKind::WeakPassword,
+            "over_email_send_rate_limit" => AuthErrorKind::EmailRateLimited,
+            "over_sms_send_rate_limit" => AuthErrorKind::SmsRateLimited,
+            "over_request_rate_limit" => AuthErrorKind::RequestRateLimited,
+            "email_not_confirmed" => AuthErrorKind::EmailNotConfirmed,
+            "phone_not_confirmed" => AuthErrorKind::PhoneNotConfirmed,
+            "user_already_exists" | "email_exists" | "phone_exists" => {
+                AuthErrorKind::UserAlreadyExists
+            }
+            "invalid_credentials" => AuthErrorKind::InvalidCredentials,
+            _ if status == 429 => AuthErrorKind::RequestRateLimited,
+            _ => AuthErrorKind::Other,
+        }
+    }
+
+    /// Reads an Auth error body, in the shape of current or older versions of Auth.
+    ///
+    /// Returns `None` for bodies without a message, e.g. the page of a proxy in front of Auth.
+    pub fn parse(body: &str) -> Option<Self> {
+        let body: serde_json::Value = serde_json::from_str(body).ok()?;
+        let text = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| body.get(*key).and_then(|value| value.as_str()))
+                .map(str::to_string)
+        };
+
+        let weak_password_reasons: Vec<String> = body["weak_password"]["reasons"]
+            .as_array()
+            .map(|reasons| {
+                reasons
+                    .iter()
+                    .filter_map(|reason| reason.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(AuthApiError {
+            error_code: text(&["error_code", "error"]),
+            message: text(&["msg", "message", "error_description"])?,
+            weak_password_reasons,
+        })
+    }
+}
+
+impl Display for AuthApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(code) = &self.error_code {
+            write!(f, "[{}] ", code)?;
+        }
+        f.write_str(&self.message)
+    }
+}
 
 #[derive(thiserror::Error, Debug)]
 pub enum ErrorTypes {
@@ -18,22 +237,583 @@ pub enum ErrorTypes {
     ReqwestError(#[from] reqwest::Error),
     #[error("Environment variable error: {0}")]
     EnvironmentError(#[from] std::env::VarError),
+    #[error("Circuit breaker is open for {host}, retry in {retry_in:?}")]
+    CircuitOpen { host: String, retry_in: Duration },
+    #[error("Payload too large: {size} bytes exceeds the limit of {limit} bytes")]
+    PayloadTooLarge { size: usize, limit: usize },
+    #[error("Request was cancelled")]
+    Cancelled,
+    #[error("Request timed out")]
+    Timeout,
+    #[error("No session, sign in first")]
+    MissingSession,
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(String),
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+    #[error("Invalid JWT: {0}")]
+    InvalidJwt(String),
+    #[error("Session store error: {0}")]
+    SessionStore(#[from] std::io::Error),
+    #[error("Request failed: {0}")]
+    RequestFailed(String),
+    #[error("Realtime error: {0}")]
+    Realtime(String),
+    #[error("API error {status}: {message}")]
+    ApiError { status: u16, message: String },
+    #[error("PostgREST error {status}: {error}")]
+    Postgrest { status: u16, error: PostgrestError },
+    /// An error body of Auth, with the body as it was received.
+    #[error("Auth error {status}: {error}")]
+    Auth {
+        status: u16,
+        error: AuthApiError,
+        body: String,
+    },
+    /// A row with the same value in a unique column exists already, `23505`.
+    #[error("Unique violation: {0}")]
+    UniqueViolation(PostgrestError),
+    #[error("Request entity too large: {message}")]
+    RequestTooLarge { message: String },
+    #[error("Rate limited (retry after {retry_after:?}): {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    #[error("Service unavailable (retry after {retry_after:?}): {message}")]
+    ServiceUnavailable {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    /// The digest of downloaded or uploaded bytes differs from the one they were checked against.
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+}
+
+impl ErrorTypes {
+    /// Builds the error matching an unsuccessful response, reading its `Retry-After` header and body.
+    pub async fn from_response(response: Response) -> Self {
+        let status: u16 = response.status().as_u16();
+        let retry_after: Option<Duration> = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        let message: String = response.text().await.unwrap_or_default();
+
+        match status {
+            413 => ErrorTypes::RequestTooLarge { message },
+            429 => ErrorTypes::RateLimited {
+                retry_after,
+                message,
+            },
+            503 => ErrorTypes::ServiceUnavailable {
+                retry_after,
+                message,
+            },
+            _ => ErrorTypes::ApiError { status, message },
+        }
+    }
+
+    /// Builds the error matching an unsuccessful PostgREST response, parsing its error body.
+    ///
+    /// Bodies that aren't PostgREST errors fall back to [`from_response`](Self::from_response) rules.
+    pub async fn from_postgrest_response(response: Response) -> Self {
+        let status: u16 = response.status().as_u16();
+        if matches!(status, 413 | 429 | 503) {
+            return Self::from_response(response).await;
+        }
+
+        let body: String = response.text().await.unwrap_or_default();
+        Self::from_postgrest_body(status, body)
+    }
+
+    /// Builds the error of a PostgREST error body, see [`from_postgrest_response`](Self::from_postgrest_response).
+    pub fn from_postgrest_body(status: u16, body: String) -> Self {
+        match serde_json::from_str::<PostgrestError>(&body) {
+            Ok(error) if error.code.as_deref() == Some("23505") => {
+                ErrorTypes::UniqueViolation(error)
+            }
+            Ok(error) if !error.message.is_empty() => ErrorTypes::Postgrest { status, error },
+            _ => ErrorTypes::ApiError {
+                status,
+                message: body,
+            },
+        }
+    }
+
+    /// Builds the error matching an unsuccessful Auth response, parsing its error body.
+    ///
+    /// Bodies that aren't Auth errors fall back to [`from_response`](Self::from_response) rules.
+    pub async fn from_auth_response(response: Response) -> Self {
+        let status: u16 = response.status().as_u16();
+        let retry_after: Option<Duration> = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        let body: String = response.text().await.unwrap_or_default();
+
+        match AuthApiError::parse(&body) {
+            Some(error) => ErrorTypes::Auth {
+                status,
+                error,
+                body,
+            },
+            None => match status {
+                413 => ErrorTypes::RequestTooLarge { message: body },
+                429 => ErrorTypes::RateLimited {
+                    retry_after,
+                    message: body,
+                },
+                503 => ErrorTypes::ServiceUnavailable {
+                    retry_after,
+                    message: body,
+                },
+                _ => ErrorTypes::ApiError {
+                    status,
+                    message: body,
+                },
+            },
+        }
+    }
+
+    /// The Auth error body, when the error is one.
+    pub fn auth_error(&self) -> Option<&AuthApiError> {
+        match self {
+            ErrorTypes::Auth { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// The kind of the Auth error, when the error is one.
+    pub fn auth_error_kind(&self) -> Option<AuthErrorKind> {
+        match self {
+            ErrorTypes::Auth { status, error, .. } => Some(error.kind(*status)),
+            _ => None,
+        }
+    }
+
+    /// The PostgREST error body and its kind, when the error is one.
+    pub fn postgrest_error(&self) -> Option<(&PostgrestError, PostgrestErrorKind)> {
+        match self {
+            ErrorTypes::Postgrest { status, error } => Some((error, error.kind(*status))),
+            ErrorTypes::UniqueViolation(error) => {
+                Some((error, PostgrestErrorKind::ConstraintViolation))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the same request may succeed when it is sent again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ErrorTypes::RateLimited { .. }
+            | ErrorTypes::ServiceUnavailable { .. }
+            | ErrorTypes::CircuitOpen { .. }
+            | ErrorTypes::Timeout
+            | ErrorTypes::RequestFailed(_) => true,
+            ErrorTypes::ApiError { status, .. } | ErrorTypes::Postgrest { status, .. } => {
+                matches!(status, 502 | 504)
+            }
+            ErrorTypes::Auth { status, .. } => matches!(status, 429 | 502..=504),
+            _ => false,
+        }
+    }
+
+    /// How long the server asked to wait before retrying, if it said so.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ErrorTypes::RateLimited { retry_after, .. }
+            | ErrorTypes::ServiceUnavailable { retry_after, .. } => *retry_after,
+            ErrorTypes::CircuitOpen { retry_in, .. } => Some(*retry_in),
+            _ => None,
+        }
+    }
+}
+
+/// The error message of inserts hitting a unique violation, which the insert helpers look for.
+pub(crate) const DUPLICATE_ENTRY: &str = "Error 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.";
+
+/// Parses a `Retry-After` header given in seconds, e.g. `120`.
+///
+/// The HTTP-date form isn't sent by Supabase and yields `None`.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
 }
 
 pub type Result<Type> = std::result::Result<Type, ErrorTypes>;
 
-pub async fn unknown_error() -> std::result::Result<(), Error> {
-    Err(Error::msg("SUPABASE_RS: unknown error"))
+/// The broad cause of an [`Error`], the same for every subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The request couldn't be sent, or its response couldn't be read.
+    Network,
+    /// The request took longer than the configured timeout.
+    Timeout,
+    /// The request was cancelled before its response arrived.
+    Cancelled,
+    /// The API key, JWT or session is missing or was rejected.
+    Unauthorized,
+    /// The server rate limited the client, see [`ErrorTypes::retry_after`].
+    RateLimited,
+    /// The server, or the circuit breaker in front of it, doesn't take requests for now.
+    Unavailable,
+    /// The request itself is wrong, e.g. an invalid query, parameter or an oversized payload.
+    InvalidRequest,
+    /// The server rejected the request for another reason, e.g. a violated constraint.
+    Api,
+    /// The realtime connection failed.
+    Realtime,
+    /// Downloaded or uploaded bytes differ from the checksum they were verified against.
+    Integrity,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorTypes {
+    /// The broad cause of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ErrorTypes::ReqwestError(error) if error.is_timeout() => ErrorKind::Timeout,
+            ErrorTypes::ReqwestError(_) | ErrorTypes::RequestFailed(_) => ErrorKind::Network,
+            ErrorTypes::Timeout => ErrorKind::Timeout,
+            ErrorTypes::Cancelled => ErrorKind::Cancelled,
+            ErrorTypes::ApiKeyMissing
+            | ErrorTypes::AuthorizationFailed
+            | ErrorTypes::MissingSession
+            | ErrorTypes::InvalidJwt(_) => ErrorKind::Unauthorized,
+            ErrorTypes::RateLimited { .. } => ErrorKind::RateLimited,
+            ErrorTypes::ServiceUnavailable { .. } | ErrorTypes::CircuitOpen { .. } => {
+                ErrorKind::Unavailable
+            }
+            ErrorTypes::InvalidQuery
+            | ErrorTypes::InvalidParameters(_)
+            | ErrorTypes::PayloadTooLarge { .. }
+            | ErrorTypes::RequestTooLarge { .. } => ErrorKind::InvalidRequest,
+            ErrorTypes::ApiError { status, .. } => status_kind(*status),
+            ErrorTypes::Auth { status, error, .. } => match error.kind(*status) {
+                AuthErrorKind::EmailRateLimited
+                | AuthErrorKind::SmsRateLimited
+                | AuthErrorKind::RequestRateLimited => ErrorKind::RateLimited,
+                AuthErrorKind::InvalidCredentials => ErrorKind::Unauthorized,
+                AuthErrorKind::WeakPassword => ErrorKind::InvalidRequest,
+                _ => status_kind(*status),
+            },
+            ErrorTypes::Postgrest { status, error } => match error.kind(*status) {
+                PostgrestErrorKind::PermissionDenied => ErrorKind::Unauthorized,
+                PostgrestErrorKind::InvalidParameters => ErrorKind::InvalidRequest,
+                _ => ErrorKind::Api,
+            },
+            ErrorTypes::UniqueViolation(_) => ErrorKind::Api,
+            ErrorTypes::Realtime(_) => ErrorKind::Realtime,
+            ErrorTypes::ChecksumMismatch { .. } => ErrorKind::Integrity,
+            ErrorTypes::UnknownError
+            | ErrorTypes::EnvironmentError(_)
+            | ErrorTypes::SessionStore(_)
+            | ErrorTypes::UnexpectedResponse(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// Classifies an unsuccessful status code.
+fn status_kind(status: u16) -> ErrorKind {
+    match status {
+        401 | 403 => ErrorKind::Unauthorized,
+        400 | 413 => ErrorKind::InvalidRequest,
+        429 => ErrorKind::RateLimited,
+        502..=504 => ErrorKind::Unavailable,
+        _ => ErrorKind::Api,
+    }
+}
+
+/// The error of a REST method, e.g. a select or an insert.
+///
+/// It reads like the `String` the REST methods failed with before: it derefs to its message,
+/// compares equal to it and converts into it. The [`ErrorTypes`] of a failed request is kept next to
+/// the message, so [`kind`](RestError::kind) doesn't depend on its wording. Errors of the SDK
+/// itself, e.g. a body that isn't a JSON object, only have a message.
+#[derive(Debug, Clone)]
+pub struct RestError {
+    message: String,
+    error: Option<Arc<ErrorTypes>>,
+    request_id: Option<String>,
+}
+
+impl RestError {
+    /// The error of a request, with the message of `error`.
+    ///
+    /// Errors wrapping the message of a failed request keep that message as it was, without the
+    /// prefix of the variant.
+    pub(crate) fn new(error: ErrorTypes) -> Self {
+        let message: String = match &error {
+            ErrorTypes::ReqwestError(error) => error.to_string(),
+            ErrorTypes::RequestFailed(message)
+            | ErrorTypes::UnexpectedResponse(message)
+            | ErrorTypes::InvalidJwt(message) => message.clone(),
+            error => error.to_string(),
+        };
+        RestError {
+            message,
+            error: Some(Arc::new(error)),
+            request_id: None,
+        }
+    }
+
+    /// Replaces the message, keeping the error it describes and its request id.
+    ///
+    /// A message quoting the previous one, which ends with the request id, isn't given it twice.
+    pub(crate) fn with_message(mut self, message: impl Into<String>) -> Self {
+        let message: String = message.into();
+        self.message = match &self.request_id {
+            Some(_) if request_id(&message) == self.request_id.as_deref() => message,
+            _ => with_request_id(message, self.request_id.clone()),
+        };
+        self
+    }
+
+    /// Keeps the request id of the response, and ends the message with it when there is one.
+    pub(crate) fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.message = with_request_id(self.message, request_id.clone());
+        self.request_id = request_id;
+        self
+    }
+
+    /// The error message, ending with the request id for responses the server rejected.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The broad cause of the error, [`ErrorKind::Other`] for errors of the SDK itself.
+    pub fn kind(&self) -> ErrorKind {
+        self.error
+            .as_deref()
+            .map_or(ErrorKind::Other, ErrorTypes::kind)
+    }
+
+    /// The [`ErrorTypes`] of the failed request, `None` for errors of the SDK itself.
+    pub fn as_error_types(&self) -> Option<&ErrorTypes> {
+        self.error.as_deref()
+    }
+
+    /// The PostgREST error body and its kind, when the server responded with one.
+    pub fn postgrest_error(&self) -> Option<(&PostgrestError, PostgrestErrorKind)> {
+        self.as_error_types()?.postgrest_error()
+    }
+
+    /// Whether a row with the same value in a unique column exists already, `23505`.
+    pub fn is_unique_violation(&self) -> bool {
+        matches!(self.as_error_types(), Some(ErrorTypes::UniqueViolation(_)))
+    }
+
+    /// The `X-Request-Id` of the response the server rejected the request with.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl Display for RestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error
+            .as_deref()
+            .map(|error| error as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl Deref for RestError {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.message
+    }
+}
+
+impl PartialEq for RestError {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+    }
+}
+
+impl PartialEq<str> for RestError {
+    fn eq(&self, other: &str) -> bool {
+        self.message == other
+    }
+}
+
+impl PartialEq<&str> for RestError {
+    fn eq(&self, other: &&str) -> bool {
+        self.message == *other
+    }
+}
+
+impl PartialEq<String> for RestError {
+    fn eq(&self, other: &String) -> bool {
+        self.message == *other
+    }
+}
+
+impl From<ErrorTypes> for RestError {
+    fn from(error: ErrorTypes) -> Self {
+        RestError::new(error)
+    }
+}
+
+impl From<String> for RestError {
+    fn from(message: String) -> Self {
+        RestError {
+            message,
+            error: None,
+            request_id: None,
+        }
+    }
+}
+
+impl From<&str> for RestError {
+    fn from(message: &str) -> Self {
+        RestError::from(message.to_string())
+    }
+}
+
+impl From<reqwest::Error> for RestError {
+    fn from(error: reqwest::Error) -> Self {
+        RestError::new(ErrorTypes::ReqwestError(error))
+    }
+}
+
+impl From<RestError> for String {
+    fn from(error: RestError) -> Self {
+        error.message
+    }
+}
+
+/// Any error of the SDK, whichever subsystem it came from.
+///
+/// Converts from the [`RestError`]s of the REST methods, [`ErrorTypes`], `String` messages and the
+/// `anyhow::Error`s of the GraphQL client, so `?` works on all of them in one function.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// An error of auth, storage, realtime or another subsystem returning [`ErrorTypes`].
+    #[error(transparent)]
+    Sdk(#[from] ErrorTypes),
+    /// The error of a REST method.
+    #[error(transparent)]
+    Rest(#[from] RestError),
+    /// An error message, e.g. of a validation in the application.
+    #[error("{0}")]
+    Message(String),
+    /// Any other error, e.g. of the GraphQL client.
+    #[error(transparent)]
+    Other(AnyError),
+}
+
+impl Error {
+    /// The broad cause of the error, [`ErrorKind::Other`] for messages.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Sdk(error) => error.kind(),
+            Error::Rest(error) => error.kind(),
+            Error::Message(_) => ErrorKind::Other,
+            Error::Other(error) => match error.downcast_ref::<reqwest::Error>() {
+                Some(error) if error.is_timeout() => ErrorKind::Timeout,
+                Some(_) => ErrorKind::Network,
+                None => ErrorKind::Other,
+            },
+        }
+    }
+
+    /// The id of the request the server rejected, see [`request_id`].
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::Rest(error) => error.request_id(),
+            Error::Message(message) => request_id(message),
+            _ => None,
+        }
+    }
+
+    /// The [`ErrorTypes`] of the error, when it is or wraps one.
+    pub fn as_error_types(&self) -> Option<&ErrorTypes> {
+        match self {
+            Error::Sdk(error) => Some(error),
+            Error::Rest(error) => error.as_error_types(),
+            _ => None,
+        }
+    }
+}
+
+/// Starts the request id at the end of an error message.
+const REQUEST_ID_PREFIX: &str = " (request id: ";
+
+/// The request id an error message ends with, `None` when it names none.
+///
+/// Only errors of responses name their request, a request that didn't reach the server has no
+/// response to name.
+pub fn request_id(message: &str) -> Option<&str> {
+    let (_, request_id) = message.rsplit_once(REQUEST_ID_PREFIX)?;
+    request_id.strip_suffix(')')
+}
+
+/// The `X-Request-Id` of a response, which is the one of its request.
+pub(crate) fn response_request_id(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get(HeadersTypes::RequestId.as_str())
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Ends an error message with the request id, when there is one.
+pub(crate) fn with_request_id(message: String, request_id: Option<String>) -> String {
+    match request_id {
+        Some(request_id) => format!("{}{}{})", message, REQUEST_ID_PREFIX, request_id),
+        None => message,
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Message(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Message(message.to_string())
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Sdk(ErrorTypes::ReqwestError(error))
+    }
+}
+
+impl From<AnyError> for Error {
+    fn from(error: AnyError) -> Self {
+        match error.downcast::<ErrorTypes>() {
+            Ok(error) => Error::Sdk(error),
+            Err(error) => Error::Other(error),
+        }
+    }
+}
+
+pub async fn unknown_error() -> std::result::Result<(), AnyError> {
+    Err(AnyError::msg("SUPABASE_RS: unknown error"))
 }
 
-pub async fn api_key_missing_error() -> std::result::Result<(), Error> {
-    Err(Error::msg("SUPABASE_RS: API key is missing"))
+pub async fn api_key_missing_error() -> std::result::Result<(), AnyError> {
+    Err(AnyError::msg("SUPABASE_RS: API key is missing"))
 }
 
-pub async fn authorization_failed_error() -> std::result::Result<(), Error> {
-    Err(Error::msg("SUPABASE_RS: Authorization failed"))
+pub async fn authorization_failed_error() -> std::result::Result<(), AnyError> {
+    Err(AnyError::msg("SUPABASE_RS: Authorization failed"))
 }
 
-pub async fn invalid_query_error() -> std::result::Result<(), Error> {
-    Err(Error::msg("SUPABASE_RS: Invalid query"))
+pub async fn invalid_query_error() -> std::result::Result<(), AnyError> {
+    Err(AnyError::msg("SUPABASE_RS: Invalid query"))
 }
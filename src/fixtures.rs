@@ -0,0 +1,175 @@
+//! ## Test fixtures
+//!
+//! Helpers for integration tests that need a known set of rows: [`Fixtures::seed_table`] inserts
+//! rows and returns them with their generated columns, [`Fixtures::truncate_table`] empties tables
+//! between tests.
+//!
+//! Truncating goes through a function which has to be created once, see [`FIXTURES_MIGRATION_SQL`].
+//! It can only be executed with the service role key, and refuses the schemas Supabase manages,
+//! like `auth` and `storage`. Projects without the function can delete every row with
+//! [`Fixtures::delete_all_rows`] instead, which fires triggers but keeps sequences going.
+//!
+//! ### Guard rails
+//! Fixtures empty tables, so [`SupabaseClient::fixtures`] refuses clients pointing anywhere else
+//! than a local project (`localhost`, `127.0.0.1`, ...), unless the `SUPABASE_RS_ALLOW_FIXTURES`
+//! environment variable is `1` or `true`, e.g. in the CI job of a dedicated test project.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let fixtures = client.fixtures()?;
+//!
+//! fixtures.truncate_tables(&["orders", "users"]).await?;
+//! let users: Vec<Value> = fixtures
+//!     .seed_table("users", vec![json!({"email": "jane@example.com"}), json!({"email": "john@example.com"})])
+//!     .await?;
+//! let jane_id = &users[0]["id"];
+//! ```
+
+use crate::errors::{Error, ErrorTypes};
+use crate::query::Returning;
+use crate::SupabaseClient;
+
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// The environment variable allowing fixtures on projects that aren't local.
+pub const ALLOW_FIXTURES_ENV: &str = "SUPABASE_RS_ALLOW_FIXTURES";
+
+/// The hosts of a locally running project, e.g. started with `supabase start`.
+const LOCAL_HOSTS: &[&str] = &[
+    "localhost",
+    "127.0.0.1",
+    "[::1]",
+    "0.0.0.0",
+    "host.docker.internal",
+];
+
+/// Creates the function [`Fixtures::truncate_table`] calls, run it once as a migration.
+pub const FIXTURES_MIGRATION_SQL: &str = r#"
+create or replace function public.supabase_rs_truncate(table_names text[], schema_name text default 'public', restart_identity boolean default true)
+returns void
+language plpgsql
+security definer
+set search_path = ''
+as $$
+begin
+  if schema_name in ('auth', 'storage', 'realtime', 'vault', 'extensions', 'graphql', 'graphql_public', 'net', 'cron', 'pgmq', 'pgsodium', 'supabase_functions', 'supabase_migrations', 'pg_catalog', 'information_schema') then
+    raise exception 'refusing to truncate tables of the % schema', schema_name using errcode = '42501';
+  end if;
+  if coalesce(array_length(table_names, 1), 0) = 0 then
+    return;
+  end if;
+
+  execute format(
+    'truncate table %s%s',
+    (select string_agg(format('%I.%I', schema_name, table_name), ', ') from unnest(table_names) as table_name),
+    case when restart_identity then ' restart identity' else '' end
+  );
+end;
+$$;
+
+revoke execute on function public.supabase_rs_truncate(text[], text, boolean) from public, anon, authenticated;
+grant execute on function public.supabase_rs_truncate(text[], text, boolean) to service_role;
+"#;
+
+/// Seeds and empties tables in integration tests, created by [`SupabaseClient::fixtures`].
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    client: SupabaseClient,
+}
+
+impl SupabaseClient {
+    /// Returns the test fixture helpers of the client.
+    ///
+    /// # Errors
+    /// [`ErrorTypes::InvalidParameters`] when the client doesn't point at a local project and
+    /// [`ALLOW_FIXTURES_ENV`] isn't set.
+    pub fn fixtures(&self) -> Result<Fixtures, Error> {
+        let host: String = Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let local: bool = LOCAL_HOSTS.contains(&host.as_str()) || host.ends_with(".localhost");
+        let allowed: bool = std::env::var(ALLOW_FIXTURES_ENV)
+            .is_ok_and(|value| matches!(value.as_str(), "1" | "true"));
+
+        if !local && !allowed {
+            return Err(ErrorTypes::InvalidParameters(format!(
+                "refusing to use fixtures on `{}`, set {}=1 if it is a test project",
+                host, ALLOW_FIXTURES_ENV
+            ))
+            .into());
+        }
+
+        Ok(Fixtures {
+            client: self.clone(),
+        })
+    }
+}
+
+impl Fixtures {
+    /// Inserts `rows` into `table_name`, the columns a row leaves out take their defaults.
+    ///
+    /// # Returns
+    /// The inserted rows, including their generated ids and defaults, in the order of `rows`.
+    pub async fn seed_table<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: Vec<T>,
+    ) -> Result<Vec<Value>, Error> {
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows: Vec<Value> = self
+            .client
+            .insert_with_defaults(table_name, rows, Returning::Representation)
+            .await?;
+        Ok(rows)
+    }
+
+    /// Removes every row of `table_name` and restarts its identity columns.
+    ///
+    /// Requires the service role key and the function of [`FIXTURES_MIGRATION_SQL`].
+    pub async fn truncate_table(&self, table_name: &str) -> Result<(), Error> {
+        self.truncate_tables(&[table_name]).await
+    }
+
+    /// Removes every row of several tables at once, which may reference each other.
+    ///
+    /// Tables of the schema of the client are truncated, `public` when it has none.
+    pub async fn truncate_tables(&self, table_names: &[&str]) -> Result<(), Error> {
+        self.client
+            .rpc(
+                "supabase_rs_truncate",
+                json!({
+                    "table_names": table_names,
+                    "schema_name": self.client.schema().unwrap_or("public"),
+                    "restart_identity": true,
+                }),
+            )
+            .schema("public")
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes every row of `table_name` through the REST API, without the truncate function.
+    ///
+    /// Unfiltered deletes are blocked by Supabase, so the rows are matched on `column` not being
+    /// `NULL`. Pass the primary key, rows with `NULL` in any other column would be kept.
+    ///
+    /// # Returns
+    /// The number of deleted rows.
+    pub async fn delete_all_rows(&self, table_name: &str, column: &str) -> Result<usize, Error> {
+        let deleted: Vec<Value> = self
+            .client
+            .delete_from(table_name)
+            .neq(column, None::<&str>)
+            .returning()
+            .execute()
+            .await?;
+        Ok(deleted.len())
+    }
+}
@@ -0,0 +1,181 @@
+//! ## Edge Functions
+//!
+//! Invokes Supabase Edge Functions on `/functions/v1/{name}`.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde_json::{json, Value};
+//! use supabase_rs::functions::InvokeOptions;
+//!
+//! let greeting: Value = client
+//!     .functions()
+//!     .invoke("hello-world", json!({"name": "Functions"}), InvokeOptions::new())
+//!     .await?;
+//!
+//! // call the function on behalf of a signed in user, and stream the response
+//! let mut response = client
+//!     .functions()
+//!     .invoke_raw(
+//!         "generate-report",
+//!         json!({}),
+//!         InvokeOptions::new().access_token(&user_jwt).header("x-report", "monthly"),
+//!     )
+//!     .await?;
+//! while let Some(chunk) = response.chunk().await? {
+//!     // ...
+//! }
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::request::Headers;
+use crate::SupabaseClient;
+
+use reqwest::{Method, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Options of a single function invocation.
+#[derive(Debug, Clone)]
+pub struct InvokeOptions {
+    /// The HTTP method, `POST` by default.
+    pub method: Method,
+    /// Additional headers sent to the function.
+    pub headers: HashMap<String, String>,
+    /// A user access token to invoke the function with instead of the client key.
+    pub access_token: Option<String>,
+    /// The region to run the function in, e.g. `us-east-1`.
+    pub region: Option<String>,
+}
+
+impl Default for InvokeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InvokeOptions {
+    /// Creates options for a `POST` invocation with the client key.
+    pub fn new() -> Self {
+        InvokeOptions {
+            method: Method::POST,
+            headers: HashMap::new(),
+            access_token: None,
+            region: None,
+        }
+    }
+
+    /// Sets the HTTP method.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Adds a header sent to the function.
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Invokes the function as the user owning `access_token`.
+    pub fn access_token(mut self, access_token: &str) -> Self {
+        self.access_token = Some(access_token.to_string());
+        self
+    }
+
+    /// Runs the function in a specific region.
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+}
+
+/// A client for Supabase Edge Functions, created by [`SupabaseClient::functions`].
+#[derive(Debug, Clone)]
+pub struct Functions {
+    client: SupabaseClient,
+}
+
+impl SupabaseClient {
+    /// Returns a client for invoking Edge Functions.
+    pub fn functions(&self) -> Functions {
+        Functions {
+            client: self.clone(),
+        }
+    }
+}
+
+impl Functions {
+    /// Invokes a function and deserializes its JSON response into `T`.
+    ///
+    /// # Arguments
+    /// * `function_name` - The name of the function.
+    /// * `body` - The request body, serialized as JSON.
+    /// * `options` - The method, headers, token and region of the invocation.
+    ///
+    /// # Returns
+    /// The deserialized response, or the error matching the status when the function responds with an error,
+    /// see [`ErrorTypes::from_response`].
+    pub async fn invoke<T: DeserializeOwned>(
+        &self,
+        function_name: &str,
+        body: impl Serialize,
+        options: InvokeOptions,
+    ) -> Result<T> {
+        let response: Response = self.invoke_raw(function_name, body, options).await?;
+        Ok(response.json::<T>().await?)
+    }
+
+    /// Invokes a function and returns the raw response, e.g. to stream it with `Response::chunk`.
+    ///
+    /// # Returns
+    /// The successful response, or the error matching the status when the function responds with an error,
+    /// see [`ErrorTypes::from_response`].
+    pub async fn invoke_raw(
+        &self,
+        function_name: &str,
+        body: impl Serialize,
+        options: InvokeOptions,
+    ) -> Result<Response> {
+        let endpoint: String = format!("{}/functions/v1/{}", self.client.url, function_name);
+
+        let mut request = self
+            .client
+            .client
+            .request(options.method.clone(), &endpoint);
+        if options.method != Method::GET && options.method != Method::HEAD {
+            request = request.json(&body);
+        }
+        for (key, value) in self.headers(&options).get_headers() {
+            request = request.header(key, value);
+        }
+
+        let response: Response = self
+            .client
+            .dispatch(function_name, "invoke", request)
+            .await?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        Err(ErrorTypes::from_response(response).await)
+    }
+
+    /// Builds the headers of an invocation.
+    pub(crate) fn headers(&self, options: &InvokeOptions) -> Headers {
+        let token: &str = options
+            .access_token
+            .as_deref()
+            .unwrap_or(&self.client.api_key);
+        let mut headers: Headers = Headers::with_defaults(&self.client.api_key, token);
+
+        if let Some(region) = &options.region {
+            headers.insert("x-region", region);
+        }
+        for (key, value) in &options.headers {
+            headers.insert(key, value);
+        }
+        headers
+    }
+}
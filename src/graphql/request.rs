@@ -62,13 +62,23 @@ impl Request {
         #[cfg(feature = "nightly")]
         print_nightly_warning();
 
+        #[cfg(feature = "metrics")]
+        let started: std::time::Instant = std::time::Instant::now();
         let res = client
             .post(&endpoint_graphql)
             .header("apiKey", headers_map.get("apiKey").unwrap())
             .header("Content-Type", headers_map.get("Content-Type").unwrap())
             .body(formatted_query)
             .send()
-            .await?;
+            .await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request(
+            &table_name,
+            "graphql",
+            res.as_ref().ok().map(|res| res.status().as_u16()),
+            started.elapsed(),
+        );
+        let res = res?;
 
         let data: Value = res
             .json()
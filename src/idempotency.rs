@@ -0,0 +1,97 @@
+//! ## Idempotency keys
+//!
+//! A write that timed out may still have been applied, so retrying it can apply it twice. Filtered
+//! updates and deletes can carry an idempotency key, sent as the `Idempotency-Key` header, which a
+//! trigger on the table claims before the write. A retry with the same key then fails instead of
+//! writing again, and [`is_replay`] tells that failure apart from others. The trigger raises it as
+//! `P0001` with the hint [`REPLAY_HINT`], so it can't be taken for a unique violation (`23505`) of
+//! the write itself.
+//!
+//! The trigger function and the table of claimed keys have to be created once, see
+//! [`IDEMPOTENCY_MIGRATION_SQL`], and the trigger added to every table written with keys:
+//! ```sql
+//! create trigger orders_idempotency
+//!   before insert or update or delete on public.orders
+//!   for each statement execute function public.supabase_rs_claim_idempotency_key();
+//! ```
+//!
+//! Writes without a key aren't affected. Keys are claimed across all tables, so use a new one for
+//! every logical write, e.g. a UUID stored with the job that issues it.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::idempotency::is_replay;
+//!
+//! let result = client
+//!     .update_table("orders")
+//!     .eq("id", 42)
+//!     .set(json!({"status": "shipped"}))
+//!     .idempotency_key(&job.id)
+//!     .execute()
+//!     .await;
+//!
+//! match result {
+//!     Ok(_) => {}
+//!     Err(error) if is_replay(&error) => {} // applied by an earlier attempt
+//!     Err(error) => return Err(error),
+//! }
+//! ```
+
+use crate::errors::{PostgrestError, RestError};
+
+/// The hint of the exception the trigger rejects a write with when its key was claimed before.
+pub const REPLAY_HINT: &str = "supabase_rs: the write was applied by an earlier request";
+
+/// Creates the trigger function claiming idempotency keys and the table it stores them in, run it
+/// once as a migration.
+///
+/// Row level security is enabled on the table without policies, so only the service role can read
+/// and clean up old keys.
+pub const IDEMPOTENCY_MIGRATION_SQL: &str = r#"
+create table if not exists public.supabase_rs_idempotency_keys (
+  key text primary key,
+  table_name text not null,
+  created_at timestamptz not null default now()
+);
+
+alter table public.supabase_rs_idempotency_keys enable row level security;
+
+create or replace function public.supabase_rs_claim_idempotency_key()
+returns trigger
+language plpgsql
+security definer
+set search_path = ''
+as $$
+declare
+  idempotency_key text := nullif(current_setting('request.headers', true), '')::json ->> 'idempotency-key';
+begin
+  if idempotency_key is null then
+    return null;
+  end if;
+
+  insert into public.supabase_rs_idempotency_keys (key, table_name)
+  values (idempotency_key, tg_table_name)
+  on conflict (key) do nothing;
+  if not found then
+    raise exception 'Idempotency key already used: %', idempotency_key
+      using errcode = 'P0001', hint = 'supabase_rs: the write was applied by an earlier request';
+  end if;
+  return null;
+end;
+$$;
+
+revoke execute on function public.supabase_rs_claim_idempotency_key() from public, anon, authenticated;
+"#;
+
+/// Whether a write failed because its idempotency key was claimed by an earlier request, which
+/// means the write was applied already.
+pub fn is_replay(error: &RestError) -> bool {
+    error
+        .postgrest_error()
+        .is_some_and(|(error, _)| is_replay_error(error))
+}
+
+/// Whether the PostgREST error is the exception the trigger raises for a claimed key.
+pub fn is_replay_error(error: &PostgrestError) -> bool {
+    error.code.as_deref() == Some("P0001") && error.hint.as_deref() == Some(REPLAY_HINT)
+}
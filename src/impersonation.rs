@@ -0,0 +1,106 @@
+//! ## Impersonation
+//!
+//! Requests are authorized with the API key of the client. To check that row level security
+//! policies behave as intended, a clone of the client can send its requests as a user or role
+//! instead, while the API key still identifies the project:
+//! - [`impersonate_user`](SupabaseClient::impersonate_user) sends an access token of a signed in user.
+//! - [`as_role`](SupabaseClient::as_role) and [`as_user`](SupabaseClient::as_user) sign a short-lived
+//!   token with the JWT secret of the project, set with [`with_jwt_secret`](SupabaseClient::with_jwt_secret).
+//!   They need the `auth` feature.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let admin = SupabaseClient::new(url, service_role_key)?.with_jwt_secret(&jwt_secret);
+//!
+//! // the policy only lets users read their own rows
+//! let rows = admin.as_user(&jane_id)?.select("notes").execute().await?;
+//! assert!(rows.iter().all(|note| note["owner_id"] == jane_id));
+//!
+//! // anonymous visitors see nothing
+//! let rows = admin.as_role("anon")?.select("notes").execute().await?;
+//! assert!(rows.is_empty());
+//! ```
+//!
+//! The token only replaces the `Authorization` header of the REST and RPC requests of the returned
+//! client, the client it was made from keeps using its API key.
+
+use crate::SupabaseClient;
+
+#[cfg(feature = "auth")]
+use crate::errors::{ErrorTypes, Result};
+#[cfg(feature = "auth")]
+use serde_json::{json, Value};
+
+/// How long the tokens signed by `as_role` and `as_user` are valid.
+#[cfg(feature = "auth")]
+const IMPERSONATION_TOKEN_TTL_SECS: u64 = 3600;
+
+impl SupabaseClient {
+    /// Returns a client sending its requests with the access token of a user, so they pass the row
+    /// level security policies as that user.
+    ///
+    /// # Arguments
+    /// * `jwt` - The access token of the user, e.g. of a `Session`.
+    pub fn impersonate_user(&self, jwt: &str) -> Self {
+        let mut client: SupabaseClient = self.clone();
+        client.access_token = Some(jwt.to_string());
+        client
+    }
+
+    /// Signs the tokens of [`as_role`](Self::as_role) and [`as_user`](Self::as_user) with `secret`,
+    /// the legacy JWT secret of the project.
+    #[cfg(feature = "auth")]
+    pub fn with_jwt_secret(mut self, secret: &str) -> Self {
+        self.jwt_secret = Some(secret.to_string());
+        self
+    }
+
+    /// Returns a client sending its requests as the Postgres `role`, e.g. `anon` or `authenticated`,
+    /// without a user.
+    ///
+    /// # Errors
+    /// [`ErrorTypes::InvalidJwt`] when no JWT secret was set or the token can't be signed.
+    #[cfg(feature = "auth")]
+    pub fn as_role(&self, role: &str) -> Result<Self> {
+        let token: String = self.sign_token(json!({ "role": role }))?;
+        Ok(self.impersonate_user(&token))
+    }
+
+    /// Returns a client sending its requests as the `authenticated` user with id `user_id`, which
+    /// `auth.uid()` returns in policies.
+    ///
+    /// # Errors
+    /// [`ErrorTypes::InvalidJwt`] when no JWT secret was set or the token can't be signed.
+    #[cfg(feature = "auth")]
+    pub fn as_user(&self, user_id: &str) -> Result<Self> {
+        let token: String = self.sign_token(json!({
+            "sub": user_id,
+            "role": "authenticated",
+            "aud": "authenticated",
+        }))?;
+        Ok(self.impersonate_user(&token))
+    }
+
+    /// Signs `claims` with the JWT secret, adding the issue and expiry times.
+    #[cfg(feature = "auth")]
+    fn sign_token(&self, mut claims: Value) -> Result<String> {
+        let secret: &str = self.jwt_secret.as_deref().ok_or_else(|| {
+            ErrorTypes::InvalidJwt("set the JWT secret with `with_jwt_secret` first".to_string())
+        })?;
+
+        let now: u64 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        claims["iss"] = json!("supabase");
+        claims["iat"] = json!(now);
+        claims["exp"] = json!(now + IMPERSONATION_TOKEN_TTL_SECS);
+
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .map_err(|error| ErrorTypes::InvalidJwt(error.to_string()))
+    }
+}
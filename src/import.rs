@@ -0,0 +1,324 @@
+//! ## Bulk imports
+//!
+//! With the `import` feature, CSV and newline delimited JSON files can be imported into a table
+//! without loading them into memory first: records are parsed one by one, collected into chunks of
+//! [`ImportOptions::batch_size`] rows and every chunk is sent as one bulk insert.
+//!
+//! Chunks are sent like the ones of [`SupabaseClient::bulk_insert_chunked`]: a chunk the server
+//! never received (a refused connection, an open circuit, or a `429` or `503` rejection) is sent
+//! again after a backoff, up to [`ImportOptions::max_retries`] times. Any other error stops the
+//! import, also a timeout, after which the chunk may have been imported. The chunks before it stay
+//! imported.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::import::{ImportOptions, ImportReport};
+//! use std::fs::File;
+//!
+//! let options = ImportOptions::new()
+//!     .batch_size(1000)
+//!     .on_progress(|progress| println!("{} rows imported", progress.rows_imported));
+//!
+//! let report: ImportReport = client
+//!     .import_csv("animals", File::open("animals.csv")?, options)
+//!     .await?;
+//! ```
+//!
+//! The readers are read synchronously between requests, which suits files and in-memory buffers.
+//!
+//! CSV fields are sent as strings, which PostgREST casts to the type of their column. Empty fields
+//! are sent as `NULL`, unless [`ImportOptions::empty_as_null`] is turned off.
+
+#![cfg(feature = "import")]
+
+use crate::bulk::{BulkOptions, BulkReport};
+use crate::errors::{Error, RestError};
+use crate::query::Returning;
+use crate::SupabaseClient;
+
+use serde_json::{Map, Value};
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, Read};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The progress of an import, reported after every imported chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// The number of the chunk, starting at 1.
+    pub chunk: usize,
+    /// The number of rows in the chunk.
+    pub rows: usize,
+    /// The number of rows imported so far, including this chunk.
+    pub rows_imported: usize,
+    /// How often the chunk was sent again because the server didn't receive it.
+    pub retries: u32,
+}
+
+/// Receives the progress of an import, see [`ImportOptions::on_progress`].
+pub type ProgressCallback = Arc<dyn Fn(&ImportProgress) + Send + Sync>;
+
+/// The outcome of a finished import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// The number of imported rows.
+    pub rows_imported: usize,
+    /// The number of chunks the rows were sent in.
+    pub chunks: usize,
+    /// How often chunks were sent again because the server didn't receive them.
+    pub retries: u32,
+}
+
+/// Options of [`SupabaseClient::import_csv`] and [`SupabaseClient::import_ndjson`].
+#[derive(Clone)]
+pub struct ImportOptions {
+    /// The number of rows sent per bulk insert, 500 by default.
+    pub batch_size: usize,
+    /// How often a chunk the server never received is sent again, 3 by default.
+    pub max_retries: u32,
+    /// The wait before the first retry of a chunk, doubled for every further retry, 500ms by default.
+    pub retry_backoff: Duration,
+    /// Whether empty CSV fields are sent as `NULL`, `true` by default.
+    pub empty_as_null: bool,
+    /// The field delimiter of CSV files, `,` by default.
+    pub delimiter: u8,
+    /// Whether columns a row leaves out take their defaults, see [`SupabaseClient::insert_with_defaults`].
+    pub missing_default: bool,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ImportOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportOptions")
+            .field("batch_size", &self.batch_size)
+            .field("max_retries", &self.max_retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("empty_as_null", &self.empty_as_null)
+            .field("delimiter", &(self.delimiter as char))
+            .field("missing_default", &self.missing_default)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl ImportOptions {
+    pub fn new() -> Self {
+        ImportOptions {
+            batch_size: 500,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+            empty_as_null: true,
+            delimiter: b',',
+            missing_default: false,
+            on_progress: None,
+        }
+    }
+
+    /// Sets the number of rows sent per bulk insert, at least 1.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets how often a chunk the server never received is sent again.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the wait before the first retry of a chunk.
+    pub fn retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// Sets whether empty CSV fields are sent as `NULL` rather than empty strings.
+    pub fn empty_as_null(mut self, empty_as_null: bool) -> Self {
+        self.empty_as_null = empty_as_null;
+        self
+    }
+
+    /// Sets the field delimiter of CSV files, e.g. `b';'` or `b'\t'`.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Fills the columns a row leaves out with their defaults, for NDJSON rows setting different columns.
+    pub fn missing_default(mut self) -> Self {
+        self.missing_default = true;
+        self
+    }
+
+    /// Calls `on_progress` after every imported chunk.
+    pub fn on_progress(
+        mut self,
+        on_progress: impl Fn(&ImportProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+}
+
+impl SupabaseClient {
+    /// Imports the records of a CSV file with a header line into `table_name`.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `reader` - The CSV source, its header line names the columns.
+    /// * `options` - The chunk size, retries and parsing options.
+    ///
+    /// # Returns
+    /// The number of imported rows and chunks, or the first parse error or failed chunk. The chunks
+    /// before a failure stay imported.
+    pub async fn import_csv<R: Read>(
+        &self,
+        table_name: &str,
+        reader: R,
+        options: ImportOptions,
+    ) -> Result<ImportReport, Error> {
+        let mut reader: csv::Reader<R> = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .from_reader(reader);
+        let headers: csv::StringRecord = reader
+            .headers()
+            .map_err(|error| Error::Message(format!("Failed to read the CSV header: {}", error)))?
+            .clone();
+
+        let empty_as_null: bool = options.empty_as_null;
+        let rows = reader.into_records().enumerate().map(|(index, record)| {
+            let record: csv::StringRecord = record.map_err(|error| {
+                Error::Message(format!(
+                    "Failed to parse CSV record {}: {}",
+                    index + 1,
+                    error
+                ))
+            })?;
+
+            let row: Map<String, Value> = headers
+                .iter()
+                .zip(record.iter())
+                .map(|(column, field)| {
+                    let value: Value = match field.is_empty() && empty_as_null {
+                        true => Value::Null,
+                        false => Value::String(field.to_string()),
+                    };
+                    (column.to_string(), value)
+                })
+                .collect();
+            Ok(Value::Object(row))
+        });
+
+        self.import_rows(table_name, rows, &options).await
+    }
+
+    /// Imports newline delimited JSON, one object per line, into `table_name`.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `reader` - The NDJSON source, blank lines are skipped.
+    /// * `options` - The chunk size and retries.
+    ///
+    /// # Returns
+    /// The number of imported rows and chunks, or the first parse error or failed chunk. The chunks
+    /// before a failure stay imported.
+    pub async fn import_ndjson<R: BufRead>(
+        &self,
+        table_name: &str,
+        reader: R,
+        options: ImportOptions,
+    ) -> Result<ImportReport, Error> {
+        let rows = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|(index, line)| {
+                let line: String = line.map_err(|error| {
+                    Error::Message(format!("Failed to read line {}: {}", index + 1, error))
+                })?;
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(row @ Value::Object(_)) => Ok(row),
+                    Ok(_) => Err(Error::Message(format!(
+                        "Line {} is not a JSON object",
+                        index + 1
+                    ))),
+                    Err(error) => Err(Error::Message(format!(
+                        "Failed to parse line {}: {}",
+                        index + 1,
+                        error
+                    ))),
+                }
+            });
+
+        self.import_rows(table_name, rows, &options).await
+    }
+
+    /// Sends the parsed rows in chunks, stopping at the first parse error or failed chunk.
+    async fn import_rows(
+        &self,
+        table_name: &str,
+        rows: impl Iterator<Item = Result<Value, Error>>,
+        options: &ImportOptions,
+    ) -> Result<ImportReport, Error> {
+        let mut report: ImportReport = ImportReport::default();
+        let mut chunk: Vec<Value> = Vec::with_capacity(options.batch_size);
+        let bulk_options: BulkOptions = BulkOptions::new()
+            .chunk_size(options.batch_size)
+            .max_retries(options.max_retries)
+            .retry_backoff(options.retry_backoff);
+
+        let mut rows = rows.peekable();
+        while let Some(row) = rows.next() {
+            chunk.push(row?);
+            if chunk.len() < options.batch_size && rows.peek().is_some() {
+                continue;
+            }
+
+            let sent: BulkReport = self
+                .run_chunks(&chunk, bulk_options, |rows: &[Value]| async move {
+                    let result: Result<Vec<Value>, RestError> = match options.missing_default {
+                        true => {
+                            self.insert_with_defaults(table_name, rows, Returning::Minimal)
+                                .await
+                        }
+                        false => {
+                            self.insert_with_returning(table_name, rows, Returning::Minimal)
+                                .await
+                        }
+                    };
+                    result.map(|_| ())
+                })
+                .await;
+            if let Some(failure) = sent.failed.first() {
+                return Err(Error::Message(format!(
+                    "Chunk {} failed, {} rows were imported before it: {}",
+                    report.chunks + 1,
+                    report.rows_imported,
+                    failure.error
+                )));
+            }
+
+            let retries: u32 = sent.retries;
+            report.chunks += 1;
+            report.rows_imported += chunk.len();
+            report.retries += retries;
+            if let Some(on_progress) = &options.on_progress {
+                on_progress(&ImportProgress {
+                    chunk: report.chunks,
+                    rows: chunk.len(),
+                    rows_imported: report.rows_imported,
+                    retries,
+                });
+            }
+            chunk.clear();
+        }
+
+        Ok(report)
+    }
+}
@@ -6,12 +6,15 @@
 //! ## Features
 //!
 //! - **Insert**: Add new rows to a table.
-//! - **Insert if Unique**: Add a new row only if it does not violate a UNIQUE constraint.
+//! - **Insert if Unique**: Add a new row only if no row with the same values exists, deprecated for **Insert if Unique on**.
+//! - **Insert if Unique on**: Add a new row unless its conflict columns exist, and tell whether it was added.
+//! - **Insert with Defaults**: Add rows setting different columns, the left out ones take their defaults.
+//! - **Bulk Insert if Unique**: Add a batch of rows, skipping those whose key columns already exist.
 //!
 //! ## Usage
 //!
 //! Before using these operations, ensure you have a valid `SupabaseClient` instance.
-//! You can then use the `insert` or `insert_if_unique` methods provided by the client to perform the desired operation.
+//! You can then use the `insert` or `insert_if_unique_on` methods provided by the client to perform the desired operation.
 //!
 //! ### Insert Example
 //!
@@ -29,19 +32,30 @@
 //! }
 //! ```
 //!
-//! ### Insert if Unique Example
+//! ### Insert if Unique on Example
 //!
+//! ```rust,ignore
+//! let inserted: bool = client
+//!     .insert_if_unique_on("users", json!({"email": "jane@example.com"}), &["email"])
+//!     .await?;
+//! if !inserted {
+//!     println!("jane@example.com is already registered");
+//! }
 //! ```
-//! # use serde_json::json;
-//! # use supabase_rs::SupabaseClient;
-//! #[tokio::main]
-//! async fn main() {
-//!     let client = SupabaseClient::new(
-//!         "your_supabase_url".to_string(), "your_supabase_key".to_string()
-//!     ).unwrap();
-//!     let unique_insert_result = client.insert_if_unique(
-//!         "your_table_name", json!({"unique_column_name": "unique_value"})
-//!     ).await;
+//!
+//! ### Bulk Insert if Unique Example
+//!
+//! ```rust,ignore
+//! use supabase_rs::insert::InsertVerdict;
+//!
+//! let verdicts: Vec<InsertVerdict> = client
+//!     .bulk_insert_if_unique("users", users, &["email"])
+//!     .await?;
+//!
+//! for (user, verdict) in users.iter().zip(&verdicts) {
+//!     if verdict.is_duplicate() {
+//!         println!("{} is already registered", user.email);
+//!     }
 //! }
 //! ```
 //!
@@ -50,9 +64,40 @@
 //! Both `insert` and `insert_if_unique` methods return a `Result<String, String>`, where `Ok(String)` contains the ID of the inserted row,
 //! and `Err(String)` contains an error message in case of failure.
 
+use crate::errors::{response_request_id, ErrorTypes, PostgrestError, RestError, DUPLICATE_ENTRY};
+use crate::query::Returning;
+use crate::request::headers::HeadersTypes;
+use crate::request::prefer::PreferBuilder;
+use crate::success::{location_row, response_rows};
+use crate::update::DuplicateResolution;
 use crate::{generate_random_id, SupabaseClient};
 use reqwest::Response;
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+
+/// The number of key values looked up per request by `bulk_insert_if_unique`, keeping URLs short.
+const UNIQUE_LOOKUP_CHUNK: usize = 100;
+
+/// The outcome of a single row of [`SupabaseClient::bulk_insert_if_unique`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InsertVerdict {
+    /// The row was inserted.
+    Inserted,
+    /// A row with the same key columns exists, either in the table or earlier in the batch.
+    Duplicate,
+    /// The row could not be inserted, with the reason.
+    Failed(String),
+}
+
+impl InsertVerdict {
+    pub fn is_inserted(&self) -> bool {
+        matches!(self, InsertVerdict::Inserted)
+    }
+
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, InsertVerdict::Duplicate)
+    }
+}
 
 impl SupabaseClient {
     /// Inserts a new row into the specified table with automatically generated ID for column `id`.
@@ -80,42 +125,14 @@ impl SupabaseClient {
     /// # Returns
     /// This method returns a `Result<String, String>`. On success, it returns `Ok(String)` with the new row's ID,
     /// and on failure, it returns `Err(String)` with an error message.
+    /// [`insert_with_returning`](Self::insert_with_returning) returns a [`RestError`] instead.
     pub async fn insert(&self, table_name: &str, mut body: Value) -> Result<String, String> {
-        let endpoint: String = format!("{}/rest/v1/{}", self.url, table_name);
-
-        #[cfg(feature = "nightly")]
-        use crate::nightly::print_nightly_warning;
-        #[cfg(feature = "nightly")]
-        print_nightly_warning();
-
         let new_id: i64 = generate_random_id();
         body["id"] = json!(new_id);
 
-        let response: Response = match self
-            .client
-            .post(&endpoint)
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", &self.api_key))
-            .header("Content-Type", "application/json")
-            .header("x_client_info", "supabase-rs/0.3.7")
-            .body(body.to_string())
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(e) => return Err(e.to_string()),
-        };
-
-        if response.status().is_success() {
-            Ok(new_id.to_string())
-        } else if response.status().as_u16() == 409 {
-            println!("\x1b[31mError 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.\x1b[0m");
-
-            return Err("\x1b[31mError 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.\x1b[0m".to_string());
-        } else {
-            println!("\x1b[31mError: {:?}\x1b[0m", response);
-            return Err(response.status().to_string());
-        }
+        self.post_rows(table_name, body.to_string(), Returning::Minimal)
+            .await?;
+        Ok(new_id.to_string())
     }
 
     /// Inserts a new row into the specified table with a user-defined ID or Supabase backend generated ID.
@@ -149,42 +166,19 @@ impl SupabaseClient {
         table_name: &str,
         body: Value,
     ) -> Result<(), String> {
-        let endpoint: String = format!("{}/rest/v1/{}", self.url, table_name);
-
-        #[cfg(feature = "nightly")]
-        use crate::nightly::print_nightly_warning;
-        #[cfg(feature = "nightly")]
-        print_nightly_warning();
-
-        let response: Response = match self
-            .client
-            .post(&endpoint)
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", &self.api_key))
-            .header("Content-Type", "application/json")
-            .header("x_client_info", "supabase-rs/0.3.7")
-            .body(body.to_string())
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(e) => return Err(e.to_string()),
-        };
-
-        if response.status().is_success() {
-            Ok(())
-        } else if response.status().as_u16() == 409 {
-            println!("\x1b[31mError 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.\x1b[0m");
-
-            return Err("\x1b[31mError 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.\x1b[0m".to_string());
-        } else {
-            println!("\x1b[31mError: {:?}\x1b[0m", response);
-            return Err(response.status().to_string());
-        }
+        self.post_rows(table_name, body.to_string(), Returning::Minimal)
+            .await?;
+        Ok(())
     }
 
     /// Inserts a row into the specified table if the value is unique and does not exist in the table already.
     ///
+    /// A row matching every column-value pair of `body` is looked up first, and the row is only
+    /// inserted when there is none. Two clients inserting the same value at once can both pass the
+    /// check, only a UNIQUE constraint rejects the second insert. Use
+    /// [`insert_if_unique_on`](Self::insert_if_unique_on) instead, which checks and inserts in a
+    /// single request.
+    ///
     /// # Arguments
     /// * `table_name` - A string slice that holds the name of the table.
     /// * `body` - A JSON value containing the data to be inserted.
@@ -209,12 +203,16 @@ impl SupabaseClient {
     /// # Returns
     /// This method returns a `Result<String, String>`. On success, it returns `Ok(String)` with the new row's ID,
     /// and on failure, it returns `Err(String)` with an error message indicating a duplicate entry.
+    #[deprecated(
+        note = "use `insert_if_unique_on`, which skips duplicates of a unique constraint in a single request"
+    )]
     pub async fn insert_if_unique(&self, table_name: &str, body: Value) -> Result<String, String> {
-        let conditions: &serde_json::Map<String, Value> = match body.as_object() {
+        let conditions: &Map<String, Value> = match body.as_object() {
             Some(map) => map,
             None => {
-                println!("\x1b[31mFailed to parse body as JSON object\x1b[0m");
-                return Err("Failed to parse body as JSON object".to_string());
+                #[cfg(feature = "tracing")]
+                tracing::warn!(table = table_name, "insert body is not a JSON object");
+                return Err("Failed to parse body as JSON object".into());
             }
         };
 
@@ -232,19 +230,189 @@ impl SupabaseClient {
             query = query.eq(column_name, column_value_str.as_str());
         }
 
-        let response: Result<Vec<Value>, String> = query.execute().await;
+        let response: Result<Vec<Value>, RestError> = query.fetch().await;
 
-        // If no existing row matches all conditions, proceed with the insert
+        // If no existing row matches all conditions, proceed with the insert, whose unique
+        // violation still comes back as `DUPLICATE_ENTRY` when a concurrent insert won
         if let Ok(results) = response {
             if results.is_empty() {
                 return self.insert(table_name, body).await;
             }
         } else {
-            println!("\x1b[31mFailed to execute select query\x1b[0m");
-            return Err("Failed to execute select query".to_string());
+            #[cfg(feature = "tracing")]
+            tracing::warn!(table = table_name, "select of existing rows failed");
+            return Err("Failed to execute select query".into());
         }
 
-        Err("Error 409: Duplicate entry. The values you're trying to insert may already exist in a column with a UNIQUE constraint".to_string())
+        Err(DUPLICATE_ENTRY.to_string())
+    }
+
+    /// Inserts a row unless a row with the same values in the `on_conflict` columns exists, in a
+    /// single `ON CONFLICT DO NOTHING` request.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table.
+    /// * `row` - The row to insert, serializing to a JSON object.
+    /// * `on_conflict` - The columns of a unique constraint to detect duplicates on, e.g. `&["email"]`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let inserted: bool = client
+    ///     .insert_if_unique_on("users", json!({"email": "jane@example.com"}), &["email"])
+    ///     .await?;
+    /// ```
+    ///
+    /// # Returns
+    /// `true` when the row was inserted, `false` when it existed already, or a `RestError`.
+    pub async fn insert_if_unique_on<T>(
+        &self,
+        table_name: &str,
+        row: T,
+        on_conflict: &[&str],
+    ) -> Result<bool, RestError>
+    where
+        T: serde::Serialize,
+    {
+        if on_conflict.is_empty() {
+            return Err("At least one unique column is required".into());
+        }
+        let row: Value = match serde_json::to_value(row) {
+            Ok(row @ Value::Object(_)) => row,
+            _ => return Err("Failed to parse body as JSON object".into()),
+        };
+
+        // skipped rows are left out of the representation
+        let inserted: Vec<Value> = self
+            .upsert_with_returning(
+                table_name,
+                vec![row],
+                on_conflict,
+                DuplicateResolution::IgnoreDuplicates,
+                Returning::Representation,
+            )
+            .await?;
+        Ok(!inserted.is_empty())
+    }
+
+    /// Inserts the rows whose key columns don't exist in the table yet, and reports what happened to each row.
+    ///
+    /// The existing keys are fetched with `in.()` lookups rather than one select per row, then the new rows
+    /// are inserted in a single request. When that request hits a conflict (e.g. a concurrent insert),
+    /// the rows are retried one by one so every row still gets its own verdict.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table.
+    /// * `body` - The rows to insert, each serializing to a JSON object.
+    /// * `unique_columns` - The columns which together identify a row.
+    ///
+    /// # Returns
+    /// One [`InsertVerdict`] per row, in the order of `body`, or a `RestError` when the rows can't be
+    /// serialized or the existing keys can't be fetched.
+    pub async fn bulk_insert_if_unique<T>(
+        &self,
+        table_name: &str,
+        body: Vec<T>,
+        unique_columns: &[&str],
+    ) -> Result<Vec<InsertVerdict>, RestError>
+    where
+        T: serde::Serialize,
+    {
+        if unique_columns.is_empty() {
+            return Err("At least one unique column is required".into());
+        }
+
+        let mut rows: Vec<Map<String, Value>> = Vec::with_capacity(body.len());
+        for row in body {
+            match serde_json::to_value(row) {
+                Ok(Value::Object(row)) => rows.push(row),
+                _ => return Err("Failed to parse body as JSON object".into()),
+            }
+        }
+
+        let keys: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| unique_key(row, unique_columns))
+            .collect();
+        let existing: HashSet<Vec<String>> = self
+            .existing_unique_keys(table_name, unique_columns, &keys)
+            .await?;
+
+        let mut seen: HashSet<&Vec<String>> = HashSet::new();
+        let mut verdicts: Vec<InsertVerdict> = Vec::with_capacity(rows.len());
+        let mut pending: Vec<usize> = Vec::new();
+        for (index, key) in keys.iter().enumerate() {
+            if existing.contains(key) || !seen.insert(key) {
+                verdicts.push(InsertVerdict::Duplicate);
+            } else {
+                verdicts.push(InsertVerdict::Inserted);
+                pending.push(index);
+            }
+        }
+        if pending.is_empty() {
+            return Ok(verdicts);
+        }
+
+        let batch: Value = Value::Array(
+            pending
+                .iter()
+                .map(|index| Value::Object(rows[*index].clone()))
+                .collect(),
+        );
+        match self
+            .post_rows(table_name, batch.to_string(), Returning::Minimal)
+            .await
+        {
+            Ok(_) => {}
+            Err(error) if error.is_unique_violation() => {
+                // somebody inserted one of the keys in the meantime, find out which row it was
+                for index in pending {
+                    let row: String =
+                        Value::Array(vec![Value::Object(rows[index].clone())]).to_string();
+                    verdicts[index] =
+                        match self.post_rows(table_name, row, Returning::Minimal).await {
+                            Ok(_) => InsertVerdict::Inserted,
+                            Err(error) if error.is_unique_violation() => InsertVerdict::Duplicate,
+                            Err(error) => InsertVerdict::Failed(error.to_string()),
+                        };
+                }
+            }
+            Err(error) => {
+                for index in pending {
+                    verdicts[index] = InsertVerdict::Failed(error.to_string());
+                }
+            }
+        }
+
+        Ok(verdicts)
+    }
+
+    /// Fetches which of `keys` already exist in the table.
+    async fn existing_unique_keys(
+        &self,
+        table_name: &str,
+        unique_columns: &[&str],
+        keys: &[Vec<String>],
+    ) -> Result<HashSet<Vec<String>>, RestError> {
+        let mut existing: HashSet<Vec<String>> = HashSet::new();
+
+        for chunk in keys.chunks(UNIQUE_LOOKUP_CHUNK) {
+            let mut query: crate::query::QueryBuilder =
+                self.select(table_name).columns(unique_columns.to_vec());
+            for (position, column) in unique_columns.iter().enumerate() {
+                let values: HashSet<&str> =
+                    chunk.iter().map(|key| key[position].as_str()).collect();
+                query.query.add_param(column, &in_filter(values));
+            }
+
+            // every column is filtered on its own, so keep only the rows matching a whole key
+            for row in query.execute().await? {
+                if let Value::Object(row) = row {
+                    existing.insert(unique_key(&row, unique_columns));
+                }
+            }
+        }
+
+        Ok(existing)
     }
 
     /// Inserts new rows into the specified table in bulk.
@@ -278,47 +446,293 @@ impl SupabaseClient {
     /// let insert_result = client.insert("your_table_name", body).await;
     /// ```
     ///
+    /// # Payload limit
+    /// When the client has a [`PayloadLimit`](crate::payload::PayloadLimit), payloads above it are either
+    /// split into several requests (sent in order) or rejected before reaching the server.
+    ///
     /// # Returns
     /// This method returns a `Result<(), String>`. On success, it returns `Ok(())`,
     /// and on failure, it returns `Err(String)` with an error message.
+    /// [`insert_with_returning`](Self::insert_with_returning) returns a [`RestError`] instead.
     pub async fn bulk_insert<T>(&self, table_name: &str, body: Vec<T>) -> Result<(), String>
+    where
+        T: serde::Serialize,
+    {
+        self.insert_with_returning(table_name, body, Returning::Minimal)
+            .await?;
+        Ok(())
+    }
+
+    /// Inserts a row or a list of rows as they are, and returns what `returning` asks the server for.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table.
+    /// * `body` - A single row or a list of rows, serialized as JSON.
+    /// * `returning` - What the server sends back, [`Returning::Minimal`] skips the response body.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use supabase_rs::query::Returning;
+    ///
+    /// // the ids and defaults filled in by the database
+    /// let users: Vec<Value> = client
+    ///     .insert_with_returning("users", json!([{"name": "Jane"}]), Returning::Representation)
+    ///     .await?;
+    /// ```
+    ///
+    /// # Payload limit
+    /// Bodies above the [`PayloadLimit`](crate::payload::PayloadLimit) are split like in `bulk_insert`,
+    /// and the rows of every batch are returned in order.
+    ///
+    /// # Returns
+    /// The inserted rows with [`Returning::Representation`], the primary key of the inserted row
+    /// with [`Returning::HeadersOnly`], an empty vector otherwise, or the error.
+    pub async fn insert_with_returning<T>(
+        &self,
+        table_name: &str,
+        body: T,
+        returning: Returning,
+    ) -> Result<Vec<Value>, RestError>
+    where
+        T: serde::Serialize,
+    {
+        let Ok(body) = serde_json::to_value(body) else {
+            return Err("Failed to serialize body".into());
+        };
+
+        self.insert_rows(table_name, body, returning, None).await
+    }
+
+    /// Inserts rows which don't all set the same columns, filling the columns a row leaves out
+    /// with their defaults (`Prefer: missing=default`) instead of `NULL`.
+    ///
+    /// PostgREST takes the columns of a bulk insert from its first row, so a `NOT NULL` column with
+    /// a default that the first row sets but a later row leaves out fails the whole insert. The
+    /// columns of all rows are named in the `columns` parameter instead.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table.
+    /// * `body` - A single row or a list of rows, serialized as JSON.
+    /// * `returning` - What the server sends back, [`Returning::Minimal`] skips the response body.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use supabase_rs::query::Returning;
+    ///
+    /// // `status` falls back to its default for Jane instead of violating its NOT NULL constraint
+    /// client
+    ///     .insert_with_defaults(
+    ///         "users",
+    ///         json!([{"name": "John", "status": "active"}, {"name": "Jane"}]),
+    ///         Returning::Minimal,
+    ///     )
+    ///     .await?;
+    /// ```
+    ///
+    /// # Returns
+    /// The inserted rows with [`Returning::Representation`], the primary key of the inserted row
+    /// with [`Returning::HeadersOnly`], an empty vector otherwise, or the error.
+    pub async fn insert_with_defaults<T>(
+        &self,
+        table_name: &str,
+        body: T,
+        returning: Returning,
+    ) -> Result<Vec<Value>, RestError>
     where
         T: serde::Serialize,
     {
         let Ok(body) = serde_json::to_value(body) else {
-            return Err("Failed to serialize body".to_string());
+            return Err("Failed to serialize body".into());
         };
-        let endpoint: String = format!("{}/rest/v1/{}", self.url, table_name);
+
+        let columns: String = row_columns(&body).join(",");
+        self.insert_rows(table_name, body, returning, Some(&columns))
+            .await
+    }
+
+    /// Inserts a list of rows like [`bulk_insert`](Self::bulk_insert), filling the columns a row
+    /// leaves out with their defaults, see [`insert_with_defaults`](Self::insert_with_defaults).
+    pub async fn bulk_insert_with_defaults<T>(
+        &self,
+        table_name: &str,
+        body: Vec<T>,
+    ) -> Result<(), RestError>
+    where
+        T: serde::Serialize,
+    {
+        self.insert_with_defaults(table_name, body, Returning::Minimal)
+            .await?;
+        Ok(())
+    }
+
+    /// Inserts a row or a list of rows in batches below the payload limit, naming `columns` when given.
+    async fn insert_rows(
+        &self,
+        table_name: &str,
+        body: Value,
+        returning: Returning,
+        columns: Option<&str>,
+    ) -> Result<Vec<Value>, RestError> {
+        let mut batches: Vec<String> = self.payload_batches(&body)?;
+        let batch_count: usize = batches.len();
+        if batch_count == 1 {
+            return self
+                .post_rows_with(table_name, batches.remove(0), returning, columns)
+                .await;
+        }
+
+        // send the batches in order and stop at the first failure
+        let mut rows: Vec<Value> = Vec::new();
+        for (index, batch) in batches.into_iter().enumerate() {
+            match self
+                .post_rows_with(table_name, batch, returning, columns)
+                .await
+            {
+                Ok(batch_rows) => rows.extend(batch_rows),
+                Err(error) => {
+                    let message: String = format!(
+                        "Batch {} of {} failed, earlier batches were inserted: {}",
+                        index + 1,
+                        batch_count,
+                        error
+                    );
+                    return Err(error.with_message(message));
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Sends a serialized row or JSON array of rows to the specified table.
+    ///
+    /// # Returns
+    /// The inserted rows with [`Returning::Representation`], an empty vector otherwise.
+    async fn post_rows(
+        &self,
+        table_name: &str,
+        body: String,
+        returning: Returning,
+    ) -> Result<Vec<Value>, RestError> {
+        self.post_rows_with(table_name, body, returning, None).await
+    }
+
+    /// Sends rows like [`post_rows`](Self::post_rows), with the left out `columns` taking their defaults.
+    async fn post_rows_with(
+        &self,
+        table_name: &str,
+        body: String,
+        returning: Returning,
+        columns: Option<&str>,
+    ) -> Result<Vec<Value>, RestError> {
+        let mut endpoint: String = format!("{}/rest/v1/{}", self.url, table_name);
+        let mut prefer: PreferBuilder = PreferBuilder::new().returning(returning);
+        if let Some(columns) = columns {
+            endpoint = format!("{}?columns={}", endpoint, columns);
+            prefer = prefer.missing_default();
+        }
 
         #[cfg(feature = "nightly")]
         use crate::nightly::print_nightly_warning;
         #[cfg(feature = "nightly")]
         print_nightly_warning();
 
-        let response: Response = match self
-            .client
-            .post(&endpoint)
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", &self.api_key))
-            .header("Content-Type", "application/json")
-            .header("x_client_info", "supabase-rs/0.3.7")
-            .body(body.to_string())
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(e) => return Err(e.to_string()),
-        };
+        let response: Response = self
+            .dispatch(
+                table_name,
+                "insert",
+                self.content_profile(
+                    self.client
+                        .post(&endpoint)
+                        .header("apikey", &self.api_key)
+                        .header("Authorization", format!("Bearer {}", &self.api_key))
+                        .header("Content-Type", "application/json")
+                        .header("x_client_info", "supabase-rs/0.3.7")
+                        .header(HeadersTypes::Prefer.as_str(), prefer.build())
+                        .body(body),
+                ),
+            )
+            .await?;
 
         if response.status().is_success() {
-            Ok(())
-        } else if response.status().as_u16() == 409 {
-            println!("\x1b[31mError 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.\x1b[0m");
-
-            return Err("\x1b[31mError 409: Duplicate entry. The value you're trying to insert may already exist in a column with a UNIQUE constraint.\x1b[0m".to_string());
+            match returning {
+                Returning::Representation => response_rows(response).await,
+                Returning::HeadersOnly => Ok(location_row(&response).into_iter().collect()),
+                Returning::Minimal => Ok(Vec::new()),
+            }
         } else {
-            println!("\x1b[31mError: {:?}\x1b[0m", response);
-            return Err(response.status().to_string());
+            let request_id: Option<String> = response_request_id(&response);
+            let error: ErrorTypes = match ErrorTypes::from_postgrest_response(response).await {
+                // a bodyless 409 is taken for a unique violation too, like before the body was read
+                ErrorTypes::ApiError { status: 409, .. } => {
+                    ErrorTypes::UniqueViolation(duplicate_entry())
+                }
+                error => error,
+            };
+            let error: RestError = match error {
+                error @ ErrorTypes::UniqueViolation(_) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        table = table_name,
+                        "duplicate entry, a UNIQUE constraint was violated"
+                    );
+
+                    RestError::new(error).with_message(DUPLICATE_ENTRY)
+                }
+                error => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(table = table_name, error = %error, "insert failed");
+                    RestError::new(error)
+                }
+            };
+            Err(error.with_request_id(request_id))
         }
     }
 }
+
+/// The unique violation of a row found in the table, or of a `409` without a PostgREST body.
+fn duplicate_entry() -> PostgrestError {
+    PostgrestError {
+        code: Some("23505".to_string()),
+        message: DUPLICATE_ENTRY.to_string(),
+        ..PostgrestError::default()
+    }
+}
+
+/// The columns set by any of the rows, in the order they first appear.
+fn row_columns(body: &Value) -> Vec<&str> {
+    let rows: &[Value] = match body {
+        Value::Array(rows) => rows,
+        row => std::slice::from_ref(row),
+    };
+
+    let mut columns: Vec<&str> = Vec::new();
+    for column in rows.iter().filter_map(Value::as_object).flat_map(Map::keys) {
+        if !columns.contains(&column.as_str()) {
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+/// The values of the unique columns of a row, as they appear in a filter.
+pub(crate) fn unique_key(row: &Map<String, Value>, unique_columns: &[&str]) -> Vec<String> {
+    unique_columns
+        .iter()
+        .map(|column| match row.get(*column) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => "null".to_string(),
+            Some(value) => value.to_string(),
+        })
+        .collect()
+}
+
+/// Builds an `in.()` filter, quoting every value so commas and parentheses are kept intact.
+pub(crate) fn in_filter<'a>(values: impl IntoIterator<Item = &'a str>) -> String {
+    let mut values: Vec<String> = values
+        .into_iter()
+        .map(|value| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    values.sort();
+    format!("in.({})", values.join(","))
+}
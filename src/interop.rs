@@ -0,0 +1,113 @@
+//! ## `postgrest` interop
+//!
+//! Helpers for moving code written against the [`postgrest`](https://docs.rs/postgrest) crate onto
+//! `SupabaseClient`, without depending on that crate.
+//!
+//! - [`SupabaseClient::from_postgrest_config`] builds a client from the REST URL and headers a
+//!   `Postgrest` client was configured with.
+//! - [`PostgrestCompat`] adds the builder calls of `postgrest` that `QueryBuilder` names differently,
+//!   so most call chains keep compiling after swapping the client.
+//!
+//! ### Migrating
+//! ```rust,ignore
+//! // before
+//! let client = Postgrest::new("https://project.supabase.co/rest/v1").insert_header("apikey", key);
+//! let response = client.from("users").select("id,email").eq("plan", "pro").limit(10).execute().await?;
+//! let users: Vec<Value> = response.json().await?;
+//!
+//! // after
+//! use supabase_rs::interop::PostgrestCompat;
+//!
+//! let client = SupabaseClient::from_postgrest_config(
+//!     "https://project.supabase.co/rest/v1",
+//!     &[("apikey", key.as_str())],
+//! )?;
+//! let users: Vec<Value> = client.from("users").select("id,email").eq("plan", "pro").limit(10).execute().await?;
+//! ```
+//!
+//! | `postgrest` | `supabase_rs` |
+//! |---|---|
+//! | `Postgrest::new(url).insert_header("apikey", key)` | `SupabaseClient::from_postgrest_config(url, &[("apikey", key)])` |
+//! | `.from(table).select(columns)` | `.from(table).select(columns)` with [`PostgrestCompat`] |
+//! | `.eq`, `.neq`, `.gt`, `.gte`, `.lt`, `.lte` | the `QueryBuilder` methods of the same name |
+//! | `.in_(column, values)`, `.order(columns)`, `.limit(count)` | [`PostgrestCompat`] |
+//! | `.execute().await?.json().await?` | `.execute().await?`, already deserialized |
+//! | `.from(table).insert(body)` | [`SupabaseClient::insert`] or `bulk_insert` |
+//! | `.from(table).update(body)` | [`SupabaseClient::update_table`] |
+//! | `.from(table).delete()` | [`SupabaseClient::delete_from`] |
+//! | `.rpc(function, params)` | [`SupabaseClient::rpc`] |
+
+use crate::errors::{ErrorTypes, Result};
+use crate::insert::in_filter;
+use crate::query::QueryBuilder;
+use crate::SupabaseClient;
+
+impl SupabaseClient {
+    /// Builds a client from the configuration of a `postgrest` client.
+    ///
+    /// # Arguments
+    /// * `rest_url` - The URL the `Postgrest` client was created with, e.g. `https://project.supabase.co/rest/v1`.
+    /// * `headers` - The headers inserted on the `Postgrest` client, the key is read from `apikey`,
+    ///   or from a bearer `Authorization` header when there is none.
+    ///
+    /// # Returns
+    /// The client, or [`ErrorTypes::ApiKeyMissing`] when neither header holds a key.
+    pub fn from_postgrest_config(rest_url: &str, headers: &[(&str, &str)]) -> Result<Self> {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim())
+        };
+        let api_key: &str = header("apikey")
+            .or_else(|| header("authorization").and_then(|value| value.strip_prefix("Bearer ")))
+            .filter(|key| !key.is_empty())
+            .ok_or(ErrorTypes::ApiKeyMissing)?;
+
+        SupabaseClient::new(project_url(rest_url), api_key.to_string())
+    }
+}
+
+/// Strips the REST path from a `postgrest` URL, leaving the project URL.
+pub(crate) fn project_url(rest_url: &str) -> String {
+    let url: &str = rest_url.trim_end_matches('/');
+    url.strip_suffix("/rest/v1").unwrap_or(url).to_string()
+}
+
+/// The `postgrest` builder calls that `QueryBuilder` doesn't have under the same name.
+pub trait PostgrestCompat {
+    /// Selects a comma separated list of columns, like `postgrest`'s `select("id,email")`.
+    fn select(self, columns: &str) -> Self;
+
+    /// Matches rows whose column equals one of `values`.
+    fn in_(self, column: &str, values: &[&str]) -> Self;
+
+    /// Orders by a PostgREST order expression, e.g. `created_at.desc,id`, after the previous orderings.
+    fn order(self, columns: &str) -> Self;
+
+    /// Limits the number of rows returned.
+    fn limit(self, count: usize) -> Self;
+}
+
+impl PostgrestCompat for QueryBuilder {
+    fn select(mut self, columns: &str) -> Self {
+        self.query.add_param("select", &columns.replace(' ', ""));
+        self
+    }
+
+    fn in_(mut self, column: &str, values: &[&str]) -> Self {
+        self.query
+            .add_param(column, &in_filter(values.iter().copied()));
+        self
+    }
+
+    fn order(mut self, columns: &str) -> Self {
+        self.query.append_order(columns);
+        self
+    }
+
+    fn limit(mut self, count: usize) -> Self {
+        self.query.add_param("limit", &count.to_string());
+        self
+    }
+}
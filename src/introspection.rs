@@ -0,0 +1,230 @@
+//! ## Schema introspection
+//!
+//! PostgREST describes the exposed schema as an OpenAPI (Swagger 2.0) document on the root of the
+//! REST endpoint. This module fetches that document and parses the table and function definitions
+//! out of it, which is what `type_gen` and other schema-aware helpers build on.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let openapi: Value = client.fetch_openapi().await?;
+//! let tables: Vec<TableDefinition> = parse_table_definitions(&openapi);
+//! let functions: Vec<FunctionDefinition> = parse_function_definitions(&openapi);
+//! ```
+
+use crate::errors::RestError;
+use crate::request::headers::HeadersTypes;
+use crate::request::Headers;
+use crate::success::{unexpected_response, write_error};
+use crate::SupabaseClient;
+
+use reqwest::Response;
+use serde_json::Value;
+
+/// A column of a table as described by the PostgREST OpenAPI document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDefinition {
+    /// The column name.
+    pub name: String,
+    /// The Postgres type, e.g. `bigint`, `text[]` or `timestamp with time zone`.
+    pub pg_type: String,
+    /// The JSON schema type, e.g. `integer`, `string` or `array`.
+    pub json_type: String,
+    /// Whether the column is `NOT NULL`.
+    pub required: bool,
+    /// Whether the column is (part of) the primary key.
+    pub primary_key: bool,
+    /// The column comment, if any.
+    pub description: Option<String>,
+    /// The column referenced by this column, if it is a foreign key.
+    pub foreign_key: Option<ForeignKey>,
+    /// The allowed values when the column is a Postgres enum, or an array of one, empty otherwise.
+    pub enum_values: Vec<String>,
+}
+
+/// The target of a foreign key column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKey {
+    /// The referenced table.
+    pub table: String,
+    /// The referenced column.
+    pub column: String,
+}
+
+/// A table or view as described by the PostgREST OpenAPI document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDefinition {
+    /// The table name.
+    pub name: String,
+    /// The columns of the table, sorted by name.
+    pub columns: Vec<ColumnDefinition>,
+}
+
+/// A function exposed on `/rpc/{name}`, as described by the PostgREST OpenAPI document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDefinition {
+    /// The function name.
+    pub name: String,
+    /// The arguments of the function, sorted by name. Arguments with a default aren't `required`.
+    pub arguments: Vec<ColumnDefinition>,
+    /// The function comment, if any.
+    pub description: Option<String>,
+}
+
+impl TableDefinition {
+    /// Returns the name of the first primary key column, if the table has one.
+    pub fn primary_key(&self) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|column| column.primary_key)
+            .map(|column| column.name.as_str())
+    }
+}
+
+impl SupabaseClient {
+    /// Fetches the OpenAPI document PostgREST serves for the exposed schema.
+    ///
+    /// # Returns
+    /// A `Result` containing the raw OpenAPI document, or a `RestError`.
+    pub async fn fetch_openapi(&self) -> Result<Value, RestError> {
+        let endpoint: String = format!("{}/rest/v1/", self.url);
+        let mut headers: Headers = Headers::with_defaults(&self.api_key, &self.api_key);
+        if let Some(schema) = &self.schema {
+            headers.insert(HeadersTypes::AcceptProfile.as_str(), schema);
+        }
+
+        let mut request = self.client.get(&endpoint);
+        for (key, value) in headers.get_headers() {
+            request = request.header(key, value);
+        }
+
+        let response: Response = self.dispatch("", "introspect", request).await?;
+        if !response.status().is_success() {
+            return Err(write_error(response).await);
+        }
+
+        response.json::<Value>().await.map_err(unexpected_response)
+    }
+}
+
+/// Parses every table definition out of a PostgREST OpenAPI document, sorted by name.
+///
+/// # Arguments
+/// * `openapi` - The document returned by [`SupabaseClient::fetch_openapi`].
+pub fn parse_table_definitions(openapi: &Value) -> Vec<TableDefinition> {
+    let Some(definitions) = openapi["definitions"].as_object() else {
+        return Vec::new();
+    };
+
+    let mut tables: Vec<TableDefinition> = definitions
+        .iter()
+        .map(|(name, definition)| parse_table_definition(name, definition))
+        .collect();
+
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+    tables
+}
+
+/// Parses every function definition out of a PostgREST OpenAPI document, sorted by name.
+///
+/// # Arguments
+/// * `openapi` - The document returned by [`SupabaseClient::fetch_openapi`].
+pub fn parse_function_definitions(openapi: &Value) -> Vec<FunctionDefinition> {
+    let Some(paths) = openapi["paths"].as_object() else {
+        return Vec::new();
+    };
+
+    let mut functions: Vec<FunctionDefinition> = paths
+        .iter()
+        .filter_map(|(path, operations)| {
+            let name: &str = path.strip_prefix("/rpc/")?;
+            let operation: &Value = &operations["post"];
+            let body: Option<&Value> = operation["parameters"]
+                .as_array()?
+                .iter()
+                .find(|parameter| parameter["in"] == "body");
+
+            Some(FunctionDefinition {
+                name: name.to_string(),
+                arguments: body
+                    .map(|body| parse_columns(&body["schema"]))
+                    .unwrap_or_default(),
+                description: operation["summary"].as_str().map(str::to_string),
+            })
+        })
+        .collect();
+
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    functions
+}
+
+fn parse_table_definition(name: &str, definition: &Value) -> TableDefinition {
+    TableDefinition {
+        name: name.to_string(),
+        columns: parse_columns(definition),
+    }
+}
+
+/// Parses the properties of an object schema, the columns of a table or the arguments of a function.
+fn parse_columns(definition: &Value) -> Vec<ColumnDefinition> {
+    let required: Vec<&str> = definition["required"]
+        .as_array()
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    definition["properties"]
+        .as_object()
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(column, property)| {
+                    let description: Option<String> =
+                        property["description"].as_str().map(str::to_string);
+
+                    ColumnDefinition {
+                        name: column.clone(),
+                        pg_type: property["format"].as_str().unwrap_or("text").to_string(),
+                        json_type: property["type"].as_str().unwrap_or("string").to_string(),
+                        required: required.contains(&column.as_str()),
+                        primary_key: description
+                            .as_deref()
+                            .is_some_and(|description| description.contains("<pk/>")),
+                        foreign_key: description.as_deref().and_then(parse_foreign_key),
+                        enum_values: parse_enum_values(property),
+                        description,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the values of an enum column, listed on the property itself or on the items of an array.
+fn parse_enum_values(property: &Value) -> Vec<String> {
+    property["enum"]
+        .as_array()
+        .or_else(|| property["items"]["enum"].as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the `<fk table='authors' column='id'/>` marker PostgREST adds to foreign key descriptions.
+fn parse_foreign_key(description: &str) -> Option<ForeignKey> {
+    let marker: &str = &description[description.find("<fk ")?..];
+    let marker: &str = &marker[..marker.find("/>")?];
+    let attribute = |name: &str| -> Option<String> {
+        let start: usize = marker.find(&format!("{}='", name))? + name.len() + 2;
+        let end: usize = start + marker[start..].find('\'')?;
+        Some(marker[start..end].to_string())
+    };
+
+    Some(ForeignKey {
+        table: attribute("table")?,
+        column: attribute("column")?,
+    })
+}
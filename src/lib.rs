@@ -5,12 +5,24 @@
 //! ## Features
 //! - [**`Insert`**](#insert): Add new rows to a table.
 //! - [**`Insert if unique`**](#insert-if-unique): Add a new row only if it does not violate a UNIQUE constraint.
+//! - [**`Bulk insert if unique`**](insert): Insert a batch of rows, skipping existing keys with a verdict per row.
 //! - [**`Update`**](#update): Modify existing rows in a table based on a unique identifier.
 //! - [**`Select`**](#select): Insert a new row into a table if it does not exist, or update it if it does.
 //! - [**`Select with count`**](#select-with-count): Select rows from a table and count the number of rows that match the filter criteria.
 //! - [**`Select with filter`**](#select-with-filter): Select rows from a table based on a filter criteria.
 //! - [**`Select with filter and count`**](#selecting-with-filter-and-count): Select rows from a table based on a filter criteria and count the number of rows that match the filter criteria.
+//! - [**`Select related`**](query_builder::embed): Embed the rows of related tables in one select.
 //! - [**`Delete`**](#delete): Delete a row from a table based on a unique identifier.
+//! - [**`RPC`**](rpc): Call Postgres functions exposed by PostgREST.
+//! - [**`Queues`**](queues): Send, read, archive and delete `pgmq` queue messages.
+//! - [**`Functions`**](functions): Invoke Edge Functions, with typed or streamed responses.
+//! - [**`Cron`**](cron): Schedule, list and remove `pg_cron` jobs with the service role key.
+//! - [**`Locks`**](lock): Take a lock shared by every instance of a service, with a time to live.
+//! - [**`Batches`**](batch): Apply several writes in one transaction, all or nothing.
+//!
+//! Coming from the `postgrest` crate? See [`interop`] for the migration helpers.
+//!
+//! Import the common types at once with `use supabase_rs::prelude::*;`, see [`prelude`].
 //!
 //! ## Graphql features
 //! - [**`Query request`**](#query-request): Runs a GraphQL query to supabase
@@ -19,6 +31,19 @@
 //! - **`storage`**: Enables the `Storage` module to interact with Supabase Storage.
 //! - **`nightly`**: Enables the nightly features.
 //! - **`rustls`**: Forces the client into using `rustls` over `OpenSSL`.
+//! - **`metrics`**: Records request counters and latencies through the [`metrics`](https://docs.rs/metrics) facade.
+//! - **`cache`**: Caches the rows of selects with a TTL, see [`cache`].
+//! - **`tracing`**: Instruments every request with a [`tracing`](https://docs.rs/tracing) span and an event with its status and latency.
+//! - **`derive`**: Enables `#[derive(SupabaseTable)]` for table-backed structs.
+//! - **`realtime`**: Enables the Realtime websocket client, with broadcast and presence channels and row
+//!   changes, and publishing broadcast messages over HTTP.
+//! - **`cancellation`**: Enables aborting queries with a `tokio_util` `CancellationToken`.
+//! - **`admin`**: Enables service role helpers for Supabase Vault secrets.
+//! - **`auth`**: Enables the `auth` module, with user sessions, MFA, JWT verification and service role user management on the Auth API.
+//! - **`uuid`**, **`chrono`**: Enable filtering on `uuid` and `chrono` values, see [`FilterValue`](query::FilterValue).
+//! - **`audit`**: Enables reporting every write to an audit sink, see [`audit`].
+//! - **`import`**: Enables importing CSV and NDJSON files in chunks, see [`import`].
+//! - **`type_gen`**: Enables generating typed table structs, column enums, Postgres enums and RPC wrappers from the exposed schema.
 //!
 //! ## Nightly Build
 //! - **`nightly`**: Enables the `GraphQL` module to interact with Supabase GraphQL API.
@@ -102,10 +127,11 @@
 //!
 //! ### Insert if unique
 //! This will insert a new row into the `test` table with the value `value_test` in the `dog` column if the value is unique.
-//! It's a drop-in replacement for `insert` without relying on Supabase's unique constraints on the database.
+//! The `dog` column needs a UNIQUE constraint, a row with the same value is skipped in the same request.
 //!
 //! ```rust,ignore
 //! use serde_json::json;
+//! use supabase_rs::errors::RestError;
 //! use supabase_rs::SupabaseClient;
 //!
 //! // always pass an initialized SupabaseClient to the method
@@ -115,14 +141,15 @@
 //!
 //! async fn insert_example(
 //!    client: SupabaseClient
-//! ) -> Result<(), String> {
-//!     let insert_result = client
-//!         .insert_if_unique(
+//! ) -> Result<(), RestError> {
+//!     let inserted: bool = client
+//!         .insert_if_unique_on(
 //!             "test",
 //!             json!({
 //!                 "dog": "value_test"
 //!             }),
-//!        ).await;
+//!             &["dog"],
+//!        ).await?;
 //! ```
 //!
 //! ### Update
@@ -282,26 +309,17 @@
 //!     .await;
 //! ```
 //!
-//! //! <div class="warning">Experimental features, Not ready for prod!</div>
-//!
-//!
-//! ### Get ID by Column, Cell values
-//! This will return the ID of the row in the specified table where the column matches the provided email.
+//! ### Looking up a row
+//! This will return the ID of the first row in the `users` table whose `email` matches, or `None`.
 //!
 //! ```rust,ignore
-//! #[tokio::main]
-//! async fn main() {
-//!     // Initialize the Supabase Client
-//!     let supabase_client = SupabaseClient::new("your_supabase_url", "your_supabase_key");
-//!
-//!     let email = "example@email.com".to_string();
-//!     let table_name = "users".to_string();
-//!     let column_name = "email".to_string();
-//!     match supabase_client.get_id(email, table_name, column_name).await {
-//!         Ok(id) => println!("Found ID: {}", id),
-//!         Err(e) => println!("Error: {}", e),
-//!     }
-//! }
+//! let id: Option<String> = supabase_client
+//!     .find_id_by("users", "email", "example@email.com")
+//!     .await?;
+//!
+//! let user: Option<Value> = supabase_client
+//!     .find_one_by("users", "email", "example@email.com")
+//!     .await?;
 //! ```
 //!
 //!
@@ -324,21 +342,54 @@
 //! ## Contributers
 //!
 
+// lets `#[derive(SupabaseTable)]` refer to `::supabase_rs` from within this crate
+extern crate self as supabase_rs;
+
 use rand::prelude::ThreadRng;
 use rand::Rng;
+use reqwest::redirect::Policy;
 use reqwest::Client;
 
+pub mod audit;
+pub mod auth;
+pub mod batch;
+pub mod bulk;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod cron;
 pub mod delete;
 pub mod errors;
+pub mod fixtures;
+pub mod functions;
+pub mod idempotency;
+pub mod impersonation;
+pub mod import;
 pub mod insert;
+pub mod interop;
+pub mod introspection;
+pub mod lock;
+pub mod metrics;
+pub mod payload;
+pub mod prelude;
 pub mod query;
 pub mod query_builder;
+pub mod queues;
+pub mod rate_limit;
 pub mod request;
 pub mod routing;
+pub mod rpc;
+pub mod schema_cache;
 pub mod select;
+pub mod stats;
 pub mod success;
+pub mod table;
+pub mod testing;
 pub mod tests;
+pub mod trace_context;
+pub mod transport;
+pub mod type_gen;
 pub mod update;
+pub mod vault;
 
 pub mod graphql;
 pub mod nightly;
@@ -347,7 +398,16 @@ pub mod nightly;
 pub mod realtime;
 pub mod storage;
 
+use circuit_breaker::CircuitBreaker;
 use errors::Result;
+use payload::PayloadLimit;
+use request::headers::HeadersTypes;
+
+pub use errors::{Error, ErrorKind};
+pub use table::SupabaseTable;
+
+#[cfg(feature = "derive")]
+pub use supabase_rs_derive::SupabaseTable;
 
 /// A client structure for interacting with Supabase services.
 ///
@@ -357,11 +417,45 @@ use errors::Result;
 /// # Fields
 /// - `url`: The base URL of the Supabase project.
 /// - `api_key`: The API key used for authenticating requests to Supabase.
+/// - `circuit_breaker`: An optional circuit breaker shared between clones of the client.
+/// - `payload_limit`: An optional maximum size for bulk request bodies.
+/// - `timezone`: An optional timezone in which selected timestamps are rendered.
+/// - `schema`: An optional schema read and written instead of the default exposed schema.
+/// - `schema_cache`: The tables, columns and relationships of the schema, when warmed with `with_schema_cache`.
+/// - `timeout`: An optional maximum duration of each request, unbounded when unset.
+/// - `cache`: The cached rows of selects (`cache` feature), shared between clones of the client.
+/// - `rate_limiter`: An optional limiter of the requests in flight and per second, shared between clones.
+/// - `transport`: An optional transport sending the requests instead of `client`, see [`transport`].
+/// - `audit_sink`: An optional sink the writes are reported to (`audit` feature), see [`audit`].
+/// - `trace_propagator`: An optional source of the trace context sent with every request, see [`trace_context`].
+/// - `access_token`: An optional token sent instead of the API key as bearer, see [`impersonation`].
+/// - `jwt_secret`: The JWT secret impersonation tokens are signed with (`auth` feature).
+/// - `session_store`: An optional store of the session of the user signed in with
+///   [`auth`](SupabaseClient::auth), whose access token is sent as bearer (`auth` feature), shared
+///   between clones.
 #[derive(Debug, Clone)]
 pub struct SupabaseClient {
     url: String,
     api_key: String,
     client: reqwest::Client,
+    circuit_breaker: Option<CircuitBreaker>,
+    payload_limit: Option<PayloadLimit>,
+    timezone: Option<String>,
+    schema: Option<String>,
+    schema_cache: Option<std::sync::Arc<schema_cache::SchemaCache>>,
+    timeout: Option<std::time::Duration>,
+    #[cfg(feature = "cache")]
+    cache: Option<cache::ResponseCache>,
+    rate_limiter: Option<rate_limit::RateLimiter>,
+    transport: Option<std::sync::Arc<dyn transport::HttpTransport>>,
+    #[cfg(feature = "audit")]
+    audit_sink: Option<std::sync::Arc<dyn audit::AuditSink>>,
+    trace_propagator: Option<std::sync::Arc<dyn trace_context::TracePropagator>>,
+    access_token: Option<String>,
+    #[cfg(feature = "auth")]
+    jwt_secret: Option<String>,
+    #[cfg(feature = "auth")]
+    session_store: Option<std::sync::Arc<dyn auth::SessionStore>>,
 }
 
 impl SupabaseClient {
@@ -380,18 +474,90 @@ impl SupabaseClient {
     /// );
     /// ```
     pub fn new(supabase_url: String, private_key: String) -> Result<Self> {
+        // redirects are followed by `dispatch`, which keeps the method and body intact
+        let builder = Client::builder().redirect(Policy::none());
+
         #[cfg(feature = "rustls")]
-        let client = Client::builder().use_rustls_tls().build()?;
+        let builder = builder.use_rustls_tls();
 
-        #[cfg(not(feature = "rustls"))]
-        let client = Client::new();
+        let client = builder.build()?;
 
         Ok(Self {
             url: supabase_url,
             api_key: private_key,
             client,
+            circuit_breaker: None,
+            payload_limit: None,
+            timezone: None,
+            schema: None,
+            schema_cache: None,
+            timeout: None,
+            #[cfg(feature = "cache")]
+            cache: None,
+            rate_limiter: None,
+            transport: None,
+            #[cfg(feature = "audit")]
+            audit_sink: None,
+            trace_propagator: None,
+            access_token: None,
+            #[cfg(feature = "auth")]
+            jwt_secret: None,
+            #[cfg(feature = "auth")]
+            session_store: None,
         })
     }
+
+    /// Renders the `timestamptz` values of every select in `timezone` instead of UTC.
+    ///
+    /// # Arguments
+    /// * `timezone` - An IANA timezone name, e.g. `Europe/Amsterdam`.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the timezone applied.
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        self.timezone = Some(timezone.to_string());
+        self
+    }
+
+    /// Reads and writes the tables and functions of `schema` instead of the default exposed schema.
+    ///
+    /// The schema has to be exposed in the API settings of the project. Single queries and RPC calls
+    /// can still pick another schema with their own `schema()`.
+    ///
+    /// # Arguments
+    /// * `schema` - The name of the schema, e.g. `analytics`.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the schema applied.
+    pub fn with_schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    /// The schema set with `with_schema`, `None` for the default exposed schema.
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Selects the schema of the client on a write with the `Content-Profile` header, if it has one.
+    pub(crate) fn content_profile(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        match &self.schema {
+            Some(schema) => request.header(HeadersTypes::ContentProfile.as_str(), schema),
+            None => request,
+        }
+    }
+
+    /// Fails every request that takes longer than `timeout`, including reading the response headers.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the timeout applied.
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
 }
 
 /// Generates a random 64-bit signed integer within a larger range
@@ -0,0 +1,161 @@
+//! ## Distributed locks
+//!
+//! A lightweight mutex shared by every instance of a service, stored in the Supabase database.
+//!
+//! Postgres advisory locks belong to a database session, which PostgREST doesn't keep between
+//! requests, so the locks are rows of a table instead: a lock is held until it is unlocked or its
+//! time to live runs out, which also frees locks of crashed instances. The table and the functions
+//! taking and releasing locks have to be created once, see [`LOCK_MIGRATION_SQL`]. They can only be
+//! executed with the service role key.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use std::time::Duration;
+//!
+//! if let Some(lock) = client.try_lock("nightly-report", Duration::from_secs(300)).await? {
+//!     generate_report().await;
+//!     client.unlock(&lock).await?;
+//! }
+//! ```
+//!
+//! Work that may outlive the time to live should call [`SupabaseClient::extend_lock`] periodically.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Creates the lock table and the functions the lock helpers call, run it once as a migration.
+pub const LOCK_MIGRATION_SQL: &str = r#"
+create table if not exists public.supabase_rs_locks (
+  name text primary key,
+  holder text not null,
+  expires_at timestamptz not null
+);
+
+alter table public.supabase_rs_locks enable row level security;
+
+create or replace function public.supabase_rs_try_lock(lock_name text, lock_holder text, ttl_seconds double precision)
+returns boolean
+language plpgsql
+security definer
+set search_path = ''
+as $$
+declare
+  acquired boolean;
+begin
+  insert into public.supabase_rs_locks as locks (name, holder, expires_at)
+  values (lock_name, lock_holder, now() + make_interval(secs => ttl_seconds))
+  on conflict (name) do update
+    set holder = excluded.holder, expires_at = excluded.expires_at
+    where locks.expires_at <= now() or locks.holder = excluded.holder
+  returning true into acquired;
+  return coalesce(acquired, false);
+end;
+$$;
+
+create or replace function public.supabase_rs_unlock(lock_name text, lock_holder text)
+returns boolean
+language plpgsql
+security definer
+set search_path = ''
+as $$
+begin
+  delete from public.supabase_rs_locks where name = lock_name and holder = lock_holder;
+  return found;
+end;
+$$;
+
+revoke execute on function public.supabase_rs_try_lock(text, text, double precision) from public, anon, authenticated;
+revoke execute on function public.supabase_rs_unlock(text, text) from public, anon, authenticated;
+grant execute on function public.supabase_rs_try_lock(text, text, double precision) to service_role;
+grant execute on function public.supabase_rs_unlock(text, text) to service_role;
+"#;
+
+/// A held lock, returned by [`SupabaseClient::try_lock`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lock {
+    /// The name of the lock.
+    pub name: String,
+    /// The random token identifying this holder, only the holder can extend or unlock the lock.
+    pub holder: String,
+}
+
+impl Lock {
+    /// Creates a lock handle with a new random holder token.
+    pub(crate) fn new(name: &str) -> Self {
+        Lock {
+            name: name.to_string(),
+            holder: format!("{:032x}", rand::random::<u128>()),
+        }
+    }
+
+    /// The parameters of `supabase_rs_try_lock`.
+    pub(crate) fn try_lock_params(&self, ttl: Duration) -> Value {
+        json!({
+            "lock_name": self.name,
+            "lock_holder": self.holder,
+            "ttl_seconds": ttl.as_secs_f64(),
+        })
+    }
+}
+
+impl SupabaseClient {
+    /// Takes the lock `name` unless another holder has it.
+    ///
+    /// Requires the service role key and the functions of [`LOCK_MIGRATION_SQL`].
+    ///
+    /// # Arguments
+    /// * `name` - The name of the lock, shared by every instance.
+    /// * `ttl` - How long the lock is held at most, it is released afterwards even without `unlock`.
+    ///
+    /// # Returns
+    /// The held lock, or `None` when another holder has it.
+    pub async fn try_lock(&self, name: &str, ttl: Duration) -> Result<Option<Lock>> {
+        let lock: Lock = Lock::new(name);
+        match self.acquire(&lock, ttl).await? {
+            true => Ok(Some(lock)),
+            false => Ok(None),
+        }
+    }
+
+    /// Extends a held lock to expire `ttl` from now.
+    ///
+    /// # Returns
+    /// Whether the lock is still held, `false` when it expired and another holder took it.
+    pub async fn extend_lock(&self, lock: &Lock, ttl: Duration) -> Result<bool> {
+        self.acquire(lock, ttl).await
+    }
+
+    /// Releases a held lock.
+    ///
+    /// # Returns
+    /// Whether the lock was still held by `lock`, `false` when it had expired already.
+    pub async fn unlock(&self, lock: &Lock) -> Result<bool> {
+        let result: Value = self
+            .rpc(
+                "supabase_rs_unlock",
+                json!({ "lock_name": lock.name, "lock_holder": lock.holder }),
+            )
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_bool().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a boolean, got {}", result))
+        })
+    }
+
+    async fn acquire(&self, lock: &Lock, ttl: Duration) -> Result<bool> {
+        let result: Value = self
+            .rpc("supabase_rs_try_lock", lock.try_lock_params(ttl))
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_bool().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a boolean, got {}", result))
+        })
+    }
+}
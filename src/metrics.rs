@@ -0,0 +1,178 @@
+//! ## Metrics
+//!
+//! When the `metrics` feature is enabled every request of the SDK, to the REST API, auth, storage,
+//! edge functions and GraphQL, is recorded through the [`metrics`](https://docs.rs/metrics) facade. Install any recorder in your application
+//! (for example `metrics-exporter-prometheus`) to scrape the SDK's behaviour without writing
+//! your own middleware.
+//!
+//! ### Exported metrics
+//! - `supabase_rs_requests_total` (counter): labelled by `table`, `op`, `status` and `status_class`.
+//!   The status is the HTTP status code, or `error` when the request never got a response, and its
+//!   class is `1xx`, `2xx`, `3xx`, `4xx`, `5xx`, `other` for codes outside of them, or `error`.
+//! - `supabase_rs_request_duration_seconds` (histogram): labelled by `table` and `op`.
+//! - `supabase_rs_rows_returned` (histogram): labelled by `table`.
+//! - `supabase_rs_rate_limit_queued` and `supabase_rs_rate_limit_in_flight` (gauges): labelled by
+//!   `host`, the queue of the [rate limiter](crate::rate_limit) of the project.
+//! - `supabase_rs_rate_limit_wait_seconds` (histogram): how long requests waited for the rate limiter.
+//! - `supabase_rs_realtime_change_lag_seconds` (histogram): labelled by `table`, the time from the
+//!   commit of a [row change](crate::realtime::changes) until it was received.
+//! - `supabase_rs_realtime_changes_queued` (gauge): labelled by `table`, the row changes waiting for
+//!   their receiver.
+//!
+//! The `table` label is the table, function or GraphQL collection the request targeted, `auth` for
+//! auth and empty for storage, and `op` the kind of operation, e.g. `select`, `sign_in` or `graphql`.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! // call once at startup, after installing your recorder
+//! supabase_rs::metrics::describe_metrics();
+//! ```
+//!
+//! The error rate of the SDK per operation, in PromQL:
+//! ```text
+//! sum by (op) (rate(supabase_rs_requests_total{status_class=~"5xx|error"}[5m]))
+//!   / sum by (op) (rate(supabase_rs_requests_total[5m]))
+//! ```
+#![cfg(feature = "metrics")]
+
+use metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit,
+};
+use std::time::Duration;
+
+/// Name of the counter tracking every request sent to Supabase.
+pub const REQUESTS_TOTAL: &str = "supabase_rs_requests_total";
+/// Name of the histogram tracking request latency.
+pub const REQUEST_DURATION_SECONDS: &str = "supabase_rs_request_duration_seconds";
+/// Name of the histogram tracking the amount of rows returned by selects.
+pub const ROWS_RETURNED: &str = "supabase_rs_rows_returned";
+/// Name of the gauge tracking the requests waiting for the rate limiter.
+pub const RATE_LIMIT_QUEUED: &str = "supabase_rs_rate_limit_queued";
+/// Name of the gauge tracking the requests let through by the rate limiter.
+pub const RATE_LIMIT_IN_FLIGHT: &str = "supabase_rs_rate_limit_in_flight";
+/// Name of the histogram tracking how long requests waited for the rate limiter.
+pub const RATE_LIMIT_WAIT_SECONDS: &str = "supabase_rs_rate_limit_wait_seconds";
+/// Name of the histogram tracking the time from the commit of a row change until it was received.
+pub const REALTIME_CHANGE_LAG_SECONDS: &str = "supabase_rs_realtime_change_lag_seconds";
+/// Name of the gauge tracking the row changes waiting for their receiver.
+pub const REALTIME_CHANGES_QUEUED: &str = "supabase_rs_realtime_changes_queued";
+
+/// Registers descriptions and units for all metrics emitted by the SDK.
+pub fn describe_metrics() {
+    describe_counter!(
+        REQUESTS_TOTAL,
+        Unit::Count,
+        "Total requests sent to Supabase by table, operation, status and status class"
+    );
+    describe_histogram!(
+        REQUEST_DURATION_SECONDS,
+        Unit::Seconds,
+        "Latency of requests sent to Supabase by table and operation"
+    );
+    describe_histogram!(
+        ROWS_RETURNED,
+        Unit::Count,
+        "Amount of rows returned by select queries"
+    );
+    describe_gauge!(
+        RATE_LIMIT_QUEUED,
+        Unit::Count,
+        "Requests waiting for the rate limiter, by host"
+    );
+    describe_gauge!(
+        RATE_LIMIT_IN_FLIGHT,
+        Unit::Count,
+        "Requests let through by the rate limiter and not finished yet, by host"
+    );
+    describe_histogram!(
+        RATE_LIMIT_WAIT_SECONDS,
+        Unit::Seconds,
+        "Time requests waited for the rate limiter"
+    );
+    describe_histogram!(
+        REALTIME_CHANGE_LAG_SECONDS,
+        Unit::Seconds,
+        "Time from the commit of a row change until it was received, by table"
+    );
+    describe_gauge!(
+        REALTIME_CHANGES_QUEUED,
+        Unit::Count,
+        "Row changes waiting for their receiver, by table"
+    );
+}
+
+/// Records a finished request.
+///
+/// # Arguments
+/// * `table_name` - The table the request targeted.
+/// * `operation` - The kind of operation, e.g. `select` or `insert`.
+/// * `status` - The HTTP status code, `None` when no response was received.
+/// * `elapsed` - How long the request took.
+pub fn record_request(table_name: &str, operation: &str, status: Option<u16>, elapsed: Duration) {
+    let status_class: &'static str = status_class(status);
+    let status: String = match status {
+        Some(code) => code.to_string(),
+        None => "error".to_string(),
+    };
+
+    counter!(
+        REQUESTS_TOTAL,
+        "table" => table_name.to_string(),
+        "op" => operation.to_string(),
+        "status" => status,
+        "status_class" => status_class
+    )
+    .increment(1);
+
+    histogram!(
+        REQUEST_DURATION_SECONDS,
+        "table" => table_name.to_string(),
+        "op" => operation.to_string()
+    )
+    .record(elapsed.as_secs_f64());
+}
+
+/// The class of an HTTP status code, `other` for codes outside of the classes, `error` when no
+/// response was received.
+fn status_class(status: Option<u16>) -> &'static str {
+    match status {
+        Some(100..=199) => "1xx",
+        Some(200..=299) => "2xx",
+        Some(300..=399) => "3xx",
+        Some(400..=499) => "4xx",
+        Some(500..=599) => "5xx",
+        Some(_) => "other",
+        None => "error",
+    }
+}
+
+/// Records the amount of rows a select returned.
+pub fn record_rows_returned(table_name: &str, rows: usize) {
+    histogram!(ROWS_RETURNED, "table" => table_name.to_string()).record(rows as f64);
+}
+
+/// Records the amount of requests waiting for the rate limiter of the project at `host`.
+pub fn record_rate_limit_queued(host: &str, queued: usize) {
+    gauge!(RATE_LIMIT_QUEUED, "host" => host.to_string()).set(queued as f64);
+}
+
+/// Records the amount of requests let through by the rate limiter of the project at `host`.
+pub fn record_rate_limit_in_flight(host: &str, in_flight: usize) {
+    gauge!(RATE_LIMIT_IN_FLIGHT, "host" => host.to_string()).set(in_flight as f64);
+}
+
+/// Records how long a request waited for the rate limiter.
+pub fn record_rate_limit_wait(waited: Duration) {
+    histogram!(RATE_LIMIT_WAIT_SECONDS).record(waited.as_secs_f64());
+}
+
+/// Records the time from the commit of a row change of `table_name` until it was received.
+pub fn record_change_lag(table_name: &str, lag: Duration) {
+    histogram!(REALTIME_CHANGE_LAG_SECONDS, "table" => table_name.to_string())
+        .record(lag.as_secs_f64());
+}
+
+/// Records the amount of row changes of `table_name` waiting for their receiver.
+pub fn record_changes_queued(table_name: &str, queued: usize) {
+    gauge!(REALTIME_CHANGES_QUEUED, "table" => table_name.to_string()).set(queued as f64);
+}
@@ -0,0 +1,128 @@
+//! ## Payload size guard
+//!
+//! Supabase rejects request bodies above its configured limit with an opaque `413`.
+//! A [`PayloadLimit`] set on the client checks bulk insert and upsert payloads before they are sent and either
+//! splits them into several requests or rejects them with [`ErrorTypes::PayloadTooLarge`].
+//!
+//! ### Usage
+//! ```
+//! # use supabase_rs::SupabaseClient;
+//! use supabase_rs::payload::{OversizedPayload, PayloadLimit};
+//!
+//! let client = SupabaseClient::new(
+//!     "https://your-project.supabase.co".to_string(),
+//!     "your-secret-key".to_string(),
+//! )
+//! .unwrap()
+//! .with_payload_limit(PayloadLimit {
+//!     max_bytes: 1024 * 1024,
+//!     on_oversized: OversizedPayload::Split,
+//! });
+//! ```
+
+use crate::errors::{ErrorTypes, RestError};
+use crate::SupabaseClient;
+
+use serde_json::Value;
+
+/// What to do with a bulk payload that exceeds the configured limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedPayload {
+    /// Split the rows into several requests, each below the limit, sent in order.
+    Split,
+    /// Reject the payload with [`ErrorTypes::PayloadTooLarge`] without sending it.
+    Reject,
+}
+
+/// A byte threshold for bulk request bodies.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadLimit {
+    /// The maximum size of a single request body in bytes.
+    pub max_bytes: usize,
+    /// What to do when a payload exceeds `max_bytes`.
+    pub on_oversized: OversizedPayload,
+}
+
+/// Serializes `rows` into JSON array bodies that each stay within `max_bytes`, preserving order.
+///
+/// # Errors
+/// Returns [`ErrorTypes::PayloadTooLarge`] when a single row is larger than `max_bytes` on its own.
+pub fn split_into_batches(rows: &[Value], max_bytes: usize) -> Result<Vec<String>, ErrorTypes> {
+    let mut batches: Vec<String> = Vec::new();
+    let mut current: String = String::from("[");
+
+    for row in rows {
+        let row: String = row.to_string();
+
+        // the two brackets of the array are always part of the body
+        if row.len() + 2 > max_bytes {
+            return Err(ErrorTypes::PayloadTooLarge {
+                size: row.len() + 2,
+                limit: max_bytes,
+            });
+        }
+
+        // adding the row needs a separating comma unless the batch is still empty
+        let separator: usize = usize::from(current.len() > 1);
+        if current.len() + separator + row.len() + 1 > max_bytes {
+            current.push(']');
+            batches.push(current);
+            current = String::from("[");
+        }
+
+        if current.len() > 1 {
+            current.push(',');
+        }
+        current.push_str(&row);
+    }
+
+    if current.len() > 1 {
+        current.push(']');
+        batches.push(current);
+    }
+
+    Ok(batches)
+}
+
+impl SupabaseClient {
+    /// Guards bulk payloads sent by this client against a maximum body size.
+    ///
+    /// # Arguments
+    /// * `limit` - The byte threshold and what to do when it is exceeded.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the payload limit applied.
+    pub fn with_payload_limit(mut self, limit: PayloadLimit) -> Self {
+        self.payload_limit = Some(limit);
+        self
+    }
+
+    /// Serializes a bulk body into the request bodies to send, honouring the payload limit.
+    ///
+    /// # Returns
+    /// A single body when no limit is set or the body fits, the batches when it has to be split,
+    /// or a `RestError` when the payload is rejected or is a single row above the limit.
+    pub(crate) fn payload_batches(&self, body: &Value) -> Result<Vec<String>, RestError> {
+        let serialized: String = body.to_string();
+
+        let Some(limit) = self.payload_limit else {
+            return Ok(vec![serialized]);
+        };
+        if serialized.len() <= limit.max_bytes {
+            return Ok(vec![serialized]);
+        }
+
+        let rows: &[Value] = match body.as_array() {
+            Some(rows) if limit.on_oversized == OversizedPayload::Split => rows,
+            // a single row can't be split
+            _ => {
+                return Err(ErrorTypes::PayloadTooLarge {
+                    size: serialized.len(),
+                    limit: limit.max_bytes,
+                }
+                .into())
+            }
+        };
+        Ok(split_into_batches(rows, limit.max_bytes)?)
+    }
+}
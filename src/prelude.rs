@@ -0,0 +1,47 @@
+//! ## Prelude
+//!
+//! Re-exports the types most applications need, so a single import covers the client, the query
+//! builders, their options and the error type.
+//!
+//! ```rust,ignore
+//! use supabase_rs::prelude::*;
+//!
+//! let rows = client
+//!     .select("users")
+//!     .count_mode(CountMode::Exact)
+//!     .execute_with_count()
+//!     .await?;
+//! ```
+//!
+//! Items of optional modules are only exported when their feature is enabled.
+
+pub use crate::batch::Batch;
+pub use crate::circuit_breaker::CircuitBreakerConfig;
+pub use crate::cron::CronJob;
+pub use crate::errors::ErrorTypes;
+pub use crate::functions::InvokeOptions;
+pub use crate::insert::InsertVerdict;
+pub use crate::payload::{OversizedPayload, PayloadLimit};
+pub use crate::query::{CountMode, OrderOptions, QueryBuilder, Returning, SortOrder};
+pub use crate::query_builder::delete::DeleteBuilder;
+pub use crate::query_builder::paginate::{Page, PageIterator};
+pub use crate::query_builder::scan::{ScanCheckpoint, TableScanner};
+pub use crate::query_builder::update::UpdateBuilder;
+pub use crate::queues::QueueMessage;
+pub use crate::rpc::RpcBuilder;
+pub use crate::table::{Column, Relationship, SupabaseTable, TypedQueryBuilder};
+pub use crate::update::DuplicateResolution;
+pub use crate::SupabaseClient;
+
+#[cfg(feature = "auth")]
+pub use crate::auth::{AdminAuthClient, AuthClient, Session, User};
+#[cfg(feature = "realtime")]
+pub use crate::realtime::broadcast::BroadcastMessage;
+#[cfg(feature = "realtime")]
+pub use crate::realtime::{ConnectionState, PresenceState, RealtimeChannel, RealtimeClient};
+#[cfg(feature = "storage")]
+pub use crate::storage::{
+    ResumableUploadOptions, StorageBucket, SupabaseStorage, TransformOptions,
+};
+#[cfg(feature = "type_gen")]
+pub use crate::type_gen::TypeGenOptions;
@@ -32,7 +32,7 @@
 use crate::SupabaseClient;
 
 /// Represents the type of comparison to be performed in a query filter.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Operator {
     /// Represents equality comparison.
     Equals,
@@ -49,7 +49,7 @@ pub enum Operator {
 }
 
 /// Specifies the order in which results should be sorted.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SortOrder {
     /// Results should be sorted in ascending order.
     Ascending,
@@ -57,8 +57,54 @@ pub enum SortOrder {
     Descending,
 }
 
+/// The strategy PostgREST uses to count the rows matching a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountMode {
+    /// Counts every matching row with `COUNT(*)`, accurate but slow on large tables.
+    Exact,
+    /// Uses the row estimate of the Postgres query planner.
+    Planned,
+    /// Counts exactly up to `db-max-rows`, and uses the planner estimate beyond that.
+    Estimated,
+}
+
+impl CountMode {
+    /// Returns the value used in the `Prefer: count=...` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CountMode::Exact => "exact",
+            CountMode::Planned => "planned",
+            CountMode::Estimated => "estimated",
+        }
+    }
+}
+
+/// What PostgREST sends back after a write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Returning {
+    /// Nothing but the status, the cheapest for large writes. The default.
+    #[default]
+    Minimal,
+    /// The written rows, including the columns filled in by the database.
+    Representation,
+    /// No body, only the `Location` header of the inserted row. Writes return the primary key it
+    /// names, e.g. `{"id": "7"}`, or nothing when the table has no primary key.
+    HeadersOnly,
+}
+
+impl Returning {
+    /// Returns the value used in the `Prefer: return=...` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Returning::Minimal => "minimal",
+            Returning::Representation => "representation",
+            Returning::HeadersOnly => "headers-only",
+        }
+    }
+}
+
 /// Represents a filter to be applied to a query, consisting of a column name, an operator, and a value to compare against.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Filter {
     /// The name of the column to which the filter applies.
     pub column: String,
@@ -68,8 +114,20 @@ pub struct Filter {
     pub value: String,
 }
 
+/// A value to filter a column on, created from strings, numbers, booleans, and `Option`s of them.
+///
+/// With the `uuid` and `chrono` features, UUIDs and timestamps convert as well.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    /// SQL `NULL`, from `None`. `eq` and `neq` match it with `is.null` and `not.is.null`, other
+    /// operators fail the query with [`ErrorTypes::InvalidParameters`](crate::errors::ErrorTypes::InvalidParameters).
+    Null,
+    /// Any other value, as it appears in the query string.
+    Text(String),
+}
+
 /// Represents sorting criteria for query results, consisting of a column name and the order of sorting.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Sort {
     /// The name of the column by which to sort.
     pub column: String,
@@ -77,8 +135,19 @@ pub struct Sort {
     pub order: SortOrder,
 }
 
+/// The ordering of query results by one column, added with `QueryBuilder::order_with`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderOptions {
+    /// The name of the column by which to order.
+    pub column: String,
+    /// Whether to order from the smallest to the largest value.
+    pub ascending: bool,
+    /// Whether null values come first or last, the Postgres default (last when ascending) when `None`.
+    pub nulls_first: Option<bool>,
+}
+
 /// Represents a query with a collection of parameters that define specific conditions and sorting orders.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Query {
     /// A map where each key-value pair represents a column and the condition or sorting order applied to it.
     pub params: Vec<(String, String)>,
@@ -86,6 +155,8 @@ pub struct Query {
     pub filters: Vec<Filter>,
     /// A vector of sorting criteria to be applied to the query results.
     pub sorts: Vec<Sort>,
+    /// The filters that can't be sent, e.g. `gt` on `NULL`, failing the query before it is sent.
+    pub invalid: Vec<String>,
 }
 
 /// A `QueryBuilder` is used to construct and manage SQL queries for a specific table using a `SupabaseClient`.
@@ -96,9 +167,21 @@ pub struct Query {
 /// - `client`: The `SupabaseClient` used to execute the query.
 /// - `query`: A `Query` object that stores the parameters and conditions of the SQL query.
 /// - `table_name`: The name of the table in the database to which the query will be applied.
-#[derive(Debug)]
+/// - `count_mode`: The counting strategy used by `execute_with_count`, `exact` when unset.
+/// - `timezone`: The timezone timestamps are rendered in, overriding the one of the client.
+/// - `schema`: The schema the table is read from, overriding the one of the client.
+/// - `cancel_token`: A token that aborts the request once cancelled (`cancellation` feature).
+/// - `cache_policy`: Whether and for how long the rows are cached (`cache` feature).
+#[derive(Debug, Clone)]
 pub struct QueryBuilder {
     pub client: SupabaseClient,
     pub query: Query,
     pub table_name: String, // option columns
+    pub count_mode: Option<CountMode>,
+    pub timezone: Option<String>,
+    pub schema: Option<String>,
+    #[cfg(feature = "cancellation")]
+    pub cancel_token: Option<tokio_util::sync::CancellationToken>,
+    #[cfg(feature = "cache")]
+    pub cache_policy: crate::cache::CachePolicy,
 }
@@ -1,7 +1,18 @@
-use crate::query::{Filter, Query, QueryBuilder, Sort};
+use crate::errors::{ErrorTypes, RestError};
+use crate::query::{CountMode, Filter, FilterValue, Query, QueryBuilder, Sort};
+use crate::query_builder::embed::embed_relation;
+use crate::request::headers::HeadersTypes;
+use crate::request::prefer::PreferBuilder;
+use crate::request::Headers;
+use crate::success::{
+    handle_count_response, parse_content_range_total, response_error, unexpected_response,
+};
 use crate::SupabaseClient;
 
+use reqwest::{Method, Response};
 use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
 
 impl QueryBuilder {
     /// Constructs a new `QueryBuilder` for a specified table.
@@ -17,13 +28,25 @@ impl QueryBuilder {
             client,
             query: Query::new(),
             table_name: table_name.to_string(),
+            count_mode: None,
+            timezone: None,
+            schema: None,
+            #[cfg(feature = "cancellation")]
+            cancel_token: None,
+            #[cfg(feature = "cache")]
+            cache_policy: Default::default(),
         }
     }
 
     pub fn columns(mut self, columns: Vec<&str>) -> QueryBuilder {
-        // add query params &select=column1,column2
-        let columns_str = columns.join(",");
-        self.query.add_param("select", &columns_str);
+        // add query params &select=column1,column2, keeping the embeds of `select_related`
+        let mut items: Vec<String> = columns.iter().map(|column| column.to_string()).collect();
+        items.extend(
+            self.select_items()
+                .into_iter()
+                .filter(|item| embed_relation(item).is_some()),
+        );
+        self.set_select(items);
         self
     }
 
@@ -35,8 +58,8 @@ impl QueryBuilder {
     ///
     /// # Returns
     /// Returns the `QueryBuilder` instance to allow for method chaining.
-    pub fn eq(mut self, column: &str, value: &str) -> Self {
-        self.query.add_param(column, &format!("eq.{}", value));
+    pub fn eq(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "eq", value.into());
         self
     }
 
@@ -48,8 +71,8 @@ impl QueryBuilder {
     ///
     /// # Returns
     /// Returns the `QueryBuilder` instance to allow for method chaining.
-    pub fn neq(mut self, column: &str, value: &str) -> Self {
-        self.query.add_param(column, &format!("neq.{}", value));
+    pub fn neq(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "neq", value.into());
         self
     }
 
@@ -61,8 +84,8 @@ impl QueryBuilder {
     ///
     /// # Returns
     /// Returns the `QueryBuilder` instance to allow for method chaining.
-    pub fn gt(mut self, column: &str, value: &str) -> Self {
-        self.query.add_param(column, &format!("gt.{}", value));
+    pub fn gt(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "gt", value.into());
         self
     }
 
@@ -74,8 +97,8 @@ impl QueryBuilder {
     ///
     /// # Returns
     /// Returns the `QueryBuilder` instance to allow for method chaining.
-    pub fn lt(mut self, column: &str, value: &str) -> Self {
-        self.query.add_param(column, &format!("lt.{}", value));
+    pub fn lt(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "lt", value.into());
         self
     }
 
@@ -87,8 +110,8 @@ impl QueryBuilder {
     ///
     /// # Returns
     /// Returns the `QueryBuilder` instance to allow for method chaining.
-    pub fn gte(mut self, column: &str, value: &str) -> Self {
-        self.query.add_param(column, &format!("gte.{}", value));
+    pub fn gte(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "gte", value.into());
         self
     }
 
@@ -100,8 +123,8 @@ impl QueryBuilder {
     ///
     /// # Returns
     /// Returns the `QueryBuilder` instance to allow for method chaining.
-    pub fn lte(mut self, column: &str, value: &str) -> Self {
-        self.query.add_param(column, &format!("lte.{}", value));
+    pub fn lte(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "lte", value.into());
         self
     }
 
@@ -114,14 +137,270 @@ impl QueryBuilder {
         self
     }
 
+    /// Aborts the request when `token` is cancelled, e.g. because the client of a web handler disconnected.
+    ///
+    /// A cancelled request fails with the message of [`ErrorTypes::Cancelled`].
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    #[cfg(feature = "cancellation")]
+    pub fn abort_signal(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Fails the request when it takes longer than `timeout`, overriding the timeout of the client.
+    ///
+    /// A request that runs out of time fails with the message of [`ErrorTypes::Timeout`](crate::errors::ErrorTypes::Timeout).
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.client.timeout = Some(timeout);
+        self
+    }
+
+    /// Renders the `timestamptz` values of this query in `timezone` instead of UTC.
+    ///
+    /// # Arguments
+    /// * `timezone` - An IANA timezone name, e.g. `Europe/Amsterdam`.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.timezone = Some(timezone.to_string());
+        self
+    }
+
+    /// Reads the table from `schema` instead of the schema of the client.
+    ///
+    /// # Arguments
+    /// * `schema` - The name of an exposed schema, e.g. `analytics`.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    /// Sets the counting strategy used by `execute_with_count`.
+    ///
+    /// # Arguments
+    /// * `mode` - `Exact`, `Planned` or `Estimated`, trading accuracy for speed on large tables.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn count_mode(mut self, mode: CountMode) -> Self {
+        self.count_mode = Some(mode);
+        self
+    }
+
+    /// Executes the constructed query and returns the rows along with the total number of matching rows.
+    ///
+    /// The total is read from the `Content-Range` header, so unlike `count()` it isn't appended to the rows.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the fetched records and the total count, or a `RestError`.
+    pub async fn execute_with_count(self) -> Result<(Vec<Value>, u64), RestError> {
+        let mode: CountMode = self.count_mode.unwrap_or(CountMode::Exact);
+
+        let headers: Headers = self.preferences(Some(mode));
+
+        let query_string: String = self.query.build();
+        let request = async {
+            let response = self
+                .client
+                .send_select(&self.table_name, query_string.as_str(), headers)
+                .await?;
+            handle_count_response(response).await
+        };
+        let result: Result<(Vec<Value>, u64), RestError> = self.run(request).await;
+
+        #[cfg(feature = "metrics")]
+        if let Ok((rows, _)) = &result {
+            crate::metrics::record_rows_returned(&self.table_name, rows.len());
+        }
+
+        result
+    }
+
+    /// Counts the rows matching the query with a `HEAD` request, without transferring any rows.
+    ///
+    /// Uses the count mode set with `count_mode`, `exact` when unset.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the number of matching rows, or a `RestError`.
+    pub async fn count_only(self) -> Result<u64, RestError> {
+        let mode: CountMode = self.count_mode.unwrap_or(CountMode::Exact);
+        let headers: Headers = self.preferences(Some(mode));
+
+        let query_string: String = self.query.build();
+        let request = async {
+            let response: Response = self
+                .client
+                .send_select_with(Method::HEAD, &self.table_name, &query_string, headers)
+                .await?;
+
+            if !response.status().is_success() {
+                return Err(response_error(response).await);
+            }
+            response
+                .headers()
+                .get("content-range")
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range_total)
+                .ok_or_else(|| {
+                    unexpected_response("Response has no total count in its Content-Range header")
+                })
+        };
+
+        self.run(request).await
+    }
+
+    /// Checks whether any row matches the query with a `HEAD` request, without transferring any rows.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing whether a row matches, or a `RestError`.
+    pub async fn exists(self) -> Result<bool, RestError> {
+        Ok(self.count_only().await? > 0)
+    }
+
     /// Executes the constructed query against the database.
     ///
     /// # Returns
-    /// Returns a `Result` containing either a vector of `Value` representing the fetched records, or a `String` error message.
+    /// Returns a `Result` containing either a vector of `Value` representing the fetched records, or a `String`
+    /// error message. [`fetch`](Self::fetch) returns the [`RestError`] instead.
     pub async fn execute(self) -> Result<Vec<Value>, String> {
+        Ok(self.fetch().await?)
+    }
+
+    /// Executes the constructed query like [`execute`](Self::execute), keeping the kind, status and
+    /// request id of a failure.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either a vector of `Value` representing the fetched records, or a `RestError`.
+    pub async fn fetch(self) -> Result<Vec<Value>, RestError> {
+        let query_string: String = self.query.build();
+
+        #[cfg(feature = "cache")]
+        let cache_key = self.cache_key(&query_string);
+        #[cfg(feature = "cache")]
+        if let Some(rows) = cache_key.as_ref().and_then(|(cache, key)| cache.get(key)) {
+            return Ok(rows);
+        }
+
+        let request = self.client.execute_with_headers(
+            &self.table_name,
+            query_string.as_str(),
+            self.preferences(None),
+        );
+        let result: Result<Vec<Value>, RestError> = self.run(request).await;
+
+        #[cfg(feature = "cache")]
+        if let (Some((cache, key)), Ok(rows)) = (cache_key, &result) {
+            cache.insert(
+                key,
+                &self.table_name,
+                rows.clone(),
+                self.cache_ttl_override(),
+            );
+        }
+
+        result
+    }
+
+    /// Executes the constructed query and returns the rows as CSV, including a header line.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the raw CSV, or a `RestError`.
+    pub async fn execute_csv(self) -> Result<String, RestError> {
+        let mut headers: Headers = self.preferences(None);
+        headers.insert(HeadersTypes::Accept.as_str(), "text/csv");
+
+        let query_string: String = self.query.build();
+        let request = async {
+            let response: Response = self
+                .client
+                .send_select(&self.table_name, query_string.as_str(), headers)
+                .await?;
+
+            if !response.status().is_success() {
+                return Err(response_error(response).await);
+            }
+            response.text().await.map_err(unexpected_response)
+        };
+
+        self.run(request).await
+    }
+
+    /// Returns the URL `execute` sends the query to, with its query string, without sending it.
+    pub fn to_url(&self) -> String {
         self.client
-            .execute(&self.table_name, self.query.build().as_str())
-            .await
+            .select_endpoint(&self.table_name, &self.query.build())
+    }
+
+    /// Builds the headers of this query: `Prefer` from its count mode and timezone, and
+    /// `Accept-Profile` from its schema.
+    pub(crate) fn preferences(&self, count: Option<CountMode>) -> Headers {
+        let mut preferences: PreferBuilder = PreferBuilder::new();
+
+        if let Some(mode) = count {
+            preferences = preferences.count(mode);
+        }
+        if let Some(timezone) = self.timezone.as_ref().or(self.client.timezone.as_ref()) {
+            preferences = preferences.timezone(timezone);
+        }
+
+        let mut headers: Headers = Headers::new();
+        preferences.apply(&mut headers);
+        if let Some(schema) = self.effective_schema() {
+            headers.insert(HeadersTypes::AcceptProfile.as_str(), schema);
+        }
+        headers
+    }
+
+    /// The schema the table is read from: the one of this query, or else the one of the client.
+    pub(crate) fn effective_schema(&self) -> Option<&str> {
+        self.schema.as_deref().or(self.client.schema.as_deref())
+    }
+
+    /// Awaits a request of this query, aborting it when the cancel token fires.
+    pub(crate) async fn run<T>(
+        &self,
+        request: impl Future<Output = Result<T, RestError>>,
+    ) -> Result<T, RestError> {
+        self.query.check_filters()?;
+        if let Some(cache) = self.client.schema_cache() {
+            cache.validate_query(self.effective_schema(), &self.table_name, &self.query, None)?;
+        }
+        self.validate_embeds()?;
+
+        #[cfg(feature = "cancellation")]
+        if let Some(token) = &self.cancel_token {
+            return token
+                .run_until_cancelled(request)
+                .await
+                .unwrap_or_else(|| Err(ErrorTypes::Cancelled.into()));
+        }
+
+        request.await
+    }
+}
+
+/// Shows the request `execute` sends: the method and URL, then the headers set by the query, one
+/// per line and sorted by name. The headers of the client, like its API key, are left out.
+impl Display for QueryBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GET {}", self.to_url())?;
+
+        let mut headers: Vec<(String, String)> =
+            self.preferences(None).get_headers().into_iter().collect();
+        headers.sort();
+        for (name, value) in headers {
+            write!(f, "\n{}: {}", name, value)?;
+        }
+        Ok(())
     }
 }
 
@@ -158,6 +437,24 @@ impl Query {
         }
     }
 
+    /// Adds a parameter filtering `column` on `value` with a PostgREST operator, e.g. `age=gt.30`.
+    ///
+    /// A filter the operator can't express, e.g. `gt` on `NULL`, is kept in `invalid` instead.
+    pub(crate) fn add_filter_param(&mut self, column: &str, operator: &str, value: FilterValue) {
+        match value.with_operator(operator) {
+            Ok(value) => self.add_param(column, &value),
+            Err(error) => self.invalid.push(format!("{}: {}", column, error)),
+        }
+    }
+
+    /// Fails with [`ErrorTypes::InvalidParameters`] when one of the filters can't be sent.
+    pub(crate) fn check_filters(&self) -> Result<(), ErrorTypes> {
+        match self.invalid.first() {
+            Some(message) => Err(ErrorTypes::InvalidParameters(message.clone())),
+            None => Ok(()),
+        }
+    }
+
     /// Adds a filter to the query.
     ///
     /// # Arguments
@@ -0,0 +1,221 @@
+//! ## Filtered deletes
+//!
+//! The [`DeleteBuilder`] deletes every row matching its filters, and can return the deleted rows for
+//! auditing. A delete without any filter would empty the table, so it is refused unless explicitly allowed.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let deleted: Vec<Value> = client
+//!     .delete_from("sessions")
+//!     .eq("user_id", "42")
+//!     .lt("expires_at", "2024-01-01")
+//!     .returning()
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! `returning_columns()` returns only a few columns of the deleted rows instead:
+//! ```rust,ignore
+//! let deleted: Vec<Value> = client
+//!     .delete_from("invoices")
+//!     .eq("status", "draft")
+//!     .returning_columns(&["id", "number"])
+//!     .execute()
+//!     .await?;
+//! ```
+
+use crate::errors::RestError;
+use crate::insert::in_filter;
+use crate::query::{FilterValue, Query, Returning};
+use crate::request::headers::HeadersTypes;
+use crate::request::prefer::PreferBuilder;
+use crate::request::Headers;
+use crate::success::{response_rows, write_error};
+use crate::SupabaseClient;
+
+use reqwest::Response;
+use serde_json::Value;
+
+/// A delete of every row matching a set of filters, created by [`SupabaseClient::delete_from`].
+#[derive(Debug, Clone)]
+pub struct DeleteBuilder {
+    client: SupabaseClient,
+    table_name: String,
+    query: Query,
+    returning: bool,
+    select: Option<String>,
+    idempotency_key: Option<String>,
+    allow_unfiltered: bool,
+}
+
+impl SupabaseClient {
+    /// Initializes a `DeleteBuilder` for the specified table.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table to delete from.
+    ///
+    /// # Returns
+    /// A `DeleteBuilder` instance configured for the specified table.
+    pub fn delete_from(&self, table_name: &str) -> DeleteBuilder {
+        DeleteBuilder {
+            client: self.clone(),
+            table_name: table_name.to_string(),
+            query: Query::new(),
+            returning: false,
+            select: None,
+            idempotency_key: None,
+            allow_unfiltered: false,
+        }
+    }
+}
+
+impl DeleteBuilder {
+    /// Only deletes rows where the column is equal to a specified value.
+    pub fn eq(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "eq", value.into());
+        self
+    }
+
+    /// Only deletes rows where the column is not equal to a specified value.
+    pub fn neq(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "neq", value.into());
+        self
+    }
+
+    /// Only deletes rows where the column is greater than a specified value.
+    pub fn gt(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "gt", value.into());
+        self
+    }
+
+    /// Only deletes rows where the column is less than a specified value.
+    pub fn lt(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "lt", value.into());
+        self
+    }
+
+    /// Only deletes rows where the column is greater than or equal to a specified value.
+    pub fn gte(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "gte", value.into());
+        self
+    }
+
+    /// Only deletes rows where the column is less than or equal to a specified value.
+    pub fn lte(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "lte", value.into());
+        self
+    }
+
+    /// Only deletes rows where the column is one of the specified values.
+    pub fn in_(mut self, column: &str, values: &[&str]) -> Self {
+        self.query
+            .add_param(column, &in_filter(values.iter().copied()));
+        self
+    }
+
+    /// Returns the deleted rows from `execute`.
+    pub fn returning(mut self) -> Self {
+        self.returning = true;
+        self
+    }
+
+    /// Returns only these columns of the deleted rows from `execute`.
+    pub fn returning_columns(mut self, columns: &[&str]) -> Self {
+        self.returning = true;
+        self.select = Some(columns.join(","));
+        self
+    }
+
+    /// Sends the delete with an idempotency key, so a retry fails instead of applying it twice, see
+    /// [`idempotency`](crate::idempotency).
+    pub fn idempotency_key(mut self, key: &str) -> Self {
+        self.idempotency_key = Some(key.to_string());
+        self
+    }
+
+    /// Allows executing the delete without any filter, deleting every row of the table.
+    pub fn allow_unfiltered(mut self) -> Self {
+        self.allow_unfiltered = true;
+        self
+    }
+
+    /// Builds the query string of the delete.
+    ///
+    /// # Returns
+    /// The query string, or a `String` error when the delete has no filter and unfiltered deletes aren't allowed.
+    pub fn build(&self) -> Result<String, String> {
+        if self.query.params.is_empty() && !self.allow_unfiltered {
+            return Err(format!(
+                "Refusing to delete every row of `{}` without a filter, call `allow_unfiltered()` to do so",
+                self.table_name
+            ));
+        }
+        Ok(self.query().build())
+    }
+
+    /// The filters of the delete, with the columns to return.
+    fn query(&self) -> Query {
+        let mut query: Query = self.query.clone();
+        if let Some(select) = &self.select {
+            query
+                .params
+                .insert(0, ("select".to_string(), select.clone()));
+        }
+        query
+    }
+
+    /// Executes the delete.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the deleted rows when `returning()` was set, an empty vector otherwise,
+    /// or a `RestError`.
+    pub async fn execute(self) -> Result<Vec<Value>, RestError> {
+        self.query.check_filters()?;
+        let query_string: String = self.build()?;
+        if let Some(cache) = self.client.schema_cache() {
+            cache.validate_query(self.client.schema(), &self.table_name, &self.query(), None)?;
+        }
+        let endpoint: String = format!(
+            "{}/rest/v1/{}?{}",
+            self.client.url, self.table_name, query_string
+        );
+
+        #[cfg(feature = "nightly")]
+        use crate::nightly::print_nightly_warning;
+        #[cfg(feature = "nightly")]
+        print_nightly_warning();
+
+        let mut headers: Headers =
+            Headers::with_defaults(&self.client.api_key, &self.client.api_key);
+        if self.returning {
+            PreferBuilder::new()
+                .returning(Returning::Representation)
+                .apply(&mut headers);
+        }
+        if let Some(key) = &self.idempotency_key {
+            headers.insert(HeadersTypes::IdempotencyKey.as_str(), key);
+        }
+
+        let mut request = self.client.client.delete(&endpoint);
+        for (key, value) in headers.get_headers() {
+            request = request.header(key, value);
+        }
+
+        let response: Response = self
+            .client
+            .dispatch(
+                &self.table_name,
+                "delete",
+                self.client.content_profile(request),
+            )
+            .await?;
+
+        if self.returning {
+            response_rows(response).await
+        } else if response.status().is_success() {
+            Ok(Vec::new())
+        } else {
+            Err(write_error(response).await)
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! ## Embedded resources
+//!
+//! PostgREST embeds rows of related tables through their foreign keys, e.g. `select=*,orders(id,total)`
+//! returns every customer with an `orders` array. [`QueryBuilder::select_related`] adds such an embed to
+//! the select, keeping the columns chosen with `columns()`.
+//!
+//! ```rust,ignore
+//! let customers = client
+//!     .select("customers")
+//!     .columns(vec!["id", "name"])
+//!     .select_related("orders", &["id", "total", "items(sku,quantity)"])
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! The relation may be aliased and disambiguated like in PostgREST, e.g. `author:users!author_id`.
+//!
+//! Filters on the columns of embedded rows are prefixed with the relation. They only filter the embedded
+//! rows, unless the embed is an inner join, which also drops the parent rows without a matching child:
+//! ```rust,ignore
+//! // customers with at least one paid order, and only their paid orders
+//! let customers = client
+//!     .select("customers")
+//!     .select_related("orders", &["id", "total"])
+//!     .inner_join("orders")
+//!     .eq_related("orders", "status", "paid")
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! When the client has a [schema cache](crate::schema_cache), the relations embedded directly in the
+//! queried table are checked against it before the request is sent. Aggregates, e.g. `count()` or
+//! `amount.sum()`, aren't relations, and a relation the cache can't see, e.g. one of a view, is
+//! left to PostgREST.
+
+use crate::query::{FilterValue, QueryBuilder};
+
+impl QueryBuilder {
+    /// Embeds the rows of a related table in every returned row.
+    ///
+    /// # Arguments
+    /// * `relation` - The related table or foreign key, optionally with an alias (`author:users`) or a
+    ///   hint (`users!author_id`).
+    /// * `columns` - The columns of the related rows, which may be embeds themselves, all when empty.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn select_related(mut self, relation: &str, columns: &[&str]) -> Self {
+        let columns: String = match columns.is_empty() {
+            true => "*".to_string(),
+            false => columns.join(","),
+        };
+
+        let mut items: Vec<String> = self.select_items();
+        if items.is_empty() {
+            items.push("*".to_string());
+        }
+        items.push(format!("{}({})", relation, columns));
+        self.set_select(items);
+        self
+    }
+
+    /// Makes an embed an inner join (`orders!inner(...)`), so rows without a related row matching the
+    /// filters on the relation are left out.
+    ///
+    /// A relation that isn't embedded yet is embedded without columns, so it only filters the rows.
+    ///
+    /// # Arguments
+    /// * `relation` - The relation, or its alias, as passed to `select_related`.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn inner_join(mut self, relation: &str) -> Self {
+        let mut items: Vec<String> = self.select_items();
+        match items
+            .iter_mut()
+            .find(|item| embed_name(item) == Some(relation))
+        {
+            Some(item) if !item.contains("!inner(") => {
+                let open: usize = item.find('(').unwrap_or(item.len());
+                item.insert_str(open, "!inner");
+            }
+            Some(_) => {}
+            None => {
+                if items.is_empty() {
+                    items.push("*".to_string());
+                }
+                items.push(format!("{}!inner()", relation));
+            }
+        }
+        self.set_select(items);
+        self
+    }
+
+    /// Adds a filter on a column of an embedded relation, e.g. `orders.status=eq.paid`.
+    ///
+    /// # Arguments
+    /// * `relation` - The relation, or its alias, as passed to `select_related`.
+    /// * `column` - The column of the related rows to apply the filter.
+    /// * `value` - The value to compare against the column.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn eq_related(
+        mut self,
+        relation: &str,
+        column: &str,
+        value: impl Into<FilterValue>,
+    ) -> Self {
+        self.query
+            .add_filter_param(&format!("{}.{}", relation, column), "eq", value.into());
+        self
+    }
+
+    /// Returns the items of the `select` parameter, split on their top level commas.
+    pub(crate) fn select_items(&self) -> Vec<String> {
+        self.query
+            .params
+            .iter()
+            .find(|(key, _)| key == "select")
+            .map(|(_, select)| split_top_level(select))
+            .unwrap_or_default()
+    }
+
+    /// Replaces the `select` parameter, keeping its position among the parameters.
+    pub(crate) fn set_select(&mut self, items: Vec<String>) {
+        let select: String = items.join(",");
+        match self
+            .query
+            .params
+            .iter_mut()
+            .find(|(key, _)| key == "select")
+        {
+            Some((_, value)) => *value = select,
+            None => self.query.params.push(("select".to_string(), select)),
+        }
+    }
+
+    /// Checks the embedded relations against the schema cache of the client, if it has one.
+    ///
+    /// # Returns
+    /// `Ok(())` when every relation exists or there is no cache, or the error naming the known relations.
+    pub(crate) fn validate_embeds(&self) -> Result<(), String> {
+        let Some(cache) = self.client.schema_cache() else {
+            return Ok(());
+        };
+        if !cache.describes(self.effective_schema()) {
+            return Ok(());
+        }
+
+        for item in self.select_items() {
+            match embed_relation(&item) {
+                Some(relation) if cache.shows_relationship(&self.table_name, relation) => {
+                    cache.validate_relation(&self.table_name, relation)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits a select on the commas outside of parentheses.
+pub(crate) fn split_top_level(select: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    let mut current: String = String::new();
+    let mut depth: usize = 0;
+
+    for character in select.chars() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(character);
+    }
+    if !current.is_empty() {
+        items.push(current);
+    }
+
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Returns the name a select refers to an embed item by: its alias, or its relation when it has none.
+fn embed_name(item: &str) -> Option<&str> {
+    let relation: &str = &item[..item.find('(')?];
+    let name: &str = relation
+        .split_once(':')
+        .map_or(relation, |(alias, _)| alias);
+    Some(name.split('!').next().unwrap_or(name).trim())
+}
+
+/// Returns the relation name of an embed item, without its alias and hint, e.g. `users` for
+/// `author:users!author_id(name)`. Plain columns and aggregates, e.g. `count()` or
+/// `total:amount.sum()`, have none.
+pub(crate) fn embed_relation(item: &str) -> Option<&str> {
+    let open: usize = item.find('(')?;
+    let relation: &str = &item[..open];
+    let relation: &str = relation
+        .rsplit_once(':')
+        .map_or(relation, |(_, relation)| relation);
+    let relation: &str = relation.split('!').next().unwrap_or(relation).trim();
+
+    let is_aggregate: bool =
+        relation.contains('.') || (relation == "count" && item[open..].starts_with("()"));
+    (!is_aggregate).then_some(relation)
+}
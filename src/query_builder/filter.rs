@@ -1,5 +1,6 @@
-use crate::query::Filter;
+use crate::errors::ErrorTypes;
 use crate::query::Operator;
+use crate::query::{Filter, FilterValue};
 
 use std::fmt::{Display, Formatter, Result};
 
@@ -61,3 +62,115 @@ impl Display for Filter {
         )
     }
 }
+
+impl FilterValue {
+    /// Renders the value of a filter parameter with the given PostgREST operator, e.g. `eq.30`.
+    ///
+    /// `NULL` only matches with `eq` or `is`, as `is.null`, and `neq`, as `not.is.null`. Other
+    /// operators can't compare with it and fail with [`ErrorTypes::InvalidParameters`].
+    pub(crate) fn with_operator(&self, operator: &str) -> std::result::Result<String, ErrorTypes> {
+        match (self, operator) {
+            (FilterValue::Null, "eq" | "is") => Ok("is.null".to_string()),
+            (FilterValue::Null, "neq") => Ok("not.is.null".to_string()),
+            (FilterValue::Null, operator) => Err(ErrorTypes::InvalidParameters(format!(
+                "`{}` can't compare with NULL, only `eq` and `neq` match it",
+                operator
+            ))),
+            (FilterValue::Text(value), operator) => Ok(format!("{}.{}", operator, value)),
+        }
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        FilterValue::Text(value)
+    }
+}
+
+impl From<&String> for FilterValue {
+    fn from(value: &String) -> Self {
+        FilterValue::Text(value.clone())
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        FilterValue::Text(value.to_string())
+    }
+}
+
+macro_rules! filter_value_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for FilterValue {
+                fn from(value: $integer) -> Self {
+                    FilterValue::Text(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+filter_value_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<f64> for FilterValue {
+    /// Infinities are spelled the way Postgres parses them.
+    fn from(value: f64) -> Self {
+        FilterValue::Text(match value {
+            f64::INFINITY => "Infinity".to_string(),
+            f64::NEG_INFINITY => "-Infinity".to_string(),
+            value => value.to_string(),
+        })
+    }
+}
+
+impl From<f32> for FilterValue {
+    fn from(value: f32) -> Self {
+        FilterValue::from(f64::from(value))
+    }
+}
+
+impl<T: Into<FilterValue>> From<Option<T>> for FilterValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(FilterValue::Null, Into::into)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for FilterValue {
+    fn from(value: uuid::Uuid) -> Self {
+        FilterValue::Text(value.hyphenated().to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for FilterValue {
+    /// Timestamps are sent in UTC, a `+` offset would be decoded as a space in the query string.
+    fn from(value: chrono::DateTime<Tz>) -> Self {
+        FilterValue::Text(
+            value
+                .with_timezone(&chrono::Utc)
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for FilterValue {
+    fn from(value: chrono::NaiveDateTime) -> Self {
+        FilterValue::Text(value.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for FilterValue {
+    fn from(value: chrono::NaiveDate) -> Self {
+        FilterValue::Text(value.format("%Y-%m-%d").to_string())
+    }
+}
@@ -0,0 +1,65 @@
+//! ## JSON filters
+//!
+//! Filters for `jsonb` columns: containment (`cs`, `cd`) and arrow paths into documents.
+//!
+//! ```rust,ignore
+//! use serde_json::json;
+//! use supabase_rs::query_builder::json::json_path;
+//!
+//! let events = client
+//!     .select("events")
+//!     .contains("metadata", &json!({ "source": "webhook" }))
+//!     .eq(&json_path("metadata", &["kind"]), "signup")
+//!     .execute()
+//!     .await?;
+//! ```
+
+use crate::query::QueryBuilder;
+
+use serde_json::Value;
+
+impl QueryBuilder {
+    /// Adds a filter to the query to check if a `jsonb` column contains a document (`cs`).
+    ///
+    /// # Arguments
+    /// * `column` - The column name, or a [`json_path`] into it, to apply the filter.
+    /// * `value` - The document the column must contain, e.g. `{"tags": ["rust"]}`.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn contains(mut self, column: &str, value: &Value) -> Self {
+        self.query.add_param(column, &format!("cs.{}", value));
+        self
+    }
+
+    /// Adds a filter to the query to check if a `jsonb` column is contained in a document (`cd`).
+    ///
+    /// # Arguments
+    /// * `column` - The column name, or a [`json_path`] into it, to apply the filter.
+    /// * `value` - The document that must contain the column.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn contained_by(mut self, column: &str, value: &Value) -> Self {
+        self.query.add_param(column, &format!("cd.{}", value));
+        self
+    }
+}
+
+/// Builds an arrow path into a `jsonb` column, usable as the column of any filter.
+///
+/// The last key is read as text (`->>`), so it compares with plain values, e.g.
+/// `json_path("metadata", &["address", "city"])` is `metadata->address->>city`.
+/// Array elements are addressed by their index, e.g. `"0"`.
+pub fn json_path(column: &str, path: &[&str]) -> String {
+    let mut expression: String = column.to_string();
+    for (index, key) in path.iter().enumerate() {
+        let arrow: &str = match index + 1 == path.len() {
+            true => "->>",
+            false => "->",
+        };
+        expression.push_str(arrow);
+        expression.push_str(key);
+    }
+    expression
+}
@@ -1,3 +1,10 @@
 pub mod builder;
+pub mod delete;
+pub mod embed;
 pub mod filter;
+pub mod json;
+pub mod paginate;
+pub mod range;
+pub mod scan;
 pub mod sort;
+pub mod update;
@@ -0,0 +1,264 @@
+//! ## Pagination
+//!
+//! PostgREST caps every response at `db-max-rows` (1000 on Supabase), so a plain `execute()` on a large
+//! table silently returns a truncated result. [`PageIterator`] fetches the rows page by page with
+//! `limit` and `offset` until the table is exhausted.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let mut pages = client.select("animals").paginate(500);
+//!
+//! while let Some(page) = pages.next_page().await {
+//!     for row in page? {
+//!         // ...
+//!     }
+//! }
+//!
+//! // or everything at once
+//! let rows: Vec<Value> = client.select("animals").paginate(500).collect_all().await?;
+//! ```
+//!
+//! Offsets only give a stable result when the query has a deterministic order and the table isn't
+//! written to while paginating.
+//!
+//! ### Numbered pages
+//! `execute_paged()` fetches a single page with a `Range` header and counts the matching rows in the
+//! same request, for backends serving paginated lists. A page past the last one is empty.
+//! ```rust,ignore
+//! let page: Page<Value> = client
+//!     .select("animals")
+//!     .order_with(OrderOptions::asc("id"))
+//!     .execute_paged(3, 25)
+//!     .await?;
+//!
+//! println!("rows {:?} of {}, page {} of {}", page.range, page.total, page.page, page.total_pages());
+//! if let Some(next) = page.next_page() {
+//!     // ...
+//! }
+//! ```
+
+use crate::errors::RestError;
+use crate::query::{CountMode, QueryBuilder};
+use crate::request::Headers;
+use crate::success::{parse_content_range_total, response_error, unexpected_response};
+
+use reqwest::{Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::ops::Range;
+
+/// Fetches the results of a query one page at a time, created by [`QueryBuilder::paginate`].
+#[derive(Debug, Clone)]
+pub struct PageIterator {
+    builder: QueryBuilder,
+    page_size: usize,
+    offset: usize,
+    exhausted: bool,
+}
+
+/// A single page of the rows matching a query, returned by [`QueryBuilder::execute_paged`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// The rows of the page.
+    pub items: Vec<T>,
+    /// The number of rows matching the query, across all pages.
+    pub total: u64,
+    /// The positions of the rows of the page among all matching rows, empty past the last page.
+    pub range: Range<u64>,
+    /// Whether more rows follow this page.
+    pub has_more: bool,
+    /// The number of the page, starting at 1.
+    pub page: u64,
+    /// The number of rows per page.
+    pub per_page: u64,
+}
+
+impl<T> Page<T> {
+    /// The number of pages holding the matching rows, 0 when no row matches.
+    pub fn total_pages(&self) -> u64 {
+        self.total.div_ceil(self.per_page)
+    }
+
+    /// The number of the next page, `None` on the last page.
+    pub fn next_page(&self) -> Option<u64> {
+        self.has_more.then_some(self.page + 1)
+    }
+
+    /// The number of the previous page, `None` on the first page.
+    pub fn previous_page(&self) -> Option<u64> {
+        (self.page > 1).then(|| self.page - 1)
+    }
+}
+
+impl Page<Value> {
+    /// Deserializes every row of the page into `T`.
+    pub(crate) fn deserialize<T: DeserializeOwned>(self) -> Result<Page<T>, RestError> {
+        let items: Vec<T> = self
+            .items
+            .into_iter()
+            .map(|row| serde_json::from_value(row).map_err(unexpected_response))
+            .collect::<Result<Vec<T>, RestError>>()?;
+
+        Ok(Page {
+            items,
+            total: self.total,
+            range: self.range,
+            has_more: self.has_more,
+            page: self.page,
+            per_page: self.per_page,
+        })
+    }
+}
+
+impl QueryBuilder {
+    /// Fetches a single page of the rows matching the query, along with the total count.
+    ///
+    /// The total is counted with the mode set with `count_mode`, `exact` when unset.
+    ///
+    /// # Arguments
+    /// * `page` - The number of the page, starting at 1.
+    /// * `per_page` - The number of rows per page, keep it at or below the `db-max-rows` setting of
+    ///   the project.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the page, or a `RestError`.
+    pub async fn execute_paged(self, page: u64, per_page: u64) -> Result<Page<Value>, RestError> {
+        if page == 0 {
+            return Err("Page numbers start at 1".into());
+        }
+        let per_page: u64 = per_page.max(1);
+        let start: u64 = (page - 1).saturating_mul(per_page);
+        let end: u64 = start.saturating_add(per_page - 1);
+
+        let mode: CountMode = self.count_mode.unwrap_or(CountMode::Exact);
+        let mut headers: Headers = self.preferences(Some(mode));
+        headers.insert("Range-Unit", "items");
+        headers.insert("Range", &format!("{}-{}", start, end));
+
+        let query_string: String = self.query.build();
+        let request = async {
+            let response: Response = self
+                .client
+                .send_select(&self.table_name, &query_string, headers)
+                .await?;
+            paged_response(response).await
+        };
+        let (items, range, total) = self.run(request).await?;
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_rows_returned(&self.table_name, items.len());
+
+        let range: Range<u64> = range.unwrap_or(start..start);
+        Ok(Page {
+            items,
+            has_more: range.end < total,
+            total,
+            range,
+            page,
+            per_page,
+        })
+    }
+
+    /// Splits the query into pages of `page_size` rows.
+    ///
+    /// # Arguments
+    /// * `page_size` - The number of rows per request, keep it at or below the `db-max-rows` setting
+    ///   of the project, otherwise pagination stops after the first capped page.
+    ///
+    /// # Returns
+    /// A `PageIterator` which issues a request per page.
+    pub fn paginate(self, page_size: usize) -> PageIterator {
+        PageIterator {
+            builder: self,
+            page_size: page_size.max(1),
+            offset: 0,
+            exhausted: false,
+        }
+    }
+}
+
+impl PageIterator {
+    /// Fetches the next page.
+    ///
+    /// # Returns
+    /// `None` once every row has been returned, otherwise the rows of the page or a `RestError`.
+    /// After an error the same page is requested again on the next call.
+    pub async fn next_page(&mut self) -> Option<Result<Vec<Value>, RestError>> {
+        if self.exhausted {
+            return None;
+        }
+
+        let mut builder: QueryBuilder = self.builder.clone();
+        builder
+            .query
+            .params
+            .retain(|(key, _)| key != "limit" && key != "offset");
+        builder
+            .query
+            .add_param("limit", &self.page_size.to_string());
+        builder.query.add_param("offset", &self.offset.to_string());
+
+        let rows: Vec<Value> = match builder.fetch().await {
+            Ok(rows) => rows,
+            Err(error) => return Some(Err(error)),
+        };
+
+        self.offset += rows.len();
+        if rows.len() < self.page_size {
+            self.exhausted = true;
+        }
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(Ok(rows))
+    }
+
+    /// The number of rows returned so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Fetches every remaining page and concatenates the rows.
+    pub async fn collect_all(mut self) -> Result<Vec<Value>, RestError> {
+        let mut rows: Vec<Value> = Vec::new();
+        while let Some(page) = self.next_page().await {
+            rows.extend(page?);
+        }
+        Ok(rows)
+    }
+}
+
+/// Reads the rows, the range they cover and the total count of a paged response.
+///
+/// PostgREST answers a range past the last row with `416` and a `*/total` content range, which is
+/// an empty page rather than an error.
+async fn paged_response(
+    response: Response,
+) -> Result<(Vec<Value>, Option<Range<u64>>, u64), RestError> {
+    let status: StatusCode = response.status();
+    if !status.is_success() && status != StatusCode::RANGE_NOT_SATISFIABLE {
+        return Err(response_error(response).await);
+    }
+
+    let content_range: String = response
+        .headers()
+        .get("content-range")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let total: u64 = parse_content_range_total(&content_range)
+        .ok_or("Response has no total count in its Content-Range header")?;
+    // `0-24/3573`, or `*/3573` when the page holds no rows
+    let range: Option<Range<u64>> = content_range
+        .split('/')
+        .next()
+        .and_then(|range| range.split_once('-'))
+        .and_then(|(first, last)| Some(first.parse::<u64>().ok()?..last.parse::<u64>().ok()? + 1));
+
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok((Vec::new(), None, total));
+    }
+    let rows: Vec<Value> = response.json().await.map_err(|error| error.to_string())?;
+    Ok((rows, range, total))
+}
@@ -0,0 +1,71 @@
+//! ## Range and array filters
+//!
+//! Filters for range columns (`tstzrange`, `int4range`, ...) and arrays. Ranges are written like in
+//! Postgres, e.g. `[2024-01-01,2024-02-01)`, arrays as `{1,2,3}`.
+//!
+//! ```rust,ignore
+//! // bookings overlapping January that don't run into February
+//! let bookings = client
+//!     .select("bookings")
+//!     .overlaps("during", "[2024-01-01,2024-02-01)")
+//!     .strictly_left("during", "[2024-02-01,2024-03-01)")
+//!     .execute()
+//!     .await?;
+//! ```
+
+use crate::query::QueryBuilder;
+
+impl QueryBuilder {
+    /// Adds a filter to the query to check if a range or array column has elements in common with
+    /// a value (`ov`).
+    ///
+    /// # Arguments
+    /// * `column` - The column name to apply the filter.
+    /// * `value` - The range, e.g. `[1,10)`, or array, e.g. `{1,2}`, to compare against the column.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn overlaps(mut self, column: &str, value: &str) -> Self {
+        self.query.add_param(column, &format!("ov.{}", value));
+        self
+    }
+
+    /// Adds a filter to the query to check if a range column is strictly left of a range (`sl`).
+    ///
+    /// # Arguments
+    /// * `column` - The column name to apply the filter.
+    /// * `range` - The range to compare against the column.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn strictly_left(mut self, column: &str, range: &str) -> Self {
+        self.query.add_param(column, &format!("sl.{}", range));
+        self
+    }
+
+    /// Adds a filter to the query to check if a range column is strictly right of a range (`sr`).
+    ///
+    /// # Arguments
+    /// * `column` - The column name to apply the filter.
+    /// * `range` - The range to compare against the column.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn strictly_right(mut self, column: &str, range: &str) -> Self {
+        self.query.add_param(column, &format!("sr.{}", range));
+        self
+    }
+
+    /// Adds a filter to the query to check if a range column is adjacent to a range (`adj`).
+    ///
+    /// # Arguments
+    /// * `column` - The column name to apply the filter.
+    /// * `range` - The range to compare against the column.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    pub fn adjacent(mut self, column: &str, range: &str) -> Self {
+        self.query.add_param(column, &format!("adj.{}", range));
+        self
+    }
+}
@@ -0,0 +1,162 @@
+//! ## Resumable table scans
+//!
+//! A [`TableScanner`] walks a table in the order of a unique key column (keyset pagination), and
+//! emits a serializable [`ScanCheckpoint`] after each page. Persist the checkpoint and a crashed
+//! extraction resumes right after the last processed row, without relying on offsets which drift
+//! while the table is written to.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let mut scanner = client.select("events").scan("id", 1000);
+//! if let Some(checkpoint) = load_checkpoint()? {
+//!     scanner = scanner.resume_from(checkpoint)?;
+//! }
+//!
+//! while let Some(page) = scanner.next_page().await {
+//!     let (rows, checkpoint) = page?;
+//!     export(rows)?;
+//!     save_checkpoint(&serde_json::to_string(&checkpoint)?)?;
+//! }
+//! ```
+
+use crate::errors::RestError;
+use crate::query::QueryBuilder;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The position of a [`TableScanner`], safe to persist between runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// The scanned table.
+    pub table: String,
+    /// The unique column the table is scanned by.
+    pub key_column: String,
+    /// The key of the last returned row, `None` before the first page.
+    pub last_key: Option<Value>,
+    /// The number of rows returned so far.
+    pub rows_scanned: u64,
+}
+
+/// Scans a table page by page in key order, created by [`QueryBuilder::scan`].
+#[derive(Debug, Clone)]
+pub struct TableScanner {
+    builder: QueryBuilder,
+    page_size: usize,
+    checkpoint: ScanCheckpoint,
+    exhausted: bool,
+}
+
+impl QueryBuilder {
+    /// Scans the query results in ascending order of `key_column`.
+    ///
+    /// # Arguments
+    /// * `key_column` - A unique, sortable column like the primary key, it must be among the selected columns.
+    /// * `page_size` - The number of rows per request.
+    ///
+    /// # Returns
+    /// A `TableScanner` which issues a request per page.
+    pub fn scan(self, key_column: &str, page_size: usize) -> TableScanner {
+        TableScanner {
+            checkpoint: ScanCheckpoint {
+                table: self.table_name.clone(),
+                key_column: key_column.to_string(),
+                last_key: None,
+                rows_scanned: 0,
+            },
+            builder: self,
+            page_size: page_size.max(1),
+            exhausted: false,
+        }
+    }
+}
+
+impl TableScanner {
+    /// Continues a scan from a previously emitted checkpoint.
+    ///
+    /// # Returns
+    /// The scanner, or a `String` error when the checkpoint belongs to another table or key column.
+    pub fn resume_from(mut self, checkpoint: ScanCheckpoint) -> Result<Self, String> {
+        if checkpoint.table != self.checkpoint.table
+            || checkpoint.key_column != self.checkpoint.key_column
+        {
+            return Err(format!(
+                "Checkpoint of {}.{} can't resume a scan of {}.{}",
+                checkpoint.table,
+                checkpoint.key_column,
+                self.checkpoint.table,
+                self.checkpoint.key_column
+            ));
+        }
+
+        self.checkpoint = checkpoint;
+        Ok(self)
+    }
+
+    /// The position after the last returned page.
+    pub fn checkpoint(&self) -> &ScanCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Fetches the next page.
+    ///
+    /// # Returns
+    /// `None` once the table is exhausted, otherwise the rows of the page along with the checkpoint
+    /// to persist once they are processed, or a `RestError`.
+    pub async fn next_page(&mut self) -> Option<Result<(Vec<Value>, ScanCheckpoint), RestError>> {
+        if self.exhausted {
+            return None;
+        }
+
+        let rows: Vec<Value> = match self.page_builder().fetch().await {
+            Ok(rows) => rows,
+            Err(error) => return Some(Err(error)),
+        };
+
+        if rows.len() < self.page_size {
+            self.exhausted = true;
+        }
+        let last_row: &Value = rows.last()?;
+
+        let last_key: Value = last_row[&self.checkpoint.key_column].clone();
+        if last_key.is_null() {
+            return Some(Err(format!(
+                "Rows have no value for the key column `{}`",
+                self.checkpoint.key_column
+            )
+            .into()));
+        }
+
+        self.checkpoint.last_key = Some(last_key);
+        self.checkpoint.rows_scanned += rows.len() as u64;
+
+        Some(Ok((rows, self.checkpoint.clone())))
+    }
+
+    /// Builds the request of the next page.
+    pub(crate) fn page_builder(&self) -> QueryBuilder {
+        let key_column: &str = &self.checkpoint.key_column;
+        let mut builder: QueryBuilder = self.builder.clone();
+
+        if let Some(last_key) = &self.checkpoint.last_key {
+            let last_key: String = match last_key {
+                Value::String(key) => key.clone(),
+                key => key.to_string(),
+            };
+            builder = builder.gt(key_column, &last_key);
+        }
+
+        // the pages follow the key column alone, an order, limit or offset of the query would break them
+        builder
+            .query
+            .params
+            .retain(|(key, _)| !matches!(key.as_str(), "order" | "limit" | "offset"));
+        builder
+            .query
+            .add_param("order", &format!("{}.asc", key_column));
+        builder
+            .query
+            .add_param("limit", &self.page_size.to_string());
+        builder
+    }
+}
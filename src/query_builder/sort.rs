@@ -1,4 +1,4 @@
-use crate::query::{Sort, SortOrder};
+use crate::query::{OrderOptions, Query, QueryBuilder, Sort, SortOrder};
 
 use std::fmt::{Display, Formatter, Result};
 
@@ -15,3 +15,92 @@ impl Display for Sort {
         )
     }
 }
+
+impl OrderOptions {
+    /// Orders by `column` from the smallest to the largest value.
+    pub fn asc(column: &str) -> Self {
+        OrderOptions {
+            column: column.to_string(),
+            ascending: true,
+            nulls_first: None,
+        }
+    }
+
+    /// Orders by `column` from the largest to the smallest value.
+    pub fn desc(column: &str) -> Self {
+        OrderOptions {
+            ascending: false,
+            ..OrderOptions::asc(column)
+        }
+    }
+
+    /// Puts null values before the other values.
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls_first = Some(true);
+        self
+    }
+
+    /// Puts null values after the other values.
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls_first = Some(false);
+        self
+    }
+}
+
+impl Display for OrderOptions {
+    /// Formats the ordering as a PostgREST order term, e.g. `created_at.desc.nullslast`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{}.{}",
+            self.column,
+            match self.ascending {
+                true => "asc",
+                false => "desc",
+            }
+        )?;
+        match self.nulls_first {
+            Some(true) => write!(f, ".nullsfirst"),
+            Some(false) => write!(f, ".nullslast"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl QueryBuilder {
+    /// Orders the results by a column, after the orderings added before.
+    ///
+    /// # Arguments
+    /// * `options` - The column, direction and position of null values.
+    ///
+    /// # Returns
+    /// Returns the `QueryBuilder` instance to allow for method chaining.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// // newest first, undated rows last, then by name
+    /// let rows = client
+    ///     .select("events")
+    ///     .order_with(OrderOptions::desc("starts_at").nulls_last())
+    ///     .order_with(OrderOptions::asc("name"))
+    ///     .execute()
+    ///     .await?;
+    /// ```
+    pub fn order_with(mut self, options: OrderOptions) -> Self {
+        self.query.append_order(&options.to_string());
+        self
+    }
+}
+
+impl Query {
+    /// Appends terms to the `order` parameter, so each ordering breaks the ties of the previous ones.
+    pub(crate) fn append_order(&mut self, terms: &str) {
+        match self.params.iter_mut().find(|(key, _)| key == "order") {
+            Some((_, order)) => {
+                order.push(',');
+                order.push_str(terms);
+            }
+            None => self.params.push(("order".to_string(), terms.to_string())),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! ## Filtered updates
+//!
+//! The [`UpdateBuilder`] applies the same changes to every row matching its filters, and reports how
+//! many rows were affected. An update without any filter would change the whole table, so it is
+//! refused unless explicitly allowed.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde_json::json;
+//!
+//! let expired: u64 = client
+//!     .update_table("orders")
+//!     .eq("status", "pending")
+//!     .lt("created_at", "2024-01-01")
+//!     .set(json!({"status": "expired"}))
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! ### Returning the updated rows
+//! `execute_returning()` returns the updated rows, `returning_columns()` limits them to a few columns
+//! so wide rows aren't transferred back after every write.
+//! ```rust,ignore
+//! let updated: Vec<Value> = client
+//!     .update_table("invoices")
+//!     .eq("customer_id", 42)
+//!     .set(json!({"status": "paid"}))
+//!     .returning_columns(&["id", "status"])
+//!     .execute_returning()
+//!     .await?;
+//! ```
+
+use crate::errors::RestError;
+use crate::insert::in_filter;
+use crate::query::{CountMode, FilterValue, Query, Returning};
+use crate::request::headers::HeadersTypes;
+use crate::request::prefer::PreferBuilder;
+use crate::request::Headers;
+use crate::success::{handle_count_response, response_rows};
+use crate::SupabaseClient;
+
+use reqwest::Response;
+use serde_json::Value;
+
+/// An update of every row matching a set of filters, created by [`SupabaseClient::update_table`].
+#[derive(Debug, Clone)]
+pub struct UpdateBuilder {
+    client: SupabaseClient,
+    table_name: String,
+    query: Query,
+    body: Option<Value>,
+    select: Option<String>,
+    idempotency_key: Option<String>,
+    allow_unfiltered: bool,
+}
+
+impl SupabaseClient {
+    /// Initializes an `UpdateBuilder` for the specified table.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table to update.
+    ///
+    /// # Returns
+    /// An `UpdateBuilder` instance configured for the specified table.
+    pub fn update_table(&self, table_name: &str) -> UpdateBuilder {
+        UpdateBuilder {
+            client: self.clone(),
+            table_name: table_name.to_string(),
+            query: Query::new(),
+            body: None,
+            select: None,
+            idempotency_key: None,
+            allow_unfiltered: false,
+        }
+    }
+}
+
+impl UpdateBuilder {
+    /// Sets the columns to change, as a JSON object of column names and new values.
+    pub fn set(mut self, body: Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Only updates rows where the column is equal to a specified value.
+    pub fn eq(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "eq", value.into());
+        self
+    }
+
+    /// Only updates rows where the column is not equal to a specified value.
+    pub fn neq(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "neq", value.into());
+        self
+    }
+
+    /// Only updates rows where the column is greater than a specified value.
+    pub fn gt(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "gt", value.into());
+        self
+    }
+
+    /// Only updates rows where the column is less than a specified value.
+    pub fn lt(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "lt", value.into());
+        self
+    }
+
+    /// Only updates rows where the column is greater than or equal to a specified value.
+    pub fn gte(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "gte", value.into());
+        self
+    }
+
+    /// Only updates rows where the column is less than or equal to a specified value.
+    pub fn lte(mut self, column: &str, value: impl Into<FilterValue>) -> Self {
+        self.query.add_filter_param(column, "lte", value.into());
+        self
+    }
+
+    /// Only updates rows where the column is one of the specified values.
+    pub fn in_(mut self, column: &str, values: &[&str]) -> Self {
+        self.query
+            .add_param(column, &in_filter(values.iter().copied()));
+        self
+    }
+
+    /// Only returns these columns of the updated rows from `execute_returning`, all of them when unset.
+    pub fn returning_columns(mut self, columns: &[&str]) -> Self {
+        self.select = Some(columns.join(","));
+        self
+    }
+
+    /// Sends the update with an idempotency key, so a retry fails instead of applying it twice, see
+    /// [`idempotency`](crate::idempotency).
+    pub fn idempotency_key(mut self, key: &str) -> Self {
+        self.idempotency_key = Some(key.to_string());
+        self
+    }
+
+    /// Allows executing the update without any filter, changing every row of the table.
+    pub fn allow_unfiltered(mut self) -> Self {
+        self.allow_unfiltered = true;
+        self
+    }
+
+    /// Builds the query string of the update.
+    ///
+    /// # Returns
+    /// The query string, or a `String` error when no changes were set, or when the update has no
+    /// filter and unfiltered updates aren't allowed.
+    pub fn build(&self) -> Result<String, String> {
+        if self.body.is_none() {
+            return Err(format!(
+                "Update of `{}` has no changes, call `set()` first",
+                self.table_name
+            ));
+        }
+        if self.query.params.is_empty() && !self.allow_unfiltered {
+            return Err(format!(
+                "Refusing to update every row of `{}` without a filter, call `allow_unfiltered()` to do so",
+                self.table_name
+            ));
+        }
+        Ok(self.query().build())
+    }
+
+    /// The filters of the update, with the columns to return.
+    fn query(&self) -> Query {
+        let mut query: Query = self.query.clone();
+        if let Some(select) = &self.select {
+            query
+                .params
+                .insert(0, ("select".to_string(), select.clone()));
+        }
+        query
+    }
+
+    /// Executes the update.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the number of updated rows, or a `RestError`.
+    pub async fn execute(self) -> Result<u64, RestError> {
+        let response: Response = self
+            .send(
+                PreferBuilder::new()
+                    .returning(Returning::Minimal)
+                    .count(CountMode::Exact),
+            )
+            .await?;
+        let (_, affected): (Vec<Value>, u64) = handle_count_response(response).await?;
+        Ok(affected)
+    }
+
+    /// Executes the update and returns the updated rows.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the updated rows, or a `RestError`.
+    pub async fn execute_returning(self) -> Result<Vec<Value>, RestError> {
+        let response: Response = self
+            .send(PreferBuilder::new().returning(Returning::Representation))
+            .await?;
+        response_rows(response).await
+    }
+
+    async fn send(&self, prefer: PreferBuilder) -> Result<Response, RestError> {
+        self.query.check_filters()?;
+        let query_string: String = self.build()?;
+        if let Some(cache) = self.client.schema_cache() {
+            cache.validate_query(
+                self.client.schema(),
+                &self.table_name,
+                &self.query(),
+                self.body.as_ref(),
+            )?;
+        }
+        let endpoint: String = format!(
+            "{}/rest/v1/{}?{}",
+            self.client.url, self.table_name, query_string
+        );
+
+        #[cfg(feature = "nightly")]
+        use crate::nightly::print_nightly_warning;
+        #[cfg(feature = "nightly")]
+        print_nightly_warning();
+
+        let mut headers: Headers =
+            Headers::with_defaults(&self.client.api_key, &self.client.api_key);
+        prefer.apply(&mut headers);
+        if let Some(key) = &self.idempotency_key {
+            headers.insert(HeadersTypes::IdempotencyKey.as_str(), key);
+        }
+
+        let mut request = self.client.client.patch(&endpoint).json(&self.body);
+        for (key, value) in headers.get_headers() {
+            request = request.header(key, value);
+        }
+
+        self.client
+            .dispatch(
+                &self.table_name,
+                "update",
+                self.client.content_profile(request),
+            )
+            .await
+            .map_err(RestError::from)
+    }
+}
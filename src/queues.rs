@@ -0,0 +1,198 @@
+//! ## Queues
+//!
+//! Supabase Queues are backed by the `pgmq` extension and exposed through the functions of the
+//! `pgmq_public` schema. Make sure that schema is exposed in the API settings of the project.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde::{Deserialize, Serialize};
+//! use supabase_rs::queues::QueueMessage;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Job {
+//!     user_id: i64,
+//! }
+//!
+//! let msg_id: i64 = client.queues().send("jobs", &Job { user_id: 1 }, 0).await?;
+//!
+//! // hide the messages for 30 seconds while they are being processed
+//! let messages: Vec<QueueMessage<Job>> = client.queues().read("jobs", 30, 10).await?;
+//! for message in messages {
+//!     // ...
+//!     client.queues().delete("jobs", message.msg_id).await?;
+//! }
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// The schema in which Supabase exposes the `pgmq` functions.
+pub const QUEUES_SCHEMA: &str = "pgmq_public";
+
+/// A message read from a queue, wrapping the payload of type `T`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueMessage<T> {
+    /// The id of the message within its queue.
+    pub msg_id: i64,
+    /// How many times the message has been read.
+    pub read_ct: i32,
+    /// When the message was sent.
+    pub enqueued_at: String,
+    /// When the message becomes visible to readers again.
+    pub vt: String,
+    /// The message payload.
+    pub message: T,
+}
+
+/// A client for Supabase Queues, created by [`SupabaseClient::queues`].
+#[derive(Debug, Clone)]
+pub struct Queues {
+    client: SupabaseClient,
+}
+
+impl SupabaseClient {
+    /// Returns a client for the `pgmq` backed Supabase Queues.
+    pub fn queues(&self) -> Queues {
+        Queues {
+            client: self.clone(),
+        }
+    }
+}
+
+impl Queues {
+    /// Sends a message to a queue.
+    ///
+    /// # Arguments
+    /// * `queue_name` - The queue to send the message to.
+    /// * `message` - The payload, serialized as JSON.
+    /// * `delay_seconds` - How long the message stays invisible to readers after sending.
+    ///
+    /// # Returns
+    /// The id of the new message.
+    pub async fn send<T: Serialize>(
+        &self,
+        queue_name: &str,
+        message: &T,
+        delay_seconds: u32,
+    ) -> Result<i64> {
+        let message: Value = serde_json::to_value(message)
+            .map_err(|error| ErrorTypes::InvalidParameters(error.to_string()))?;
+
+        let result: Value = self
+            .client
+            .rpc(
+                "send",
+                json!({
+                    "queue_name": queue_name,
+                    "message": message,
+                    "sleep_seconds": delay_seconds,
+                }),
+            )
+            .schema(QUEUES_SCHEMA)
+            .execute()
+            .await?;
+
+        // `send` returns a set with the single new message id
+        result
+            .as_array()
+            .and_then(|ids| ids.first())
+            .or(Some(&result))
+            .and_then(Value::as_i64)
+            .ok_or_else(|| {
+                ErrorTypes::UnexpectedResponse(format!("expected a message id, got {}", result))
+            })
+    }
+
+    /// Reads up to `count` messages, hiding them from other readers for `visibility_timeout` seconds.
+    ///
+    /// Messages are not removed, call [`Queues::delete`] or [`Queues::archive`] once processed.
+    pub async fn read<T: DeserializeOwned>(
+        &self,
+        queue_name: &str,
+        visibility_timeout: u32,
+        count: u32,
+    ) -> Result<Vec<QueueMessage<T>>> {
+        let result: Value = self
+            .client
+            .rpc(
+                "read",
+                json!({
+                    "queue_name": queue_name,
+                    "sleep_seconds": visibility_timeout,
+                    "n": count,
+                }),
+            )
+            .schema(QUEUES_SCHEMA)
+            .execute()
+            .await?;
+
+        parse_messages(result)
+    }
+
+    /// Reads and removes the next message of a queue, if there is one.
+    pub async fn pop<T: DeserializeOwned>(
+        &self,
+        queue_name: &str,
+    ) -> Result<Option<QueueMessage<T>>> {
+        let result: Value = self
+            .client
+            .rpc("pop", json!({ "queue_name": queue_name }))
+            .schema(QUEUES_SCHEMA)
+            .execute()
+            .await?;
+
+        Ok(parse_messages(result)?.into_iter().next())
+    }
+
+    /// Moves a message to the archive table of its queue.
+    ///
+    /// # Returns
+    /// Whether a message with `msg_id` was found and archived.
+    pub async fn archive(&self, queue_name: &str, msg_id: i64) -> Result<bool> {
+        self.call_with_message_id("archive", queue_name, msg_id)
+            .await
+    }
+
+    /// Permanently deletes a message from its queue.
+    ///
+    /// # Returns
+    /// Whether a message with `msg_id` was found and deleted.
+    pub async fn delete(&self, queue_name: &str, msg_id: i64) -> Result<bool> {
+        self.call_with_message_id("delete", queue_name, msg_id)
+            .await
+    }
+
+    async fn call_with_message_id(
+        &self,
+        function_name: &str,
+        queue_name: &str,
+        msg_id: i64,
+    ) -> Result<bool> {
+        let result: Value = self
+            .client
+            .rpc(
+                function_name,
+                json!({ "queue_name": queue_name, "message_id": msg_id }),
+            )
+            .schema(QUEUES_SCHEMA)
+            .execute()
+            .await?;
+
+        result.as_bool().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a boolean, got {}", result))
+        })
+    }
+}
+
+/// Parses the `pgmq.message_record` rows returned by `read` and `pop`.
+pub fn parse_messages<T: DeserializeOwned>(rows: Value) -> Result<Vec<QueueMessage<T>>> {
+    match rows {
+        Value::Null => Ok(Vec::new()),
+        rows => serde_json::from_value(rows)
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string())),
+    }
+}
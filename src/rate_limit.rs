@@ -0,0 +1,187 @@
+//! ## Rate limiting
+//!
+//! A client-side limiter that keeps bulk jobs under the request limits of a Supabase project, instead
+//! of tripping `429`s. It bounds the number of requests in flight and the number of requests started
+//! per second. Requests over the limits wait in a queue, the limiter is shared between clones of the
+//! client.
+//!
+//! ```rust,ignore
+//! use supabase_rs::rate_limit::RateLimitConfig;
+//!
+//! let client = SupabaseClient::new(url, key)?.with_rate_limit(RateLimitConfig {
+//!     max_in_flight: Some(8),
+//!     requests_per_second: Some(50),
+//! });
+//!
+//! // all 10_000 inserts run concurrently, but at most 8 at a time and 50 per second
+//! futures::future::join_all(rows.into_iter().map(|row| client.insert("events", row))).await;
+//!
+//! let stats = client.rate_limit_stats().unwrap();
+//! println!("{} queued, {} in flight", stats.queued, stats.in_flight);
+//! ```
+//!
+//! With the `metrics` feature the queue is exported as the `supabase_rs_rate_limit_queued` and
+//! `supabase_rs_rate_limit_in_flight` gauges, labelled by the `host` of the project, and the time
+//! spent waiting as the `supabase_rs_rate_limit_wait_seconds` histogram.
+
+use crate::SupabaseClient;
+
+use reqwest::Url;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// The limits of a [`SupabaseClient`], unbounded when `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// The maximum number of requests sent at the same time.
+    pub max_in_flight: Option<usize>,
+    /// The maximum number of requests started per second, spread evenly over the second.
+    pub requests_per_second: Option<u32>,
+}
+
+/// A snapshot of the queue of a rate limiter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitStats {
+    /// The requests waiting for their turn.
+    pub queued: usize,
+    /// The requests being sent.
+    pub in_flight: usize,
+}
+
+/// The shared state of a rate limiter.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    semaphore: Option<Arc<Semaphore>>,
+    interval: Option<Duration>,
+    next_slot: Arc<Mutex<Option<Instant>>>,
+    queued: Arc<AtomicUsize>,
+    in_flight: Arc<AtomicUsize>,
+    /// The host of the project, labelling the gauges of the queue.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    host: Arc<str>,
+}
+
+/// The turn of a request, it is in flight until dropped.
+#[derive(Debug)]
+pub(crate) struct RateLimitPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+    limiter: RateLimiter,
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        let in_flight: usize = self.limiter.in_flight.fetch_sub(1, Ordering::SeqCst) - 1;
+        self.limiter.record_in_flight(in_flight);
+    }
+}
+
+/// A request waiting in the queue, it leaves the queue when dropped, also when the request is.
+struct Queued<'a> {
+    limiter: &'a RateLimiter,
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        let queued: usize = self.limiter.queued.fetch_sub(1, Ordering::SeqCst) - 1;
+        self.limiter.record_queued(queued);
+    }
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimitConfig, host: &str) -> Self {
+        RateLimiter {
+            semaphore: config
+                .max_in_flight
+                .map(|permits| Arc::new(Semaphore::new(permits.max(1)))),
+            interval: config
+                .requests_per_second
+                .map(|rate| Duration::from_secs(1) / rate.max(1)),
+            next_slot: Arc::new(Mutex::new(None)),
+            queued: Arc::new(AtomicUsize::new(0)),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            host: Arc::from(host),
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub(crate) async fn acquire(&self) -> RateLimitPermit {
+        #[cfg(feature = "metrics")]
+        let started: Instant = Instant::now();
+        self.record_queued(self.queued.fetch_add(1, Ordering::SeqCst) + 1);
+        let queued: Queued<'_> = Queued { limiter: self };
+
+        let permit: Option<OwnedSemaphorePermit> = match &self.semaphore {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        if let Some(slot) = self.reserve_slot() {
+            tokio::time::sleep_until(slot).await;
+        }
+
+        drop(queued);
+        self.record_in_flight(self.in_flight.fetch_add(1, Ordering::SeqCst) + 1);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_rate_limit_wait(started.elapsed());
+
+        RateLimitPermit {
+            _permit: permit,
+            limiter: self.clone(),
+        }
+    }
+
+    /// Reserves the next start time allowed by the requests per second.
+    fn reserve_slot(&self) -> Option<Instant> {
+        let interval: Duration = self.interval?;
+        let mut next_slot = self
+            .next_slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let now: Instant = Instant::now();
+        let slot: Instant = next_slot.map_or(now, |next| next.max(now));
+        *next_slot = Some(slot + interval);
+        Some(slot)
+    }
+
+    pub(crate) fn stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            queued: self.queued.load(Ordering::SeqCst),
+            in_flight: self.in_flight.load(Ordering::SeqCst),
+        }
+    }
+
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_queued(&self, queued: usize) {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_rate_limit_queued(&self.host, queued);
+    }
+
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_in_flight(&self, in_flight: usize) {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_rate_limit_in_flight(&self.host, in_flight);
+    }
+}
+
+impl SupabaseClient {
+    /// Limits the requests of the client and its clones, see [`rate_limit`](crate::rate_limit).
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the rate limiter attached.
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        let host: String = Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        self.rate_limiter = Some(RateLimiter::new(config, &host));
+        self
+    }
+
+    /// Returns the queue of the rate limiter, if the client has one.
+    pub fn rate_limit_stats(&self) -> Option<RateLimitStats> {
+        self.rate_limiter.as_ref().map(RateLimiter::stats)
+    }
+}
@@ -0,0 +1,111 @@
+//! ## Broadcast
+//!
+//! Publishes messages to Realtime channels through the broadcast REST endpoint, which is handy for
+//! server triggered UI updates. Subscribers receive them like any other broadcast message.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde_json::json;
+//!
+//! client
+//!     .realtime_broadcast("room:1", "new_message", json!({"text": "hello"}))
+//!     .await?;
+//! ```
+
+use crate::errors::{ErrorTypes, RestError};
+use crate::request::Headers;
+use crate::SupabaseClient;
+
+use reqwest::{Response, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// A message published to a Realtime channel.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BroadcastMessage {
+    /// The channel topic, e.g. `room:1`.
+    pub topic: String,
+    /// The event name subscribers listen for.
+    pub event: String,
+    /// The message payload.
+    pub payload: Value,
+    /// Whether the channel is private and protected by Realtime authorization policies.
+    pub private: bool,
+}
+
+impl BroadcastMessage {
+    /// Creates a message for a public channel.
+    pub fn new(topic: &str, event: &str, payload: Value) -> Self {
+        BroadcastMessage {
+            topic: topic.to_string(),
+            event: event.to_string(),
+            payload,
+            private: false,
+        }
+    }
+
+    /// Marks the channel as private.
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+}
+
+impl SupabaseClient {
+    /// Publishes a single message to a public Realtime channel.
+    ///
+    /// # Arguments
+    /// * `topic` - The channel topic.
+    /// * `event` - The event name.
+    /// * `payload` - The message payload.
+    ///
+    /// # Returns
+    /// This method returns a `Result<(), RestError>`. On success, it returns `Ok(())`,
+    /// and on failure, it returns `Err(RestError)` with an error message.
+    pub async fn realtime_broadcast(
+        &self,
+        topic: &str,
+        event: &str,
+        payload: Value,
+    ) -> Result<(), RestError> {
+        self.realtime_broadcast_messages(&[BroadcastMessage::new(topic, event, payload)])
+            .await
+    }
+
+    /// Publishes several messages in a single request.
+    ///
+    /// # Returns
+    /// This method returns a `Result<(), RestError>`. On success, it returns `Ok(())`,
+    /// and on failure, it returns `Err(RestError)` with an error message.
+    pub async fn realtime_broadcast_messages(
+        &self,
+        messages: &[BroadcastMessage],
+    ) -> Result<(), RestError> {
+        let endpoint: String = format!("{}/realtime/v1/api/broadcast", self.url);
+        let headers: Headers = Headers::with_defaults(&self.api_key, &self.api_key);
+
+        let mut request = self.client.post(&endpoint).json(&broadcast_body(messages));
+        for (key, value) in headers.get_headers() {
+            request = request.header(key, value);
+        }
+
+        let response: Response = self.dispatch("", "broadcast", request).await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status: StatusCode = response.status();
+            let body: String = response.text().await.unwrap_or_default();
+            let message: String = format!("{}: {}", status, body);
+            let error: ErrorTypes = ErrorTypes::ApiError {
+                status: status.as_u16(),
+                message: body,
+            };
+            Err(RestError::new(error).with_message(message))
+        }
+    }
+}
+
+/// Builds the request body of the broadcast endpoint.
+pub fn broadcast_body(messages: &[BroadcastMessage]) -> Value {
+    json!({ "messages": messages })
+}
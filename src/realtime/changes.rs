@@ -0,0 +1,396 @@
+//! ## Row changes
+//!
+//! [`RealtimeClient::table_changes`] subscribes to the inserts, updates and deletes of a table and
+//! hands them to a bounded `tokio` channel, for services consuming them in a task of their own. The
+//! rows are decoded into the type of the receiver, changes whose row doesn't decode are skipped.
+//!
+//! ```rust,ignore
+//! #[derive(Deserialize, Debug)]
+//! struct Order { id: i64, status: String }
+//!
+//! let mut changes: Receiver<ChangeEvent<Order>> = client
+//!     .realtime()
+//!     .table_changes::<Order>("public", "orders")
+//!     .await?;
+//!
+//! while let Some(change) = changes.recv().await {
+//!     match change.kind {
+//!         ChangeKind::Insert | ChangeKind::Update => println!("{:?}", change.new),
+//!         ChangeKind::Delete => println!("deleted {}", change.old_record["id"]),
+//!     }
+//! }
+//! ```
+//!
+//! The table has to be part of the `supabase_realtime` publication. Without `replica identity full`
+//! Postgres only sends the primary key of the old row of updates and deletes, which leaves
+//! [`ChangeEvent::old`] empty for row types with more columns, [`ChangeEvent::old_record`] holds it.
+//!
+//! ### Backpressure
+//! Once the queue of the receiver is full, the connection stops reading until it has room again, so
+//! no change is lost, but the other channels of the client wait too. A receiver stalled for longer
+//! than the heartbeat timeout of Realtime loses the connection, which is opened again without the
+//! changes in between, keep the consumer fast or the capacity large enough to absorb bursts.
+//!
+//! Dropping the receiver leaves the channel. With the `metrics` feature the time from the commit
+//! until a change was received and the changes waiting in the queue are recorded, see
+//! [`metrics`](crate::metrics).
+
+use crate::errors::Result;
+use crate::realtime::client::RealtimeClient;
+use crate::realtime::RealtimeChannel;
+
+use futures_util::future::BoxFuture;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use tokio::sync::Notify;
+
+/// A row change waiting for room in the queue of its subscriber.
+pub(crate) type Delivery = BoxFuture<'static, ()>;
+
+/// Decodes the data of a change and queues it, returning the queueing when the queue is full.
+type ChangeCallback = Arc<dyn Fn(&Value) -> Option<Delivery> + Send + Sync>;
+
+/// The kind of a row change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeKind {
+    /// The name Realtime uses for the change, e.g. `INSERT`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Insert => "INSERT",
+            ChangeKind::Update => "UPDATE",
+            ChangeKind::Delete => "DELETE",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "INSERT" => Some(ChangeKind::Insert),
+            "UPDATE" => Some(ChangeKind::Update),
+            "DELETE" => Some(ChangeKind::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// An insert, update or delete of a row, decoded into `T`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent<T> {
+    pub kind: ChangeKind,
+    pub schema: String,
+    pub table: String,
+    /// When the transaction of the change was committed, e.g. `2024-05-09T14:05:33.915Z`.
+    pub commit_timestamp: Option<String>,
+    /// The row after an insert or update.
+    pub new: Option<T>,
+    /// The row before an update or delete, `None` when [`old_record`](Self::old_record) doesn't
+    /// decode into `T`.
+    pub old: Option<T>,
+    /// The columns of the row before an update or delete sent by Postgres, only its primary key
+    /// unless the table has `replica identity full`.
+    pub old_record: Value,
+    /// The time from the commit until the change was received, `None` when unknown.
+    pub lag: Option<Duration>,
+}
+
+/// Which changes of a table to subscribe to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangesOptions {
+    /// The kind of changes, every kind when `None`.
+    pub kind: Option<ChangeKind>,
+    /// A filter on the rows, e.g. `status=eq.open`.
+    pub filter: Option<String>,
+    /// The number of changes the receiver can fall behind before the connection waits for it.
+    pub capacity: usize,
+}
+
+impl Default for ChangesOptions {
+    fn default() -> Self {
+        ChangesOptions {
+            kind: None,
+            filter: None,
+            capacity: 256,
+        }
+    }
+}
+
+impl ChangesOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only receives changes of `kind`.
+    pub fn kind(mut self, kind: ChangeKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Only receives changes of the rows matching `filter`, e.g. `status=eq.open`.
+    pub fn filter(mut self, filter: &str) -> Self {
+        self.filter = Some(filter.to_string());
+        self
+    }
+
+    /// Sets the number of changes the receiver can fall behind, at least 1.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+}
+
+/// A subscription to the row changes of a table, sent with the join of its channel.
+#[derive(Clone)]
+pub(crate) struct ChangeBinding {
+    schema: String,
+    table: String,
+    options: ChangesOptions,
+    /// The id Realtime gave the subscription when joining.
+    pub(crate) id: Option<u64>,
+    callback: ChangeCallback,
+    /// Notified after every join of the channel, including the ones after reconnecting.
+    joined: Option<Arc<Notify>>,
+}
+
+impl Debug for ChangeBinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeBinding")
+            .field("schema", &self.schema)
+            .field("table", &self.table)
+            .field("options", &self.options)
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChangeBinding {
+    /// The entry of the subscription in the `postgres_changes` of the join.
+    pub(crate) fn config(&self) -> Value {
+        let mut config: Value = json!({
+            "event": self.options.kind.map_or("*", |kind| kind.as_str()),
+            "schema": self.schema,
+            "table": self.table,
+        });
+        if let Some(filter) = &self.options.filter {
+            config["filter"] = json!(filter);
+        }
+        config
+    }
+
+    /// Whether a change with the subscription `ids` and `data` belongs to this subscription.
+    pub(crate) fn matches(&self, ids: &[u64], data: &Value) -> bool {
+        match self.id {
+            Some(id) => ids.contains(&id),
+            None => data["schema"] == self.schema.as_str() && data["table"] == self.table.as_str(),
+        }
+    }
+
+    /// Queues a change, returning the queueing when the queue is full.
+    pub(crate) fn deliver(&self, data: &Value) -> Option<Delivery> {
+        (self.callback)(data)
+    }
+
+    /// Tells the subscriber its channel was joined.
+    pub(crate) fn joined(&self) {
+        if let Some(joined) = &self.joined {
+            joined.notify_one();
+        }
+    }
+}
+
+impl RealtimeClient {
+    /// Subscribes to every insert, update and delete of `schema.table`, connecting the client first
+    /// when needed.
+    ///
+    /// # Returns
+    /// The receiver of the changes, or [`ErrorTypes::Realtime`](crate::errors::ErrorTypes::Realtime)
+    /// when the connection fails or Realtime refuses the subscription.
+    pub async fn table_changes<T>(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Receiver<ChangeEvent<T>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        self.table_changes_with(schema, table, ChangesOptions::default())
+            .await
+    }
+
+    /// Subscribes to the changes of `schema.table` selected by `options`, see
+    /// [`table_changes`](Self::table_changes).
+    pub async fn table_changes_with<T>(
+        &self,
+        schema: &str,
+        table: &str,
+        options: ChangesOptions,
+    ) -> Result<Receiver<ChangeEvent<T>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        self.subscribe_changes(schema, table, options, None).await
+    }
+
+    /// Subscribes to the changes of `schema.table`, notifying `joined` after every join of the
+    /// channel.
+    pub(crate) async fn subscribe_changes<T>(
+        &self,
+        schema: &str,
+        table: &str,
+        options: ChangesOptions,
+        joined: Option<Arc<Notify>>,
+    ) -> Result<Receiver<ChangeEvent<T>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<ChangeEvent<T>>(options.capacity.max(1));
+        let binding: ChangeBinding = ChangeBinding {
+            schema: schema.to_string(),
+            table: table.to_string(),
+            options,
+            id: None,
+            callback: queue(sender.clone()),
+            joined,
+        };
+
+        let channel: RealtimeChannel = self
+            .channel(&format!("changes:{}:{}:{}", schema, table, self.make_ref()))
+            .postgres_changes(binding);
+        channel.subscribe().await?;
+
+        // keeps the connection open while the receiver is around, and leaves once it is dropped
+        tokio::spawn(async move {
+            sender.closed().await;
+            let _ = channel.unsubscribe().await;
+        });
+        Ok(receiver)
+    }
+}
+
+/// The callback decoding changes into `T` and queueing them on `sender`.
+fn queue<T>(sender: Sender<ChangeEvent<T>>) -> ChangeCallback
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    Arc::new(move |data: &Value| {
+        let event: ChangeEvent<T> = decode(data)?;
+        #[cfg(feature = "metrics")]
+        let table: String = event.table.clone();
+        #[cfg(feature = "metrics")]
+        if let Some(lag) = event.lag {
+            crate::metrics::record_change_lag(&table, lag);
+        }
+
+        let delivery: Option<Delivery> = match sender.try_send(event) {
+            Ok(()) | Err(TrySendError::Closed(_)) => None,
+            Err(TrySendError::Full(event)) => {
+                let sender: Sender<ChangeEvent<T>> = sender.clone();
+                Some(Box::pin(async move {
+                    let _ = sender.send(event).await;
+                }))
+            }
+        };
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_changes_queued(
+            &table,
+            sender.max_capacity() - sender.capacity() + usize::from(delivery.is_some()),
+        );
+        delivery
+    })
+}
+
+/// Decodes the `data` of a `postgres_changes` message.
+///
+/// # Returns
+/// `None` when the change is malformed or its new row doesn't decode into `T`.
+fn decode<T: DeserializeOwned>(data: &Value) -> Option<ChangeEvent<T>> {
+    let kind: ChangeKind =
+        ChangeKind::parse(data["type"].as_str().or(data["eventType"].as_str())?)?;
+    let commit_timestamp: Option<String> = data["commit_timestamp"].as_str().map(str::to_string);
+    let lag: Option<Duration> = commit_timestamp
+        .as_deref()
+        .and_then(parse_timestamp)
+        .and_then(|commit| SystemTime::now().duration_since(commit).ok());
+
+    let new: Option<T> = match kind {
+        ChangeKind::Insert | ChangeKind::Update => {
+            match serde_json::from_value(data["record"].clone()) {
+                Ok(row) => Some(row),
+                Err(_error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %_error, "skipped a row change");
+                    return None;
+                }
+            }
+        }
+        ChangeKind::Delete => None,
+    };
+    let old_record: Value = match &data["old_record"] {
+        Value::Object(record) if !record.is_empty() => Value::Object(record.clone()),
+        _ => Value::Null,
+    };
+
+    Some(ChangeEvent {
+        kind,
+        schema: data["schema"].as_str().unwrap_or_default().to_string(),
+        table: data["table"].as_str().unwrap_or_default().to_string(),
+        commit_timestamp,
+        new,
+        old: serde_json::from_value(old_record.clone()).ok(),
+        old_record,
+        lag,
+    })
+}
+
+/// Parses an RFC 3339 timestamp, e.g. `2024-05-09T14:05:33.915Z` or `2024-05-09 16:05:33+02:00`.
+fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.split_once(['T', ' '])?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+
+    let offset_at: usize = time.find(['Z', 'z', '+', '-']).unwrap_or(time.len());
+    let (time, offset) = time.split_at(offset_at);
+    let mut time = time.splitn(3, ':');
+    let hours: i64 = time.next()?.parse().ok()?;
+    let minutes: i64 = time.next()?.parse().ok()?;
+    let seconds: f64 = time.next()?.parse().ok()?;
+
+    let offset_seconds: i64 = match offset.as_bytes().first() {
+        Some(sign @ (b'+' | b'-')) => {
+            let (offset_hours, offset_minutes) =
+                offset[1..].split_once(':').unwrap_or((&offset[1..], "0"));
+            let offset: i64 =
+                offset_hours.parse::<i64>().ok()? * 3600 + offset_minutes.parse::<i64>().ok()? * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => 0,
+    };
+
+    let whole_seconds: i64 =
+        days_from_civil(year, month, day) * 86_400 + hours * 3600 + minutes * 60 - offset_seconds;
+    let since_epoch: f64 = whole_seconds as f64 + seconds;
+    (since_epoch >= 0.0).then(|| UNIX_EPOCH + Duration::from_secs_f64(since_epoch))
+}
+
+/// The days from 1970-01-01 until a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year: i64 = if month <= 2 { year - 1 } else { year };
+    let era: i64 = year.div_euclid(400);
+    let year_of_era: i64 = year - era * 400;
+    let day_of_year: i64 = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
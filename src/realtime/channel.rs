@@ -0,0 +1,408 @@
+//! ## Channels
+//!
+//! A [`RealtimeChannel`] joins a topic to exchange broadcast messages and share presence with the
+//! other clients on it. Callbacks are set before subscribing and receive typed payloads, messages
+//! that don't deserialize into the type of a callback are skipped.
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize, Debug)]
+//! struct Cursor { x: i32, y: i32 }
+//!
+//! #[derive(Serialize, Deserialize, Debug)]
+//! struct Visitor { name: String }
+//!
+//! let room = client
+//!     .realtime()
+//!     .channel("room:1")
+//!     .presence_key("jane")
+//!     .on_broadcast("cursor", |cursor: Cursor| println!("moved to {:?}", cursor))
+//!     .on_presence_join(|key: &str, visitors: Vec<Visitor>| println!("{} joined: {:?}", key, visitors))
+//!     .on_presence_leave(|key: &str, _: Vec<Visitor>| println!("{} left", key))
+//!     .on_presence_sync(|state| println!("{} online", state.len()));
+//! room.subscribe().await?;
+//!
+//! room.track(&Visitor { name: "Jane".into() }).await?;
+//! room.send("cursor", &Cursor { x: 10, y: 20 }).await?;
+//!
+//! room.unsubscribe().await?;
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::realtime::changes::{ChangeBinding, Delivery};
+use crate::realtime::client::RealtimeClient;
+use crate::realtime::presence::{deserialize_metas, PresenceChanges, PresenceState};
+use crate::realtime::protocol::{
+    broadcast_payload, channel_topic, track_payload, untrack_payload, PhoenixMessage, BROADCAST,
+    PHX_JOIN, PHX_LEAVE, POSTGRES_CHANGES, PRESENCE, PRESENCE_DIFF, PRESENCE_STATE,
+};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+type BroadcastCallback = Arc<dyn Fn(Value) + Send + Sync>;
+type PresenceCallback = Arc<dyn Fn(&str, &[Value]) + Send + Sync>;
+type SyncCallback = Arc<dyn Fn(&PresenceState) + Send + Sync>;
+
+/// The options sent when joining a channel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ChannelConfig {
+    broadcast_self: bool,
+    broadcast_ack: bool,
+    presence_key: String,
+    private: bool,
+}
+
+/// The state of a channel, shared with the connection that routes its messages.
+pub(crate) struct ChannelState {
+    pub(crate) topic: String,
+    config: Mutex<ChannelConfig>,
+    join_ref: Mutex<Option<String>>,
+    broadcast: Mutex<Vec<(String, BroadcastCallback)>>,
+    presence_join: Mutex<Vec<PresenceCallback>>,
+    presence_leave: Mutex<Vec<PresenceCallback>>,
+    presence_sync: Mutex<Vec<SyncCallback>>,
+    presence: Mutex<PresenceState>,
+    /// The presence shared with `track`, shared again after reconnecting.
+    tracked: Mutex<Option<Value>>,
+    /// The row changes the channel subscribes to.
+    postgres_changes: Mutex<Vec<ChangeBinding>>,
+}
+
+impl Debug for ChannelState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChannelState")
+            .field("topic", &self.topic)
+            .field("config", &*lock(&self.config))
+            .field("join_ref", &*lock(&self.join_ref))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChannelState {
+    /// Joins the channel with a new join `ref`, and shares the tracked presence again.
+    pub(crate) async fn join(&self, client: &RealtimeClient) -> Result<()> {
+        let access_token: Option<String> = client.access_token();
+        let join_ref: String = client.make_ref();
+        *lock(&self.join_ref) = Some(join_ref.clone());
+
+        let mut message: PhoenixMessage =
+            PhoenixMessage::new(&self.topic, PHX_JOIN, self.join_payload(access_token));
+        message.join_ref = Some(join_ref);
+        let reply: PhoenixMessage = client.push(message).await?;
+
+        // Realtime numbers the change subscriptions in the order they were sent
+        let ids: &Value = &reply.payload["response"][POSTGRES_CHANGES];
+        for (index, binding) in lock(&self.postgres_changes).iter_mut().enumerate() {
+            binding.id = ids[index]["id"].as_u64();
+            binding.joined();
+        }
+
+        let tracked: Option<Value> = lock(&self.tracked).clone();
+        if let Some(state) = tracked {
+            client
+                .push(self.message(PRESENCE, track_payload(state)))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// A message of `event` on the topic of the channel, if it is joined.
+    pub(crate) fn joined_message(&self, event: &str, payload: Value) -> Option<PhoenixMessage> {
+        let join_ref: Option<String> = lock(&self.join_ref).clone();
+        join_ref.map(|join_ref| {
+            let mut message: PhoenixMessage = PhoenixMessage::new(&self.topic, event, payload);
+            message.join_ref = Some(join_ref);
+            message
+        })
+    }
+
+    /// A message of `event` on the topic of the channel.
+    fn message(&self, event: &str, payload: Value) -> PhoenixMessage {
+        let mut message: PhoenixMessage = PhoenixMessage::new(&self.topic, event, payload);
+        message.join_ref = lock(&self.join_ref).clone();
+        message
+    }
+
+    /// The payload of the `phx_join` of the channel.
+    fn join_payload(&self, access_token: Option<String>) -> Value {
+        let config: ChannelConfig = lock(&self.config).clone();
+        let mut payload: Value = json!({
+            "config": {
+                "broadcast": { "self": config.broadcast_self, "ack": config.broadcast_ack },
+                "presence": { "key": config.presence_key },
+                "postgres_changes": lock(&self.postgres_changes)
+                    .iter()
+                    .map(ChangeBinding::config)
+                    .collect::<Vec<Value>>(),
+                "private": config.private,
+            }
+        });
+        if let Some(access_token) = access_token {
+            payload["access_token"] = json!(access_token);
+        }
+        payload
+    }
+
+    /// Handles a message received on the topic of the channel.
+    ///
+    /// # Returns
+    /// The row changes waiting for room in the queue of their subscriber.
+    pub(crate) fn handle(&self, message: &PhoenixMessage) -> Vec<Delivery> {
+        match message.event.as_str() {
+            BROADCAST => {
+                let event: &str = message.payload["event"].as_str().unwrap_or_default();
+                let callbacks: Vec<BroadcastCallback> = lock(&self.broadcast)
+                    .iter()
+                    .filter(|(name, _)| name == event || name == "*")
+                    .map(|(_, callback)| callback.clone())
+                    .collect();
+                for callback in callbacks {
+                    callback(message.payload["payload"].clone());
+                }
+            }
+            PRESENCE_STATE => {
+                let changes: PresenceChanges = lock(&self.presence).sync_state(&message.payload);
+                self.presence_changed(changes);
+            }
+            PRESENCE_DIFF => {
+                let changes: PresenceChanges = lock(&self.presence).sync_diff(&message.payload);
+                self.presence_changed(changes);
+            }
+            POSTGRES_CHANGES => {
+                let ids: Vec<u64> = message.payload["ids"]
+                    .as_array()
+                    .map(|ids| ids.iter().filter_map(Value::as_u64).collect())
+                    .unwrap_or_default();
+                let data: &Value = &message.payload["data"];
+
+                let bindings: Vec<ChangeBinding> = lock(&self.postgres_changes)
+                    .iter()
+                    .filter(|binding| binding.matches(&ids, data))
+                    .cloned()
+                    .collect();
+                return bindings
+                    .iter()
+                    .filter_map(|binding| binding.deliver(data))
+                    .collect();
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Calls the presence callbacks after a sync.
+    fn presence_changed(&self, changes: PresenceChanges) {
+        let joins: Vec<PresenceCallback> = lock(&self.presence_join).clone();
+        let leaves: Vec<PresenceCallback> = lock(&self.presence_leave).clone();
+        let syncs: Vec<SyncCallback> = lock(&self.presence_sync).clone();
+
+        for (key, metas) in &changes.joins {
+            joins.iter().for_each(|callback| callback(key, metas));
+        }
+        for (key, metas) in &changes.leaves {
+            leaves.iter().for_each(|callback| callback(key, metas));
+        }
+
+        let state: PresenceState = lock(&self.presence).clone();
+        syncs.iter().for_each(|callback| callback(&state));
+    }
+}
+
+/// A channel of a [`RealtimeClient`], cheap to clone.
+#[derive(Debug, Clone)]
+pub struct RealtimeChannel {
+    client: RealtimeClient,
+    state: Arc<ChannelState>,
+}
+
+impl RealtimeChannel {
+    pub(crate) fn new(client: RealtimeClient, name: &str) -> Self {
+        RealtimeChannel {
+            client,
+            state: Arc::new(ChannelState {
+                topic: channel_topic(name),
+                config: Mutex::new(ChannelConfig::default()),
+                join_ref: Mutex::new(None),
+                broadcast: Mutex::new(Vec::new()),
+                presence_join: Mutex::new(Vec::new()),
+                presence_leave: Mutex::new(Vec::new()),
+                presence_sync: Mutex::new(Vec::new()),
+                presence: Mutex::new(PresenceState::default()),
+                tracked: Mutex::new(None),
+                postgres_changes: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// The topic of the channel, e.g. `realtime:room:1`.
+    pub fn topic(&self) -> &str {
+        &self.state.topic
+    }
+
+    /// Receives the broadcast messages sent by this client too.
+    pub fn broadcast_self(self, enabled: bool) -> Self {
+        lock(&self.state.config).broadcast_self = enabled;
+        self
+    }
+
+    /// Waits for Realtime to acknowledge every broadcast message sent with [`send`](Self::send).
+    pub fn broadcast_ack(self, enabled: bool) -> Self {
+        lock(&self.state.config).broadcast_ack = enabled;
+        self
+    }
+
+    /// Tracks the presence of this client under `key` instead of a key generated by Realtime.
+    pub fn presence_key(self, key: &str) -> Self {
+        lock(&self.state.config).presence_key = key.to_string();
+        self
+    }
+
+    /// Joins the channel as a private channel, protected by Realtime authorization policies.
+    pub fn private(self) -> Self {
+        lock(&self.state.config).private = true;
+        self
+    }
+
+    /// Calls `callback` with the payload of every broadcast message of `event`, `*` for every event.
+    pub fn on_broadcast<T, F>(self, event: &str, callback: F) -> Self
+    where
+        T: DeserializeOwned,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let callback: BroadcastCallback = Arc::new(move |payload: Value| {
+            let payload: serde_json::Result<T> = serde_json::from_value(payload);
+            match payload {
+                Ok(payload) => callback(payload),
+                #[cfg(feature = "tracing")]
+                Err(error) => tracing::warn!(error = %error, "skipped a broadcast payload"),
+                #[cfg(not(feature = "tracing"))]
+                Err(_) => {}
+            }
+        });
+        lock(&self.state.broadcast).push((event.to_string(), callback));
+        self
+    }
+
+    /// Calls `callback` with the key and the states of every presence joining the channel.
+    pub fn on_presence_join<T, F>(self, callback: F) -> Self
+    where
+        T: DeserializeOwned,
+        F: Fn(&str, Vec<T>) + Send + Sync + 'static,
+    {
+        let callback: PresenceCallback =
+            Arc::new(move |key: &str, metas: &[Value]| callback(key, deserialize_metas(metas)));
+        lock(&self.state.presence_join).push(callback);
+        self
+    }
+
+    /// Calls `callback` with the key and the states of every presence leaving the channel.
+    pub fn on_presence_leave<T, F>(self, callback: F) -> Self
+    where
+        T: DeserializeOwned,
+        F: Fn(&str, Vec<T>) + Send + Sync + 'static,
+    {
+        let callback: PresenceCallback =
+            Arc::new(move |key: &str, metas: &[Value]| callback(key, deserialize_metas(metas)));
+        lock(&self.state.presence_leave).push(callback);
+        self
+    }
+
+    /// Calls `callback` with the presence state every time it changes.
+    pub fn on_presence_sync<F>(self, callback: F) -> Self
+    where
+        F: Fn(&PresenceState) + Send + Sync + 'static,
+    {
+        lock(&self.state.presence_sync).push(Arc::new(callback));
+        self
+    }
+
+    /// Subscribes to the row changes of `binding`, before joining.
+    pub(crate) fn postgres_changes(self, binding: ChangeBinding) -> Self {
+        lock(&self.state.postgres_changes).push(binding);
+        self
+    }
+
+    /// Joins the channel, connecting the client first when needed.
+    ///
+    /// # Returns
+    /// [`ErrorTypes::Realtime`] when the connection fails or Realtime refuses the join.
+    pub async fn subscribe(&self) -> Result<()> {
+        self.client.connect().await?;
+        self.client.register(self.state.clone());
+
+        if let Err(error) = self.state.join(&self.client).await {
+            self.client.unregister(&self.state.topic);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Leaves the channel, its callbacks aren't called anymore.
+    pub async fn unsubscribe(&self) -> Result<()> {
+        let result = self
+            .client
+            .push(self.message(PHX_LEAVE, json!({})))
+            .await
+            .map(|_| ());
+        self.client.unregister(&self.state.topic);
+        *lock(&self.state.join_ref) = None;
+        result
+    }
+
+    /// Sends a broadcast message of `event` to the clients on the channel.
+    ///
+    /// # Returns
+    /// [`ErrorTypes::InvalidParameters`] when the payload can't be serialized, and
+    /// [`ErrorTypes::Realtime`] when it can't be sent or isn't acknowledged with `broadcast_ack`.
+    pub async fn send<T: Serialize>(&self, event: &str, payload: &T) -> Result<()> {
+        let payload: Value = serde_json::to_value(payload)
+            .map_err(|error| ErrorTypes::InvalidParameters(error.to_string()))?;
+        let message: PhoenixMessage = self.message(BROADCAST, broadcast_payload(event, payload));
+
+        if lock(&self.state.config).broadcast_ack {
+            self.client.push(message).await.map(|_| ())
+        } else {
+            self.client.send(message)
+        }
+    }
+
+    /// Shares the presence of this client with the clients on the channel, replacing the state
+    /// tracked before.
+    pub async fn track<T: Serialize>(&self, state: &T) -> Result<()> {
+        let state: Value = serde_json::to_value(state)
+            .map_err(|error| ErrorTypes::InvalidParameters(error.to_string()))?;
+        self.client
+            .push(self.message(PRESENCE, track_payload(state.clone())))
+            .await?;
+        *lock(&self.state.tracked) = Some(state);
+        Ok(())
+    }
+
+    /// Stops sharing the presence of this client.
+    pub async fn untrack(&self) -> Result<()> {
+        self.client
+            .push(self.message(PRESENCE, untrack_payload()))
+            .await?;
+        *lock(&self.state.tracked) = None;
+        Ok(())
+    }
+
+    /// The presences on the channel.
+    pub fn presence_state(&self) -> PresenceState {
+        lock(&self.state.presence).clone()
+    }
+
+    /// A message of `event` on the topic of the channel.
+    fn message(&self, event: &str, payload: Value) -> PhoenixMessage {
+        self.state.message(event, payload)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
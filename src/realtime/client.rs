@@ -0,0 +1,621 @@
+//! ## Realtime client
+//!
+//! A websocket connection to Realtime, shared by the channels created with
+//! [`RealtimeClient::channel`]. The connection is read and written by a background task, which
+//! routes replies to the pending pushes and every other message to the channel of its topic. It also
+//! keeps the connection alive, see [`connection`](super::connection).
+//!
+//! ```rust,ignore
+//! let realtime = client.realtime();
+//! realtime.connect().await?;
+//!
+//! let room = realtime.channel("room:1").on_broadcast("cursor", |cursor: Cursor| {
+//!     println!("{:?}", cursor);
+//! });
+//! room.subscribe().await?;
+//! ```
+//!
+//! The connection is closed by [`RealtimeClient::disconnect`], or once every handle of the client
+//! and its channels is dropped.
+//!
+//! ### Row level security
+//! Channels are authorized with the API key, unless the client has the access token of a user. Then
+//! private channels and change subscriptions are filtered by the policies of that user:
+//!
+//! ```rust,ignore
+//! // follows the session of the auth client, including its refreshes
+//! let realtime = auth.realtime();
+//!
+//! // or with a token of your own
+//! let realtime = client.realtime().with_access_token(move || Some(token_store.current()));
+//! realtime.set_auth(Some(&new_token));
+//! ```
+//!
+//! The token is sent with every join, and to the joined channels whenever it changes. A token source is
+//! checked before joining and on every heartbeat, so refreshed tokens reach Realtime before the
+//! previous one expires.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::realtime::changes::Delivery;
+use crate::realtime::channel::{ChannelState, RealtimeChannel};
+use crate::realtime::connection::{ConnectionState, RealtimeOptions, ReconnectConfig};
+use crate::realtime::protocol::{PhoenixMessage, ACCESS_TOKEN, HEARTBEAT, PHOENIX, PHX_REPLY};
+use crate::SupabaseClient;
+
+use futures_util::stream::BoxStream;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Returns the current access token of the user, see [`RealtimeClient::with_access_token`].
+#[derive(Clone)]
+struct TokenSource(Arc<dyn Fn() -> Option<String> + Send + Sync>);
+
+impl Debug for TokenSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TokenSource")
+    }
+}
+
+/// A connection to Realtime, cheap to clone.
+#[derive(Debug, Clone)]
+pub struct RealtimeClient {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Inner {
+    endpoint: String,
+    api_key: String,
+    options: RealtimeOptions,
+    outgoing: Mutex<Option<mpsc::UnboundedSender<PhoenixMessage>>>,
+    channels: Mutex<HashMap<String, Arc<ChannelState>>>,
+    pending: Mutex<HashMap<String, oneshot::Sender<PhoenixMessage>>>,
+    next_ref: AtomicU64,
+    /// Set by `disconnect`, so lost connections aren't opened again.
+    closing: AtomicBool,
+    state: Mutex<ConnectionState>,
+    states: broadcast::Sender<ConnectionState>,
+    access_token: Mutex<Option<String>>,
+    token_source: Mutex<Option<TokenSource>>,
+}
+
+/// The queue of the messages to send over an open websocket.
+struct Outgoing {
+    receiver: mpsc::UnboundedReceiver<PhoenixMessage>,
+    sender: mpsc::WeakUnboundedSender<PhoenixMessage>,
+}
+
+impl SupabaseClient {
+    /// Creates a [`RealtimeClient`] for the project of this client.
+    ///
+    /// Every call creates a new client with its own connection, keep it around and create the
+    /// channels from it.
+    pub fn realtime(&self) -> RealtimeClient {
+        RealtimeClient::new(&self.url, &self.api_key)
+    }
+
+    /// Creates a [`RealtimeClient`] for the project of this client, with `options`.
+    pub fn realtime_with_options(&self, options: RealtimeOptions) -> RealtimeClient {
+        RealtimeClient::with_options(&self.url, &self.api_key, options)
+    }
+}
+
+#[cfg(feature = "auth")]
+impl crate::auth::AuthClient {
+    /// Creates a [`RealtimeClient`] authorized with the session of this client, following its
+    /// refreshes and sign outs.
+    pub fn realtime(&self) -> RealtimeClient {
+        let auth: crate::auth::AuthClient = self.clone();
+        self.client
+            .realtime()
+            .with_access_token(move || auth.session().map(|session| session.access_token))
+    }
+}
+
+impl RealtimeClient {
+    /// Creates a client for the project at `url`, e.g. `https://project.supabase.co`.
+    ///
+    /// Nothing is sent until [`connect`](Self::connect) or the first subscribe.
+    pub fn new(url: &str, api_key: &str) -> Self {
+        Self::with_options(url, api_key, RealtimeOptions::default())
+    }
+
+    /// Creates a client with custom heartbeat, timeout and reconnect options.
+    pub fn with_options(url: &str, api_key: &str, options: RealtimeOptions) -> Self {
+        RealtimeClient {
+            inner: Arc::new(Inner {
+                endpoint: websocket_endpoint(url),
+                api_key: api_key.to_string(),
+                options,
+                outgoing: Mutex::new(None),
+                channels: Mutex::new(HashMap::new()),
+                pending: Mutex::new(HashMap::new()),
+                next_ref: AtomicU64::new(1),
+                closing: AtomicBool::new(false),
+                state: Mutex::new(ConnectionState::Disconnected),
+                states: broadcast::channel(16).0,
+                access_token: Mutex::new(None),
+                token_source: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Authorizes the channels with the access token returned by `source`, instead of the API key.
+    ///
+    /// `source` is called before joining and on every heartbeat, the token is sent to the joined
+    /// channels whenever it changes.
+    pub fn with_access_token<F>(self, source: F) -> Self
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static,
+    {
+        *lock(&self.inner.token_source) = Some(TokenSource(Arc::new(source)));
+        self.inner.refresh_access_token();
+        self
+    }
+
+    /// Authorizes the channels with `token`, `None` to go back to the API key.
+    ///
+    /// The joined channels receive the new token right away, the next joins send it too.
+    pub fn set_auth(&self, token: Option<&str>) {
+        self.inner.update_access_token(token.map(str::to_string));
+    }
+
+    /// The access token the channels are authorized with, if any.
+    pub fn access_token(&self) -> Option<String> {
+        self.inner.refresh_access_token();
+        lock(&self.inner.access_token).clone()
+    }
+
+    /// The websocket URL, including the API key.
+    pub fn socket_url(&self) -> String {
+        self.inner.socket_url()
+    }
+
+    /// Whether the websocket is open.
+    pub fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    /// The current state of the websocket.
+    pub fn connection_state(&self) -> ConnectionState {
+        *lock(&self.inner.state)
+    }
+
+    /// The changes of the state of the websocket, from now on.
+    ///
+    /// Consumers too slow to keep up skip the oldest changes.
+    pub fn connection_states(&self) -> BoxStream<'static, ConnectionState> {
+        let receiver: broadcast::Receiver<ConnectionState> = self.inner.states.subscribe();
+        futures_util::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(state) => return Some((state, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Opens the websocket, unless it is open already.
+    ///
+    /// # Returns
+    /// [`ErrorTypes::Realtime`] when the websocket can't be opened.
+    pub async fn connect(&self) -> Result<()> {
+        if self.is_connected() {
+            return Ok(());
+        }
+
+        self.inner.closing.store(false, Ordering::SeqCst);
+        self.inner.set_state(ConnectionState::Connecting);
+        let socket: Socket = match self.inner.open().await {
+            Ok(socket) => socket,
+            Err(error) => {
+                self.inner.set_state(ConnectionState::Disconnected);
+                return Err(error);
+            }
+        };
+
+        let outgoing: Outgoing = self.inner.install();
+        tokio::spawn(run(Arc::downgrade(&self.inner), socket, outgoing));
+        Ok(())
+    }
+
+    /// Closes the websocket, without opening it again. The channels stay registered and are joined
+    /// again by their next subscribe.
+    pub fn disconnect(&self) {
+        self.inner.closing.store(true, Ordering::SeqCst);
+        lock(&self.inner.outgoing).take();
+        lock(&self.inner.pending).clear();
+        self.inner.set_state(ConnectionState::Disconnected);
+    }
+
+    /// Creates a channel for the topic `name`, e.g. `room:1`.
+    ///
+    /// The channel is joined by [`RealtimeChannel::subscribe`], after its callbacks are set.
+    pub fn channel(&self, name: &str) -> RealtimeChannel {
+        RealtimeChannel::new(self.clone(), name)
+    }
+
+    /// The topics of the subscribed channels.
+    pub fn channels(&self) -> Vec<String> {
+        let mut topics: Vec<String> = lock(&self.inner.channels).keys().cloned().collect();
+        topics.sort();
+        topics
+    }
+
+    pub(crate) fn register(&self, channel: Arc<ChannelState>) {
+        lock(&self.inner.channels).insert(channel.topic.clone(), channel);
+    }
+
+    pub(crate) fn unregister(&self, topic: &str) {
+        lock(&self.inner.channels).remove(topic);
+    }
+
+    /// A new message `ref`.
+    pub(crate) fn make_ref(&self) -> String {
+        self.inner.make_ref()
+    }
+
+    /// Sends a message without waiting for a reply.
+    pub(crate) fn send(&self, mut message: PhoenixMessage) -> Result<()> {
+        if message.reference.is_none() {
+            message.reference = Some(self.make_ref());
+        }
+
+        let outgoing = lock(&self.inner.outgoing);
+        let outgoing = outgoing
+            .as_ref()
+            .ok_or_else(|| ErrorTypes::Realtime("not connected".to_string()))?;
+        outgoing
+            .send(message)
+            .map_err(|_| ErrorTypes::Realtime("connection closed".to_string()))
+    }
+
+    /// Sends a message and waits for its reply.
+    ///
+    /// # Returns
+    /// The reply, or [`ErrorTypes::Realtime`] when its status isn't `ok`, it timed out or the
+    /// connection closed.
+    pub(crate) async fn push(&self, mut message: PhoenixMessage) -> Result<PhoenixMessage> {
+        let reference: String = self.make_ref();
+        message.reference = Some(reference.clone());
+        let event: String = message.event.clone();
+
+        let (sender, receiver) = oneshot::channel();
+        lock(&self.inner.pending).insert(reference.clone(), sender);
+        if let Err(error) = self.send(message) {
+            lock(&self.inner.pending).remove(&reference);
+            return Err(error);
+        }
+
+        let timeout = self.inner.options.timeout;
+        let reply: PhoenixMessage = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(_)) => return Err(ErrorTypes::Realtime("connection closed".to_string())),
+            Err(_) => {
+                lock(&self.inner.pending).remove(&reference);
+                return Err(ErrorTypes::Realtime(format!("{} timed out", event)));
+            }
+        };
+
+        match reply.reply_status() {
+            Some("ok") => Ok(reply),
+            _ => Err(ErrorTypes::Realtime(format!(
+                "{} failed: {}",
+                event, reply.payload["response"]
+            ))),
+        }
+    }
+}
+
+impl Inner {
+    fn socket_url(&self) -> String {
+        format!("{}?apikey={}&vsn=1.0.0", self.endpoint, self.api_key)
+    }
+
+    fn is_connected(&self) -> bool {
+        lock(&self.outgoing)
+            .as_ref()
+            .is_some_and(|outgoing| !outgoing.is_closed())
+    }
+
+    fn make_ref(&self) -> String {
+        self.next_ref.fetch_add(1, Ordering::SeqCst).to_string()
+    }
+
+    /// Fetches the access token from the token source, if the client has one.
+    fn refresh_access_token(&self) {
+        let source: Option<TokenSource> = lock(&self.token_source).clone();
+        if let Some(source) = source {
+            self.update_access_token((source.0)());
+        }
+    }
+
+    /// Replaces the access token, sending it to the joined channels when it changed. Without a token
+    /// they are authorized with the API key again.
+    fn update_access_token(&self, token: Option<String>) {
+        {
+            let mut current = lock(&self.access_token);
+            if *current == token {
+                return;
+            }
+            current.clone_from(&token);
+        }
+
+        let token: String = token.unwrap_or_else(|| self.api_key.clone());
+        let outgoing = lock(&self.outgoing);
+        let Some(outgoing) = outgoing.as_ref() else {
+            return;
+        };
+        for channel in lock(&self.channels).values() {
+            if let Some(mut message) =
+                channel.joined_message(ACCESS_TOKEN, json!({ "access_token": token }))
+            {
+                message.reference = Some(self.make_ref());
+                let _ = outgoing.send(message);
+            }
+        }
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        let mut current = lock(&self.state);
+        if *current != state {
+            *current = state;
+            let _ = self.states.send(state);
+        }
+    }
+
+    async fn open(&self) -> Result<Socket> {
+        let (socket, _) = tokio_tungstenite::connect_async(self.socket_url())
+            .await
+            .map_err(|error| ErrorTypes::Realtime(error.to_string()))?;
+        Ok(socket)
+    }
+
+    /// Makes a new queue the outgoing messages of the client.
+    fn install(&self) -> Outgoing {
+        let (sender, receiver) = mpsc::unbounded_channel::<PhoenixMessage>();
+        let outgoing: Outgoing = Outgoing {
+            receiver,
+            sender: sender.downgrade(),
+        };
+        *lock(&self.outgoing) = Some(sender);
+        self.set_state(ConnectionState::Connected);
+        outgoing
+    }
+
+    /// Forgets a lost connection, failing its pending pushes.
+    ///
+    /// # Returns
+    /// Whether it was the connection of the client, and not replaced by a newer one.
+    fn lost(&self, connection: &mpsc::WeakUnboundedSender<PhoenixMessage>) -> bool {
+        let Some(connection) = connection.upgrade() else {
+            return false;
+        };
+
+        let mut outgoing = lock(&self.outgoing);
+        if !outgoing
+            .as_ref()
+            .is_some_and(|outgoing| outgoing.same_channel(&connection))
+        {
+            return false;
+        }
+        outgoing.take();
+        lock(&self.pending).clear();
+        true
+    }
+
+    /// Routes a message received from Realtime.
+    ///
+    /// # Returns
+    /// The row changes waiting for room in the queue of their subscriber.
+    fn dispatch(&self, message: PhoenixMessage) -> Vec<Delivery> {
+        if message.event == PHX_REPLY {
+            let pending = message
+                .reference
+                .as_ref()
+                .and_then(|reference| lock(&self.pending).remove(reference));
+            if let Some(pending) = pending {
+                let _ = pending.send(message);
+                return Vec::new();
+            }
+        }
+
+        let channel: Option<Arc<ChannelState>> = lock(&self.channels).get(&message.topic).cloned();
+        match channel {
+            Some(channel) => channel.handle(&message),
+            None => Vec::new(),
+        }
+    }
+
+    /// Joins the subscribed channels again, after reconnecting.
+    fn rejoin_channels(self: &Arc<Self>) {
+        let channels: Vec<Arc<ChannelState>> = lock(&self.channels).values().cloned().collect();
+        for channel in channels {
+            let client: RealtimeClient = RealtimeClient {
+                inner: self.clone(),
+            };
+            tokio::spawn(async move {
+                let result: Result<()> = channel.join(&client).await;
+                #[cfg(feature = "tracing")]
+                if let Err(error) = result {
+                    tracing::warn!(topic = %channel.topic, error = %error, "failed to rejoin channel");
+                }
+                #[cfg(not(feature = "tracing"))]
+                let _ = result;
+            });
+        }
+    }
+}
+
+/// Serves the connection, and the ones opening it again once it is lost.
+async fn run(inner: Weak<Inner>, mut socket: Socket, mut outgoing: Outgoing) {
+    loop {
+        let connection: mpsc::WeakUnboundedSender<PhoenixMessage> = outgoing.sender.clone();
+        if !serve(&inner, socket, outgoing.receiver).await {
+            return;
+        }
+        match inner.upgrade() {
+            Some(inner) if inner.lost(&connection) => {}
+            _ => return,
+        }
+
+        match reconnect(&inner).await {
+            Some((next_socket, next_outgoing)) => {
+                socket = next_socket;
+                outgoing = next_outgoing;
+            }
+            None => return,
+        }
+    }
+}
+
+/// Sends the outgoing messages and heartbeats, and routes the incoming messages.
+///
+/// # Returns
+/// Whether the connection was lost, instead of closed by the client.
+async fn serve(
+    inner: &Weak<Inner>,
+    socket: Socket,
+    mut receiver: mpsc::UnboundedReceiver<PhoenixMessage>,
+) -> bool {
+    let Some(interval) = inner
+        .upgrade()
+        .map(|inner| inner.options.heartbeat_interval)
+    else {
+        return false;
+    };
+    let (mut write, mut read) = socket.split();
+    let mut heartbeat = tokio::time::interval_at(Instant::now() + interval, interval);
+    let mut pending_heartbeat: Option<String> = None;
+
+    loop {
+        tokio::select! {
+            outgoing = receiver.recv() => {
+                let Some(message) = outgoing else {
+                    let _ = write.close().await;
+                    return false;
+                };
+                let Ok(text) = serde_json::to_string(&message) else {
+                    continue;
+                };
+                if write.send(Message::Text(text)).await.is_err() {
+                    return true;
+                }
+            }
+            incoming = read.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    let Some(inner) = inner.upgrade() else {
+                        return false;
+                    };
+                    let Ok(message) = serde_json::from_str::<PhoenixMessage>(&text) else {
+                        continue;
+                    };
+                    if message.topic == PHOENIX && message.reference == pending_heartbeat {
+                        pending_heartbeat = None;
+                        continue;
+                    }
+
+                    // a full subscriber queue stops reading until it has room again
+                    let deliveries: Vec<Delivery> = inner.dispatch(message);
+                    drop(inner);
+                    for delivery in deliveries {
+                        delivery.await;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return true,
+                Some(Ok(_)) => {}
+            },
+            _ = heartbeat.tick() => {
+                if pending_heartbeat.is_some() {
+                    let _ = write.close().await;
+                    return true;
+                }
+                let Some(inner) = inner.upgrade() else {
+                    return false;
+                };
+                inner.refresh_access_token();
+                let mut message: PhoenixMessage = PhoenixMessage::new(PHOENIX, HEARTBEAT, json!({}));
+                message.reference = Some(inner.make_ref());
+                pending_heartbeat = message.reference.clone();
+                let Ok(text) = serde_json::to_string(&message) else {
+                    continue;
+                };
+                if write.send(Message::Text(text)).await.is_err() {
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+/// Opens a lost connection again, with an exponential backoff, and joins its channels again.
+///
+/// # Returns
+/// The new connection, or `None` when the client gave up, disconnected, connected by itself or was
+/// dropped.
+async fn reconnect(inner: &Weak<Inner>) -> Option<(Socket, Outgoing)> {
+    let config: ReconnectConfig = inner.upgrade()?.options.reconnect;
+    let mut attempt: u32 = 0;
+
+    loop {
+        attempt += 1;
+        {
+            let inner: Arc<Inner> = inner.upgrade()?;
+            if inner.closing.load(Ordering::SeqCst) {
+                return None;
+            }
+            if !config.enabled || config.max_attempts.is_some_and(|max| attempt > max) {
+                inner.set_state(ConnectionState::Disconnected);
+                return None;
+            }
+            inner.set_state(ConnectionState::Reconnecting {
+                attempt,
+                retry_in: config.delay(attempt),
+            });
+        }
+
+        tokio::time::sleep(config.delay(attempt)).await;
+
+        let inner: Arc<Inner> = inner.upgrade()?;
+        if inner.closing.load(Ordering::SeqCst) || inner.is_connected() {
+            return None;
+        }
+        if let Ok(socket) = inner.open().await {
+            let outgoing: Outgoing = inner.install();
+            inner.rejoin_channels();
+            return Some((socket, outgoing));
+        }
+    }
+}
+
+/// The websocket endpoint of the project at `url`.
+fn websocket_endpoint(url: &str) -> String {
+    let url: &str = url.trim_end_matches('/');
+    let url: String = match url.split_once("://") {
+        Some(("http", rest)) => format!("ws://{}", rest),
+        Some(("https", rest)) => format!("wss://{}", rest),
+        Some(_) => url.to_string(),
+        None => format!("wss://{}", url),
+    };
+    format!("{}/realtime/v1/websocket", url)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
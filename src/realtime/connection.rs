@@ -0,0 +1,98 @@
+//! ## Connection
+//!
+//! The options and the state of the websocket of a [`RealtimeClient`](super::RealtimeClient).
+//!
+//! The client sends a Phoenix heartbeat every [`RealtimeOptions::heartbeat_interval`] and considers
+//! the connection lost when one isn't answered before the next. Lost connections are opened again
+//! with an exponential backoff, after which the subscribed channels are joined again and their
+//! tracked presence is shared again.
+//!
+//! ```rust,ignore
+//! use futures_util::StreamExt;
+//! use supabase_rs::realtime::connection::ConnectionState;
+//!
+//! let realtime = client.realtime();
+//! let mut states = realtime.connection_states();
+//! tokio::spawn(async move {
+//!     while let Some(state) = states.next().await {
+//!         match state {
+//!             ConnectionState::Connected => println!("online"),
+//!             ConnectionState::Reconnecting { attempt, retry_in } => {
+//!                 println!("offline, attempt {} in {:?}", attempt, retry_in)
+//!             }
+//!             _ => {}
+//!         }
+//!     }
+//! });
+//! ```
+
+use std::time::Duration;
+
+/// The state of the websocket of a `RealtimeClient`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Not connected, and not trying to.
+    #[default]
+    Disconnected,
+    /// Opening the websocket.
+    Connecting,
+    /// The websocket is open.
+    Connected,
+    /// The connection was lost, attempt `attempt` to open it again starts in `retry_in`.
+    Reconnecting { attempt: u32, retry_in: Duration },
+}
+
+/// How lost connections are opened again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectConfig {
+    /// Whether lost connections are opened again at all.
+    pub enabled: bool,
+    /// The delay before the first attempt, doubled after every failed attempt.
+    pub initial_delay: Duration,
+    /// The maximum delay between two attempts.
+    pub max_delay: Duration,
+    /// The number of attempts before giving up, `None` to never give up.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        ReconnectConfig {
+            enabled: true,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// The delay before the attempt `attempt`, counting from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor: u32 = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// The options of a `RealtimeClient`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RealtimeOptions {
+    /// How often a heartbeat is sent, a heartbeat not answered by the next one drops the connection.
+    pub heartbeat_interval: Duration,
+    /// How long a join, leave or presence update waits for its reply.
+    pub timeout: Duration,
+    pub reconnect: ReconnectConfig,
+}
+
+impl Default for RealtimeOptions {
+    fn default() -> Self {
+        RealtimeOptions {
+            heartbeat_interval: Duration::from_secs(25),
+            timeout: Duration::from_secs(10),
+            reconnect: ReconnectConfig::default(),
+        }
+    }
+}
@@ -1 +1,31 @@
-// TODO: #![cfg(feature = "realtime")]
+//! ## Realtime
+//!
+//! Helpers for Supabase Realtime. Enabled with the `realtime` feature.
+//!
+//! - [`broadcast`]: Publish broadcast messages over HTTP, without holding a websocket connection.
+//! - [`client`]: A websocket connection to Realtime, see [`RealtimeClient`].
+//! - [`connection`]: Heartbeats, reconnects and the connection state.
+//! - [`channel`]: Broadcast messages and presence on a channel, see [`RealtimeChannel`].
+//! - [`changes`]: Stream the row changes of a table into a channel, see
+//!   [`RealtimeClient::table_changes`].
+//! - [`presence`]: The presence state of a channel.
+//! - [`protocol`]: The Phoenix channel messages sent over the websocket.
+//! - [`sync`]: Keep a table in memory with its row changes, see
+//!   [`SupabaseClient::sync_table`](crate::SupabaseClient::sync_table).
+#![cfg(feature = "realtime")]
+
+pub mod broadcast;
+pub mod changes;
+pub mod channel;
+pub mod client;
+pub mod connection;
+pub mod presence;
+pub mod protocol;
+pub mod sync;
+
+pub use changes::{ChangeEvent, ChangeKind, ChangesOptions};
+pub use channel::RealtimeChannel;
+pub use client::RealtimeClient;
+pub use connection::{ConnectionState, RealtimeOptions, ReconnectConfig};
+pub use presence::PresenceState;
+pub use sync::SyncOptions;
@@ -0,0 +1,162 @@
+//! ## Presence
+//!
+//! The clients present on a channel, by presence key. Every client tracking its presence adds a
+//! meta (its state and a `phx_ref`) to its key, a key can have several metas when the same user is
+//! connected more than once.
+//!
+//! The state is synced from the `presence_state` the server sends after joining and the
+//! `presence_diff`s it sends afterwards, the same way `supabase-js` does.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The presences of a channel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PresenceState {
+    presences: HashMap<String, Vec<Value>>,
+}
+
+/// The presences that joined and left with a sync, by key.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PresenceChanges {
+    pub joins: Vec<(String, Vec<Value>)>,
+    pub leaves: Vec<(String, Vec<Value>)>,
+}
+
+impl PresenceState {
+    /// The presence keys, sorted.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.presences.keys().map(String::as_str).collect();
+        keys.sort();
+        keys
+    }
+
+    /// The metas of a key, each with its `phx_ref`.
+    pub fn get(&self, key: &str) -> Option<&[Value]> {
+        self.presences.get(key).map(Vec::as_slice)
+    }
+
+    /// The metas of every key, deserialized, skipping the ones that don't match `T`.
+    pub fn list<T: DeserializeOwned>(&self) -> Vec<(String, Vec<T>)> {
+        self.keys()
+            .into_iter()
+            .map(|key| (key.to_string(), deserialize_metas(&self.presences[key])))
+            .collect()
+    }
+
+    /// The number of presence keys.
+    pub fn len(&self) -> usize {
+        self.presences.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presences.is_empty()
+    }
+
+    /// Replaces the state with a `presence_state` payload.
+    pub(crate) fn sync_state(&mut self, state: &Value) -> PresenceChanges {
+        let state: HashMap<String, Vec<Value>> = parse_presences(state);
+        let mut changes: PresenceChanges = PresenceChanges::default();
+
+        for (key, current) in &self.presences {
+            let next: &[Value] = state.get(key).map_or(&[], Vec::as_slice);
+            let left: Vec<Value> = without_refs(current, next);
+            if !left.is_empty() {
+                changes.leaves.push((key.clone(), left));
+            }
+        }
+        for (key, next) in &state {
+            let current: &[Value] = self.presences.get(key).map_or(&[], Vec::as_slice);
+            let joined: Vec<Value> = without_refs(next, current);
+            if !joined.is_empty() {
+                changes.joins.push((key.clone(), joined));
+            }
+        }
+
+        self.presences = state;
+        changes.sort();
+        changes
+    }
+
+    /// Applies a `presence_diff` payload.
+    pub(crate) fn sync_diff(&mut self, diff: &Value) -> PresenceChanges {
+        let joins: HashMap<String, Vec<Value>> = parse_presences(&diff["joins"]);
+        let leaves: HashMap<String, Vec<Value>> = parse_presences(&diff["leaves"]);
+        let mut changes: PresenceChanges = PresenceChanges::default();
+
+        for (key, joined) in joins {
+            let metas: &mut Vec<Value> = self.presences.entry(key.clone()).or_default();
+            let joined: Vec<Value> = without_refs(&joined, metas);
+            metas.extend(joined.iter().cloned());
+            if !joined.is_empty() {
+                changes.joins.push((key, joined));
+            }
+        }
+        for (key, left) in leaves {
+            let Some(metas) = self.presences.get_mut(&key) else {
+                continue;
+            };
+            let remaining: Vec<Value> = without_refs(metas, &left);
+            let left: Vec<Value> = without_refs(metas, &remaining);
+            *metas = remaining;
+            if metas.is_empty() {
+                self.presences.remove(&key);
+            }
+            if !left.is_empty() {
+                changes.leaves.push((key, left));
+            }
+        }
+
+        changes.sort();
+        changes
+    }
+}
+
+impl PresenceChanges {
+    fn sort(&mut self) {
+        self.joins.sort_by(|a, b| a.0.cmp(&b.0));
+        self.leaves.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+}
+
+/// Deserializes metas, skipping the ones that don't match `T`.
+pub(crate) fn deserialize_metas<T: DeserializeOwned>(metas: &[Value]) -> Vec<T> {
+    metas
+        .iter()
+        .filter_map(|meta| serde_json::from_value(meta.clone()).ok())
+        .collect()
+}
+
+/// Parses `{"key": {"metas": [...]}}` into the metas by key.
+fn parse_presences(presences: &Value) -> HashMap<String, Vec<Value>> {
+    let Some(presences) = presences.as_object() else {
+        return HashMap::new();
+    };
+
+    presences
+        .iter()
+        .map(|(key, presence)| {
+            let metas: Vec<Value> = presence
+                .get("metas")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            (key.clone(), metas)
+        })
+        .filter(|(_, metas)| !metas.is_empty())
+        .collect()
+}
+
+/// The metas of `metas` whose `phx_ref` isn't in `others`.
+fn without_refs(metas: &[Value], others: &[Value]) -> Vec<Value> {
+    metas
+        .iter()
+        .filter(|meta| {
+            !others
+                .iter()
+                .any(|other| other.get("phx_ref") == meta.get("phx_ref"))
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,86 @@
+//! ## Realtime protocol
+//!
+//! The Phoenix channel messages exchanged with Realtime over its websocket (`vsn=1.0.0`, JSON objects).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// The topic of the messages about the connection itself.
+pub const PHOENIX: &str = "phoenix";
+/// Keeps the connection alive, sent on the `phoenix` topic.
+pub const HEARTBEAT: &str = "heartbeat";
+/// Joins a channel.
+pub const PHX_JOIN: &str = "phx_join";
+/// Leaves a channel.
+pub const PHX_LEAVE: &str = "phx_leave";
+/// The reply to a message with a `ref`.
+pub const PHX_REPLY: &str = "phx_reply";
+/// The channel was closed by the server.
+pub const PHX_CLOSE: &str = "phx_close";
+/// The channel crashed on the server.
+pub const PHX_ERROR: &str = "phx_error";
+/// Replaces the access token a joined channel is authorized with.
+pub const ACCESS_TOKEN: &str = "access_token";
+/// A broadcast message, sent or received.
+pub const BROADCAST: &str = "broadcast";
+/// Tracks or untracks the presence of this client.
+pub const PRESENCE: &str = "presence";
+/// The full presence state of a channel, received after joining.
+pub const PRESENCE_STATE: &str = "presence_state";
+/// The presences that joined and left a channel.
+pub const PRESENCE_DIFF: &str = "presence_diff";
+/// An insert, update or delete of a row, received on a channel subscribed to its table.
+pub const POSTGRES_CHANGES: &str = "postgres_changes";
+
+/// A message of the Phoenix channel protocol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhoenixMessage {
+    /// The channel topic, e.g. `realtime:room:1`.
+    pub topic: String,
+    pub event: String,
+    #[serde(default)]
+    pub payload: Value,
+    /// Identifies the message, replies carry the `ref` of the message they answer.
+    #[serde(rename = "ref", default)]
+    pub reference: Option<String>,
+    /// The `ref` of the join of the channel the message belongs to.
+    #[serde(default)]
+    pub join_ref: Option<String>,
+}
+
+impl PhoenixMessage {
+    pub fn new(topic: &str, event: &str, payload: Value) -> Self {
+        PhoenixMessage {
+            topic: topic.to_string(),
+            event: event.to_string(),
+            payload,
+            reference: None,
+            join_ref: None,
+        }
+    }
+
+    /// The status of a reply, e.g. `ok` or `error`.
+    pub fn reply_status(&self) -> Option<&str> {
+        self.payload.get("status").and_then(Value::as_str)
+    }
+}
+
+/// The topic of the channel `name`, e.g. `realtime:room:1`.
+pub fn channel_topic(name: &str) -> String {
+    format!("realtime:{}", name)
+}
+
+/// The payload of a broadcast message.
+pub fn broadcast_payload(event: &str, payload: Value) -> Value {
+    json!({ "type": BROADCAST, "event": event, "payload": payload })
+}
+
+/// The payload tracking the presence of this client with `state`.
+pub fn track_payload(state: Value) -> Value {
+    json!({ "type": PRESENCE, "event": "track", "payload": state })
+}
+
+/// The payload untracking the presence of this client.
+pub fn untrack_payload() -> Value {
+    json!({ "type": PRESENCE, "event": "untrack" })
+}
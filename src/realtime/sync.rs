@@ -0,0 +1,253 @@
+//! ## Table sync
+//!
+//! [`SupabaseClient::sync_table`] keeps a copy of a table in memory, for small reference tables like
+//! countries, plans or feature flags read on every request. It subscribes to the
+//! [row changes](super::changes) of the table, reads the table page by page in the order of its
+//! primary key, and then applies every change to the copy. The copy is shared through a `tokio`
+//! [`watch`] channel, keyed by the primary key of the rows.
+//!
+//! ```rust,ignore
+//! #[derive(Deserialize, Debug, Clone)]
+//! struct Plan { id: i64, name: String, seats: u32 }
+//!
+//! let realtime = client.realtime();
+//! let plans: watch::Receiver<HashMap<i64, Plan>> = client.sync_table(&realtime, "plans").await?;
+//!
+//! let seats: Option<u32> = plans.borrow().get(&plan_id).map(|plan| plan.seats);
+//! ```
+//!
+//! The subscription is joined before the table is read, so changes committed while reading are
+//! applied on top of it. After a lost connection the channel is joined again and the table read
+//! again, as the changes in between are gone. Reading again failing leaves the copy as it was, until
+//! the next reconnect.
+//!
+//! The copy stays in sync while a receiver is around, dropping the last one leaves the channel. Rows
+//! or keys which don't decode are skipped, as are changes without the primary key, which Postgres
+//! always sends for the old row of a delete. See [row changes](super::changes) for the publication
+//! the table has to be part of.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::query_builder::scan::TableScanner;
+use crate::realtime::changes::{ChangeEvent, ChangeKind, ChangesOptions};
+use crate::realtime::client::RealtimeClient;
+use crate::SupabaseClient;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::{watch, Notify};
+
+/// How a table is kept in sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// The schema of the table, the schema of the client or `public` when `None`.
+    pub schema: Option<String>,
+    /// The column the rows are keyed by.
+    pub primary_key: String,
+    /// The number of rows read per request.
+    pub page_size: u64,
+    /// The number of changes waiting to be applied before the connection waits for them.
+    pub capacity: usize,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            schema: None,
+            primary_key: "id".to_string(),
+            page_size: 1000,
+            capacity: 256,
+        }
+    }
+}
+
+impl SyncOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the table from `schema`.
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    /// Keys the rows by `column`, `id` by default.
+    pub fn primary_key(mut self, column: &str) -> Self {
+        self.primary_key = column.to_string();
+        self
+    }
+
+    /// Reads `page_size` rows per request, at least 1.
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Sets the number of changes waiting to be applied, at least 1.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+}
+
+impl SupabaseClient {
+    /// Keeps the rows of `table` in memory, keyed by their `id`, with the changes received by
+    /// `realtime`.
+    ///
+    /// # Returns
+    /// The receiver of the rows once the table was read, [`ErrorTypes::Realtime`] when the
+    /// subscription fails or [`ErrorTypes::RequestFailed`] when the table can't be read.
+    pub async fn sync_table<K, T>(
+        &self,
+        realtime: &RealtimeClient,
+        table: &str,
+    ) -> Result<watch::Receiver<HashMap<K, T>>>
+    where
+        K: DeserializeOwned + Eq + Hash + Send + Sync + 'static,
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.sync_table_with(realtime, table, SyncOptions::default())
+            .await
+    }
+
+    /// Keeps the rows of `table` in memory as set by `options`, see
+    /// [`sync_table`](Self::sync_table).
+    pub async fn sync_table_with<K, T>(
+        &self,
+        realtime: &RealtimeClient,
+        table: &str,
+        options: SyncOptions,
+    ) -> Result<watch::Receiver<HashMap<K, T>>>
+    where
+        K: DeserializeOwned + Eq + Hash + Send + Sync + 'static,
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let schema: String = options
+            .schema
+            .clone()
+            .or_else(|| self.schema.clone())
+            .unwrap_or_else(|| "public".to_string());
+        let joined: Arc<Notify> = Arc::new(Notify::new());
+        let changes: Receiver<ChangeEvent<Value>> = realtime
+            .subscribe_changes(
+                &schema,
+                table,
+                ChangesOptions::new().capacity(options.capacity),
+                Some(joined.clone()),
+            )
+            .await?;
+
+        let mut sync: TableSync = TableSync {
+            client: self.clone(),
+            table: table.to_string(),
+            schema,
+            options,
+            changes,
+        };
+        // the first join was awaited by the subscription
+        joined.notified().await;
+        let rows: HashMap<K, T> = sync.snapshot().await?;
+        let (sender, receiver) = watch::channel(rows);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    change = sync.changes.recv() => match change {
+                        Some(change) => sync.apply(&sender, change),
+                        None => return,
+                    },
+                    _ = joined.notified() => match sync.snapshot::<K, T>().await {
+                        Ok(rows) => {
+                            sender.send_replace(rows);
+                        }
+                        Err(_error) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(table = %sync.table, error = %_error, "failed to read the synced table again");
+                        }
+                    },
+                    // dropping the changes leaves the channel
+                    _ = sender.closed() => return,
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+/// The state of a synced table, owned by the task applying its changes.
+struct TableSync {
+    client: SupabaseClient,
+    table: String,
+    schema: String,
+    options: SyncOptions,
+    changes: Receiver<ChangeEvent<Value>>,
+}
+
+impl TableSync {
+    /// Reads every row of the table in the order of the primary key, each page starting after the
+    /// last key of the previous one, so rows deleted meanwhile don't shift the pages.
+    ///
+    /// The changes received until then are dropped, as they were committed before the rows were read.
+    async fn snapshot<K, T>(&mut self) -> Result<HashMap<K, T>>
+    where
+        K: DeserializeOwned + Eq + Hash,
+        T: DeserializeOwned,
+    {
+        while self.changes.try_recv().is_ok() {}
+
+        let mut rows: HashMap<K, T> = HashMap::new();
+        let mut scanner: TableScanner = self
+            .client
+            .select(&self.table)
+            .schema(&self.schema)
+            .scan(&self.options.primary_key, self.options.page_size as usize);
+        while let Some(page) = scanner.next_page().await {
+            let (page, _) = page.map_err(|error| ErrorTypes::RequestFailed(error.to_string()))?;
+            rows.extend(
+                page.into_iter()
+                    .filter_map(|row| decode(&self.options.primary_key, row)),
+            );
+        }
+        Ok(rows)
+    }
+
+    /// Applies a change to the rows of `sender`.
+    fn apply<K, T>(&self, sender: &watch::Sender<HashMap<K, T>>, change: ChangeEvent<Value>)
+    where
+        K: DeserializeOwned + Eq + Hash,
+        T: DeserializeOwned,
+    {
+        let primary_key: &str = &self.options.primary_key;
+        match change.kind {
+            ChangeKind::Insert | ChangeKind::Update => {
+                let Some((key, row)) = change.new.and_then(|row| decode(primary_key, row)) else {
+                    return;
+                };
+                sender.send_modify(|rows| {
+                    rows.insert(key, row);
+                });
+            }
+            ChangeKind::Delete => {
+                let Ok(key) = K::deserialize(&change.old_record[primary_key]) else {
+                    return;
+                };
+                sender.send_if_modified(|rows| rows.remove(&key).is_some());
+            }
+        }
+    }
+}
+
+/// Decodes a row and its primary key, `None` when either doesn't decode.
+fn decode<K, T>(primary_key: &str, row: Value) -> Option<(K, T)>
+where
+    K: DeserializeOwned,
+    T: DeserializeOwned,
+{
+    let key: K = K::deserialize(&row[primary_key]).ok()?;
+    let row: T = serde_json::from_value(row).ok()?;
+    Some((key, row))
+}
@@ -0,0 +1,334 @@
+//! ## Request dispatching
+//!
+//! Every REST operation sends its request through [`SupabaseClient::dispatch`],
+//! so behaviour that applies to all calls (like metrics, tracing and the circuit breaker) lives in one place.
+//! The request is sent by the [transport](crate::transport) of the client, `reqwest` unless replaced.
+//!
+//! ### Tracing
+//! With the `tracing` feature every request runs in a `supabase_rs.request` span with the method, table
+//! and operation, and ends with an event carrying the status and latency: `debug` for successful
+//! responses, `warn` for error responses and failed requests.
+//!
+//! ### Redirects
+//! Deployments behind a proxy sometimes redirect, e.g. from `http` to `https`. Automatic redirects
+//! turn a `POST` into a `GET` on `301`/`302`/`303` and silently drop its body, so they are disabled
+//! on the client and followed here instead:
+//! - `307` and `308` are followed with the original method and body.
+//! - `301`, `302` and `303` are only followed for `GET` and `HEAD` requests.
+//! - Redirects to another host or port, or from `https` to `http`, are never followed, the
+//!   credentials would leak. Only the upgrade from `http` to `https` leaves the origin.
+//!
+//! Every other redirect is returned as an error naming the target, so the client can be pointed at it.
+//!
+//! Requests wait for the [rate limiter](crate::rate_limit) of the client, if it has one, after the
+//! circuit breaker let them through.
+//!
+//! With the `audit` feature, writes are reported to the [audit sink](crate::audit) of the client
+//! once their response arrived.
+//!
+//! A client made by [impersonation](crate::impersonation) sends its access token as the bearer of
+//! every request, instead of the API key. Otherwise, with the `auth` feature, requests sent with the
+//! API key as bearer are sent with the access token of the user signed in with
+//! [`auth`](SupabaseClient::auth) instead, while there is one.
+//!
+//! ### Request ids
+//! Every request is sent with a random `X-Request-Id`, unless it already has one. The id is copied
+//! onto the response when the server doesn't echo it, so the error message of a rejected request can
+//! end with it, see [`RestError::request_id`](crate::errors::RestError::request_id).
+//!
+//! The [trace context](crate::trace_context) of the caller is added when the client has a propagator.
+//!
+//! ### Timeouts
+//! Requests have no timeout unless one is set with [`SupabaseClient::with_timeout`] or on a single query.
+//! A request that runs out of time fails with [`ErrorTypes::Timeout`].
+
+use crate::circuit_breaker::CircuitPermit;
+use crate::errors::ErrorTypes;
+use crate::request::headers::HeadersTypes;
+use crate::SupabaseClient;
+
+use reqwest::header::{HeaderValue, AUTHORIZATION, LOCATION};
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode, Url};
+
+/// The maximum number of redirects followed for a single request.
+const MAX_REDIRECTS: usize = 10;
+
+impl SupabaseClient {
+    /// Sends a prepared request to Supabase.
+    ///
+    /// # Arguments
+    /// * `table_name` - The table the request targets, used to label metrics.
+    /// * `operation` - The kind of operation (`select`, `insert`, ...), used to label metrics.
+    /// * `request` - The fully built `reqwest` request.
+    ///
+    /// # Returns
+    /// The raw `Response`, or the error of a request that got no response.
+    #[cfg_attr(
+        not(any(feature = "metrics", feature = "tracing", feature = "audit")),
+        allow(unused_variables)
+    )]
+    pub(crate) async fn dispatch(
+        &self,
+        table_name: &str,
+        operation: &str,
+        request: RequestBuilder,
+    ) -> Result<Response, ErrorTypes> {
+        let request: RequestBuilder = match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+        let (client, request): (Client, reqwest::Result<Request>) = request.build_split();
+        let mut request: Request = request?;
+        if let Some(access_token) = &self.access_token {
+            let bearer: HeaderValue = HeaderValue::from_str(&format!("Bearer {}", access_token))
+                .map_err(|error| ErrorTypes::InvalidJwt(error.to_string()))?;
+            request.headers_mut().insert(AUTHORIZATION, bearer);
+        }
+        #[cfg(feature = "auth")]
+        if self.access_token.is_none() {
+            crate::auth::client::authorize_session(
+                self.session_store.as_ref(),
+                &self.api_key,
+                request.headers_mut(),
+            );
+        }
+        let request_id: HeaderValue = request
+            .headers_mut()
+            .entry(HeadersTypes::RequestId.as_str())
+            .or_insert_with(|| {
+                HeaderValue::from_str(&new_request_id()).expect("a UUID is a valid header value")
+            })
+            .clone();
+        crate::trace_context::inject(self.trace_propagator.as_ref(), request.headers_mut());
+        let host: String = request.url().host_str().unwrap_or_default().to_string();
+
+        // writes make the cached selects of the table stale
+        #[cfg(feature = "cache")]
+        let writes: bool = !matches!(*request.method(), Method::GET | Method::HEAD);
+        #[cfg(feature = "cache")]
+        if let (Some(cache), true) = (&self.cache, writes) {
+            cache.invalidate(table_name);
+        }
+
+        // held until the outcome is recorded, a dropped request frees its probe slot
+        let circuit_permit: Option<CircuitPermit> = match &self.circuit_breaker {
+            Some(circuit_breaker) => Some(circuit_breaker.try_acquire(&host)?),
+            None => None,
+        };
+
+        // held until the response arrived, so it counts as in flight until then
+        let _permit = match &self.rate_limiter {
+            Some(rate_limiter) => Some(rate_limiter.acquire().await),
+            None => None,
+        };
+
+        #[cfg(feature = "audit")]
+        let audit: Option<crate::audit::PendingAudit> = crate::audit::PendingAudit::start(
+            self.audit_sink.as_ref(),
+            table_name,
+            operation,
+            &request,
+        );
+
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        let started: std::time::Instant = std::time::Instant::now();
+
+        #[cfg(feature = "tracing")]
+        let span: tracing::Span = tracing::debug_span!(
+            "supabase_rs.request",
+            method = %request.method(),
+            table = table_name,
+            operation = operation,
+            request_id = request_id.to_str().unwrap_or_default(),
+        );
+
+        let result = async {
+            match &self.transport {
+                Some(transport) => transport
+                    .execute(request)
+                    .await
+                    .map_err(ErrorTypes::RequestFailed),
+                None => execute_following_redirects(&client, request).await,
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let result = tracing::Instrument::instrument(result, span.clone());
+        let result: Result<Response, ErrorTypes> = result.await.map(|mut response| {
+            response
+                .headers_mut()
+                .entry(HeadersTypes::RequestId.as_str())
+                .or_insert(request_id);
+            response
+        });
+
+        // and again once it was applied, a select answered in the meantime may hold the old rows
+        #[cfg(feature = "cache")]
+        if let (Some(cache), true) = (&self.cache, writes) {
+            cache.invalidate(table_name);
+        }
+
+        #[cfg(feature = "tracing")]
+        span.in_scope(|| trace_outcome(&result, started.elapsed()));
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request(
+            table_name,
+            operation,
+            result
+                .as_ref()
+                .ok()
+                .map(|response| response.status().as_u16()),
+            started.elapsed(),
+        );
+
+        #[cfg(feature = "audit")]
+        if let Some(audit) = audit {
+            audit.finish(&result).await;
+        }
+
+        if let Some(circuit_permit) = circuit_permit {
+            match &result {
+                Ok(response) if !response.status().is_server_error() => circuit_permit.success(),
+                _ => circuit_permit.failure(),
+            }
+        }
+
+        result
+    }
+
+    /// The bearer the requests of this client are sent with, which decides the rows row level
+    /// security lets them see: the impersonated access token, the access token of the signed in user
+    /// or the API key.
+    #[cfg(feature = "cache")]
+    pub(crate) fn bearer(&self) -> String {
+        if let Some(access_token) = &self.access_token {
+            return access_token.clone();
+        }
+        #[cfg(feature = "auth")]
+        if let Some(session) = self.session_store.as_ref().and_then(|store| store.get()) {
+            return session.access_token;
+        }
+        self.api_key.clone()
+    }
+}
+
+/// Emits the event ending a request span.
+#[cfg(feature = "tracing")]
+fn trace_outcome(result: &Result<Response, ErrorTypes>, elapsed: std::time::Duration) {
+    let latency_ms: u128 = elapsed.as_millis();
+    match result {
+        Ok(response) if response.status().is_success() => {
+            tracing::debug!(
+                status = response.status().as_u16(),
+                latency_ms,
+                "request completed"
+            )
+        }
+        Ok(response) => {
+            tracing::warn!(
+                status = response.status().as_u16(),
+                latency_ms,
+                "request returned an error"
+            )
+        }
+        Err(error) => tracing::warn!(error = %error, latency_ms, "request failed"),
+    }
+}
+
+/// Executes a request, following the redirects that are safe to follow.
+async fn execute_following_redirects(
+    client: &Client,
+    request: Request,
+) -> Result<Response, ErrorTypes> {
+    let mut request: Request = request;
+
+    for _ in 0..MAX_REDIRECTS {
+        let retry: Option<Request> = request.try_clone();
+        let response: Response = client.execute(request).await.map_err(transport_error)?;
+
+        let status: StatusCode = response.status();
+        if !status.is_redirection() || status == StatusCode::NOT_MODIFIED {
+            return Ok(response);
+        }
+
+        let location: Url = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| response.url().join(location).ok())
+            .ok_or_else(|| {
+                ErrorTypes::UnexpectedResponse(format!(
+                    "Redirect ({}) from {} has no valid Location",
+                    status,
+                    response.url()
+                ))
+            })?;
+
+        let mut next: Request = match retry {
+            Some(next) if may_follow(status, next.method(), next.url(), &location) => next,
+            _ => {
+                return Err(ErrorTypes::UnexpectedResponse(format!(
+                "Unexpected redirect ({}) from {} to {}, point the client at the final URL instead",
+                status,
+                response.url(),
+                location
+            )))
+            }
+        };
+
+        *next.url_mut() = location;
+        request = next;
+    }
+
+    Err(ErrorTypes::UnexpectedResponse(format!(
+        "Too many redirects, gave up after {}",
+        MAX_REDIRECTS
+    )))
+}
+
+/// A random version 4 UUID, identifying a request.
+fn new_request_id() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Maps a failed request onto its error, timeouts onto [`ErrorTypes::Timeout`].
+fn transport_error(error: reqwest::Error) -> ErrorTypes {
+    match error.is_timeout() {
+        true => ErrorTypes::Timeout,
+        false => ErrorTypes::ReqwestError(error),
+    }
+}
+
+/// Whether a redirect can be followed without changing the request or leaking credentials.
+pub(crate) fn may_follow(status: StatusCode, method: &Method, from: &Url, to: &Url) -> bool {
+    // the upgrade of `http` to `https` on the default ports sends the credentials to the same
+    // server, only encrypted
+    let upgrade: bool = from.scheme() == "http"
+        && to.scheme() == "https"
+        && from.host_str() == to.host_str()
+        && from.port().is_none()
+        && to.port().is_none();
+    if from.origin() != to.origin() && !upgrade {
+        return false;
+    }
+
+    match status {
+        StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => true,
+        StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER => {
+            matches!(*method, Method::GET | Method::HEAD)
+        }
+        _ => false,
+    }
+}
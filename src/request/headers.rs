@@ -42,6 +42,11 @@ pub enum HeadersTypes {
     ContentType,
     Prefer,
     ClientInfo,
+    Accept,
+    AcceptProfile,
+    ContentProfile,
+    RequestId,
+    IdempotencyKey,
 }
 
 impl HeadersTypes {
@@ -52,6 +57,11 @@ impl HeadersTypes {
             HeadersTypes::ContentType => "Content-Type",
             HeadersTypes::Prefer => "prefer",
             HeadersTypes::ClientInfo => "x_client_info",
+            HeadersTypes::Accept => "Accept",
+            HeadersTypes::AcceptProfile => "Accept-Profile",
+            HeadersTypes::ContentProfile => "Content-Profile",
+            HeadersTypes::RequestId => "x-request-id",
+            HeadersTypes::IdempotencyKey => "idempotency-key",
         }
     }
 }
@@ -1,4 +1,6 @@
+pub mod dispatch;
 pub mod headers;
+pub mod prefer;
 
 use std::collections::HashMap;
 
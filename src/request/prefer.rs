@@ -0,0 +1,94 @@
+//! ## Prefer header
+//!
+//! PostgREST reads its preferences, like what a write returns or how rows are counted, from a single
+//! comma-separated `Prefer` header. [`PreferBuilder`] collects them so every request sends exactly
+//! one header, whatever combination of preferences it needs.
+//!
+//! ```rust,ignore
+//! use supabase_rs::query::{CountMode, Returning};
+//! use supabase_rs::request::prefer::PreferBuilder;
+//! use supabase_rs::update::DuplicateResolution;
+//!
+//! let prefer: String = PreferBuilder::new()
+//!     .resolution(DuplicateResolution::MergeDuplicates)
+//!     .returning(Returning::Representation)
+//!     .count(CountMode::Exact)
+//!     .build();
+//! assert_eq!(prefer, "resolution=merge-duplicates,return=representation,count=exact");
+//! ```
+
+use crate::query::{CountMode, Returning};
+use crate::request::headers::HeadersTypes;
+use crate::request::Headers;
+use crate::update::DuplicateResolution;
+
+/// The preferences of a request, sent as one `Prefer` header.
+///
+/// Preferences keep the order they were set in, and setting one again replaces its value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreferBuilder {
+    preferences: Vec<(&'static str, String)>,
+}
+
+impl PreferBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How an upsert treats conflicting rows, `resolution=...`.
+    pub fn resolution(self, resolution: DuplicateResolution) -> Self {
+        let value: &str = resolution.as_str().trim_start_matches("resolution=");
+        self.set("resolution", value)
+    }
+
+    /// What a write returns, `return=...`.
+    pub fn returning(self, returning: Returning) -> Self {
+        self.set("return", returning.as_str())
+    }
+
+    /// How the matching rows are counted, `count=...`.
+    pub fn count(self, count: CountMode) -> Self {
+        self.set("count", count.as_str())
+    }
+
+    /// Fills the columns a row leaves out with their defaults instead of `NULL`, `missing=default`.
+    ///
+    /// Only applies to the columns named in the `columns` query parameter.
+    pub fn missing_default(self) -> Self {
+        self.set("missing", "default")
+    }
+
+    /// The timezone timestamps are rendered in, `timezone=...`.
+    pub fn timezone(self, timezone: &str) -> Self {
+        self.set("timezone", timezone)
+    }
+
+    /// Whether no preference was set.
+    pub fn is_empty(&self) -> bool {
+        self.preferences.is_empty()
+    }
+
+    /// Returns the value of the `Prefer` header, empty when no preference was set.
+    pub fn build(&self) -> String {
+        self.preferences
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Inserts the `Prefer` header into `headers`, unless no preference was set.
+    pub(crate) fn apply(&self, headers: &mut Headers) {
+        if !self.is_empty() {
+            headers.insert(HeadersTypes::Prefer.as_str(), &self.build());
+        }
+    }
+
+    fn set(mut self, key: &'static str, value: &str) -> Self {
+        match self.preferences.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.preferences.push((key, value.to_string())),
+        }
+        self
+    }
+}
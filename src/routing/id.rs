@@ -1,7 +1,47 @@
+//! ## Row lookups
+//!
+//! Looks up a single row by the value of one of its columns, returning `None` when no row matches
+//! instead of an error, so a missing row and a failed request can be told apart.
+//!
+//! ```rust,ignore
+//! let user: Option<Value> = client.find_one_by("users", "email", "a@b.c").await?;
+//! let id: Option<String> = client.find_id_by("users", "email", "a@b.c").await?;
+//!
+//! // typed, with the table and column checked at compile time
+//! let user: Option<User> = client.find_row_by::<User>(UserColumn::Email, "a@b.c").await?;
+//! ```
+//!
+//! Lookups on several columns chain the filters on a query and end it with `first()`:
+//! ```rust,ignore
+//! let member: Option<Value> = client
+//!     .select("members")
+//!     .eq("org_id", 7)
+//!     .eq("email", "a@b.c")
+//!     .first()
+//!     .await?;
+//! ```
+//!
+//! Queries that must match exactly one row end with `single_strict()` instead, which lets PostgREST
+//! fail the request with a `406` when no row or several rows match:
+//! ```rust,ignore
+//! let order: Value = client.select("orders").eq("id", 42).single_strict().await?;
+//! ```
+
+use crate::errors::RestError;
+use crate::query::{FilterValue, QueryBuilder};
+use crate::request::headers::HeadersTypes;
+use crate::request::Headers;
+use crate::success::{response_error, unexpected_response};
+use crate::table::{SupabaseTable, TypedQueryBuilder};
 use crate::SupabaseClient;
 
+use reqwest::Response;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
+/// The `Accept` header asking PostgREST for a single JSON object instead of an array.
+const SINGLE_OBJECT: &str = "application/vnd.pgrst.object+json";
+
 impl SupabaseClient {
     /// Retrieves the ID of a row from a specified table based on a matching email address.
     ///
@@ -34,6 +74,7 @@ impl SupabaseClient {
     ///     }
     /// }
     /// ```
+    #[deprecated(note = "use `find_id_by`, which returns `None` when no row matches")]
     pub async fn get_id(
         &self,
         email: String,
@@ -58,4 +99,143 @@ impl SupabaseClient {
             Err(error) => Err(error),
         }
     }
+
+    /// Finds the first row of a table where the column equals the value.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table to search.
+    /// * `column` - The column to match on.
+    /// * `value` - The value the column has to equal.
+    ///
+    /// # Returns
+    /// `Ok(Some(row))` with the first matching row, `Ok(None)` when no row matches, or the
+    /// error when the request fails.
+    pub async fn find_one_by(
+        &self,
+        table_name: &str,
+        column: &str,
+        value: impl Into<FilterValue>,
+    ) -> Result<Option<Value>, RestError> {
+        self.select(table_name).eq(column, value).first().await
+    }
+
+    /// Finds the `id` of the first row of a table where the column equals the value.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table to search.
+    /// * `column` - The column to match on.
+    /// * `value` - The value the column has to equal.
+    ///
+    /// # Returns
+    /// `Ok(Some(id))` with the `id` of the first matching row, text ids without quotes, `Ok(None)`
+    /// when no row matches, or a `RestError` when the request fails or the row has no `id`.
+    pub async fn find_id_by(
+        &self,
+        table_name: &str,
+        column: &str,
+        value: impl Into<FilterValue>,
+    ) -> Result<Option<String>, RestError> {
+        let row: Option<Value> = self
+            .select(table_name)
+            .columns(vec!["id"])
+            .eq(column, value)
+            .first()
+            .await?;
+
+        match row.as_ref().map(|row| &row["id"]) {
+            None => Ok(None),
+            Some(Value::String(id)) => Ok(Some(id.clone())),
+            Some(Value::Null) => Err(format!("Row of `{}` has no `id`", table_name).into()),
+            Some(id) => Ok(Some(id.to_string())),
+        }
+    }
+
+    /// Finds the first row of the table of `T` where the column equals the value.
+    ///
+    /// # Returns
+    /// `Ok(Some(row))` with the first matching row, `Ok(None)` when no row matches, or the
+    /// error when the request fails or the row doesn't deserialize into `T`.
+    pub async fn find_row_by<T: SupabaseTable>(
+        &self,
+        column: T::Column,
+        value: impl Into<FilterValue>,
+    ) -> Result<Option<T>, RestError> {
+        self.select_typed::<T>().eq(column, value).first().await
+    }
+}
+
+impl QueryBuilder {
+    /// Executes the query for its first row only.
+    ///
+    /// # Returns
+    /// `Ok(Some(row))` with the first matching row, `Ok(None)` when no row matches, or the
+    /// error.
+    pub async fn first(mut self) -> Result<Option<Value>, RestError> {
+        self.query.params.retain(|(key, _)| key != "limit");
+        self.query.add_param("limit", "1");
+
+        Ok(self.execute().await?.into_iter().next())
+    }
+
+    /// Executes the query for its first row only, and deserializes it into `T`.
+    ///
+    /// # Returns
+    /// `Ok(Some(row))` with the first matching row, `Ok(None)` when no row matches, or the
+    /// error.
+    pub async fn first_typed<T: DeserializeOwned>(self) -> Result<Option<T>, RestError> {
+        self.first()
+            .await?
+            .map(|row| serde_json::from_value(row).map_err(unexpected_response))
+            .transpose()
+    }
+
+    /// Executes the query for exactly one row, asking PostgREST for a single object.
+    ///
+    /// The database enforces the cardinality: when no row or more than one row matches, PostgREST
+    /// responds with `406` and a `PGRST116` error naming the number of rows, so nothing is read.
+    ///
+    /// # Returns
+    /// The only matching row, or a `RestError`, also when no row or several rows match.
+    pub async fn single_strict(self) -> Result<Value, RestError> {
+        let mut headers: Headers = self.preferences(None);
+        headers.insert(HeadersTypes::Accept.as_str(), SINGLE_OBJECT);
+
+        let query_string: String = self.query.build();
+        let request = async {
+            let response: Response = self
+                .client
+                .send_select(&self.table_name, &query_string, headers)
+                .await?;
+
+            if !response.status().is_success() {
+                return Err(response_error(response).await);
+            }
+            response.json::<Value>().await.map_err(unexpected_response)
+        };
+
+        self.run(request).await
+    }
+
+    /// Executes the query for exactly one row like [`single_strict`](Self::single_strict), and
+    /// deserializes it into `T`.
+    pub async fn single_strict_typed<T: DeserializeOwned>(self) -> Result<T, RestError> {
+        let row: Value = self.single_strict().await?;
+        serde_json::from_value(row).map_err(unexpected_response)
+    }
+}
+
+impl<T: SupabaseTable> TypedQueryBuilder<T> {
+    /// Executes the query for exactly one row, see [`QueryBuilder::single_strict`].
+    pub async fn single_strict(self) -> Result<T, RestError> {
+        self.into_inner().single_strict_typed::<T>().await
+    }
+
+    /// Executes the query for its first row only, and deserializes it into `T`.
+    ///
+    /// # Returns
+    /// `Ok(Some(row))` with the first matching row, `Ok(None)` when no row matches, or the
+    /// error.
+    pub async fn first(self) -> Result<Option<T>, RestError> {
+        self.into_inner().first_typed::<T>().await
+    }
 }
@@ -0,0 +1,286 @@
+//! ## Remote procedure calls
+//!
+//! PostgREST exposes every function of the exposed schema on `/rest/v1/rpc/{function}`.
+//! The [`RpcBuilder`] calls such a function with JSON parameters and returns its result.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde_json::json;
+//!
+//! let result: Value = client
+//!     .rpc("add_them", json!({"a": 1, "b": 2}))
+//!     .execute()
+//!     .await?;
+//!
+//! // functions living in another exposed schema
+//! client
+//!     .rpc("send", json!({"queue_name": "jobs", "message": {}}))
+//!     .schema("pgmq_public")
+//!     .execute_void()
+//!     .await?;
+//!
+//! // typed parameters and results
+//! #[derive(Serialize)]
+//! struct Range { from: i64, to: i64 }
+//!
+//! let total: i64 = client
+//!     .rpc("sum_between", Range { from: 1, to: 10 })
+//!     .execute_typed()
+//!     .await?;
+//!
+//! // read-only (`stable`/`immutable`) functions can be called with GET, so they can be
+//! // served by read replicas and HTTP caches
+//! let nearby: Value = client
+//!     .rpc("nearby_stores", json!({"lat": 52.37, "long": 4.89}))
+//!     .read_only()
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! ### Errors
+//! Failed calls return [`ErrorTypes::Postgrest`] with the parsed PostgREST error body, classified by
+//! [`PostgrestErrorKind`](crate::errors::PostgrestErrorKind):
+//! ```rust,ignore
+//! use supabase_rs::errors::PostgrestErrorKind;
+//!
+//! match client.rpc("transfer", params).execute_void().await {
+//!     Err(error) => match error.postgrest_error() {
+//!         Some((_, PostgrestErrorKind::NotFound)) => println!("run the migrations first"),
+//!         Some((body, PostgrestErrorKind::RaisedException)) => println!("{}", body.message),
+//!         _ => return Err(error.into()),
+//!     },
+//!     Ok(()) => {}
+//! }
+//! ```
+//!
+//! Calls that couldn't be sent fail with [`ErrorTypes::RequestFailed`], or [`ErrorTypes::Timeout`] and
+//! [`ErrorTypes::Cancelled`] when they ran out of time or were aborted.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::request::headers::HeadersTypes;
+use crate::request::Headers;
+use crate::SupabaseClient;
+
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// A pending call to a Postgres function, created by [`SupabaseClient::rpc`].
+#[derive(Debug, Clone)]
+pub struct RpcBuilder {
+    client: SupabaseClient,
+    function_name: String,
+    params: std::result::Result<Value, String>,
+    schema: Option<String>,
+    read_only: bool,
+    #[cfg(feature = "cancellation")]
+    cancel_token: Option<tokio_util::sync::CancellationToken>,
+}
+
+impl SupabaseClient {
+    /// Prepares a call to the Postgres function `function_name`.
+    ///
+    /// # Arguments
+    /// * `function_name` - The name of the function to call.
+    /// * `params` - The named arguments of the function, any type serializing to a JSON object,
+    ///   e.g. a `#[derive(Serialize)]` struct, `json!({..})` or `()` for none.
+    ///
+    /// # Returns
+    /// An `RpcBuilder` which sends the call once executed. When `params` can't be serialized the call
+    /// isn't sent and executing it returns [`ErrorTypes::InvalidParameters`].
+    pub fn rpc(&self, function_name: &str, params: impl Serialize) -> RpcBuilder {
+        RpcBuilder::new(self.clone(), function_name, params)
+    }
+}
+
+impl RpcBuilder {
+    /// Constructs a new `RpcBuilder` for the function `function_name`.
+    pub fn new(client: SupabaseClient, function_name: &str, params: impl Serialize) -> Self {
+        RpcBuilder {
+            client,
+            function_name: function_name.to_string(),
+            params: serialize_params(params),
+            schema: None,
+            read_only: false,
+            #[cfg(feature = "cancellation")]
+            cancel_token: None,
+        }
+    }
+
+    /// Calls the function in `schema` instead of the schema of the client.
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    /// Calls the function with `GET`, passing the parameters in the query string.
+    ///
+    /// Only functions declared `stable` or `immutable` can be called this way, PostgREST then runs them
+    /// in a read-only transaction, which read replicas and HTTP caches can serve.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Fails the call when it takes longer than `timeout`, with [`ErrorTypes::Timeout`].
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.client.timeout = Some(timeout);
+        self
+    }
+
+    /// Aborts the call when `token` is cancelled, with [`ErrorTypes::Cancelled`].
+    #[cfg(feature = "cancellation")]
+    pub fn abort_signal(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// The parameters as query string pairs, for read-only calls.
+    ///
+    /// Strings are passed as they are, arrays as Postgres array literals, e.g. `{1,2}`, and other
+    /// values as their JSON text.
+    pub(crate) fn query_params(&self) -> Vec<(String, String)> {
+        let Some(params) = self.params.as_ref().ok().and_then(Value::as_object) else {
+            return Vec::new();
+        };
+
+        params
+            .iter()
+            .map(|(key, value)| {
+                let value: String = match value {
+                    Value::String(value) => value.clone(),
+                    Value::Array(values) => array_literal(values),
+                    value => value.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+
+    async fn execute_internal(self, single: bool) -> Result<Response> {
+        let params: Value = self.params.clone().map_err(ErrorTypes::InvalidParameters)?;
+        let endpoint: String = format!("{}/rest/v1/rpc/{}", self.client.url, self.function_name);
+        let mut headers: Headers =
+            Headers::with_defaults(&self.client.api_key, &self.client.api_key);
+
+        if let Some(schema) = self.schema.as_ref().or(self.client.schema.as_ref()) {
+            headers.insert(HeadersTypes::AcceptProfile.as_str(), schema);
+            headers.insert(HeadersTypes::ContentProfile.as_str(), schema);
+        }
+        if single {
+            headers.insert(
+                HeadersTypes::Accept.as_str(),
+                "application/vnd.pgrst.object+json",
+            );
+        }
+
+        let mut request = if self.read_only {
+            self.client
+                .client
+                .get(&endpoint)
+                .query(&self.query_params())
+        } else {
+            self.client.client.post(&endpoint).json(&params)
+        };
+        for (key, value) in headers.get_headers() {
+            request = request.header(key, value);
+        }
+
+        let request = self.client.dispatch(&self.function_name, "rpc", request);
+
+        #[cfg(feature = "cancellation")]
+        let request = async {
+            match &self.cancel_token {
+                Some(token) => token
+                    .run_until_cancelled(request)
+                    .await
+                    .unwrap_or(Err(ErrorTypes::Cancelled)),
+                None => request.await,
+            }
+        };
+
+        let response: Response = request.await?;
+
+        if !response.status().is_success() {
+            return Err(ErrorTypes::from_postgrest_response(response).await);
+        }
+
+        Ok(response)
+    }
+
+    /// Calls the function and returns its result as JSON.
+    ///
+    /// # Returns
+    /// The JSON result of the function, `Value::Null` when it returns nothing.
+    pub async fn execute(self) -> Result<Value> {
+        let response: Response = self.execute_internal(false).await?;
+        let body: String = response.text().await?;
+
+        if body.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&body)
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string()))
+    }
+
+    /// Calls the function and deserializes its result into `T`.
+    ///
+    /// Functions returning nothing deserialize from `null`, e.g. into `()` or an `Option`.
+    ///
+    /// # Returns
+    /// The typed result, or [`ErrorTypes::UnexpectedResponse`] when it doesn't match `T`.
+    pub async fn execute_typed<T: DeserializeOwned>(self) -> Result<T> {
+        let result: Value = self.execute().await?;
+        serde_json::from_value(result)
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string()))
+    }
+
+    /// Calls a set-returning function and returns its only row.
+    ///
+    /// # Returns
+    /// The single row as JSON, or an error of kind `NoRows`/`MultipleRows` when the function returns
+    /// zero or multiple rows.
+    pub async fn execute_single(self) -> Result<Value> {
+        let response: Response = self.execute_internal(true).await?;
+        response
+            .json::<Value>()
+            .await
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string()))
+    }
+
+    /// Calls the function and discards its result.
+    pub async fn execute_void(self) -> Result<()> {
+        self.execute_internal(false).await?;
+        Ok(())
+    }
+}
+
+/// Serializes the parameters of a call, no parameters (`()` or `None`) become an empty object.
+pub(crate) fn serialize_params(params: impl Serialize) -> std::result::Result<Value, String> {
+    match serde_json::to_value(params) {
+        Ok(Value::Null) => Ok(json!({})),
+        Ok(params) => Ok(params),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Builds a Postgres array literal, e.g. `{"open","bio"}`, quoting strings and JSON objects so their
+/// commas, braces and quotes are kept intact.
+fn array_literal(values: &[Value]) -> String {
+    let elements: Vec<String> = values
+        .iter()
+        .map(|value| match value {
+            Value::Null => "NULL".to_string(),
+            Value::Array(values) => array_literal(values),
+            Value::String(value) => quote_element(value),
+            Value::Object(_) => quote_element(&value.to_string()),
+            value => value.to_string(),
+        })
+        .collect();
+    format!("{{{}}}", elements.join(","))
+}
+
+fn quote_element(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
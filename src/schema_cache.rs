@@ -0,0 +1,413 @@
+//! ## Schema cache
+//!
+//! Caches the tables, columns and foreign key relationships of the exposed schema, parsed from the
+//! PostgREST OpenAPI document, so the names used by a query can be checked before a request is
+//! sent. An unknown table, column or relationship then fails immediately with the closest name that
+//! does exist, instead of an opaque `400` from PostgREST.
+//!
+//! The cache is opt-in, warm it once at startup:
+//! ```rust,ignore
+//! let client = SupabaseClient::new(url, key)?.with_schema_cache().await?;
+//!
+//! // fails without a request: Unknown column `emial` on `users`, did you mean `email`?
+//! client.select("users").eq("emial", "a@b.c").execute().await?;
+//!
+//! // fails without a request when `books` has no relationship named `authorz`
+//! client.schema_cache().unwrap().validate_relation("books", "authorz")?;
+//! ```
+//!
+//! Without network access at startup, build the cache from the types generated by `type_gen`, or
+//! derived with `SupabaseTable`:
+//! ```rust,ignore
+//! let cache = SchemaCache::new().with_table::<Users>().with_table::<Books>();
+//! let client = SupabaseClient::new(url, key)?.with_schema_cache_from(cache);
+//! ```
+//!
+//! Selects, and the builders of `update_table` and `delete_from`, are checked: their table, the
+//! columns of their select, filters and ordering, and the columns they set. Filters on embedded
+//! relations and queries on another schema than the one of the client are left to PostgREST.
+//! Computed columns aren't part of the OpenAPI document, so queries using them fail the check.
+//!
+//! The cache isn't refreshed by itself, call [`SupabaseClient::with_schema_cache`] again after migrations.
+
+use crate::errors::RestError;
+use crate::introspection::{parse_table_definitions, ColumnDefinition, TableDefinition};
+use crate::query::Query;
+use crate::query_builder::embed::split_top_level;
+use crate::table::SupabaseTable;
+use crate::SupabaseClient;
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A foreign key between two tables, from `table.column` to `foreign_table.foreign_column`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relationship {
+    pub table: String,
+    pub column: String,
+    pub foreign_table: String,
+    pub foreign_column: String,
+}
+
+/// Query parameters which aren't filters on a column.
+const RESERVED_PARAMS: &[&str] = &[
+    "select",
+    "order",
+    "limit",
+    "offset",
+    "count",
+    "or",
+    "and",
+    "on_conflict",
+    "columns",
+];
+
+/// The tables and relationships of the exposed schema.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaCache {
+    tables: HashMap<String, TableDefinition>,
+    relationships: Vec<Relationship>,
+    /// The schema of the client the cache was attached to, queries on other schemas aren't checked.
+    schema: Option<String>,
+}
+
+impl SchemaCache {
+    /// Creates an empty cache, to fill with [`with_table`](Self::with_table).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the cache from a PostgREST OpenAPI document.
+    pub fn from_openapi(openapi: &Value) -> Self {
+        Self::from_tables(parse_table_definitions(openapi))
+    }
+
+    /// Builds the cache from table definitions, e.g. parsed with
+    /// [`parse_table_definitions`](crate::introspection::parse_table_definitions).
+    pub fn from_tables(tables: Vec<TableDefinition>) -> Self {
+        let relationships: Vec<Relationship> = tables
+            .iter()
+            .flat_map(|table| {
+                table.columns.iter().filter_map(|column| {
+                    let foreign_key = column.foreign_key.as_ref()?;
+                    Some(Relationship {
+                        table: table.name.clone(),
+                        column: column.name.clone(),
+                        foreign_table: foreign_key.table.clone(),
+                        foreign_column: foreign_key.column.clone(),
+                    })
+                })
+            })
+            .collect();
+
+        SchemaCache {
+            tables: tables
+                .into_iter()
+                .map(|table| (table.name.clone(), table))
+                .collect(),
+            relationships,
+            schema: None,
+        }
+    }
+
+    /// Adds the table of `T`, with the columns and foreign keys it knows about.
+    ///
+    /// The column types aren't known, only their names are checked.
+    pub fn with_table<T: SupabaseTable>(mut self) -> Self {
+        let columns: Vec<ColumnDefinition> = T::columns()
+            .iter()
+            .map(|column| ColumnDefinition {
+                name: column.to_string(),
+                pg_type: "text".to_string(),
+                json_type: "string".to_string(),
+                required: false,
+                primary_key: *column == T::primary_key(),
+                description: None,
+                foreign_key: None,
+                enum_values: Vec::new(),
+            })
+            .collect();
+        self.tables.insert(
+            T::table_name().to_string(),
+            TableDefinition {
+                name: T::table_name().to_string(),
+                columns,
+            },
+        );
+
+        for relationship in T::belongs_to() {
+            let relationship: Relationship = Relationship {
+                table: T::table_name().to_string(),
+                column: relationship.column.to_string(),
+                foreign_table: relationship.table.to_string(),
+                foreign_column: relationship.foreign_column.to_string(),
+            };
+            if !self.relationships.contains(&relationship) {
+                self.relationships.push(relationship);
+            }
+        }
+        self
+    }
+
+    /// Returns the definition of a table or view.
+    pub fn table(&self, name: &str) -> Option<&TableDefinition> {
+        self.tables.get(name)
+    }
+
+    /// Returns every relationship the table is part of, in either direction.
+    pub fn relationships(&self, table: &str) -> Vec<&Relationship> {
+        self.relationships
+            .iter()
+            .filter(|relationship| {
+                relationship.table == table || relationship.foreign_table == table
+            })
+            .collect()
+    }
+
+    /// Returns the names a table can embed: the tables it references or is referenced by, the tables
+    /// it is joined to through a join table, and its foreign key columns, sorted.
+    pub fn relation_names(&self, table: &str) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for relationship in self.relationships(table) {
+            if relationship.table == table {
+                names.push(relationship.foreign_table.clone());
+                names.push(relationship.column.clone());
+            }
+            if relationship.foreign_table == table {
+                names.push(relationship.table.clone());
+                // many-to-many, through the other foreign keys of the referencing table
+                names.extend(
+                    self.relationships
+                        .iter()
+                        .filter(|other| {
+                            other.table == relationship.table && other.column != relationship.column
+                        })
+                        .map(|other| other.foreign_table.clone()),
+                );
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Checks that `relation` can be embedded in a select on `table`.
+    ///
+    /// # Returns
+    /// `Ok(())` when the relationship exists, or a `String` error naming the ones that do.
+    pub fn validate_relation(&self, table: &str, relation: &str) -> Result<(), String> {
+        self.validate_table(table)?;
+
+        let names: Vec<String> = self.relation_names(table);
+        if names.iter().any(|name| name == relation) {
+            return Ok(());
+        }
+
+        if names.is_empty() {
+            Err(format!(
+                "`{}` has no relationship named `{}`, it has no relationships at all",
+                table, relation
+            ))
+        } else {
+            Err(format!(
+                "`{}` has no relationship named `{}`, expected one of: {}",
+                table,
+                relation,
+                names.join(", ")
+            ))
+        }
+    }
+
+    /// Whether the cache would show a relationship between `table` and the table `relation`. Views
+    /// have no foreign keys of their own in the OpenAPI document, PostgREST infers their
+    /// relationships from the tables they select from, so a table or view without any relationship
+    /// may still be embedded. Names which aren't a table of the cache are always shown.
+    pub(crate) fn shows_relationship(&self, table: &str, relation: &str) -> bool {
+        !self.tables.contains_key(relation)
+            || (!self.relationships(table).is_empty() && !self.relationships(relation).is_empty())
+    }
+
+    /// Checks that `table` is a table or view of the schema.
+    ///
+    /// # Returns
+    /// `Ok(())` when the table exists, or a `String` error naming the closest table that does.
+    pub fn validate_table(&self, table: &str) -> Result<(), String> {
+        if self.tables.contains_key(table) {
+            return Ok(());
+        }
+
+        let message: String = format!("Unknown table `{}`", table);
+        Err(with_suggestion(
+            message,
+            table,
+            self.tables.keys().map(String::as_str),
+        ))
+    }
+
+    /// Checks that every column belongs to `table`.
+    ///
+    /// # Returns
+    /// `Ok(())` when every column exists, or a `String` error naming the first unknown column and
+    /// the closest column that does exist.
+    pub fn validate_columns<'a>(
+        &self,
+        table: &str,
+        columns: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), String> {
+        self.validate_table(table)?;
+        let definition: &TableDefinition = &self.tables[table];
+
+        for column in columns {
+            if definition
+                .columns
+                .iter()
+                .any(|candidate| candidate.name == column)
+            {
+                continue;
+            }
+
+            let message: String = format!("Unknown column `{}` on `{}`", column, table);
+            return Err(with_suggestion(
+                message,
+                column,
+                definition.columns.iter().map(|column| column.name.as_str()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the cache describes `schema`, the schema of the client when `None`.
+    pub(crate) fn describes(&self, schema: Option<&str>) -> bool {
+        schema == self.schema.as_deref()
+    }
+
+    /// Checks the table of a query and the columns it selects, filters and orders on, and the columns
+    /// of `body`, the JSON object a write sets.
+    ///
+    /// Does nothing when `schema` isn't the schema the cache describes.
+    pub(crate) fn validate_query(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        query: &Query,
+        body: Option<&Value>,
+    ) -> Result<(), String> {
+        if !self.describes(schema) {
+            return Ok(());
+        }
+
+        let mut columns: Vec<String> = query_columns(query);
+        if let Some(Value::Object(body)) = body {
+            columns.extend(body.keys().cloned());
+        }
+        self.validate_columns(table, columns.iter().map(String::as_str))
+    }
+}
+
+impl SupabaseClient {
+    /// Fetches the schema and caches its tables and relationships on the client and its clones.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the schema cache, or a `RestError` when the schema can't be fetched.
+    pub async fn with_schema_cache(self) -> Result<Self, RestError> {
+        let openapi: Value = self.fetch_openapi().await?;
+        Ok(self.with_schema_cache_from(SchemaCache::from_openapi(&openapi)))
+    }
+
+    /// Caches a schema built beforehand on the client and its clones, describing the schema of the client.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the schema cache.
+    pub fn with_schema_cache_from(mut self, mut cache: SchemaCache) -> Self {
+        cache.schema = self.schema.clone();
+        self.schema_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Returns the schema cache, if it was warmed with [`with_schema_cache`](Self::with_schema_cache).
+    pub fn schema_cache(&self) -> Option<&SchemaCache> {
+        self.schema_cache.as_deref()
+    }
+}
+
+/// Returns the plain columns a query selects, filters and orders on. Embeds, filters on embedded
+/// relations and aggregates are left out.
+fn query_columns(query: &Query) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+
+    for (key, value) in &query.params {
+        match key.as_str() {
+            "select" => columns.extend(
+                split_top_level(value)
+                    .iter()
+                    .filter(|item| *item != "*" && !item.contains('('))
+                    .map(|item| {
+                        // `alias:column::cast`, only the column is checked
+                        let item: &str = item.split("::").next().unwrap_or(item);
+                        column_name(item.split_once(':').map_or(item, |(_, column)| column))
+                    }),
+            ),
+            "order" => columns.extend(
+                value
+                    .split(',')
+                    .filter(|term| !term.contains('('))
+                    .map(|term| column_name(term.split('.').next().unwrap_or(term))),
+            ),
+            key if RESERVED_PARAMS.contains(&key) || key.contains('.') => {}
+            key => columns.push(column_name(key)),
+        }
+    }
+    columns.extend(
+        query
+            .filters
+            .iter()
+            .map(|filter| column_name(&filter.column)),
+    );
+    columns.extend(query.sorts.iter().map(|sort| column_name(&sort.column)));
+    columns
+}
+
+/// Strips a JSON path from a column reference, e.g. `address` for `address->>city`.
+fn column_name(reference: &str) -> String {
+    reference
+        .split("->")
+        .next()
+        .unwrap_or(reference)
+        .trim()
+        .to_string()
+}
+
+/// Appends the candidate closest to `name` to `message`, when one is close enough to be a typo.
+fn with_suggestion<'a>(
+    message: String,
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+) -> String {
+    let threshold: usize = (name.chars().count() / 3).max(2);
+    let closest: Option<(usize, &str)> = candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min();
+
+    match closest {
+        Some((_, candidate)) => format!("{}, did you mean `{}`?", message, candidate),
+        None => message,
+    }
+}
+
+/// The Levenshtein distance between two names, the number of single character edits between them.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current: Vec<usize> = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution: usize = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -57,6 +57,20 @@
 //!    .await;
 //! ```
 //!
+//! ### Counting into a separate value
+//! `execute_with_count()` reads the total from the `Content-Range` header instead, and supports the
+//! `Planned` and `Estimated` modes which are much cheaper on large tables.
+//! ```rust,ignore
+//! use supabase_rs::query::CountMode;
+//!
+//! let (rows, total): (Vec<Value>, u64) = supabase_client
+//!    .select("animals")
+//!    .eq("dog", "scooby")
+//!    .count_mode(CountMode::Estimated)
+//!    .execute_with_count()
+//!    .await?;
+//! ```
+//!
 //! ### Counting without filtering
 //! ```rust,ignore
 //! let data: Result<Vec<Value>, String> = supabase_client
@@ -123,15 +137,80 @@
 //!     .await;
 //! ```
 //!
+//! ### Typed values
+//! Filters take anything converting into a [`FilterValue`](crate::query::FilterValue): strings,
+//! numbers and booleans, and `Option`s of them where `None` matches `NULL`.
+//! ```rust,ignore
+//! let data: Vec<Value> = supabase_client
+//!     .select("animals")
+//!     .gte("weight", 100)
+//!     .eq("adopted", false)
+//!     .eq("owner_id", None::<i64>) // owner_id=is.null
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! ## Timezones
+//! Timestamps are rendered in UTC, unless a timezone is set on the client or on a single query.
+//! ```rust,ignore
+//! let client = supabase_client.with_timezone("Europe/Amsterdam");
+//!
+//! let data: Vec<Value> = client
+//!    .select("events")
+//!    .timezone("America/New_York") // overrides the client timezone
+//!    .execute()
+//!    .await?;
+//! ```
+//!
+//! ## Schemas
+//! Tables are read from the default exposed schema, unless a schema is set on the client or on a
+//! single query. The schema of the client also applies to its writes and RPC calls.
+//! ```rust,ignore
+//! let client = supabase_client.with_schema("analytics");
+//!
+//! let data: Vec<Value> = client
+//!    .select("events")
+//!    .schema("archive") // overrides the client schema
+//!    .execute()
+//!    .await?;
+//! ```
+//!
+//! ## Inspecting a query
+//! `to_url()` returns the URL a query is sent to, and its `Display` adds the headers the query
+//! sets, to log queries or snapshot test them without sending them.
+//! ```rust,ignore
+//! let query = supabase_client
+//!    .select("animals")
+//!    .eq("dog", "scooby")
+//!    .schema("archive");
+//!
+//! assert_eq!(query.to_url(), "https://project.supabase.co/rest/v1/animals?dog=eq.scooby");
+//! println!("{}", query);
+//! // GET https://project.supabase.co/rest/v1/animals?dog=eq.scooby
+//! // Accept-Profile: archive
+//! ```
+//!
+//! ## Exporting as CSV
+//! `execute_csv()` asks PostgREST for `text/csv`, which skips the JSON round trip for export pipelines.
+//! ```rust,ignore
+//! let csv: String = supabase_client
+//!    .select("animals")
+//!    .columns(vec!["id", "dog"])
+//!    .execute_csv()
+//!    .await?;
+//! ```
 
+use crate::errors::RestError;
 use crate::query::QueryBuilder;
+use crate::request::headers::HeadersTypes;
+use crate::request::prefer::PreferBuilder;
 use crate::request::Headers;
-use crate::success::handle_response;
+use crate::success::response_rows;
 use crate::SupabaseClient;
 
 use reqwest::header::HeaderMap;
 use reqwest::header::{HeaderName, HeaderValue};
-use reqwest::Response;
+use reqwest::{Method, Response};
 use serde_json::Value;
 
 impl SupabaseClient {
@@ -168,25 +247,95 @@ impl SupabaseClient {
         table_name: &str,
         query_string: &str,
     ) -> Result<Vec<Value>, String> {
-        // Build the client and the endpoint
+        let mut headers: Headers = Headers::new();
+        if let Some(timezone) = &self.timezone {
+            PreferBuilder::new().timezone(timezone).apply(&mut headers);
+        }
+
+        Ok(self
+            .execute_with_headers(table_name, query_string, headers)
+            .await?)
+    }
+
+    /// Executes a select with additional headers and processes the returned rows.
+    pub(crate) async fn execute_with_headers(
+        &self,
+        table_name: &str,
+        query_string: &str,
+        extra_headers: Headers,
+    ) -> Result<Vec<Value>, RestError> {
+        let response: Response = self
+            .send_select(table_name, query_string, extra_headers)
+            .await?;
+
+        // process the response
+        let records: Result<Vec<Value>, RestError> = response_rows(response).await;
+
+        #[cfg(feature = "metrics")]
+        if let Ok(rows) = &records {
+            crate::metrics::record_rows_returned(table_name, rows.len());
+        }
+
+        records
+    }
+
+    /// Sends a select request with additional headers, like `Prefer`, on top of the defaults.
+    ///
+    /// # Arguments
+    /// * `table_name` - A string slice that holds the name of the table to be queried.
+    /// * `query_string` - A string slice that holds the query parameters.
+    /// * `extra_headers` - Headers added to, or overriding, the default headers.
+    ///
+    /// # Returns
+    /// The raw `Response`, or a `RestError` when the request could not be sent.
+    pub(crate) async fn send_select(
+        &self,
+        table_name: &str,
+        query_string: &str,
+        extra_headers: Headers,
+    ) -> Result<Response, RestError> {
+        self.send_select_with(Method::GET, table_name, query_string, extra_headers)
+            .await
+    }
+
+    /// The URL a select of `table_name` with `query_string` is sent to.
+    pub(crate) fn select_endpoint(&self, table_name: &str, query_string: &str) -> String {
         let endpoint: String = format!("{}/rest/v1/{}?{}", self.url, table_name, query_string);
 
-        #[cfg(feature = "nightly")]
-        println!("\x1b[33mEndpoint: {}\x1b[0m", endpoint);
+        if endpoint.ends_with("?count=exact") {
+            endpoint.replace("?count=exact", "")
+        } else {
+            endpoint
+        }
+    }
+
+    /// Sends a select request with the given method, `HEAD` to only receive the headers.
+    pub(crate) async fn send_select_with(
+        &self,
+        method: Method,
+        table_name: &str,
+        query_string: &str,
+        extra_headers: Headers,
+    ) -> Result<Response, RestError> {
+        // Build the client and the endpoint
+        let endpoint: String = self.select_endpoint(table_name, query_string);
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(endpoint = %endpoint, "select endpoint");
 
         #[cfg(feature = "nightly")]
         use crate::nightly::print_nightly_warning;
         #[cfg(feature = "nightly")]
         print_nightly_warning();
 
-        let endpoint: String = if endpoint.ends_with("?count=exact") {
-            endpoint.replace("?count=exact", "")
-        } else {
-            endpoint
-        };
-
         // create headers with default values
-        let headers: Headers = Headers::with_defaults(&self.api_key, &self.api_key);
+        let mut headers: Headers = Headers::with_defaults(&self.api_key, &self.api_key);
+        if let Some(schema) = &self.schema {
+            headers.insert(HeadersTypes::AcceptProfile.as_str(), schema);
+        }
+        for (key, value) in extra_headers.get_headers() {
+            headers.insert(&key, &value);
+        }
 
         // convert headers to HeaderMap
         let mut header_map: HeaderMap = HeaderMap::new();
@@ -198,12 +347,12 @@ impl SupabaseClient {
         }
 
         // send the request
-        let response: Response = match self.client.get(&endpoint).headers(header_map).send().await {
-            Ok(response) => response,
-            Err(error) => return Err(error.to_string()),
-        };
-
-        // process the response
-        handle_response(response).await
+        self.dispatch(
+            table_name,
+            "select",
+            self.client.request(method, &endpoint).headers(header_map),
+        )
+        .await
+        .map_err(RestError::from)
     }
 }
@@ -0,0 +1,76 @@
+//! ## Column statistics
+//!
+//! Computes the minimum, maximum, and (null) counts of a column in a single request, using
+//! PostgREST aggregate functions. Aggregates are disabled by default, enable them with
+//! `alter role authenticator set pgrst.db_aggregates_enabled = 'true';` and `notify pgrst, 'reload config';`.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let stats: ColumnStats = client.column_stats("orders", "created_at").await?;
+//! println!("{} orders between {} and {}", stats.count, stats.min, stats.max);
+//! ```
+
+use crate::errors::RestError;
+use crate::SupabaseClient;
+
+use serde_json::Value;
+
+/// Statistics of a single column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// The smallest value, `Value::Null` when the column has no values.
+    pub min: Value,
+    /// The largest value, `Value::Null` when the column has no values.
+    pub max: Value,
+    /// The number of rows with a value in the column.
+    pub count: u64,
+    /// The number of rows where the column is `NULL`.
+    pub null_count: u64,
+}
+
+impl SupabaseClient {
+    /// Computes the statistics of `column` over every row of `table`.
+    ///
+    /// # Arguments
+    /// * `table_name` - The table to compute the statistics of.
+    /// * `column` - The column to compute the statistics of.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ColumnStats`, or a `RestError`.
+    pub async fn column_stats(
+        &self,
+        table_name: &str,
+        column: &str,
+    ) -> Result<ColumnStats, RestError> {
+        let query_string: String = column_stats_query(column);
+        let rows: Vec<Value> = self.execute(table_name, &query_string).await?;
+
+        let row: &Value = rows
+            .first()
+            .ok_or("Aggregate query returned no rows, are aggregates enabled?")?;
+        Ok(parse_column_stats(row)?)
+    }
+}
+
+/// Builds the aggregate select of [`SupabaseClient::column_stats`].
+pub fn column_stats_query(column: &str) -> String {
+    format!(
+        "select=min:{column}.min(),max:{column}.max(),count:{column}.count(),rows:count()",
+        column = column
+    )
+}
+
+/// Parses the row returned by the aggregate select.
+pub fn parse_column_stats(row: &Value) -> Result<ColumnStats, String> {
+    let count: u64 = row["count"]
+        .as_u64()
+        .ok_or("Aggregate row has no `count`")?;
+    let rows: u64 = row["rows"].as_u64().ok_or("Aggregate row has no `rows`")?;
+
+    Ok(ColumnStats {
+        min: row["min"].clone(),
+        max: row["max"].clone(),
+        count,
+        null_count: rows.saturating_sub(count),
+    })
+}
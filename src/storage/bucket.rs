@@ -0,0 +1,162 @@
+//! ## Buckets
+//!
+//! [`SupabaseClient::storage`] returns a [`StorageClient`], whose buckets are opened with
+//! [`StorageClient::from`]. A [`StorageBucket`] works on the objects of a bucket by their path.
+//!
+//! ```rust,ignore
+//! let avatars = client.storage().from("avatars");
+//!
+//! // https://project.supabase.co/storage/v1/object/public/avatars/users/jane.png
+//! let url: String = avatars.get_public_url("users/jane.png");
+//! ```
+//!
+//! Public URLs only serve objects of public buckets, they are built without a request.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::request::Headers;
+use crate::storage::retry::StorageRetry;
+use crate::storage::transform::TransformOptions;
+use crate::SupabaseClient;
+
+use reqwest::{Method, RequestBuilder, Response, Url};
+
+/// A client for Supabase Storage, created by [`SupabaseClient::storage`].
+#[derive(Debug, Clone)]
+pub struct StorageClient {
+    client: SupabaseClient,
+}
+
+/// A bucket of Supabase Storage, created by [`StorageClient::from`].
+#[derive(Debug, Clone)]
+pub struct StorageBucket {
+    pub(crate) client: SupabaseClient,
+    pub(crate) bucket_name: String,
+    pub(crate) retry: StorageRetry,
+}
+
+impl SupabaseClient {
+    /// Returns a client for Supabase Storage.
+    pub fn storage(&self) -> StorageClient {
+        StorageClient {
+            client: self.clone(),
+        }
+    }
+}
+
+impl StorageClient {
+    /// Opens the bucket `bucket_name`.
+    pub fn from(&self, bucket_name: &str) -> StorageBucket {
+        StorageBucket {
+            client: self.client.clone(),
+            bucket_name: bucket_name.to_string(),
+            retry: StorageRetry::default(),
+        }
+    }
+}
+
+impl StorageBucket {
+    /// The name of the bucket.
+    pub fn name(&self) -> &str {
+        &self.bucket_name
+    }
+
+    /// Returns the URL of an object of a public bucket, ready for `<img>` tags and CDNs.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the object in the bucket, e.g. `users/jane.png`.
+    pub fn get_public_url(&self, path: &str) -> String {
+        self.url(&["object", "public"], path, &[])
+    }
+
+    /// Returns the URL of an image of a public bucket, resized and converted by `transform`.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the image in the bucket, e.g. `users/jane.png`.
+    /// * `transform` - The transformations, see [`transform`](crate::storage::transform).
+    pub fn get_public_url_transformed(&self, path: &str, transform: &TransformOptions) -> String {
+        self.url(
+            &["render", "image", "public"],
+            path,
+            &transform.query_params(),
+        )
+    }
+
+    /// Starts a request authenticated with the key of the client, or the session of its user.
+    pub(crate) fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let key: &str = &self.client.api_key;
+        let mut request: RequestBuilder = self.client.client.request(method, url);
+        for (name, value) in Headers::with_defaults(key, key).get_headers() {
+            if name != "Content-Type" {
+                request = request.header(name, value);
+            }
+        }
+        request
+    }
+
+    /// Sends a request of `operation`, retrying transient failures of idempotent requests, see
+    /// [`StorageRetry`].
+    ///
+    /// # Returns
+    /// The successful response, or the error matching the status, see [`ErrorTypes::from_response`].
+    pub(crate) async fn send(&self, request: RequestBuilder, operation: &str) -> Result<Response> {
+        if !is_idempotent(&request) {
+            return self.send_once(request, operation).await;
+        }
+        self.retrying(|| async {
+            let request: RequestBuilder = request.try_clone().ok_or_else(|| {
+                ErrorTypes::InvalidParameters("the request can't be sent again".to_string())
+            })?;
+            self.send_once(request, operation).await
+        })
+        .await
+    }
+
+    /// Sends a request of `operation` once.
+    pub(crate) async fn send_once(
+        &self,
+        request: RequestBuilder,
+        operation: &str,
+    ) -> Result<Response> {
+        let response: Response = self.client.dispatch("", operation, request).await?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        Err(ErrorTypes::from_response(response).await)
+    }
+
+    /// Builds a URL under `/storage/v1/{route}/{bucket}/{path}`, percent-encoding every segment.
+    pub(crate) fn url(&self, route: &[&str], path: &str, query: &[(&str, String)]) -> String {
+        let base: String = format!("{}/storage/v1", self.client.url.trim_end_matches('/'));
+        let Ok(mut url) = Url::parse(&base) else {
+            return format!("{}/{}/{}/{}", base, route.join("/"), self.bucket_name, path);
+        };
+
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments
+                .extend(route)
+                .push(&self.bucket_name)
+                .extend(path.trim_start_matches('/').split('/'));
+        }
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        url.to_string()
+    }
+}
+
+/// Whether sending `request` twice has the effect of sending it once: reads, and uploads
+/// overwriting the object (`x-upsert`). Requests with a streamed body can't be sent twice.
+fn is_idempotent(request: &RequestBuilder) -> bool {
+    let Some(Ok(request)) = request.try_clone().map(RequestBuilder::build) else {
+        return false;
+    };
+    match *request.method() {
+        Method::GET | Method::HEAD => true,
+        Method::PUT => request
+            .headers()
+            .get("x-upsert")
+            .is_some_and(|upsert| upsert.as_bytes() == b"true"),
+        _ => false,
+    }
+}
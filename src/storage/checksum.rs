@@ -0,0 +1,136 @@
+//! ## Checksums
+//!
+//! Downloads are verified against a digest before they are returned, and uploads against the
+//! object Storage kept, failing with [`ErrorTypes::ChecksumMismatch`] when the bytes differ. A
+//! mismatch is an [`ErrorKind::Integrity`](crate::errors::ErrorKind::Integrity) failure and isn't
+//! retried, only the failures of the request itself are, see [`retry`](crate::storage::retry).
+//!
+//! ```rust,ignore
+//! use supabase_rs::storage::checksum::Checksum;
+//!
+//! let videos = client.storage().from("videos");
+//!
+//! // against the ETag Storage sends, the MD5 of objects uploaded in one request
+//! let bytes: Vec<u8> = videos.download_verified("2024/intro.mp4", Checksum::ETag).await?;
+//!
+//! // against a digest known beforehand
+//! let bytes: Vec<u8> = videos
+//!     .download_verified("2024/intro.mp4", Checksum::Sha256(digest.to_string()))
+//!     .await?;
+//!
+//! // after an upload, or with `ResumableUploadOptions::verify_checksum`
+//! videos.verify_upload("2024/intro.mp4", &bytes).await?;
+//! ```
+//!
+//! The ETag of an object uploaded in parts, e.g. a resumable or S3 multipart upload, isn't the MD5
+//! of its bytes. [`Checksum::ETag`] fails on it, and [`StorageBucket::verify_upload`] compares the
+//! size of such an object, and a `sha256` hex digest in its metadata when it was given one.
+
+use crate::errors::{ErrorTypes, Result};
+use crate::storage::bucket::StorageBucket;
+use crate::storage::object::ObjectInfo;
+
+use md5::Md5;
+use reqwest::header::ETAG;
+use reqwest::{Method, Response};
+use sha2::{Digest, Sha256};
+
+/// The digest a download is verified against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    /// The ETag of the response, the MD5 of objects uploaded in one request.
+    ETag,
+    /// An MD5 hex digest.
+    Md5(String),
+    /// A SHA-256 hex digest.
+    Sha256(String),
+}
+
+impl Checksum {
+    /// Checks `data` against the digest, with the `etag` of the response for [`Checksum::ETag`].
+    pub(crate) fn verify(&self, data: &[u8], etag: Option<&str>) -> Result<()> {
+        let (expected, actual): (String, String) = match self {
+            Checksum::ETag => {
+                let etag: &str = etag.ok_or_else(|| {
+                    ErrorTypes::UnexpectedResponse("the response has no ETag".to_string())
+                })?;
+                let expected: &str = etag_md5(etag).ok_or_else(|| {
+                    ErrorTypes::UnexpectedResponse(format!(
+                        "the ETag {} isn't an MD5 digest, the object was uploaded in parts",
+                        etag
+                    ))
+                })?;
+                (expected.to_string(), md5_hex(data))
+            }
+            Checksum::Md5(expected) => (expected.clone(), md5_hex(data)),
+            Checksum::Sha256(expected) => (expected.clone(), sha256_hex(data)),
+        };
+        matches(expected, actual)
+    }
+}
+
+impl StorageBucket {
+    /// Downloads the object at `path` and verifies it against `checksum`.
+    ///
+    /// # Returns
+    /// The bytes of the object, or [`ErrorTypes::ChecksumMismatch`] when they differ.
+    pub async fn download_verified(&self, path: &str, checksum: Checksum) -> Result<Vec<u8>> {
+        let url: String = self.url(&["object"], path, &[]);
+        let response: Response = self
+            .send(self.request(Method::GET, &url), "download")
+            .await?;
+        let etag: Option<String> = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(String::from);
+        let bytes: Vec<u8> = response.bytes().await?.to_vec();
+        checksum.verify(&bytes, etag.as_deref())?;
+        Ok(bytes)
+    }
+
+    /// Verifies the object at `path` against `data`, the bytes uploaded to it.
+    ///
+    /// The size is always compared. The MD5 is compared with the ETag of objects uploaded in one
+    /// request, and the SHA-256 with a `sha256` entry of the metadata when there is one.
+    ///
+    /// # Returns
+    /// The metadata of the object, or [`ErrorTypes::ChecksumMismatch`] when it differs.
+    pub async fn verify_upload(&self, path: &str, data: &[u8]) -> Result<ObjectInfo> {
+        let info: ObjectInfo = self.info(path).await?;
+
+        if let Some(size) = info.size {
+            matches(format!("{} bytes", data.len()), format!("{} bytes", size))?;
+        }
+        if let Some(expected) = info.etag.as_deref().and_then(etag_md5) {
+            matches(expected.to_string(), md5_hex(data))?;
+        }
+        if let Some(expected) = info.metadata.get("sha256").and_then(|value| value.as_str()) {
+            matches(expected.to_string(), sha256_hex(data))?;
+        }
+        Ok(info)
+    }
+}
+
+/// The MD5 hex digest in an ETag, e.g. `"9e107d9d372bb6826bd81d3542a419d6"`, and `None` for the
+/// ETags of objects uploaded in parts, e.g. `"…-3"`.
+fn etag_md5(etag: &str) -> Option<&str> {
+    let digest: &str = etag.trim().trim_start_matches("W/").trim_matches('"');
+    let is_md5: bool = digest.len() == 32 && digest.bytes().all(|byte| byte.is_ascii_hexdigit());
+    is_md5.then_some(digest)
+}
+
+fn md5_hex(data: &[u8]) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn matches(expected: String, actual: String) -> Result<()> {
+    if expected.eq_ignore_ascii_case(&actual) {
+        return Ok(());
+    }
+    Err(ErrorTypes::ChecksumMismatch { expected, actual })
+}
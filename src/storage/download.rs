@@ -42,7 +42,22 @@ impl SupabaseStorage {
             self.supabase_url, self.bucket_name, self.filename
         );
         let client: Client = Client::new();
-        let response: Response = client.get(&url).send().await?;
+
+        #[cfg(feature = "metrics")]
+        let started: std::time::Instant = std::time::Instant::now();
+        let response: Result<Response, ReqwestError> = client.get(&url).send().await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request(
+            "",
+            "download",
+            response
+                .as_ref()
+                .ok()
+                .map(|response| response.status().as_u16()),
+            started.elapsed(),
+        );
+
+        let response: Response = response?;
         let bytes = response.bytes().await?;
         Ok(bytes.to_vec())
     }
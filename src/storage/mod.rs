@@ -4,12 +4,26 @@
 //!
 //! - Downloading files from Supabase Storage
 //! - Saving files to the local system
+//! - Public URLs of objects, with image transformations
+//! - Resumable uploads of large files
+//! - Object metadata, and checking whether an object exists
+//! - Byte range downloads, and saving files that continue interrupted downloads
+//! - Multipart uploads over the S3 protocol (`storage_s3` feature)
+//! - Retrying transient failures, and verifying downloads and uploads against checksums
 //!
 //! # Table of Contents
 //!
 //! - [SupabaseStorage](#supabasestorage)
 //! - [download](./download/index.html)
 //! - [save](./download/index.html)
+//! - [bucket](./bucket/index.html)
+//! - [checksum](./checksum/index.html)
+//! - [object](./object/index.html)
+//! - [range](./range/index.html)
+//! - [resumable](./resumable/index.html)
+//! - [retry](./retry/index.html)
+//! - [s3](./s3/index.html)
+//! - [transform](./transform/index.html)
 //!
 //! # Usage
 //! First make sure you have initialized the Supabase Client
@@ -43,7 +57,22 @@
 //!
 #![cfg(feature = "storage")]
 
+pub mod bucket;
+pub mod checksum;
 pub mod download;
+pub mod object;
+pub mod range;
+pub mod resumable;
+pub mod retry;
+pub mod s3;
+pub mod transform;
+
+pub use bucket::{StorageBucket, StorageClient};
+pub use checksum::Checksum;
+pub use object::ObjectInfo;
+pub use resumable::{ResumableUpload, ResumableUploadOptions, UploadProgress};
+pub use retry::StorageRetry;
+pub use transform::{ImageFormat, ResizeMode, TransformOptions};
 
 /// A struct for interacting with Supabase Storage.
 #[derive(Debug, Clone)]
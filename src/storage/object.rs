@@ -0,0 +1,74 @@
+//! ## Object info
+//!
+//! The metadata of the objects of a bucket, read without downloading them.
+//!
+//! ```rust,ignore
+//! let avatars = client.storage().from("avatars");
+//!
+//! if avatars.exists("users/jane.png").await? {
+//!     let info = avatars.info("users/jane.png").await?;
+//!     println!("{:?} bytes of {:?}", info.size, info.content_type);
+//! }
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::storage::bucket::StorageBucket;
+
+use reqwest::{Method, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The metadata of an object, returned by [`StorageBucket::info`].
+///
+/// Timestamps are kept as the RFC 3339 strings Storage sends.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectInfo {
+    pub id: String,
+    /// The path of the object in the bucket.
+    pub name: String,
+    pub bucket_id: String,
+    /// The version of the object, changed by every overwrite.
+    pub version: Option<String>,
+    /// The size in bytes.
+    pub size: Option<u64>,
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub created_at: Option<String>,
+    /// The custom metadata given when the object was uploaded.
+    pub metadata: Value,
+}
+
+impl StorageBucket {
+    /// Reads the metadata of the object at `path`.
+    ///
+    /// # Returns
+    /// The metadata, or the error matching the status, e.g. a `400` or `404` when there is no object
+    /// at the path.
+    pub async fn info(&self, path: &str) -> Result<ObjectInfo> {
+        let url: String = self.url(&["object", "info"], path, &[]);
+        let response: Response = self.send(self.request(Method::GET, &url), "info").await?;
+        response
+            .json()
+            .await
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string()))
+    }
+
+    /// Whether there is an object at `path`, probed with a `HEAD` request.
+    ///
+    /// # Returns
+    /// `Ok(false)` when Storage answers with a `400` or `404`, as it does for missing objects, and
+    /// any other error as it is, e.g. a `403` for objects the user may not read.
+    pub async fn exists(&self, path: &str) -> Result<bool> {
+        let url: String = self.url(&["object"], path, &[]);
+        match self.send(self.request(Method::HEAD, &url), "exists").await {
+            Ok(_) => Ok(true),
+            Err(ErrorTypes::ApiError {
+                status: 400 | 404, ..
+            }) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! ## Range downloads
+//!
+//! Parts of an object are downloaded with HTTP `Range` requests, e.g. to serve a byte range of a
+//! video to a media player, or to continue a download that was interrupted.
+//!
+//! ```rust,ignore
+//! let videos = client.storage().from("videos");
+//!
+//! // the first kilobyte
+//! let head: Vec<u8> = videos.download_range("2024/holiday.mp4", 0, Some(1023)).await?;
+//!
+//! // continues from the bytes already in the file, if any
+//! let size: u64 = videos.save("2024/holiday.mp4", "holiday.mp4").await?;
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::storage::bucket::StorageBucket;
+
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, RANGE};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+impl StorageBucket {
+    /// Downloads the bytes `start..=end` of the object at `path`, or from `start` to the end of the
+    /// object when `end` is `None`.
+    ///
+    /// # Returns
+    /// The bytes of the range, fewer when the object ends before `end`, or the error matching the
+    /// status, e.g. a `416` when `start` lies past the end of the object.
+    pub async fn download_range(
+        &self,
+        path: &str,
+        start: u64,
+        end: Option<u64>,
+    ) -> Result<Vec<u8>> {
+        if end.is_some_and(|end| end < start) {
+            return Err(ErrorTypes::InvalidParameters(format!(
+                "the range ends before its start {}",
+                start
+            )));
+        }
+        let response: Response = self.send_range(path, start, end, None).await?;
+        let partial: bool = response.status() == StatusCode::PARTIAL_CONTENT;
+        let bytes: Vec<u8> = response.bytes().await?.to_vec();
+        if partial {
+            return Ok(bytes);
+        }
+
+        // the server ignored the range and sent the whole object
+        let length: usize = bytes.len();
+        let start: usize = usize::try_from(start).unwrap_or(usize::MAX).min(length);
+        let end: usize = end
+            .and_then(|end| usize::try_from(end).ok())
+            .map_or(length, |end| end.saturating_add(1).min(length));
+        Ok(bytes[start..end].to_vec())
+    }
+
+    /// Downloads the object at `path` into the file `file_path`, continuing from the bytes already
+    /// in the file.
+    ///
+    /// The bytes are written as they arrive, so an interrupted call leaves the start of the object
+    /// in the file, and its ETag in `{file_path}.etag` until the object is complete. A later call
+    /// only asks for the rest when the object still has that ETag (`If-Range`), otherwise it
+    /// rewrites the file. A file without an ETag next to it is downloaded from the start.
+    ///
+    /// # Returns
+    /// The size of the file once the object is complete.
+    pub async fn save(&self, path: &str, file_path: &str) -> Result<u64> {
+        let existing: u64 = std::fs::metadata(file_path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        let etag: Option<String> = std::fs::read_to_string(etag_path(file_path))
+            .ok()
+            .map(|etag| etag.trim().to_string())
+            .filter(|etag| !etag.is_empty());
+        let Some(etag) = etag.filter(|_| existing > 0) else {
+            let response: Response = self.send_range(path, 0, None, None).await?;
+            return write_response(response, file_path, 0).await;
+        };
+
+        match self.send_range(path, existing, None, Some(&etag)).await {
+            // the object ends where the file ends, or the file isn't its start
+            Err(ErrorTypes::ApiError { status: 416, .. }) => {
+                if self.info(path).await?.size == Some(existing) {
+                    remove_etag(file_path)?;
+                    return Ok(existing);
+                }
+                let response: Response = self.send_range(path, 0, None, None).await?;
+                write_response(response, file_path, 0).await
+            }
+            Err(error) => Err(error),
+            // the object changed since, or the server doesn't support ranges
+            Ok(response) if response.status() != StatusCode::PARTIAL_CONTENT => {
+                write_response(response, file_path, 0).await
+            }
+            Ok(response) => write_response(response, file_path, existing).await,
+        }
+    }
+
+    /// Requests a range of the object at `path` from the authenticated endpoint, which serves the
+    /// objects of private buckets too.
+    ///
+    /// With `if_range`, the server sends the whole object instead when its ETag differs.
+    async fn send_range(
+        &self,
+        path: &str,
+        start: u64,
+        end: Option<u64>,
+        if_range: Option<&str>,
+    ) -> Result<Response> {
+        let url: String = self.url(&["object"], path, &[]);
+        let range: String = match end {
+            Some(end) => format!("bytes={}-{}", start, end),
+            None => format!("bytes={}-", start),
+        };
+        let mut request: RequestBuilder = self.request(Method::GET, &url).header(RANGE, range);
+        if let Some(etag) = if_range {
+            request = request.header(IF_RANGE, etag);
+        }
+        let response: Response = self.send(request, "download").await?;
+
+        let unexpected_range: bool = response.status() == StatusCode::PARTIAL_CONTENT
+            && response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|range| range.to_str().ok())
+                .and_then(range_start)
+                .is_some_and(|served| served != start);
+        if unexpected_range {
+            return Err(ErrorTypes::UnexpectedResponse(format!(
+                "the server sent a range not starting at {}",
+                start
+            )));
+        }
+        Ok(response)
+    }
+}
+
+/// Reads the first byte of a `Content-Range` header, e.g. `100` of `bytes 100-199/1000`.
+fn range_start(content_range: &str) -> Option<u64> {
+    content_range
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Writes the body of `response` into `file_path` as it arrives, after the first `start` bytes of
+/// the file.
+///
+/// # Returns
+/// The size of the file once the body is complete.
+async fn write_response(mut response: Response, file_path: &str, start: u64) -> Result<u64> {
+    // a weak ETag can't resume a range, the download then starts over
+    match response
+        .headers()
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+    {
+        Some(etag) => std::fs::write(etag_path(file_path), etag)
+            .map_err(|error| file_error(&etag_path(file_path), error))?,
+        None => remove_etag(file_path)?,
+    }
+
+    let file: std::io::Result<File> = match start {
+        0 => File::create(file_path),
+        _ => OpenOptions::new().append(true).open(file_path),
+    };
+    let mut file: File = file.map_err(|error| file_error(file_path, error))?;
+    let mut size: u64 = start;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)
+            .map_err(|error| file_error(file_path, error))?;
+        size += chunk.len() as u64;
+    }
+
+    remove_etag(file_path)?;
+    Ok(size)
+}
+
+/// The file keeping the ETag of an unfinished download into `file_path`.
+fn etag_path(file_path: &str) -> String {
+    format!("{}.etag", file_path)
+}
+
+fn remove_etag(file_path: &str) -> Result<()> {
+    match std::fs::remove_file(etag_path(file_path)) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            Err(file_error(&etag_path(file_path), error))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn file_error(file_path: &str, error: std::io::Error) -> ErrorTypes {
+    ErrorTypes::InvalidParameters(format!("writing {} failed: {}", file_path, error))
+}
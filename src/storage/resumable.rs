@@ -0,0 +1,420 @@
+//! ## Resumable uploads
+//!
+//! Large files are uploaded with the [TUS protocol](https://tus.io/protocols/resumable-upload) on
+//! `/storage/v1/upload/resumable`, in chunks of 6 MB unless set with
+//! [`ResumableUploadOptions::chunk_size`]. An interrupted upload continues from the last chunk the
+//! server received, even from another process when the URL of the upload was kept.
+//!
+//! ```rust,ignore
+//! use std::fs::File;
+//! use supabase_rs::storage::resumable::ResumableUploadOptions;
+//!
+//! let videos = client.storage().from("videos");
+//! let file = File::open("holiday.mp4")?;
+//! let size: u64 = file.metadata()?.len();
+//!
+//! let mut upload = videos
+//!     .create_resumable_upload(
+//!         "2024/holiday.mp4",
+//!         size,
+//!         ResumableUploadOptions::new()
+//!             .content_type("video/mp4")
+//!             .on_progress(|progress| println!("{} of {} bytes", progress.bytes_uploaded, progress.total_bytes)),
+//!     )
+//!     .await?;
+//! save_somewhere(upload.url());
+//!
+//! if let Err(error) = upload.upload_from(file).await {
+//!     // later, or after a restart with `ResumableUpload::resume`
+//!     upload.sync_offset().await?;
+//!     upload.upload_from(File::open("holiday.mp4")?).await?;
+//! }
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::request::Headers;
+use crate::storage::bucket::StorageBucket;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::header::LOCATION;
+use reqwest::{Method, RequestBuilder, Response, Url};
+use std::fmt::{Debug, Formatter};
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+/// The TUS version spoken by Supabase Storage.
+const TUS_VERSION: &str = "1.0.0";
+
+/// The chunk size Supabase Storage requires, except for the last chunk. Chunks are a multiple of it.
+pub const CHUNK_SIZE: usize = 6 * 1024 * 1024;
+
+/// The progress of a resumable upload, after a chunk the server received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// The number of bytes the server received.
+    pub bytes_uploaded: u64,
+    /// The size of the object in bytes.
+    pub total_bytes: u64,
+}
+
+/// Receives the progress of an upload, see [`ResumableUploadOptions::on_progress`].
+pub type UploadProgressCallback = Arc<dyn Fn(&UploadProgress) + Send + Sync>;
+
+/// The options of a resumable upload.
+#[derive(Clone)]
+pub struct ResumableUploadOptions {
+    /// The `Content-Type` the object is served with, guessed by Supabase when unset.
+    pub content_type: Option<String>,
+    /// The `Cache-Control` the object is served with, e.g. `max-age=3600`.
+    pub cache_control: Option<String>,
+    /// Whether an existing object at the path is overwritten.
+    pub upsert: bool,
+    /// A user access token to upload with instead of the client key, for buckets protected by RLS.
+    pub access_token: Option<String>,
+    /// Whether [`StorageBucket::upload_resumable`] verifies the object once it is uploaded, see
+    /// [`StorageBucket::verify_upload`].
+    pub verify_checksum: bool,
+    /// The number of bytes sent per request, [`CHUNK_SIZE`] by default.
+    pub chunk_size: usize,
+    on_progress: Option<UploadProgressCallback>,
+}
+
+impl Default for ResumableUploadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ResumableUploadOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumableUploadOptions")
+            .field("content_type", &self.content_type)
+            .field("cache_control", &self.cache_control)
+            .field("upsert", &self.upsert)
+            .field("access_token", &self.access_token.is_some())
+            .field("verify_checksum", &self.verify_checksum)
+            .field("chunk_size", &self.chunk_size)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl ResumableUploadOptions {
+    pub fn new() -> Self {
+        ResumableUploadOptions {
+            content_type: None,
+            cache_control: None,
+            upsert: false,
+            access_token: None,
+            verify_checksum: false,
+            chunk_size: CHUNK_SIZE,
+            on_progress: None,
+        }
+    }
+
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.content_type = Some(content_type.to_string());
+        self
+    }
+
+    pub fn cache_control(mut self, cache_control: &str) -> Self {
+        self.cache_control = Some(cache_control.to_string());
+        self
+    }
+
+    pub fn upsert(mut self, upsert: bool) -> Self {
+        self.upsert = upsert;
+        self
+    }
+
+    pub fn access_token(mut self, access_token: &str) -> Self {
+        self.access_token = Some(access_token.to_string());
+        self
+    }
+
+    /// Sets whether the object is compared with the uploaded bytes once the upload completed,
+    /// failing with [`ErrorTypes::ChecksumMismatch`] when it differs.
+    pub fn verify_checksum(mut self, verify_checksum: bool) -> Self {
+        self.verify_checksum = verify_checksum;
+        self
+    }
+
+    /// Sets the number of bytes sent per request, rounded up to a multiple of [`CHUNK_SIZE`], the
+    /// size Storage requires of every chunk but the last.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1).div_ceil(CHUNK_SIZE) * CHUNK_SIZE;
+        self
+    }
+
+    /// Calls `on_progress` after every chunk the server received.
+    pub fn on_progress(
+        mut self,
+        on_progress: impl Fn(&UploadProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+}
+
+/// An upload created on the server, see [`StorageBucket::create_resumable_upload`].
+#[derive(Clone)]
+pub struct ResumableUpload {
+    bucket: StorageBucket,
+    url: String,
+    size: u64,
+    offset: u64,
+    access_token: Option<String>,
+    chunk_size: usize,
+    on_progress: Option<UploadProgressCallback>,
+}
+
+impl Debug for ResumableUpload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumableUpload")
+            .field("bucket", &self.bucket.bucket_name)
+            .field("url", &self.url)
+            .field("size", &self.size)
+            .field("offset", &self.offset)
+            .field("chunk_size", &self.chunk_size)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl StorageBucket {
+    /// Creates a resumable upload of `size` bytes to `path`, nothing is uploaded yet.
+    ///
+    /// # Returns
+    /// The upload, or the error matching the status when the server refuses it, e.g. a `409` when
+    /// the object exists and `upsert` is off.
+    pub async fn create_resumable_upload(
+        &self,
+        path: &str,
+        size: u64,
+        options: ResumableUploadOptions,
+    ) -> Result<ResumableUpload> {
+        let endpoint: String = self.resumable_endpoint();
+        let mut metadata: Vec<(&str, &str)> =
+            vec![("bucketName", &self.bucket_name), ("objectName", path)];
+        if let Some(content_type) = &options.content_type {
+            metadata.push(("contentType", content_type));
+        }
+        if let Some(cache_control) = &options.cache_control {
+            metadata.push(("cacheControl", cache_control));
+        }
+
+        let request: RequestBuilder = self
+            .tus_request(Method::POST, &endpoint, options.access_token.as_deref())
+            .header("Upload-Length", size.to_string())
+            .header("Upload-Metadata", upload_metadata(&metadata))
+            .header("x-upsert", options.upsert.to_string());
+        let response: Response = self.send_tus(request).await?;
+
+        let location: &str = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .ok_or_else(|| {
+                ErrorTypes::UnexpectedResponse("upload created without a Location".to_string())
+            })?;
+        let url: String = Url::parse(&endpoint)
+            .and_then(|endpoint| endpoint.join(location))
+            .map(String::from)
+            .map_err(|error| ErrorTypes::UnexpectedResponse(error.to_string()))?;
+
+        Ok(ResumableUpload {
+            bucket: self.clone(),
+            url,
+            size,
+            offset: 0,
+            access_token: options.access_token,
+            chunk_size: options.chunk_size,
+            on_progress: options.on_progress,
+        })
+    }
+
+    /// Creates a resumable upload of `data` to `path` and uploads it, verifying the object when
+    /// [`ResumableUploadOptions::verify_checksum`] is set.
+    pub async fn upload_resumable(
+        &self,
+        path: &str,
+        data: &[u8],
+        options: ResumableUploadOptions,
+    ) -> Result<ResumableUpload> {
+        let verify_checksum: bool = options.verify_checksum;
+        let mut upload: ResumableUpload = self
+            .create_resumable_upload(path, data.len() as u64, options)
+            .await?;
+        upload.upload_from(std::io::Cursor::new(data)).await?;
+        if verify_checksum {
+            self.verify_upload(path, data).await?;
+        }
+        Ok(upload)
+    }
+
+    fn resumable_endpoint(&self) -> String {
+        format!(
+            "{}/storage/v1/upload/resumable",
+            self.client.url.trim_end_matches('/')
+        )
+    }
+
+    fn tus_request(&self, method: Method, url: &str, access_token: Option<&str>) -> RequestBuilder {
+        let token: &str = access_token.unwrap_or(&self.client.api_key);
+        let mut request: RequestBuilder = self
+            .client
+            .client
+            .request(method, url)
+            .header("Tus-Resumable", TUS_VERSION);
+        for (key, value) in Headers::with_defaults(&self.client.api_key, token).get_headers() {
+            if key != "Content-Type" {
+                request = request.header(key, value);
+            }
+        }
+        request
+    }
+
+    async fn send_tus(&self, request: RequestBuilder) -> Result<Response> {
+        let response: Response = self.client.dispatch("", "upload", request).await?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        Err(ErrorTypes::from_response(response).await)
+    }
+}
+
+impl ResumableUpload {
+    /// Continues an upload created earlier, e.g. by another process, from the offset the server
+    /// received. The options must match the ones it was created with.
+    pub async fn resume(
+        bucket: &StorageBucket,
+        url: &str,
+        size: u64,
+        options: ResumableUploadOptions,
+    ) -> Result<Self> {
+        let mut upload: ResumableUpload = ResumableUpload {
+            bucket: bucket.clone(),
+            url: url.to_string(),
+            size,
+            offset: 0,
+            access_token: options.access_token,
+            chunk_size: options.chunk_size,
+            on_progress: options.on_progress,
+        };
+        upload.sync_offset().await?;
+        Ok(upload)
+    }
+
+    /// The URL of the upload, keep it to resume the upload later.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The size of the object in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The number of bytes the server received.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Whether every byte was uploaded.
+    pub fn is_complete(&self) -> bool {
+        self.offset >= self.size
+    }
+
+    /// Asks the server how many bytes it received, after a failed chunk.
+    pub async fn sync_offset(&mut self) -> Result<u64> {
+        let request: RequestBuilder =
+            self.bucket
+                .tus_request(Method::HEAD, &self.url, self.access_token.as_deref());
+        let response: Response = self.bucket.send_tus(request).await?;
+        self.offset = upload_offset(&response)?;
+        Ok(self.offset)
+    }
+
+    /// Uploads the bytes following the current offset.
+    ///
+    /// # Returns
+    /// The new offset, or the error matching the status, e.g. a `409` when the offset is stale.
+    pub async fn upload_chunk(&mut self, chunk: &[u8]) -> Result<u64> {
+        let request: RequestBuilder = self
+            .bucket
+            .tus_request(Method::PATCH, &self.url, self.access_token.as_deref())
+            .header("Upload-Offset", self.offset.to_string())
+            .header("Content-Type", "application/offset+octet-stream")
+            .body(chunk.to_vec());
+        let response: Response = self.bucket.send_tus(request).await?;
+        self.offset = upload_offset(&response)?;
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(&UploadProgress {
+                bytes_uploaded: self.offset,
+                total_bytes: self.size,
+            });
+        }
+        Ok(self.offset)
+    }
+
+    /// Uploads the rest of the object from `reader`, which holds the whole object and is read from
+    /// the current offset.
+    ///
+    /// # Returns
+    /// `Ok(())` once every byte was uploaded. On failure the offset points after the last chunk the
+    /// server received, so the upload can be continued.
+    pub async fn upload_from(&mut self, mut reader: impl Read + Seek) -> Result<()> {
+        let mut chunk: Vec<u8> = vec![0; self.chunk_size];
+
+        while !self.is_complete() {
+            let remaining: usize = usize::try_from(self.size - self.offset).unwrap_or(usize::MAX);
+            let length: usize = reader
+                .seek(SeekFrom::Start(self.offset))
+                .and_then(|_| read_chunk(&mut reader, &mut chunk[..remaining.min(self.chunk_size)]))
+                .map_err(|error| {
+                    ErrorTypes::InvalidParameters(format!("reading the data failed: {}", error))
+                })?;
+            if length == 0 {
+                return Err(ErrorTypes::InvalidParameters(format!(
+                    "the data ended at {} of {} bytes",
+                    self.offset, self.size
+                )));
+            }
+            self.upload_chunk(&chunk[..length]).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes the `Upload-Metadata` header, `key base64(value)` pairs separated by commas.
+fn upload_metadata(metadata: &[(&str, &str)]) -> String {
+    metadata
+        .iter()
+        .map(|(key, value)| format!("{} {}", key, STANDARD.encode(value)))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Reads the `Upload-Offset` header of a response.
+fn upload_offset(response: &Response) -> Result<u64> {
+    response
+        .headers()
+        .get("Upload-Offset")
+        .and_then(|offset| offset.to_str().ok())
+        .and_then(|offset| offset.parse().ok())
+        .ok_or_else(|| ErrorTypes::UnexpectedResponse("missing Upload-Offset".to_string()))
+}
+
+/// Fills `buffer` from `reader`, short only when the reader ends.
+fn read_chunk(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut length: usize = 0;
+    while length < buffer.len() {
+        match reader.read(&mut buffer[length..])? {
+            0 => break,
+            read => length += read,
+        }
+    }
+    Ok(length)
+}
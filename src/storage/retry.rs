@@ -0,0 +1,101 @@
+//! ## Retries
+//!
+//! The requests of a [`StorageBucket`] failing with a transient error, see
+//! [`ErrorTypes::is_retryable`], are sent again after a backoff, up to
+//! [`StorageRetry::max_retries`] times. A `Retry-After` sent by the server is waited for instead of
+//! the backoff. Uploads keep their own recovery, a resumable upload continues from the offset the
+//! server received.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use supabase_rs::storage::retry::StorageRetry;
+//!
+//! let videos = client
+//!     .storage()
+//!     .from("videos")
+//!     .with_retry(StorageRetry::new().max_retries(5).backoff(Duration::from_secs(1)));
+//!
+//! // or fail on the first error
+//! let videos = client.storage().from("videos").with_retry(StorageRetry::none());
+//! ```
+
+use crate::errors::{ErrorTypes, Result};
+use crate::storage::bucket::StorageBucket;
+
+use std::future::Future;
+use std::time::Duration;
+
+/// How a [`StorageBucket`] retries transient failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageRetry {
+    /// How often a request is sent again after a transient failure, 3 by default.
+    pub max_retries: u32,
+    /// The wait before the first retry, doubled for every further retry, 200ms by default.
+    pub backoff: Duration,
+}
+
+impl Default for StorageRetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StorageRetry {
+    pub fn new() -> Self {
+        StorageRetry {
+            max_retries: 3,
+            backoff: Duration::from_millis(200),
+        }
+    }
+
+    /// Sends every request once.
+    pub fn none() -> Self {
+        Self::new().max_retries(0)
+    }
+
+    /// Sets how often a request is sent again after a transient failure.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the wait before the first retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// The wait before the retry after `retries` earlier ones, or the wait the server asked for.
+    fn delay(&self, retries: u32, error: &ErrorTypes) -> Duration {
+        error
+            .retry_after()
+            .unwrap_or_else(|| self.backoff.saturating_mul(2_u32.saturating_pow(retries)))
+    }
+}
+
+impl StorageBucket {
+    /// Sets how the requests of the bucket retry transient failures, see [`StorageRetry`].
+    pub fn with_retry(mut self, retry: StorageRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Runs `attempt` until it succeeds, fails with an error that isn't transient, or ran out of
+    /// retries.
+    pub(crate) async fn retrying<T, F, Fut>(&self, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retries: u32 = 0;
+        loop {
+            match attempt().await {
+                Err(error) if error.is_retryable() && retries < self.retry.max_retries => {
+                    tokio::time::sleep(self.retry.delay(retries, &error)).await;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! ## Image transformations
+//!
+//! Supabase resizes and converts images on the fly on `/storage/v1/render/image`. The
+//! [`TransformOptions`] of a URL are passed as query parameters, and the transformed image is cached
+//! by the Supabase CDN.
+//!
+//! ```rust,ignore
+//! use supabase_rs::storage::transform::{ResizeMode, TransformOptions};
+//!
+//! let thumbnail: String = client.storage().from("avatars").get_public_url_transformed(
+//!     "jane.png",
+//!     &TransformOptions::new().width(64).height(64).resize(ResizeMode::Cover).quality(80),
+//! );
+//! ```
+
+/// How an image is fitted into the requested width and height.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResizeMode {
+    /// Fills the size, cropping what doesn't fit. The default.
+    #[default]
+    Cover,
+    /// Fits the image into the size, keeping its aspect ratio.
+    Contain,
+    /// Stretches the image to the size, ignoring its aspect ratio.
+    Fill,
+}
+
+impl ResizeMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ResizeMode::Cover => "cover",
+            ResizeMode::Contain => "contain",
+            ResizeMode::Fill => "fill",
+        }
+    }
+}
+
+/// The format of a transformed image.
+///
+/// Without a format Supabase picks a modern format the client accepts, e.g. WebP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Keeps the format of the original image.
+    Origin,
+}
+
+impl ImageFormat {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ImageFormat::Origin => "origin",
+        }
+    }
+}
+
+/// The transformations applied to an image, nothing is changed by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransformOptions {
+    /// The width in pixels.
+    pub width: Option<u32>,
+    /// The height in pixels.
+    pub height: Option<u32>,
+    pub resize: Option<ResizeMode>,
+    /// The quality from 20 to 100, 80 when unset.
+    pub quality: Option<u8>,
+    pub format: Option<ImageFormat>,
+}
+
+impl TransformOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn resize(mut self, resize: ResizeMode) -> Self {
+        self.resize = Some(resize);
+        self
+    }
+
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// The query parameters of the transformations, in the order of the Supabase docs.
+    pub fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params: Vec<(&'static str, String)> = Vec::new();
+        if let Some(width) = self.width {
+            params.push(("width", width.to_string()));
+        }
+        if let Some(height) = self.height {
+            params.push(("height", height.to_string()));
+        }
+        if let Some(resize) = self.resize {
+            params.push(("resize", resize.as_str().to_string()));
+        }
+        if let Some(quality) = self.quality {
+            params.push(("quality", quality.to_string()));
+        }
+        if let Some(format) = self.format {
+            params.push(("format", format.as_str().to_string()));
+        }
+        params
+    }
+}
@@ -3,15 +3,22 @@
 //! This is still a wip
 //!
 
-use reqwest::Response;
-use serde_json::{json, Value};
+use reqwest::header::LOCATION;
+use reqwest::{Response, Url};
+use serde_json::{json, Map, Value};
 
 use crate::errors::{
-    api_key_missing_error, authorization_failed_error, invalid_query_error, unknown_error,
+    api_key_missing_error, authorization_failed_error, invalid_query_error, response_request_id,
+    unknown_error, ErrorTypes, RestError,
 };
 
 /// Handles the response from the Supabase API.
 pub async fn handle_response(response: Response) -> Result<Vec<Value>, String> {
+    Ok(response_rows(response).await?)
+}
+
+/// Reads the rows of a response like [`handle_response`], keeping the error typed.
+pub(crate) async fn response_rows(response: Response) -> Result<Vec<Value>, RestError> {
     if response.status().is_success() {
         let headers: &reqwest::header::HeaderMap = response.headers();
         let content_range_option: Option<&str> =
@@ -28,7 +35,7 @@ pub async fn handle_response(response: Response) -> Result<Vec<Value>, String> {
 
         let mut records: Vec<Value> = match response.json::<Vec<Value>>().await {
             Ok(records) => records,
-            Err(error) => return Err(error.to_string()),
+            Err(error) => return Err(unexpected_response(error)),
         };
 
         if let Some(count) = total_records {
@@ -36,16 +43,103 @@ pub async fn handle_response(response: Response) -> Result<Vec<Value>, String> {
         }
         Ok(records)
     } else {
-        let error_message = match response.status().as_u16() {
-            401 => authorization_failed_error()
-                .await
-                .map_err(|e| e.to_string()),
-            403 => api_key_missing_error().await.map_err(|e| e.to_string()),
-            400 => invalid_query_error().await.map_err(|e| e.to_string()),
-            _ => unknown_error().await.map_err(|e| e.to_string()),
-        };
+        Err(response_error(response).await)
+    }
+}
+
+/// Handles a response requested with `Prefer: count=...`, returning the rows and the total count.
+pub async fn handle_count_response(response: Response) -> Result<(Vec<Value>, u64), RestError> {
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+
+    let total: u64 = response
+        .headers()
+        .get("content-range")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_content_range_total)
+        .ok_or("Response has no total count in its Content-Range header")?;
 
-        // Convert the error to the expected type
-        Err(error_message.unwrap_err())
+    // writes with `Prefer: return=minimal` respond without a body
+    let body: String = response.text().await?;
+    if body.is_empty() {
+        return Ok((Vec::new(), total));
     }
+
+    let records: Vec<Value> = serde_json::from_str(&body).map_err(unexpected_response)?;
+    Ok((records, total))
+}
+
+/// Parses the total out of a `Content-Range` header like `0-24/3573`.
+///
+/// Returns `None` when the total is unknown (`0-24/*`), which happens when no count was requested.
+pub fn parse_content_range_total(content_range: &str) -> Option<u64> {
+    content_range.split('/').nth(1)?.trim().parse::<u64>().ok()
+}
+
+/// The primary key of the row a `Location` header like `/users?id=eq.7` points at, as
+/// `{"id": "7"}`.
+///
+/// PostgREST sends the header for a row written with `Prefer: return=headers-only` into a table with
+/// a primary key. The values are text, the header doesn't tell their types.
+pub(crate) fn location_row(response: &Response) -> Option<Value> {
+    let location: &str = response.headers().get(LOCATION)?.to_str().ok()?;
+    let url: Url = Url::parse("http://localhost").ok()?.join(location).ok()?;
+    let row: Map<String, Value> = url
+        .query_pairs()
+        .filter_map(|(column, filter)| {
+            let value: &str = filter.strip_prefix("eq.")?;
+            Some((column.into_owned(), Value::String(value.to_string())))
+        })
+        .collect();
+    (!row.is_empty()).then_some(Value::Object(row))
+}
+
+/// Maps an unsuccessful response onto its error, keeping the retry advice of `413`, `429` and `503`.
+///
+/// PostgREST error bodies are passed on with their code, details and hint, other bodies are
+/// described by the message matching the status. The message ends with the request id.
+pub(crate) async fn response_error(response: Response) -> RestError {
+    let request_id: Option<String> = response_request_id(&response);
+    let status: u16 = response.status().as_u16();
+    let error: RestError = match ErrorTypes::from_postgrest_response(response).await {
+        error @ ErrorTypes::ApiError { .. } => {
+            RestError::new(error).with_message(status_error(status).await)
+        }
+        error => RestError::new(error),
+    };
+    error.with_request_id(request_id)
+}
+
+/// Maps an unsuccessful write response onto its error, its PostgREST error or its status.
+pub(crate) async fn write_error(response: Response) -> RestError {
+    let request_id: Option<String> = response_request_id(&response);
+    let status: reqwest::StatusCode = response.status();
+    let error: RestError = match ErrorTypes::from_postgrest_response(response).await {
+        error @ ErrorTypes::ApiError { .. } => {
+            RestError::new(error).with_message(status.to_string())
+        }
+        error => RestError::new(error),
+    };
+    error.with_request_id(request_id)
+}
+
+/// The error of a successful response whose body can't be read as expected.
+pub(crate) fn unexpected_response(error: impl ToString) -> RestError {
+    RestError::new(ErrorTypes::UnexpectedResponse(error.to_string()))
+}
+
+/// Maps an unsuccessful status code onto the error message of the matching error.
+pub(crate) async fn status_error(status: u16) -> String {
+    let error_message = match status {
+        401 => authorization_failed_error()
+            .await
+            .map_err(|e| e.to_string()),
+        403 => api_key_missing_error().await.map_err(|e| e.to_string()),
+        400 => invalid_query_error().await.map_err(|e| e.to_string()),
+        _ => unknown_error().await.map_err(|e| e.to_string()),
+    };
+
+    // Convert the error to the expected type
+    error_message.unwrap_err()
 }
@@ -0,0 +1,257 @@
+//! ## Table-backed structs
+//!
+//! The [`SupabaseTable`] trait describes a Rust struct that maps onto a Supabase table.
+//! With the `derive` feature enabled it can be derived, which also generates a typed column enum
+//! so column names are checked at compile time.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use serde::{Deserialize, Serialize};
+//! use supabase_rs::SupabaseTable;
+//!
+//! #[derive(Debug, Serialize, Deserialize, SupabaseTable)]
+//! #[supabase(table = "users")]
+//! pub struct User {
+//!     #[supabase(primary_key)]
+//!     pub id: i64,
+//!     pub email: String,
+//!     #[serde(rename = "displayName")]
+//!     pub display_name: String,
+//! }
+//!
+//! // filters only accept columns of the `users` table
+//! let users: Vec<User> = client
+//!     .select_typed::<User>()
+//!     .eq(UserColumn::Email, "a@b.c")
+//!     .execute()
+//!     .await?;
+//!
+//! client.insert_row(&user).await?;
+//! client.update_row(&user).await?;
+//! ```
+
+use crate::errors::RestError;
+use crate::query::{FilterValue, QueryBuilder, Returning};
+use crate::query_builder::paginate::Page;
+use crate::success::unexpected_response;
+use crate::SupabaseClient;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::marker::PhantomData;
+
+/// A typed column of a [`SupabaseTable`].
+pub trait Column: Copy {
+    /// The column name as known by Supabase.
+    fn name(&self) -> &'static str;
+}
+
+/// A foreign key between two tables, seen from one of them.
+///
+/// `column` belongs to the table the relationship is listed on, `foreign_column` to `table`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Relationship {
+    /// The column on this side of the foreign key.
+    pub column: &'static str,
+    /// The table on the other side of the foreign key.
+    pub table: &'static str,
+    /// The column on the other side of the foreign key.
+    pub foreign_column: &'static str,
+}
+
+/// A struct that maps onto a Supabase table.
+pub trait SupabaseTable: Serialize + DeserializeOwned {
+    /// The typed column enum for this table.
+    type Column: Column;
+
+    /// The name of the table in Supabase.
+    fn table_name() -> &'static str;
+
+    /// The names of all columns backed by fields of the struct.
+    fn columns() -> &'static [&'static str];
+
+    /// The column used to identify a row when updating it, `id` by default.
+    fn primary_key() -> &'static str {
+        "id"
+    }
+
+    /// The tables this table references through its foreign keys, none by default.
+    fn belongs_to() -> &'static [Relationship] {
+        &[]
+    }
+
+    /// The tables referencing this table through their foreign keys, none by default.
+    fn has_many() -> &'static [Relationship] {
+        &[]
+    }
+}
+
+impl SupabaseClient {
+    /// Initializes a `QueryBuilder` for the table of `T`, selecting only the columns `T` knows about.
+    ///
+    /// # Returns
+    /// A `QueryBuilder` instance configured for the table of `T`.
+    pub fn select_table<T: SupabaseTable>(&self) -> QueryBuilder {
+        self.select(T::table_name()).columns(T::columns().to_vec())
+    }
+
+    /// Initializes a `TypedQueryBuilder` for the table of `T`, whose filters only accept columns of `T`.
+    ///
+    /// # Returns
+    /// A `TypedQueryBuilder` instance configured for the table of `T`.
+    pub fn select_typed<T: SupabaseTable>(&self) -> TypedQueryBuilder<T> {
+        TypedQueryBuilder {
+            builder: self.select_table::<T>(),
+            table: PhantomData,
+        }
+    }
+
+    /// Inserts a typed row into its table.
+    ///
+    /// # Arguments
+    /// * `row` - The row to insert, serialized with `serde`.
+    ///
+    /// # Returns
+    /// This method returns a `Result<(), RestError>`. On success, it returns `Ok(())`,
+    /// and on failure, it returns `Err(RestError)` with an error message.
+    pub async fn insert_row<T: SupabaseTable>(&self, row: &T) -> Result<(), RestError> {
+        let body: Value = serde_json::to_value(row).map_err(|error| error.to_string())?;
+        self.insert_with_returning(T::table_name(), body, Returning::Minimal)
+            .await?;
+        Ok(())
+    }
+
+    /// Updates a typed row in its table, matching on the primary key of `T`.
+    ///
+    /// # Arguments
+    /// * `row` - The row to update, serialized with `serde`.
+    ///
+    /// # Returns
+    /// This method returns a `Result<String, RestError>`. On success, it returns `Ok(String)` with the primary key value,
+    /// and on failure, it returns `Err(RestError)` with an error message.
+    pub async fn update_row<T: SupabaseTable>(&self, row: &T) -> Result<String, RestError> {
+        let body: Value = serde_json::to_value(row).map_err(|error| error.to_string())?;
+
+        let key: String = match &body[T::primary_key()] {
+            Value::Null => {
+                return Err(
+                    format!("Row has no value for primary key `{}`", T::primary_key()).into(),
+                )
+            }
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+
+        self.update_with_returning(
+            T::table_name(),
+            T::primary_key(),
+            &key,
+            body,
+            Returning::Minimal,
+        )
+        .await?;
+        Ok(key)
+    }
+}
+
+impl QueryBuilder {
+    /// Executes the constructed query and deserializes every row into `T`.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either a vector of `T`, or a `RestError`.
+    pub async fn execute_typed<T: DeserializeOwned>(self) -> Result<Vec<T>, RestError> {
+        let rows: Vec<Value> = self.execute().await?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_value(row).map_err(unexpected_response))
+            .collect()
+    }
+}
+
+/// A `QueryBuilder` bound to a [`SupabaseTable`], so a typo in a column name is a compile error.
+///
+/// Created by [`SupabaseClient::select_typed`].
+#[derive(Debug)]
+pub struct TypedQueryBuilder<T: SupabaseTable> {
+    builder: QueryBuilder,
+    table: PhantomData<T>,
+}
+
+impl<T: SupabaseTable> TypedQueryBuilder<T> {
+    /// Restricts the selected columns.
+    pub fn columns(mut self, columns: &[T::Column]) -> Self {
+        self.builder = self
+            .builder
+            .columns(columns.iter().map(|column| column.name()).collect());
+        self
+    }
+
+    /// Adds a filter to the query to check if the column is equal to a specified value.
+    pub fn eq(mut self, column: T::Column, value: impl Into<FilterValue>) -> Self {
+        self.builder = self.builder.eq(column.name(), value);
+        self
+    }
+
+    /// Adds a filter to the query to check if the column is not equal to a specified value.
+    pub fn neq(mut self, column: T::Column, value: impl Into<FilterValue>) -> Self {
+        self.builder = self.builder.neq(column.name(), value);
+        self
+    }
+
+    /// Adds a filter to the query to check if the column is greater than a specified value.
+    pub fn gt(mut self, column: T::Column, value: impl Into<FilterValue>) -> Self {
+        self.builder = self.builder.gt(column.name(), value);
+        self
+    }
+
+    /// Adds a filter to the query to check if the column is less than a specified value.
+    pub fn lt(mut self, column: T::Column, value: impl Into<FilterValue>) -> Self {
+        self.builder = self.builder.lt(column.name(), value);
+        self
+    }
+
+    /// Adds a filter to the query to check if the column is greater than or equal to a specified value.
+    pub fn gte(mut self, column: T::Column, value: impl Into<FilterValue>) -> Self {
+        self.builder = self.builder.gte(column.name(), value);
+        self
+    }
+
+    /// Adds a filter to the query to check if the column is less than or equal to a specified value.
+    pub fn lte(mut self, column: T::Column, value: impl Into<FilterValue>) -> Self {
+        self.builder = self.builder.lte(column.name(), value);
+        self
+    }
+
+    /// Reads the table from the given schema, overriding the one of the client.
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.builder = self.builder.schema(schema);
+        self
+    }
+
+    /// Returns the URL `execute` sends the query to, see [`QueryBuilder::to_url`].
+    pub fn to_url(&self) -> String {
+        self.builder.to_url()
+    }
+
+    /// Returns the underlying untyped `QueryBuilder`.
+    pub fn into_inner(self) -> QueryBuilder {
+        self.builder
+    }
+
+    /// Executes the constructed query and deserializes every row into `T`.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either a vector of `T`, or a `RestError`.
+    pub async fn execute(self) -> Result<Vec<T>, RestError> {
+        self.builder.execute_typed::<T>().await
+    }
+
+    /// Fetches a single page of the matching rows, see [`QueryBuilder::execute_paged`].
+    pub async fn execute_paged(self, page: u64, per_page: u64) -> Result<Page<T>, RestError> {
+        self.builder
+            .execute_paged(page, per_page)
+            .await?
+            .deserialize::<T>()
+    }
+}
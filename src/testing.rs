@@ -0,0 +1,164 @@
+//! ## Testing helpers
+//!
+//! Presets for the [`MockTransport`], so crates built on `supabase_rs` can unit test their data access
+//! without a Supabase project or rewriting the PostgREST and Auth responses themselves.
+//!
+//! ```rust,ignore
+//! use serde_json::json;
+//! use supabase_rs::testing::SupabaseMock;
+//!
+//! let mock = SupabaseMock::new()
+//!     .select("users", json!([{ "id": 1, "email": "jane@example.com" }]))
+//!     .insert_conflict("users");
+//! let client = mock.client();
+//!
+//! assert!(register(&client, "jane@example.com").await.is_err());
+//! assert_eq!(mock.requests().len(), 1);
+//! ```
+//!
+//! Routes are answered in the order they were added, see [`MockTransport`] for responses the presets
+//! don't cover.
+
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// The project URL of mocked clients.
+pub const MOCK_URL: &str = "https://mock.supabase.co";
+
+/// The API key of mocked clients.
+pub const MOCK_KEY: &str = "mock-key";
+
+/// A mocked Supabase project with canned responses for the common REST and Auth calls.
+#[derive(Debug, Clone, Default)]
+pub struct SupabaseMock {
+    transport: MockTransport,
+}
+
+impl SupabaseMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a client sending its requests to this mock.
+    pub fn client(&self) -> SupabaseClient {
+        SupabaseClient::new(MOCK_URL.to_string(), MOCK_KEY.to_string())
+            .expect("the mock client has a valid configuration")
+            .with_transport(self.transport.clone())
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.transport.requests()
+    }
+
+    /// Returns the underlying transport, to add other routes.
+    pub fn transport(&self) -> &MockTransport {
+        &self.transport
+    }
+
+    /// Answers selects on `table` with `rows`, whatever the filters. Totals are answered by [`count`](Self::count).
+    pub fn select(self, table: &str, rows: Value) -> Self {
+        // like PostgREST without `Prefer: count`, the total is left out of the range
+        let range: String = match rows.as_array().map_or(0, Vec::len) {
+            0 => "*/*".to_string(),
+            count => format!("0-{}/*", count - 1),
+        };
+        let response: MockResponse =
+            MockResponse::json(200, &rows).with_header("Content-Range", &range);
+        self.on(Method::GET, &rest_path(table), response)
+    }
+
+    /// Answers `count_only` and `exists` on `table` with `total` rows.
+    pub fn count(self, table: &str, total: u64) -> Self {
+        let response: MockResponse =
+            MockResponse::new(200).with_header("Content-Range", &format!("*/{}", total));
+        self.on(Method::HEAD, &rest_path(table), response)
+    }
+
+    /// Accepts every insert into `table`.
+    pub fn insert(self, table: &str) -> Self {
+        self.on(Method::POST, &rest_path(table), MockResponse::new(201))
+    }
+
+    /// Rejects the next insert into `table` with a unique violation (`409`).
+    pub fn insert_conflict(self, table: &str) -> Self {
+        let response: MockResponse = MockResponse::json(
+            409,
+            &json!({
+                "code": "23505",
+                "message": "duplicate key value violates unique constraint",
+                "details": null,
+                "hint": null,
+            }),
+        );
+        self.once(Method::POST, &rest_path(table), response)
+    }
+
+    /// Accepts every update of, and delete from, `table`.
+    pub fn write(self, table: &str) -> Self {
+        self.on(Method::PATCH, &rest_path(table), MockResponse::new(204))
+            .on(Method::DELETE, &rest_path(table), MockResponse::new(204))
+    }
+
+    /// Answers calls of the Postgres function `function` with `result`.
+    pub fn rpc(self, function: &str, result: Value) -> Self {
+        let path: String = rest_path(&format!("rpc/{}", function));
+        self.on(Method::POST, &path, MockResponse::json(200, &result))
+            .on(Method::GET, &path, MockResponse::json(200, &result))
+    }
+
+    fn on(self, method: Method, path: &str, response: MockResponse) -> Self {
+        SupabaseMock {
+            transport: self.transport.on(method, path, response),
+        }
+    }
+
+    fn once(self, method: Method, path: &str, response: MockResponse) -> Self {
+        SupabaseMock {
+            transport: self.transport.once(method, path, response),
+        }
+    }
+}
+
+#[cfg(feature = "auth")]
+impl SupabaseMock {
+    /// Returns an Auth client sending its requests to this mock.
+    pub fn auth_client(&self) -> crate::auth::AuthClient {
+        self.client().auth()
+    }
+
+    /// Signs in with any password and refreshes with any token, returning `session`, and answers
+    /// `get_user` with its user and `sign_out` successfully.
+    pub fn auth_session(self, session: &crate::auth::Session) -> Self {
+        let body: Value = serde_json::to_value(session).unwrap_or_default();
+        let user: Value = serde_json::to_value(&session.user).unwrap_or_default();
+        self.on(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(200, &body),
+        )
+        .on(Method::GET, "/auth/v1/user", MockResponse::json(200, &user))
+        .on(Method::POST, "/auth/v1/logout", MockResponse::new(204))
+    }
+
+    /// Rejects sign ins with the `400` Auth answers for wrong credentials.
+    pub fn auth_invalid_credentials(self) -> Self {
+        let response: MockResponse = MockResponse::json(
+            400,
+            &json!({
+                "code": 400,
+                "error_code": "invalid_credentials",
+                "msg": "Invalid login credentials",
+            }),
+        );
+        self.on(Method::POST, "/auth/v1/token", response)
+    }
+}
+
+/// The path of a REST resource, e.g. `/rest/v1/users`.
+fn rest_path(resource: &str) -> String {
+    format!("/rest/v1/{}", resource)
+}
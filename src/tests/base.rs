@@ -24,18 +24,63 @@
 mod methods {
     // import local method tests
     use crate::tests::methods::{
-        delete::delete as test_delete, insert::insert as test_insert,
+        batch_execute::batch_execute as test_batch_execute,
+        bulk_chunked::bulk_chunked as test_bulk_chunked,
+        bulk_insert_if_unique::bulk_insert_if_unique as test_bulk_insert_if_unique,
+        bulk_upsert::bulk_upsert as test_bulk_upsert,
+        circuit_breaker::circuit_breaker as test_circuit_breaker,
+        column_stats::column_stats as test_column_stats, cron::cron as test_cron,
+        delete::delete as test_delete, delete_builder::delete_builder as test_delete_builder,
+        distributed_lock::distributed_lock as test_distributed_lock,
+        execute_paged::execute_paged as test_execute_paged,
+        filter_values::filter_values as test_filter_values, find_by::find_by as test_find_by,
+        fixtures::fixtures as test_fixtures, functions::functions as test_functions,
+        impersonation::impersonation as test_impersonation, insert::insert as test_insert,
         insert_if_unique_numeric::insert_if_unique_numeric as test_insert_if_unique_numeric,
+        insert_if_unique_on::insert_if_unique_on as test_insert_if_unique_on,
         insert_if_unique_string::insert_if_unique_string as test_insert_if_unique_string,
         insert_numeric::insert_numeric as test_insert_numeric,
-        insert_string::insert_string as test_insert_string, query::test_query,
-        select::select as test_select, select_filter::select_filter as test_select_filter,
+        insert_string::insert_string as test_insert_string,
+        insert_with_defaults::insert_with_defaults as test_insert_with_defaults,
+        migrations::migrations as test_migrations,
+        mock_transport::mock_transport as test_mock_transport, payload::payload as test_payload,
+        postgrest_errors::postgrest_errors as test_postgrest_errors,
+        postgrest_interop::postgrest_interop as test_postgrest_interop,
+        prefer_header::prefer_header as test_prefer_header, prelude::prelude as test_prelude,
+        query::test_query, query_to_url::query_to_url as test_query_to_url,
+        queues::queues as test_queues, rate_limit::rate_limit as test_rate_limit,
+        redirects::redirects as test_redirects, request_ids::request_ids as test_request_ids,
+        retry_advice::retry_advice as test_retry_advice, rpc_errors::rpc_errors as test_rpc_errors,
+        rpc_read_only::rpc_read_only as test_rpc_read_only, rpc_typed::rpc_typed as test_rpc_typed,
+        schema_cache::schema_cache as test_schema_cache,
+        schema_cache_validation::schema_cache_validation as test_schema_cache_validation,
+        schema_profile::schema_profile as test_schema_profile, select::select as test_select,
+        select_csv::select_csv as test_select_csv,
+        select_filter::select_filter as test_select_filter,
+        select_head::select_head as test_select_head,
+        select_json_filter::select_json_filter as test_select_json_filter,
+        select_order::select_order as test_select_order,
+        select_paginated::select_paginated as test_select_paginated,
+        select_range_filter::select_range_filter as test_select_range_filter,
+        select_related::select_related as test_select_related,
+        select_related_filter::select_related_filter as test_select_related_filter,
         select_stacked_queries::select_stacked_queries as test_select_stacked_queries,
+        select_timezone::select_timezone as test_select_timezone,
         select_with_columns::select_with_columns as test_select_with_columns,
         select_with_count::select_with_count as test_select_with_count,
+        select_with_count_header::select_with_count_header as test_select_with_count_header,
+        single_strict::single_strict as test_single_strict,
+        table_scanner::table_scanner as test_table_scanner,
+        testing_presets::testing_presets as test_testing_presets, timeout::timeout as test_timeout,
+        trace_context::trace_context as test_trace_context,
+        unified_error::unified_error as test_unified_error,
+        update_builder::update_builder as test_update_builder,
         update_with_column::update_with_column as test_update_with_column,
         upsert_numeric::upsert_numeric as test_upsert_numeric,
+        upsert_on_conflict::upsert_on_conflict as test_upsert_on_conflict,
         upsert_string::upsert_string as test_upsert_string,
+        write_returning::write_returning as test_write_returning,
+        write_returning_columns::write_returning_columns as test_write_returning_columns,
     };
 
     /// Tests the `insert` method of `SupabaseClient`.
@@ -92,7 +137,7 @@ mod methods {
         test_select_with_count().await;
     }
 
-    /// Tests the `select_filter` method of `SupabaseClient`.
+    // Tests the `select_filter` method of `SupabaseClient`.
     // #[tokio::test]
     // async fn select_with_count_and_filter() {
     //     test_select_with_count_and_filter().await;
@@ -135,4 +180,620 @@ mod methods {
     async fn query() {
         test_query().await;
     }
+
+    /// Tests the state transitions of the `CircuitBreaker`.
+    #[tokio::test]
+    async fn circuit_breaker() {
+        test_circuit_breaker().await;
+    }
+
+    /// Tests splitting oversized bulk payloads into batches.
+    #[tokio::test]
+    async fn payload() {
+        test_payload().await;
+    }
+
+    /// Tests the `execute_csv` method of `QueryBuilder`.
+    #[tokio::test]
+    async fn select_csv() {
+        test_select_csv().await;
+    }
+
+    /// Tests the `Prefer: timezone` header of selects.
+    #[tokio::test]
+    async fn select_timezone() {
+        test_select_timezone().await;
+    }
+
+    /// Tests the headers of Edge Function invocations.
+    #[tokio::test]
+    async fn functions() {
+        test_functions().await;
+    }
+
+    /// Tests the `paginate` method of `QueryBuilder`.
+    #[tokio::test]
+    async fn select_paginated() {
+        test_select_paginated().await;
+    }
+
+    /// Tests the requests and checkpoints of `TableScanner`.
+    #[tokio::test]
+    async fn table_scanner() {
+        test_table_scanner().await;
+    }
+
+    /// Tests building and parsing the aggregate select of `column_stats`.
+    #[tokio::test]
+    async fn column_stats() {
+        test_column_stats().await;
+    }
+
+    /// Tests the filters and safety check of `DeleteBuilder`.
+    #[tokio::test]
+    async fn delete_builder() {
+        test_delete_builder().await;
+    }
+
+    /// Tests which redirects `dispatch` follows.
+    #[tokio::test]
+    async fn redirects() {
+        test_redirects().await;
+    }
+
+    /// Tests the filters and safety checks of `UpdateBuilder`.
+    #[tokio::test]
+    async fn update_builder() {
+        test_update_builder().await;
+    }
+
+    /// Tests the `upsert_on_conflict` and `upsert_ignore_duplicates` methods of `SupabaseClient`.
+    #[tokio::test]
+    async fn upsert_on_conflict() {
+        test_upsert_on_conflict().await;
+    }
+
+    /// Tests parsing the total count out of the `Content-Range` header.
+    #[tokio::test]
+    async fn select_with_count_header() {
+        test_select_with_count_header().await;
+    }
+
+    /// Tests parsing the message envelopes returned by Supabase Queues.
+    #[tokio::test]
+    async fn queues() {
+        test_queues().await;
+    }
+
+    /// Tests parsing the `pg_cron` jobs listed by `Cron`.
+    #[tokio::test]
+    async fn cron() {
+        test_cron().await;
+    }
+
+    /// Tests the code generated by `#[derive(SupabaseTable)]`.
+    #[cfg(feature = "derive")]
+    #[tokio::test]
+    async fn derive_table() {
+        crate::tests::methods::derive_table::derive_table().await;
+    }
+
+    /// Tests rendering typed table bindings from an OpenAPI document.
+    #[cfg(feature = "type_gen")]
+    #[tokio::test]
+    async fn type_gen() {
+        crate::tests::methods::type_gen::type_gen().await;
+    }
+
+    /// Tests the request body of Realtime broadcasts.
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn realtime_broadcast() {
+        crate::tests::methods::realtime_broadcast::realtime_broadcast().await;
+    }
+
+    /// Tests aborting requests with a `CancellationToken`.
+    #[cfg(feature = "cancellation")]
+    #[tokio::test]
+    async fn cancellation() {
+        crate::tests::methods::cancellation::cancellation().await;
+    }
+
+    /// Tests the retry advice of `ErrorTypes`.
+    #[tokio::test]
+    async fn retry_advice() {
+        test_retry_advice().await;
+    }
+
+    /// Tests the admin auth request bodies, paging and generated links
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_admin() {
+        crate::tests::methods::auth_admin::auth_admin().await;
+    }
+
+    /// Tests the key and lookup helpers of `bulk_insert_if_unique`
+    #[tokio::test]
+    async fn bulk_insert_if_unique() {
+        test_bulk_insert_if_unique().await;
+    }
+
+    /// Tests the MFA models and the session requirement of `AuthClient`
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_mfa() {
+        crate::tests::methods::auth_mfa::auth_mfa().await;
+    }
+
+    /// Tests that the prelude exports the client, builders and options
+    #[tokio::test]
+    async fn prelude() {
+        test_prelude().await;
+    }
+
+    /// Tests building a client and queries the way the `postgrest` crate does
+    #[tokio::test]
+    async fn postgrest_interop() {
+        test_postgrest_interop().await;
+    }
+
+    /// Tests the in-memory and file session stores of `AuthClient`
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_session_store() {
+        crate::tests::methods::auth_session_store::auth_session_store().await;
+    }
+
+    /// Tests the relationships parsed into the schema cache
+    #[tokio::test]
+    async fn schema_cache() {
+        test_schema_cache().await;
+    }
+
+    /// Tests verifying access tokens signed with the JWT secret
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_verify_jwt() {
+        crate::tests::methods::auth_verify_jwt::auth_verify_jwt().await;
+    }
+
+    /// Tests the holder tokens and parameters of distributed locks
+    #[tokio::test]
+    async fn distributed_lock() {
+        test_distributed_lock().await;
+    }
+
+    /// Tests the query string of read-only RPC calls
+    #[tokio::test]
+    async fn rpc_read_only() {
+        test_rpc_read_only().await;
+    }
+
+    /// Tests typed RPC parameters and the serialization error of invalid ones
+    #[tokio::test]
+    async fn rpc_typed() {
+        test_rpc_typed().await;
+    }
+
+    /// Tests parsing and classifying PostgREST error bodies
+    #[tokio::test]
+    async fn rpc_errors() {
+        test_rpc_errors().await;
+    }
+
+    /// Builds embedded selects and checks them against the schema cache
+    #[tokio::test]
+    async fn select_related() {
+        test_select_related().await;
+    }
+
+    /// Filters rows on embedded relations with inner joins
+    #[tokio::test]
+    async fn select_related_filter() {
+        test_select_related_filter().await;
+    }
+
+    /// Builds jsonb containment and arrow path filters
+    #[tokio::test]
+    async fn select_json_filter() {
+        test_select_json_filter().await;
+    }
+
+    /// Builds range and array operator filters
+    #[tokio::test]
+    async fn select_range_filter() {
+        test_select_range_filter().await;
+    }
+
+    /// Orders by several columns with nulls first or last
+    #[tokio::test]
+    async fn select_order() {
+        test_select_order().await;
+    }
+
+    /// Counts and checks rows with HEAD requests
+    #[tokio::test]
+    async fn select_head() {
+        test_select_head().await;
+    }
+
+    /// Runs selects and inserts against the in-memory mock transport
+    #[tokio::test]
+    async fn mock_transport() {
+        test_mock_transport().await;
+    }
+
+    /// Runs the common calls against the SupabaseMock presets
+    #[tokio::test]
+    async fn testing_presets() {
+        test_testing_presets().await;
+    }
+
+    /// Fails requests that run out of time
+    #[tokio::test]
+    async fn timeout() {
+        test_timeout().await;
+    }
+
+    /// Serves repeated selects from the response cache
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn select_cache() {
+        crate::tests::methods::select_cache::select_cache().await;
+    }
+
+    /// Limits the requests in flight and per second
+    #[tokio::test]
+    async fn rate_limit() {
+        test_rate_limit().await;
+    }
+
+    /// Syncs the presence state of a channel
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn realtime_presence() {
+        crate::tests::methods::realtime_presence::realtime_presence().await;
+    }
+
+    /// Subscribes to a channel of a local Realtime server for broadcast and presence
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn realtime_channel() {
+        crate::tests::methods::realtime_channel::realtime_channel().await;
+    }
+
+    /// Reconnects a dropped Realtime connection and joins its channels again
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn realtime_reconnect() {
+        crate::tests::methods::realtime_reconnect::realtime_reconnect().await;
+    }
+
+    /// Authorizes Realtime channels with the access token of a user
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn realtime_auth() {
+        crate::tests::methods::realtime_auth::realtime_auth().await;
+    }
+
+    /// Builds public and transformed image URLs of a bucket
+    #[cfg(feature = "storage")]
+    #[tokio::test]
+    async fn storage_public_url() {
+        crate::tests::methods::storage_public_url::storage_public_url().await;
+    }
+
+    /// Uploads a file in resumable chunks against a mock transport
+    #[cfg(feature = "storage")]
+    #[tokio::test]
+    async fn storage_resumable_upload() {
+        crate::tests::methods::storage_resumable_upload::storage_resumable_upload().await;
+    }
+
+    /// Chooses what inserts, updates and upserts return with the Prefer header
+    #[tokio::test]
+    async fn write_returning() {
+        test_write_returning().await;
+    }
+
+    /// Combines the preferences of a request into a single Prefer header
+    #[tokio::test]
+    async fn prefer_header() {
+        test_prefer_header().await;
+    }
+
+    /// Applies a batch of writes with a single call to the batch function
+    #[tokio::test]
+    async fn batch_execute() {
+        test_batch_execute().await;
+    }
+
+    /// Selects the schema of reads, writes and RPC calls with the profile headers
+    #[tokio::test]
+    async fn schema_profile() {
+        test_schema_profile().await;
+    }
+
+    /// Generates the types of a chosen schema and pins the generated builders to it.
+    #[cfg(feature = "type_gen")]
+    #[tokio::test]
+    async fn type_gen_schema() {
+        crate::tests::methods::type_gen_schema::type_gen_schema().await;
+    }
+
+    /// Tests queries are checked against the schema cache before they are sent.
+    #[tokio::test]
+    async fn schema_cache_validation() {
+        test_schema_cache_validation().await;
+    }
+
+    /// Tests filters on numbers, booleans, nulls, UUIDs and timestamps.
+    #[tokio::test]
+    async fn filter_values() {
+        test_filter_values().await;
+    }
+
+    /// Tests looking up single rows and their ids by column values.
+    #[tokio::test]
+    async fn find_by() {
+        test_find_by().await;
+    }
+
+    /// Tests writes are reported to the audit sink of the client.
+    #[cfg(feature = "audit")]
+    #[tokio::test]
+    async fn audit_sink() {
+        crate::tests::methods::audit_sink::audit_sink().await;
+    }
+
+    /// SDK errors of every subsystem convert into `Error` with a shared kind.
+    #[tokio::test]
+    async fn unified_error() {
+        test_unified_error().await;
+    }
+
+    /// Failed requests pass on the PostgREST error body, and only `23505` counts as a duplicate.
+    #[tokio::test]
+    async fn postgrest_errors() {
+        test_postgrest_errors().await;
+    }
+
+    /// `insert_if_unique_on` skips existing rows in one request and reports whether it inserted.
+    #[tokio::test]
+    async fn insert_if_unique_on() {
+        test_insert_if_unique_on().await;
+    }
+
+    /// `insert_with_defaults` names every column and asks for `missing=default`.
+    #[tokio::test]
+    async fn insert_with_defaults() {
+        test_insert_with_defaults().await;
+    }
+
+    /// Impersonating clients send the token of the user or role as bearer.
+    #[tokio::test]
+    async fn impersonation() {
+        test_impersonation().await;
+    }
+
+    /// Fixtures refuse remote projects, and seed, truncate and empty tables.
+    #[tokio::test]
+    async fn fixtures() {
+        test_fixtures().await;
+    }
+
+    /// `to_url` and `Display` show the request a query sends.
+    #[tokio::test]
+    async fn query_to_url() {
+        test_query_to_url().await;
+    }
+
+    /// `single_strict` asks PostgREST for a single object and passes on its 406.
+    #[tokio::test]
+    async fn single_strict() {
+        test_single_strict().await;
+    }
+
+    /// CSV and NDJSON imports are sent in chunks, retrying the ones the server never received.
+    #[cfg(feature = "import")]
+    #[tokio::test]
+    async fn bulk_import() {
+        crate::tests::methods::bulk_import::bulk_import().await;
+    }
+
+    /// Chunked bulk inserts report succeeded, failed and skipped chunks.
+    #[tokio::test]
+    async fn bulk_chunked() {
+        test_bulk_chunked().await;
+    }
+
+    /// `bulk_upsert` merges a list of rows on a conflict target in one request.
+    #[tokio::test]
+    async fn bulk_upsert() {
+        test_bulk_upsert().await;
+    }
+
+    /// Update and delete return only the requested columns.
+    #[tokio::test]
+    async fn write_returning_columns() {
+        test_write_returning_columns().await;
+    }
+
+    /// Paged selects report their range, total and neighbouring pages.
+    #[tokio::test]
+    async fn execute_paged() {
+        test_execute_paged().await;
+    }
+
+    /// Requests carry an id which rejected requests name in their error, and idempotency keys.
+    #[tokio::test]
+    async fn request_ids() {
+        test_request_ids().await;
+    }
+
+    /// Requests carry the W3C trace context of the propagator of the client.
+    #[tokio::test]
+    async fn trace_context() {
+        test_trace_context().await;
+    }
+
+    /// Streams typed row changes of a table into a bounded channel and leaves it once the receiver is dropped.
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn realtime_changes() {
+        crate::tests::methods::realtime_changes::realtime_changes().await;
+    }
+
+    /// Keeps a table in memory from a paged read and its row changes, reading it again after reconnecting.
+    #[cfg(feature = "realtime")]
+    #[tokio::test]
+    async fn sync_table() {
+        crate::tests::methods::sync_table::sync_table().await;
+    }
+
+    /// Signs in with the ID token of a native provider flow.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_id_token() {
+        crate::tests::methods::auth_id_token::auth_id_token().await;
+    }
+
+    /// Signs up, verifies and signs in with a phone number.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_phone() {
+        crate::tests::methods::auth_phone::auth_phone().await;
+    }
+
+    /// Lists, links and unlinks the identities of the signed in user.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_identities() {
+        crate::tests::methods::auth_identities::auth_identities().await;
+    }
+
+    /// Calls the auth state callbacks on sign in, refresh, user update and sign out.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_events() {
+        crate::tests::methods::auth_events::auth_events().await;
+    }
+
+    /// Sends the requests of a client with the session of its Auth client.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_shared_session() {
+        crate::tests::methods::auth_shared_session::auth_shared_session().await;
+    }
+
+    /// Starts OAuth sign ins with PKCE and exchanges their codes for sessions.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_pkce() {
+        crate::tests::methods::auth_pkce::auth_pkce().await;
+    }
+
+    /// Signs out of the sessions in a scope, keeping the stored session for others.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_sign_out_scope() {
+        crate::tests::methods::auth_sign_out_scope::auth_sign_out_scope().await;
+    }
+
+    /// Sends captcha tokens and user metadata with sign ups and sign ins.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_captcha() {
+        crate::tests::methods::auth_captcha::auth_captcha().await;
+    }
+
+    /// Sends the redirect URLs and metadata of sign ups, magic links and recovery emails.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_redirects() {
+        crate::tests::methods::auth_redirects::auth_redirects().await;
+    }
+
+    /// Invites users by email with their metadata and redirect URL.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_invite() {
+        crate::tests::methods::auth_invite::auth_invite().await;
+    }
+
+    /// Generates links with the parameters their type needs, rejecting incomplete ones.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_generate_link() {
+        crate::tests::methods::auth_generate_link::auth_generate_link().await;
+    }
+
+    /// Starts SSO sign ins by domain or provider id, completed with the PKCE code exchange.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_sso() {
+        crate::tests::methods::auth_sso::auth_sso().await;
+    }
+
+    /// Keeps the status, error code and body of failed Auth requests.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_errors() {
+        crate::tests::methods::auth_errors::auth_errors().await;
+    }
+
+    /// Classifies weak passwords, rate limits and other actionable Auth errors.
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn auth_error_kinds() {
+        crate::tests::methods::auth_error_kinds::auth_error_kinds().await;
+    }
+
+    /// Reads object metadata and probes whether objects exist.
+    #[cfg(feature = "storage")]
+    #[tokio::test]
+    async fn storage_object_info() {
+        crate::tests::methods::storage_object_info::storage_object_info().await;
+    }
+
+    /// Signs S3 requests and uploads objects in parts, aborting failed uploads.
+    #[cfg(feature = "storage_s3")]
+    #[tokio::test]
+    async fn storage_s3_multipart() {
+        crate::tests::methods::storage_s3_multipart::storage_s3_multipart().await;
+    }
+
+    /// Downloads byte ranges of objects and continues interrupted saves.
+    #[cfg(feature = "storage")]
+    #[tokio::test]
+    async fn storage_range_download() {
+        crate::tests::methods::storage_range_download::storage_range_download().await;
+    }
+
+    /// Retries transient storage failures and verifies checksums.
+    #[cfg(feature = "storage")]
+    #[tokio::test]
+    async fn storage_retry_checksum() {
+        crate::tests::methods::storage_retry_checksum::storage_retry_checksum().await;
+    }
+
+    /// The migrations create the functions their clients call, revoked from `public`.
+    #[tokio::test]
+    async fn migrations() {
+        test_migrations().await;
+    }
+
+    /// Labels the request metrics with their status class, also for GraphQL queries and downloads.
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn metrics_labels() {
+        crate::tests::methods::metrics_labels::metrics_labels().await;
+    }
+
+    /// Tests the Vault secrets and their unexpected responses.
+    #[cfg(feature = "admin")]
+    #[tokio::test]
+    async fn vault() {
+        crate::tests::methods::vault::vault().await;
+    }
 }
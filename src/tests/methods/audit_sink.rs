@@ -0,0 +1,84 @@
+use crate::audit::{AuditEvent, AuditFuture, AuditSink, AuditTable};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+use sha2::Digest;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default, Clone)]
+struct RecordingSink {
+    events: Arc<Mutex<Vec<AuditEvent>>>,
+}
+
+impl AuditSink for RecordingSink {
+    fn record(&self, event: AuditEvent) -> AuditFuture<'_> {
+        Box::pin(async move { self.events.lock().unwrap().push(event) })
+    }
+}
+
+pub async fn audit_sink() {
+    let transport: MockTransport = MockTransport::new()
+        .on(Method::POST, "/rest/v1/orders", MockResponse::new(201))
+        .on(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(Method::DELETE, "/rest/v1/orders", MockResponse::new(404))
+        .on(Method::POST, "/rest/v1/audit_log", MockResponse::new(201));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let sink: RecordingSink = RecordingSink::default();
+    let audited: SupabaseClient = client.clone().with_audit_sink(sink.clone());
+
+    audited
+        .insert_without_defined_key("orders", json!({"total": 25}))
+        .await
+        .unwrap();
+    audited.select("orders").execute().await.unwrap();
+    let _ = audited.delete_from("orders").eq("id", 7).execute().await;
+
+    // selects aren't writes, and are left out
+    let events: Vec<AuditEvent> = sink.events.lock().unwrap().clone();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].table, "orders");
+    assert_eq!(events[0].operation, "insert");
+    assert_eq!(events[0].filter, None);
+    assert_eq!(events[0].status, Some(201));
+    let body: String = transport.requests()[0].body.clone().unwrap();
+    let digest: String = sha2::Sha256::digest(body.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    assert_eq!(events[0].payload_digest.as_deref(), Some(digest.as_str()));
+    assert_eq!(events[1].operation, "delete");
+    assert_eq!(events[1].filter.as_deref(), Some("id=eq.7"));
+    assert_eq!(events[1].payload_digest, None);
+    assert_eq!(events[1].status, Some(404));
+
+    // the built-in sink inserts a row per write, without auditing that insert
+    let logged: SupabaseClient = client
+        .clone()
+        .with_audit_sink(AuditTable::new(client.clone(), "audit_log"));
+    logged
+        .insert_without_defined_key("orders", json!({"total": 30}))
+        .await
+        .unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let logs: Vec<&RecordedRequest> = requests
+        .iter()
+        .filter(|request| request.url.path() == "/rest/v1/audit_log")
+        .collect();
+    assert_eq!(logs.len(), 1);
+    let row: Value = logs[0].json().unwrap();
+    assert_eq!(row["table_name"], "orders");
+    assert_eq!(row["operation"], "insert");
+    assert_eq!(row["status"], 201);
+    assert_eq!(row["payload_digest"].as_str().unwrap().len(), 64);
+}
@@ -0,0 +1,63 @@
+use crate::auth::admin::{
+    next_page, parse_generated_link, AdminUserAttributes, GenerateLinkParams, GenerateLinkType,
+    GeneratedLink,
+};
+
+use serde_json::{json, Value};
+
+pub async fn auth_admin() {
+    // unset attributes are left out, so an update only touches what was set
+    let attributes: Value =
+        serde_json::to_value(AdminUserAttributes::new().ban_duration("24h")).unwrap();
+    assert_eq!(attributes, json!({"ban_duration": "24h"}));
+
+    let attributes: Value = serde_json::to_value(
+        AdminUserAttributes::new()
+            .email("jane@example.com")
+            .email_confirm(true)
+            .user_metadata(json!({"name": "Jane"})),
+    )
+    .unwrap();
+    assert_eq!(
+        attributes,
+        json!({"email": "jane@example.com", "email_confirm": true, "user_metadata": {"name": "Jane"}})
+    );
+
+    let body: Value = GenerateLinkParams::new(GenerateLinkType::MagicLink, "jane@example.com")
+        .redirect_to("https://example.com/welcome")
+        .body()
+        .unwrap();
+    assert_eq!(
+        body,
+        json!({"type": "magiclink", "email": "jane@example.com", "redirect_to": "https://example.com/welcome"})
+    );
+
+    assert_eq!(next_page(1, 50, 50, Some(120)), Some(2));
+    assert_eq!(next_page(3, 50, 20, Some(120)), None);
+    assert_eq!(next_page(1, 50, 50, None), Some(2));
+    assert_eq!(next_page(2, 50, 10, None), None);
+
+    // current GoTrue returns the user and the link side by side
+    let link: GeneratedLink = parse_generated_link(json!({
+        "id": "4d6b0f0e",
+        "email": "jane@example.com",
+        "action_link": "https://project.supabase.co/auth/v1/verify?token=abc&type=recovery",
+        "email_otp": "123456",
+        "hashed_token": "abc",
+        "verification_type": "recovery",
+        "redirect_to": "https://example.com"
+    }))
+    .unwrap();
+    assert_eq!(link.email_otp, "123456");
+    assert_eq!(link.user.id, "4d6b0f0e");
+    assert_eq!(link.user.email.as_deref(), Some("jane@example.com"));
+
+    // older versions nest the link under `properties`
+    let link: GeneratedLink = parse_generated_link(json!({
+        "user": {"id": "4d6b0f0e"},
+        "properties": {"action_link": "https://example.com/verify", "verification_type": "invite"}
+    }))
+    .unwrap();
+    assert_eq!(link.action_link, "https://example.com/verify");
+    assert_eq!(link.user.id, "4d6b0f0e");
+}
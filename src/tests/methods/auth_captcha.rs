@@ -0,0 +1,70 @@
+use crate::auth::phone::SignUp;
+use crate::auth::{AuthClient, SignInOptions, SignUpOptions};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn auth_captcha() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/auth/v1/signup",
+            MockResponse::json(200, &json!({"id": "u1", "email": "jane@example.com"})),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                200,
+                &json!({"access_token": "jwt", "expires_in": 3600, "refresh_token": "refresh"}),
+            ),
+        );
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport.clone());
+
+    let options: SignUpOptions = SignUpOptions::new()
+        .data(json!({"name": "Jane"}))
+        .captcha_token("captcha");
+    match auth
+        .sign_up("jane@example.com", "hunter22", options)
+        .await
+        .unwrap()
+    {
+        SignUp::Unconfirmed(user) => assert_eq!(user.id, "u1"),
+        SignUp::Session(_) => panic!("the user isn't confirmed yet"),
+    }
+    assert!(auth.session().is_none());
+
+    auth.sign_in_with_password_with(
+        "jane@example.com",
+        "hunter22",
+        SignInOptions::new().captcha_token("other"),
+    )
+    .await
+    .unwrap();
+    auth.sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let bodies: Vec<Value> = requests
+        .iter()
+        .map(|request| request.json().unwrap())
+        .collect();
+    assert_eq!(
+        bodies[0],
+        json!({
+            "email": "jane@example.com",
+            "password": "hunter22",
+            "data": {"name": "Jane"},
+            "gotrue_meta_security": {"captcha_token": "captcha"},
+        })
+    );
+    assert_eq!(bodies[1]["gotrue_meta_security"]["captcha_token"], "other");
+    assert!(bodies[2].get("gotrue_meta_security").is_none());
+}
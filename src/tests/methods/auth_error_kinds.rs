@@ -0,0 +1,97 @@
+use crate::auth::{AuthClient, SignUpOptions};
+use crate::errors::{AuthApiError, AuthErrorKind, ErrorKind, ErrorTypes};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+fn kind(error_code: Option<&str>, status: u16) -> AuthErrorKind {
+    let error: AuthApiError = AuthApiError {
+        error_code: error_code.map(str::to_string),
+        message: "message".to_string(),
+        weak_password_reasons: Vec::new(),
+    };
+    error.kind(status)
+}
+
+pub async fn auth_error_kinds() {
+    assert_eq!(
+        kind(Some("over_email_send_rate_limit"), 429),
+        AuthErrorKind::EmailRateLimited
+    );
+    assert_eq!(
+        kind(Some("over_sms_send_rate_limit"), 429),
+        AuthErrorKind::SmsRateLimited
+    );
+    assert_eq!(kind(None, 429), AuthErrorKind::RequestRateLimited);
+    assert_eq!(
+        kind(Some("email_not_confirmed"), 400),
+        AuthErrorKind::EmailNotConfirmed
+    );
+    assert_eq!(
+        kind(Some("phone_not_confirmed"), 400),
+        AuthErrorKind::PhoneNotConfirmed
+    );
+    for code in ["user_already_exists", "email_exists", "phone_exists"] {
+        assert_eq!(kind(Some(code), 422), AuthErrorKind::UserAlreadyExists);
+    }
+    assert_eq!(
+        kind(Some("invalid_credentials"), 400),
+        AuthErrorKind::InvalidCredentials
+    );
+    assert_eq!(kind(Some("invalid_grant"), 400), AuthErrorKind::Other);
+
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/auth/v1/signup",
+            MockResponse::json(
+                422,
+                &json!({
+                    "code": 422,
+                    "error_code": "weak_password",
+                    "msg": "Password is known to be weak and easy to guess.",
+                    "weak_password": {"reasons": ["length", "pwned"]},
+                }),
+            ),
+        )
+        .once(
+            Method::POST,
+            "/auth/v1/signup",
+            MockResponse::json(
+                429,
+                &json!({
+                    "code": 429,
+                    "error_code": "over_email_send_rate_limit",
+                    "msg": "email rate limit exceeded",
+                }),
+            ),
+        );
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport);
+
+    let error: ErrorTypes = auth
+        .sign_up("jane@example.com", "password", SignUpOptions::new())
+        .await
+        .unwrap_err();
+    assert_eq!(error.auth_error_kind(), Some(AuthErrorKind::WeakPassword));
+    assert_eq!(
+        error.auth_error().unwrap().weak_password_reasons,
+        vec!["length", "pwned"]
+    );
+    assert_eq!(error.kind(), ErrorKind::InvalidRequest);
+
+    let error: ErrorTypes = auth
+        .sign_up("jane@example.com", "correct horse", SignUpOptions::new())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.auth_error_kind(),
+        Some(AuthErrorKind::EmailRateLimited)
+    );
+    assert_eq!(error.kind(), ErrorKind::RateLimited);
+}
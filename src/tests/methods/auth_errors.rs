@@ -0,0 +1,104 @@
+use crate::auth::AuthClient;
+use crate::errors::{AuthApiError, ErrorKind, ErrorTypes};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn auth_errors() {
+    // current versions of Auth
+    assert_eq!(
+        AuthApiError::parse(
+            r#"{"code":422,"error_code":"weak_password","msg":"Password should be at least 8 characters."}"#
+        ),
+        Some(AuthApiError {
+            error_code: Some("weak_password".to_string()),
+            message: "Password should be at least 8 characters.".to_string(),
+            weak_password_reasons: Vec::new(),
+        })
+    );
+    // older versions answer token requests with OAuth errors
+    assert_eq!(
+        AuthApiError::parse(
+            r#"{"error":"invalid_grant","error_description":"Invalid login credentials"}"#
+        ),
+        Some(AuthApiError {
+            error_code: Some("invalid_grant".to_string()),
+            message: "Invalid login credentials".to_string(),
+            weak_password_reasons: Vec::new(),
+        })
+    );
+    assert_eq!(
+        AuthApiError::parse(r#"{"message":"No API key found in request"}"#)
+            .unwrap()
+            .error_code,
+        None
+    );
+    assert_eq!(AuthApiError::parse("<html>Bad Gateway</html>"), None);
+
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                404,
+                &json!({"code": 404, "error_code": "user_not_found", "msg": "User not found"}),
+            ),
+        )
+        .once(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                500,
+                &json!({"code": 500, "error_code": "unexpected_failure", "msg": "Database error"}),
+            ),
+        )
+        .once(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::new(503).with_header("retry-after", "10"),
+        );
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport);
+
+    let error: ErrorTypes = auth
+        .sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap_err();
+    let ErrorTypes::Auth {
+        status,
+        error: auth_error,
+        body,
+    } = &error
+    else {
+        panic!("expected an Auth error, got {:?}", error);
+    };
+    assert_eq!(*status, 404);
+    assert_eq!(auth_error.error_code.as_deref(), Some("user_not_found"));
+    assert!(body.contains("\"code\":404"));
+    assert_eq!(
+        error.to_string(),
+        "Auth error 404: [user_not_found] User not found"
+    );
+    assert_eq!(error.kind(), ErrorKind::Api);
+    assert!(!error.is_retryable());
+
+    let error: ErrorTypes = auth
+        .sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::Auth { status: 500, .. }));
+    assert_eq!(error.auth_error().unwrap().message, "Database error");
+
+    // responses without an Auth error body keep the general rules
+    let error: ErrorTypes = auth
+        .sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::ServiceUnavailable { .. }));
+    assert!(error.auth_error().is_none());
+}
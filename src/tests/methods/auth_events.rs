@@ -0,0 +1,105 @@
+use crate::auth::events::{AuthEvent, AuthSubscription};
+use crate::auth::{AuthClient, UserAttributes};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// The events received, with the access token of the session after them.
+type Events = Arc<Mutex<Vec<(AuthEvent, Option<String>)>>>;
+
+pub async fn auth_events() {
+    let session: Value = json!({
+        "access_token": "jwt",
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "refresh_token": "refresh",
+        "user": {"id": "u1", "email": "jane@example.com"},
+    });
+    let transport: MockTransport = MockTransport::new()
+        .on(Method::POST, "/auth/v1/token", MockResponse::json(200, &session))
+        .on(
+            Method::PUT,
+            "/auth/v1/user",
+            MockResponse::json(
+                200,
+                &json!({"id": "u1", "email": "jane@example.com", "user_metadata": {"name": "Jane"}}),
+            ),
+        )
+        .on(Method::POST, "/auth/v1/logout", MockResponse::new(204));
+    let mut auth: AuthClient = AuthClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap();
+    auth.client = SupabaseClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let events: Events = Arc::default();
+    let subscription: AuthSubscription = auth.on_auth_state_change({
+        let events = events.clone();
+        move |event, session| {
+            let token: Option<String> = session.map(|session| session.access_token.clone());
+            events.lock().unwrap().push((event, token));
+        }
+    });
+    let names: Arc<Mutex<Vec<&'static str>>> = Arc::default();
+    // callbacks registered on a clone are shared
+    let _names: AuthSubscription = auth.clone().on_auth_state_change({
+        let names = names.clone();
+        move |event, _| names.lock().unwrap().push(event.as_str())
+    });
+
+    auth.sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap();
+    auth.refresh_session().await.unwrap();
+    auth.update_user(UserAttributes::new().data(json!({"name": "Jane"})))
+        .await
+        .unwrap();
+    assert_eq!(
+        auth.session().unwrap().user.user_metadata,
+        json!({"name": "Jane"})
+    );
+    auth.sign_out().await.unwrap();
+
+    let token: Option<String> = Some("jwt".to_string());
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            (AuthEvent::SignedIn, token.clone()),
+            (AuthEvent::TokenRefreshed, token.clone()),
+            (AuthEvent::UserUpdated, token),
+            (AuthEvent::SignedOut, None),
+        ]
+    );
+
+    // unsubscribed callbacks miss later changes
+    subscription.unsubscribe();
+    auth.sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap();
+    assert_eq!(events.lock().unwrap().len(), 4);
+    assert_eq!(
+        *names.lock().unwrap(),
+        vec![
+            "SIGNED_IN",
+            "TOKEN_REFRESHED",
+            "USER_UPDATED",
+            "SIGNED_OUT",
+            "SIGNED_IN"
+        ]
+    );
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(
+        requests[2].json().unwrap(),
+        json!({"data": {"name": "Jane"}})
+    );
+}
@@ -0,0 +1,87 @@
+use crate::auth::admin::{GenerateLinkParams, GenerateLinkType, GeneratedLink};
+use crate::errors::ErrorTypes;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn auth_generate_link() {
+    let transport: MockTransport = MockTransport::new().on(
+        Method::POST,
+        "/auth/v1/admin/generate_link",
+        MockResponse::json(
+            200,
+            &json!({
+                "id": "u1",
+                "email": "jane@example.com",
+                "action_link": "https://project.supabase.co/auth/v1/verify?token=abc&type=signup",
+                "email_otp": "123456",
+                "hashed_token": "abc",
+                "verification_type": "signup",
+                "redirect_to": "https://example.com/welcome",
+            }),
+        ),
+    );
+    let client: SupabaseClient = SupabaseClient::new(
+        "https://project.supabase.co".to_string(),
+        "service_role".to_string(),
+    )
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let link: GeneratedLink = client
+        .auth_admin()
+        .generate_link(
+            GenerateLinkParams::new(GenerateLinkType::Signup, "jane@example.com")
+                .password("hunter22")
+                .data(json!({"name": "Jane"}))
+                .redirect_to("https://example.com/welcome"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(link.hashed_token, "abc");
+    assert_eq!(link.email_otp, "123456");
+    assert_eq!(link.user.id, "u1");
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[0].headers["authorization"], "Bearer service_role");
+    assert_eq!(
+        requests[0].json().unwrap(),
+        json!({
+            "type": "signup",
+            "email": "jane@example.com",
+            "password": "hunter22",
+            "data": {"name": "Jane"},
+            "redirect_to": "https://example.com/welcome",
+        })
+    );
+
+    // links missing what their type needs aren't sent
+    for link_type in [
+        GenerateLinkType::Signup,
+        GenerateLinkType::EmailChangeCurrent,
+        GenerateLinkType::EmailChangeNew,
+    ] {
+        let error: ErrorTypes = client
+            .auth_admin()
+            .generate_link(GenerateLinkParams::new(link_type, "jane@example.com"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ErrorTypes::InvalidParameters(_)));
+    }
+    assert_eq!(transport.requests().len(), 1);
+
+    client
+        .auth_admin()
+        .generate_link(
+            GenerateLinkParams::new(GenerateLinkType::EmailChangeNew, "jane@example.com")
+                .new_email("jane@example.org"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        transport.requests()[1].json().unwrap(),
+        json!({"type": "email_change_new", "email": "jane@example.com", "new_email": "jane@example.org"})
+    );
+}
@@ -0,0 +1,71 @@
+use crate::auth::types::Session;
+use crate::auth::AuthClient;
+use crate::errors::ErrorTypes;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn auth_id_token() {
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                200,
+                &json!({
+                    "access_token": "jwt",
+                    "token_type": "bearer",
+                    "expires_in": 3600,
+                    "refresh_token": "refresh",
+                    "user": {"id": "u1", "identities": [{"provider": "apple"}]},
+                }),
+            ),
+        )
+        .once(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                400,
+                &json!({"code": 400, "error_code": "bad_oauth_callback", "msg": "Bad ID token"}),
+            ),
+        );
+    let mut auth: AuthClient = AuthClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap();
+    auth.client = SupabaseClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let session: Session = auth
+        .sign_in_with_id_token("apple", "id-token", Some("raw-nonce"))
+        .await
+        .unwrap();
+    assert_eq!(session.user.identities[0].provider, "apple");
+    assert_eq!(auth.session().unwrap().access_token, "jwt");
+
+    // tokens requested without a nonce are sent without one
+    let error: ErrorTypes = auth
+        .sign_in_with_id_token("google", "expired", None)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Bad ID token"));
+    assert_eq!(auth.session().unwrap().refresh_token, "refresh");
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[0].query("grant_type").as_deref(), Some("id_token"));
+    assert_eq!(
+        requests[0].json().unwrap(),
+        json!({"provider": "apple", "id_token": "id-token", "nonce": "raw-nonce"})
+    );
+    assert_eq!(
+        requests[1].json().unwrap(),
+        json!({"provider": "google", "id_token": "expired"})
+    );
+}
@@ -0,0 +1,92 @@
+use crate::auth::types::{Identity, Session};
+use crate::auth::AuthClient;
+use crate::errors::ErrorTypes;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn auth_identities() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/auth/v1/user",
+            MockResponse::json(
+                200,
+                &json!({
+                    "id": "u1",
+                    "identities": [
+                        {"identity_id": "i1", "id": "u1", "user_id": "u1", "provider": "email"},
+                        {"identity_id": "i2", "id": "1234", "user_id": "u1", "provider": "github"},
+                    ],
+                }),
+            ),
+        )
+        .on(
+            Method::GET,
+            "/auth/v1/user/identities/authorize",
+            MockResponse::json(
+                200,
+                &json!({"url": "https://github.com/login/oauth/authorize?client_id=abc"}),
+            ),
+        )
+        .once(
+            Method::DELETE,
+            "/auth/v1/user/identities/i2",
+            MockResponse::json(200, &json!({})),
+        )
+        .once(
+            Method::DELETE,
+            "/auth/v1/user/identities/i1",
+            MockResponse::json(
+                422,
+                &json!({"code": 422, "error_code": "single_identity_not_deletable", "msg": "User must have at least 1 identity after unlinking"}),
+            ),
+        );
+    let mut auth: AuthClient = AuthClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap();
+    auth.client = SupabaseClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap()
+    .with_transport(transport.clone());
+
+    // identities belong to the signed in user
+    assert!(matches!(
+        auth.link_identity("github").await,
+        Err(ErrorTypes::MissingSession)
+    ));
+    auth.set_session(Session {
+        access_token: "jwt".to_string(),
+        expires_in: 3600,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let identities: Vec<Identity> = auth.get_user_identities().await.unwrap();
+    let providers: Vec<&str> = identities
+        .iter()
+        .map(|identity| identity.provider.as_str())
+        .collect();
+    assert_eq!(providers, vec!["email", "github"]);
+
+    let url: String = auth.link_identity("github").await.unwrap();
+    assert!(url.starts_with("https://github.com/login/oauth/authorize"));
+
+    auth.unlink_identity("i2").await.unwrap();
+    let error: ErrorTypes = auth.unlink_identity("i1").await.unwrap_err();
+    assert!(error.to_string().contains("at least 1 identity"));
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[1].query("provider").as_deref(), Some("github"));
+    assert_eq!(
+        requests[1].query("skip_http_redirect").as_deref(),
+        Some("true")
+    );
+    assert_eq!(requests[1].headers["authorization"], "Bearer jwt");
+}
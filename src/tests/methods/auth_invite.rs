@@ -0,0 +1,64 @@
+use crate::auth::admin::InviteOptions;
+use crate::auth::types::User;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn auth_invite() {
+    let transport: MockTransport = MockTransport::new().on(
+        Method::POST,
+        "/auth/v1/invite",
+        MockResponse::json(
+            200,
+            &json!({
+                "id": "u1",
+                "email": "john@example.com",
+                "invited_at": "2024-05-09T14:05:33Z",
+                "user_metadata": {"team": "sales"},
+            }),
+        ),
+    );
+    let client: SupabaseClient = SupabaseClient::new(
+        "https://project.supabase.co".to_string(),
+        "service_role".to_string(),
+    )
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let user: User = client
+        .auth_admin()
+        .invite_user_by_email(
+            "john@example.com",
+            InviteOptions::new()
+                .data(json!({"team": "sales"}))
+                .redirect_to("https://example.com/onboarding"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(user.id, "u1");
+    assert_eq!(user.invited_at.as_deref(), Some("2024-05-09T14:05:33Z"));
+
+    client
+        .auth_admin()
+        .invite_user_by_email("jane@example.com", InviteOptions::new())
+        .await
+        .unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[0].headers["authorization"], "Bearer service_role");
+    assert_eq!(
+        requests[0].query("redirect_to").as_deref(),
+        Some("https://example.com/onboarding")
+    );
+    assert_eq!(
+        requests[0].json().unwrap(),
+        json!({"email": "john@example.com", "data": {"team": "sales"}})
+    );
+    assert_eq!(requests[1].query("redirect_to"), None);
+    assert_eq!(
+        requests[1].json().unwrap(),
+        json!({"email": "jane@example.com"})
+    );
+}
@@ -0,0 +1,54 @@
+use crate::auth::mfa::{enroll_body, TotpEnrollment};
+use crate::auth::types::{FactorStatus, Session, User};
+use crate::auth::{parse, AuthClient};
+use crate::errors::ErrorTypes;
+
+use serde_json::json;
+
+pub async fn auth_mfa() {
+    assert_eq!(
+        enroll_body(Some("Phone"), None),
+        json!({"factor_type": "totp", "friendly_name": "Phone"})
+    );
+
+    let enrollment: TotpEnrollment = parse(json!({
+        "id": "0a1b",
+        "type": "totp",
+        "friendly_name": "Phone",
+        "totp": {"qr_code": "data:image/svg+xml;utf-8,<svg/>", "secret": "JBSWY3DP", "uri": "otpauth://totp/x"}
+    }))
+    .unwrap();
+    assert_eq!(enrollment.factor_type, "totp");
+    assert_eq!(enrollment.totp.secret, "JBSWY3DP");
+
+    // factors are listed on the user
+    let user: User = parse(json!({
+        "id": "u1",
+        "factors": [
+            {"id": "0a1b", "factor_type": "totp", "status": "verified"},
+            {"id": "2c3d", "factor_type": "totp", "status": "unverified"}
+        ]
+    }))
+    .unwrap();
+    assert_eq!(user.factors[0].status, FactorStatus::Verified);
+    assert_eq!(user.factors[1].status, FactorStatus::Unverified);
+
+    // user-scoped calls need a session, clones share it
+    let auth: AuthClient =
+        AuthClient::new("http://localhost".to_string(), "anon-key".to_string()).unwrap();
+    assert!(matches!(
+        auth.list_factors().await,
+        Err(ErrorTypes::MissingSession)
+    ));
+
+    auth.clone()
+        .set_session(Session {
+            access_token: "jwt".to_string(),
+            expires_in: 3600,
+            ..Default::default()
+        })
+        .unwrap();
+    let session: Session = auth.session().unwrap();
+    assert_eq!(session.access_token, "jwt");
+    assert!(session.expires_at.unwrap() > 3600);
+}
@@ -0,0 +1,107 @@
+use crate::auth::phone::{SignUp, SmsChannel};
+use crate::auth::types::Session;
+use crate::auth::AuthClient;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn auth_phone() {
+    let session: Value = json!({
+        "access_token": "jwt",
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "refresh_token": "refresh",
+        "user": {"id": "u1", "phone": "31612345678"},
+    });
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/auth/v1/signup",
+            MockResponse::json(200, &json!({"id": "u1", "phone": "31612345678"})),
+        )
+        .once(
+            Method::POST,
+            "/auth/v1/signup",
+            MockResponse::json(200, &session),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/otp",
+            MockResponse::json(200, &json!({})),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/verify",
+            MockResponse::json(200, &session),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(200, &session),
+        );
+    let mut auth: AuthClient = AuthClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap();
+    auth.client = SupabaseClient::new(
+        "https://project.supabase.co".to_string(),
+        "anon-key".to_string(),
+    )
+    .unwrap()
+    .with_transport(transport.clone());
+
+    // projects confirming phone numbers return the user without a session
+    let SignUp::Unconfirmed(user) = auth
+        .sign_up_with_phone("+31612345678", "hunter22", SmsChannel::Sms)
+        .await
+        .unwrap()
+    else {
+        panic!("expected an unconfirmed user");
+    };
+    assert_eq!(user.phone.as_deref(), Some("31612345678"));
+    assert!(auth.session().is_none());
+
+    let confirmed: Session = auth.verify_sms_otp("+31612345678", "123456").await.unwrap();
+    assert_eq!(auth.session(), Some(confirmed));
+
+    // others sign the user in right away
+    let signed_up: SignUp = auth
+        .sign_up_with_phone("+31612345679", "hunter22", SmsChannel::Whatsapp)
+        .await
+        .unwrap();
+    assert!(matches!(signed_up, SignUp::Session(session) if session.access_token == "jwt"));
+
+    auth.sign_in_with_phone_password("+31612345678", "hunter22")
+        .await
+        .unwrap();
+    auth.send_sms_otp("+31612345678", SmsChannel::Whatsapp, false)
+        .await
+        .unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let bodies: Vec<Value> = requests
+        .iter()
+        .map(|request| request.json().unwrap())
+        .collect();
+    assert_eq!(
+        bodies[0],
+        json!({"phone": "+31612345678", "password": "hunter22", "channel": "sms"})
+    );
+    assert_eq!(
+        bodies[1],
+        json!({"type": "sms", "phone": "+31612345678", "token": "123456"})
+    );
+    assert_eq!(bodies[2]["channel"], "whatsapp");
+    assert_eq!(requests[3].query("grant_type").as_deref(), Some("password"));
+    assert_eq!(
+        bodies[3],
+        json!({"phone": "+31612345678", "password": "hunter22"})
+    );
+    assert_eq!(
+        bodies[4],
+        json!({"phone": "+31612345678", "channel": "whatsapp", "create_user": false})
+    );
+}
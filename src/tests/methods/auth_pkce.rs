@@ -0,0 +1,72 @@
+use crate::auth::pkce::{code_challenge, generate_code_verifier, Pkce};
+use crate::auth::AuthClient;
+use crate::errors::ErrorTypes;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::{Method, Url};
+use serde_json::json;
+use std::collections::HashMap;
+
+pub async fn auth_pkce() {
+    // the example of RFC 7636, appendix B
+    assert_eq!(
+        code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+    let verifier: String = generate_code_verifier();
+    assert_eq!(verifier.len(), 86);
+    assert_ne!(verifier, generate_code_verifier());
+    let pkce: Pkce = Pkce::new();
+    assert_eq!(pkce.challenge, code_challenge(&pkce.verifier));
+
+    let transport: MockTransport = MockTransport::new().on(
+        Method::POST,
+        "/auth/v1/token",
+        MockResponse::json(
+            200,
+            &json!({"access_token": "jwt", "expires_in": 3600, "refresh_token": "refresh"}),
+        ),
+    );
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport.clone());
+
+    let url: Url = Url::parse(
+        &auth
+            .authorize("github", Some("https://example.com/callback?next=/home"))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(url.path(), "/auth/v1/authorize");
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    assert_eq!(query["provider"], "github");
+    assert_eq!(
+        query["redirect_to"],
+        "https://example.com/callback?next=/home"
+    );
+    assert_eq!(query["code_challenge_method"], "s256");
+
+    // clones share the verifier, e.g. the client handling the callback
+    let session = auth
+        .clone()
+        .exchange_code_for_session("code")
+        .await
+        .unwrap();
+    assert_eq!(session.access_token, "jwt");
+    assert_eq!(auth.session().unwrap().access_token, "jwt");
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[0].query("grant_type").as_deref(), Some("pkce"));
+    let body = requests[0].json().unwrap();
+    assert_eq!(body["auth_code"], "code");
+    let verifier: &str = body["code_verifier"].as_str().unwrap();
+    assert_eq!(code_challenge(verifier), query["code_challenge"]);
+
+    // the verifier is used once
+    let error: ErrorTypes = auth.exchange_code_for_session("code").await.unwrap_err();
+    assert!(matches!(error, ErrorTypes::InvalidParameters(_)));
+    assert_eq!(transport.requests().len(), 1);
+}
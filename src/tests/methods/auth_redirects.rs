@@ -0,0 +1,85 @@
+use crate::auth::{AuthClient, MagicLinkOptions, SignUpOptions};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn auth_redirects() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/auth/v1/signup",
+            MockResponse::json(200, &json!({"id": "u1"})),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/otp",
+            MockResponse::json(200, &json!({})),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/recover",
+            MockResponse::json(200, &json!({})),
+        );
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport.clone());
+
+    auth.sign_up(
+        "jane@example.com",
+        "hunter22",
+        SignUpOptions::new().redirect_to("https://example.com/welcome?from=mail&step=1"),
+    )
+    .await
+    .unwrap();
+    auth.send_magic_link(
+        "jane@example.com",
+        MagicLinkOptions::new()
+            .redirect_to("https://example.com/home")
+            .data(json!({"referrer": "newsletter"}))
+            .create_user(false),
+    )
+    .await
+    .unwrap();
+    auth.send_magic_link("john@example.com", MagicLinkOptions::new())
+        .await
+        .unwrap();
+    auth.reset_password_for_email("jane@example.com", Some("https://example.com/reset"))
+        .await
+        .unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    // the redirect is percent-encoded, its own query isn't mixed with the one of the request
+    assert_eq!(
+        requests[0].query("redirect_to").as_deref(),
+        Some("https://example.com/welcome?from=mail&step=1")
+    );
+    assert_eq!(requests[0].query("from"), None);
+
+    assert_eq!(
+        requests[1].query("redirect_to").as_deref(),
+        Some("https://example.com/home")
+    );
+    assert_eq!(
+        requests[1].json().unwrap(),
+        json!({
+            "email": "jane@example.com",
+            "create_user": false,
+            "data": {"referrer": "newsletter"},
+        })
+    );
+    assert_eq!(requests[2].query("redirect_to"), None);
+    assert_eq!(requests[2].json().unwrap()["create_user"], true);
+
+    assert_eq!(
+        requests[3].query("redirect_to").as_deref(),
+        Some("https://example.com/reset")
+    );
+    assert_eq!(
+        requests[3].json().unwrap(),
+        json!({"email": "jane@example.com"})
+    );
+}
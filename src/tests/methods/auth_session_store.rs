@@ -0,0 +1,44 @@
+use crate::auth::session::{FileSessionStore, MemorySessionStore, SessionStore};
+use crate::auth::types::Session;
+use crate::auth::AuthClient;
+
+use std::path::PathBuf;
+
+pub async fn auth_session_store() {
+    let session: Session = Session {
+        access_token: "jwt".to_string(),
+        refresh_token: "refresh".to_string(),
+        expires_in: 3600,
+        expires_at: Some(1_700_000_000),
+        ..Default::default()
+    };
+
+    let memory: MemorySessionStore = MemorySessionStore::new();
+    assert!(memory.get().is_none());
+    memory.set(&session).unwrap();
+    assert_eq!(memory.get(), Some(session.clone()));
+    memory.clear().unwrap();
+    assert!(memory.get().is_none());
+
+    let path: PathBuf = std::env::temp_dir()
+        .join(format!("supabase_rs_session_{}", std::process::id()))
+        .join("session.json");
+    let file: FileSessionStore = FileSessionStore::new(&path);
+    assert!(file.get().is_none());
+    file.set(&session).unwrap();
+    assert_eq!(file.get(), Some(session.clone()));
+
+    // a new client picks up the session persisted by an earlier one
+    let auth: AuthClient = AuthClient::new("http://localhost".to_string(), "anon-key".to_string())
+        .unwrap()
+        .with_session_store(FileSessionStore::new(&path));
+    assert_eq!(auth.session().unwrap().refresh_token, "refresh");
+
+    file.clear().unwrap();
+    assert!(!path.exists());
+    // clearing twice is fine
+    file.clear().unwrap();
+    assert!(auth.session().is_none());
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,131 @@
+use crate::auth::session::{MemorySessionStore, SessionStore};
+use crate::auth::types::Session;
+use crate::auth::AuthClient;
+use crate::functions::InvokeOptions;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+fn bearer(request: &RecordedRequest) -> &str {
+    request.headers["authorization"].to_str().unwrap()
+}
+
+pub async fn auth_shared_session() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                200,
+                &json!({"access_token": "jwt", "expires_in": 3600, "refresh_token": "refresh"}),
+            ),
+        )
+        .on(Method::POST, "/auth/v1/logout", MockResponse::new(204))
+        .on(
+            Method::GET,
+            "/auth/v1/admin/users/u1",
+            MockResponse::json(200, &json!({"id": "u1"})),
+        )
+        .on(
+            Method::GET,
+            "/rest/v1/notes",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(
+            Method::POST,
+            "/functions/v1/hello",
+            MockResponse::json(200, &json!({})),
+        );
+    let supabase: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone())
+            .with_session_store(MemorySessionStore::new());
+    let clone: SupabaseClient = supabase.clone();
+    let auth: AuthClient = supabase.auth();
+
+    supabase.select("notes").execute().await.unwrap();
+    auth.sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap();
+    // the clones of the client follow the session too
+    clone.select("notes").execute().await.unwrap();
+    let _: Value = supabase
+        .functions()
+        .invoke("hello", json!({}), InvokeOptions::new())
+        .await
+        .unwrap();
+    // tokens set explicitly are kept
+    let _: Value = supabase
+        .functions()
+        .invoke(
+            "hello",
+            json!({}),
+            InvokeOptions::new().access_token("other"),
+        )
+        .await
+        .unwrap();
+    supabase
+        .impersonate_user("impersonated")
+        .select("notes")
+        .execute()
+        .await
+        .unwrap();
+    // admin calls keep the key of the client
+    supabase.auth_admin().get_user_by_id("u1").await.unwrap();
+    auth.sign_out().await.unwrap();
+    supabase.select("notes").execute().await.unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let bearers: Vec<&str> = requests.iter().map(bearer).collect();
+    assert_eq!(
+        bearers,
+        vec![
+            "Bearer key",
+            "Bearer key",
+            "Bearer jwt",
+            "Bearer jwt",
+            "Bearer other",
+            "Bearer impersonated",
+            "Bearer key",
+            "Bearer jwt",
+            "Bearer key",
+        ]
+    );
+
+    // a persisted session is picked up by the client and its Auth client
+    let store: MemorySessionStore = MemorySessionStore::new();
+    store
+        .set(&Session {
+            access_token: "persisted".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+    let supabase: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone())
+            .with_session_store(store);
+    assert_eq!(supabase.auth().session().unwrap().access_token, "persisted");
+    supabase.select("notes").execute().await.unwrap();
+    assert_eq!(
+        bearer(transport.requests().last().unwrap()),
+        "Bearer persisted"
+    );
+
+    // without a store the session stays with the Auth client
+    let supabase: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let auth: AuthClient = supabase.auth();
+    auth.sign_in_with_password("jane@example.com", "hunter22")
+        .await
+        .unwrap();
+    assert_eq!(auth.session().unwrap().access_token, "jwt");
+    supabase.select("notes").execute().await.unwrap();
+    assert_eq!(bearer(transport.requests().last().unwrap()), "Bearer key");
+    assert!(supabase.auth().session().is_none());
+}
@@ -0,0 +1,58 @@
+use crate::auth::types::Session;
+use crate::auth::{AuthClient, SignOutScope};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+
+fn session() -> Session {
+    Session {
+        access_token: "jwt".to_string(),
+        refresh_token: "refresh".to_string(),
+        ..Default::default()
+    }
+}
+
+pub async fn auth_sign_out_scope() {
+    let transport: MockTransport = MockTransport::new()
+        .once(Method::POST, "/auth/v1/logout", MockResponse::new(204))
+        .once(Method::POST, "/auth/v1/logout", MockResponse::new(204))
+        .once(Method::POST, "/auth/v1/logout", MockResponse::new(401));
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport.clone());
+
+    // the other sessions are revoked, this one stays signed in
+    auth.set_session(session()).unwrap();
+    auth.sign_out_with_scope(SignOutScope::Others)
+        .await
+        .unwrap();
+    assert!(auth.session().is_some());
+
+    auth.sign_out_with_scope(SignOutScope::Local).await.unwrap();
+    assert!(auth.session().is_none());
+
+    // the session is removed even when the server rejects the sign out
+    auth.set_session(session()).unwrap();
+    assert!(auth.sign_out().await.is_err());
+    assert!(auth.session().is_none());
+    // without a session there is nothing to revoke
+    auth.sign_out().await.unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let scopes: Vec<Option<String>> = requests
+        .iter()
+        .map(|request| request.query("scope"))
+        .collect();
+    assert_eq!(
+        scopes,
+        vec![
+            Some("others".to_string()),
+            Some("local".to_string()),
+            Some("global".to_string()),
+        ]
+    );
+    assert_eq!(requests[0].headers["authorization"], "Bearer jwt");
+}
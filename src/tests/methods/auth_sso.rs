@@ -0,0 +1,61 @@
+use crate::auth::pkce::code_challenge;
+use crate::auth::AuthClient;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn auth_sso() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/auth/v1/sso",
+            MockResponse::json(
+                200,
+                &json!({"url": "https://idp.acme.com/saml?request=abc"}),
+            ),
+        )
+        .on(
+            Method::POST,
+            "/auth/v1/token",
+            MockResponse::json(
+                200,
+                &json!({"access_token": "jwt", "expires_in": 3600, "refresh_token": "refresh"}),
+            ),
+        );
+    let mut auth: AuthClient =
+        AuthClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    auth.client = SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport.clone());
+
+    let url: String = auth
+        .sign_in_with_sso(
+            "1f8bc5a2-6b36-4a4e-9b0a-57a1b4a0f1c3",
+            Some("https://example.com/callback"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(url, "https://idp.acme.com/saml?request=abc");
+    auth.sign_in_with_sso("acme.com", None).await.unwrap();
+    auth.exchange_code_for_session("code").await.unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let first: Value = requests[0].json().unwrap();
+    assert_eq!(first["provider_id"], "1f8bc5a2-6b36-4a4e-9b0a-57a1b4a0f1c3");
+    assert_eq!(first["redirect_to"], "https://example.com/callback");
+    assert_eq!(first["skip_http_redirect"], true);
+    assert!(first.get("domain").is_none());
+
+    // the verifier of the latest sign in completes it
+    let second: Value = requests[1].json().unwrap();
+    assert_eq!(second["domain"], "acme.com");
+    assert!(second.get("redirect_to").is_none());
+    let exchange: Value = requests[2].json().unwrap();
+    assert_eq!(
+        code_challenge(exchange["code_verifier"].as_str().unwrap()),
+        second["code_challenge"]
+    );
+    assert_ne!(first["code_challenge"], second["code_challenge"]);
+}
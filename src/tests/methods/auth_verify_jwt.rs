@@ -0,0 +1,75 @@
+use crate::auth::{AuthClient, Claims};
+use crate::errors::ErrorTypes;
+
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub async fn auth_verify_jwt() {
+    let now: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let sign = |claims: serde_json::Value, secret: &str| {
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    };
+
+    let auth: AuthClient = AuthClient::new("http://localhost".to_string(), "anon-key".to_string())
+        .unwrap()
+        .with_jwt_secret("super-secret-jwt-token");
+
+    let token: String = sign(
+        json!({
+            "sub": "4d6b0f0e",
+            "aud": "authenticated",
+            "exp": now + 3600,
+            "role": "authenticated",
+            "email": "jane@example.com",
+            "aal": "aal1",
+            "app_metadata": {"provider": "email"}
+        }),
+        "super-secret-jwt-token",
+    );
+    let claims: Claims = auth.verify_jwt(&token).await.unwrap();
+    assert_eq!(claims.sub, "4d6b0f0e");
+    assert_eq!(claims.role, "authenticated");
+    assert_eq!(claims.email.as_deref(), Some("jane@example.com"));
+    assert_eq!(claims.app_metadata["provider"], "email");
+
+    // signed with another secret
+    let forged: String = sign(
+        json!({"sub": "4d6b0f0e", "aud": "authenticated", "exp": now + 3600}),
+        "guessed",
+    );
+    assert!(matches!(
+        auth.verify_jwt(&forged).await,
+        Err(ErrorTypes::InvalidJwt(_))
+    ));
+
+    // expired
+    let expired: String = sign(
+        json!({"sub": "4d6b0f0e", "aud": "authenticated", "exp": now - 3600}),
+        "super-secret-jwt-token",
+    );
+    assert!(matches!(
+        auth.verify_jwt(&expired).await,
+        Err(ErrorTypes::InvalidJwt(_))
+    ));
+
+    // without the secret a shared secret token can't be checked
+    let without_secret: AuthClient =
+        AuthClient::new("http://localhost".to_string(), "anon-key".to_string()).unwrap();
+    assert!(matches!(
+        without_secret.verify_jwt(&token).await,
+        Err(ErrorTypes::InvalidJwt(_))
+    ));
+    assert!(matches!(
+        auth.verify_jwt("not-a-jwt").await,
+        Err(ErrorTypes::InvalidJwt(_))
+    ));
+}
@@ -0,0 +1,74 @@
+use crate::batch::Batch;
+use crate::errors::ErrorTypes;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn batch_execute() {
+    let transport: MockTransport = MockTransport::new().on(
+        Method::POST,
+        "/rest/v1/rpc/supabase_rs_batch",
+        MockResponse::json(200, &json!([1, 2, 1, 3])),
+    );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    // an empty batch doesn't call the function
+    assert_eq!(client.batch().execute().await.unwrap(), Vec::<u64>::new());
+
+    let batch: Batch = client
+        .batch()
+        .insert("orders", json!({"id": 1, "user_id": 7}))
+        .upsert(
+            "stock",
+            json!([{"sku": "a", "count": 1}, {"sku": "b", "count": 2}]),
+            &["sku"],
+        )
+        .update("users", &[("id", "7")], json!({"last_order_id": 1}))
+        .delete("carts", &[("user_id", "7")]);
+    assert_eq!(batch.len(), 4);
+    assert_eq!(batch.execute().await.unwrap(), vec![1, 2, 1, 3]);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    let operations: Value = requests[0].json().unwrap()["operations"].clone();
+    assert_eq!(
+        operations,
+        json!([
+            {"op": "insert", "table": "orders", "rows": [{"id": 1, "user_id": 7}]},
+            {
+                "op": "upsert",
+                "table": "stock",
+                "rows": [{"sku": "a", "count": 1}, {"sku": "b", "count": 2}],
+                "on_conflict": ["sku"],
+            },
+            {
+                "op": "update",
+                "table": "users",
+                "filters": {"id": "7"},
+                "values": {"last_order_id": 1},
+            },
+            {"op": "delete", "table": "carts", "filters": {"user_id": "7"}},
+        ])
+    );
+
+    // invalid operations fail the whole batch before anything is sent
+    let unfiltered = client
+        .batch()
+        .insert("orders", json!({"id": 2}))
+        .delete("carts", &[])
+        .execute()
+        .await;
+    assert!(matches!(unfiltered, Err(ErrorTypes::InvalidParameters(_))));
+    let not_a_row = client
+        .batch()
+        .insert("orders", json!([1, 2]))
+        .execute()
+        .await;
+    assert!(matches!(not_a_row, Err(ErrorTypes::InvalidParameters(_))));
+    assert_eq!(transport.requests().len(), 1);
+}
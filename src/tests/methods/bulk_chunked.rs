@@ -0,0 +1,68 @@
+use crate::bulk::{BulkChunk, BulkOptions, BulkReport};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn bulk_chunked() {
+    let rows: Vec<Value> = (0..5).map(|id| json!({ "id": id })).collect();
+    let rejected = json!({"code": "23502", "message": "null value in column \"name\""});
+
+    let transport: MockTransport = MockTransport::new()
+        .once(Method::POST, "/rest/v1/events", MockResponse::new(201))
+        .once(
+            Method::POST,
+            "/rest/v1/events",
+            MockResponse::json(400, &rejected),
+        )
+        .on(Method::POST, "/rest/v1/events", MockResponse::new(201));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    // the failure of the second chunk stops the third one
+    let report: BulkReport = client
+        .bulk_insert_chunked("events", rows.clone(), BulkOptions::new().chunk_size(2))
+        .await
+        .unwrap();
+    assert_eq!(
+        report.succeeded,
+        vec![BulkChunk {
+            index: 0,
+            rows: 0..2
+        }]
+    );
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].chunk.rows, 2..4);
+    assert!(report.failed[0].error.contains("[23502]"));
+    assert_eq!(
+        report.skipped,
+        vec![BulkChunk {
+            index: 2,
+            rows: 4..5
+        }]
+    );
+    assert!(!report.is_complete());
+    assert_eq!(report.rows_written(), 2);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].json().unwrap(), json!([{"id": 2}, {"id": 3}]));
+
+    let report: BulkReport = client
+        .bulk_insert_chunked(
+            "events",
+            rows,
+            BulkOptions::new()
+                .chunk_size(2)
+                .parallelism(3)
+                .continue_on_error(true),
+        )
+        .await
+        .unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.rows_written(), 5);
+    assert_eq!(transport.requests().len(), 5);
+}
@@ -0,0 +1,97 @@
+use crate::import::{ImportOptions, ImportProgress, ImportReport};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub async fn bulk_import() {
+    let transport: MockTransport = MockTransport::new()
+        .once(Method::POST, "/rest/v1/animals", MockResponse::new(201))
+        .once(
+            Method::POST,
+            "/rest/v1/animals",
+            MockResponse::new(503).with_header("Retry-After", "0"),
+        )
+        .on(Method::POST, "/rest/v1/animals", MockResponse::new(201));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let progress: Arc<Mutex<Vec<ImportProgress>>> = Arc::default();
+    let reported: Arc<Mutex<Vec<ImportProgress>>> = progress.clone();
+    let options: ImportOptions = ImportOptions::new()
+        .batch_size(2)
+        .retry_backoff(Duration::from_millis(1))
+        .on_progress(move |progress| reported.lock().unwrap().push(progress.clone()));
+
+    let csv: &[u8] = b"name,age\nscooby,7\nrex,\n\"fido, jr\",3\nlassie,12\nodie,4\n";
+    let report: ImportReport = client.import_csv("animals", csv, options).await.unwrap();
+    assert_eq!(
+        report,
+        ImportReport {
+            rows_imported: 5,
+            chunks: 3,
+            retries: 1,
+        }
+    );
+
+    let progress = progress.lock().unwrap().clone();
+    assert_eq!(progress.len(), 3);
+    assert_eq!(progress[1].retries, 1);
+    assert_eq!(progress[2].rows, 1);
+    assert_eq!(progress[2].rows_imported, 5);
+
+    let requests = transport.requests();
+    // the second chunk was sent again after the 503
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        requests[0].json().unwrap(),
+        json!([{"name": "scooby", "age": "7"}, {"name": "rex", "age": null}])
+    );
+    assert_eq!(requests[1].json(), requests[2].json());
+    assert_eq!(requests[2].json().unwrap()[0]["name"], "fido, jr");
+
+    // rows of NDJSON may set different columns
+    let ndjson: &[u8] = b"{\"name\": \"scooby\", \"adopted\": true}\n\n{\"name\": \"rex\"}\n";
+    let report: ImportReport = client
+        .import_ndjson("animals", ndjson, ImportOptions::new().missing_default())
+        .await
+        .unwrap();
+    assert_eq!(report.rows_imported, 2);
+    let request = &transport.requests()[4];
+    assert_eq!(request.query("columns").as_deref(), Some("adopted,name"));
+
+    // a broken line stops the import before sending its chunk
+    let ndjson: &[u8] = b"{\"name\": \"scooby\"}\n[1, 2]\n";
+    let error = client
+        .import_ndjson("animals", ndjson, ImportOptions::new())
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Line 2 is not a JSON object");
+    assert_eq!(transport.requests().len(), 5);
+
+    // only chunks the server never received are sent again, a 502 may have been imported
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/rest/v1/animals",
+            MockResponse::new(429).with_header("Retry-After", "0"),
+        )
+        .on(Method::POST, "/rest/v1/animals", MockResponse::new(502));
+    let client: SupabaseClient = client.with_transport(transport.clone());
+    let ndjson: &[u8] = b"{\"name\": \"scooby\"}\n";
+    let error = client
+        .import_ndjson("animals", ndjson, ImportOptions::new())
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().starts_with("Chunk 1 failed, 0 rows"),
+        "{}",
+        error
+    );
+    assert_eq!(transport.requests().len(), 2);
+}
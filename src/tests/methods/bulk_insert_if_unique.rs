@@ -0,0 +1,69 @@
+use crate::errors::DUPLICATE_ENTRY;
+use crate::insert::{in_filter, unique_key, InsertVerdict};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Map, Value};
+
+pub async fn bulk_insert_if_unique() {
+    let row: Map<String, Value> = json!({"email": "jane@example.com", "tenant": 7, "name": "Jane"})
+        .as_object()
+        .unwrap()
+        .clone();
+
+    // strings are compared as-is, other values by their JSON text, like in filters
+    assert_eq!(
+        unique_key(&row, &["email", "tenant"]),
+        vec!["jane@example.com".to_string(), "7".to_string()]
+    );
+
+    // values are quoted so commas and quotes don't split the list
+    assert_eq!(in_filter(["b", "a,c"]), r#"in.("a,c","b")"#);
+    assert_eq!(in_filter([r#"say "hi""#]), r#"in.("say \"hi\"")"#);
+
+    assert!(InsertVerdict::Inserted.is_inserted());
+    assert!(InsertVerdict::Duplicate.is_duplicate());
+    assert!(!InsertVerdict::Failed("500".to_string()).is_inserted());
+
+    // a racing insert is found row by row, only a `23505` counts as a duplicate
+    let conflict: MockResponse = MockResponse::json(
+        409,
+        &json!({"code": "23505", "message": "duplicate key value violates unique constraint"}),
+    );
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::new(200)
+                .with_header("Content-Range", "*/*")
+                .with_body("[]"),
+        )
+        .once(Method::POST, "/rest/v1/users", conflict.clone())
+        .once(Method::POST, "/rest/v1/users", MockResponse::new(201))
+        .once(Method::POST, "/rest/v1/users", conflict)
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(400, &json!({"code": "23514", "message": DUPLICATE_ENTRY})),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let rows: Vec<Value> = vec![
+        json!({"email": "jane@example.com"}),
+        json!({"email": "john@example.com"}),
+        json!({"email": "joan@example.com"}),
+    ];
+    let verdicts: Vec<InsertVerdict> = client
+        .bulk_insert_if_unique("users", rows, &["email"])
+        .await
+        .unwrap();
+    assert_eq!(verdicts[0], InsertVerdict::Inserted);
+    assert_eq!(verdicts[1], InsertVerdict::Duplicate);
+    assert!(
+        matches!(&verdicts[2], InsertVerdict::Failed(error) if error.contains(DUPLICATE_ENTRY))
+    );
+    assert_eq!(transport.requests().len(), 5);
+}
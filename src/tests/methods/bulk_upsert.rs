@@ -0,0 +1,49 @@
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn bulk_upsert() {
+    let merged = json!([
+        {"id": 1, "email": "jane@example.com", "name": "Jane"},
+        {"id": 2, "email": "john@example.com", "name": "John"},
+    ]);
+    let transport: MockTransport = MockTransport::new().on(
+        Method::POST,
+        "/rest/v1/users",
+        MockResponse::json(201, &merged),
+    );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let rows: Vec<Value> = vec![
+        json!({"email": "jane@example.com", "name": "Jane"}),
+        json!({"email": "john@example.com", "name": "John"}),
+    ];
+    let users: Vec<Value> = client
+        .bulk_upsert("users", rows.clone(), &["email"])
+        .await
+        .unwrap();
+    assert_eq!(Value::Array(users), merged);
+
+    assert!(client.bulk_upsert("users", rows, &[]).await.is_err());
+    assert_eq!(
+        client
+            .bulk_upsert::<Value>("users", Vec::new(), &["email"])
+            .await,
+        Ok(Vec::new())
+    );
+
+    // both rows in one request
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].query("on_conflict").as_deref(), Some("email"));
+    assert_eq!(
+        requests[0].headers["prefer"],
+        "resolution=merge-duplicates,return=representation"
+    );
+    assert_eq!(requests[0].json().unwrap().as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,75 @@
+use crate::errors::ErrorTypes;
+use crate::transport::{HttpTransport, TransportFuture};
+use crate::SupabaseClient;
+
+use reqwest::Request;
+use std::net::TcpListener;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Fails every request with a transport error reading like a timeout or a cancellation.
+#[derive(Debug)]
+struct FailingTransport(String);
+
+impl HttpTransport for FailingTransport {
+    fn execute(&self, _request: Request) -> TransportFuture<'_> {
+        Box::pin(async move { Err(self.0.clone()) })
+    }
+}
+
+pub async fn cancellation() {
+    // a server that accepts connections but never responds
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url: String = format!("http://{}", listener.local_addr().unwrap());
+    let client: SupabaseClient = SupabaseClient::new(url, "key".to_string()).unwrap();
+
+    // an already cancelled token never sends the request
+    let token: CancellationToken = CancellationToken::new();
+    token.cancel();
+    let result = client.select("test").abort_signal(token).execute().await;
+    assert_eq!(result.unwrap_err(), ErrorTypes::Cancelled.to_string());
+
+    // an in-flight request is aborted once the token is cancelled
+    let token: CancellationToken = CancellationToken::new();
+    let canceller: CancellationToken = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        canceller.cancel();
+    });
+
+    let result = client
+        .select("test")
+        .abort_signal(token)
+        .execute_with_count()
+        .await;
+    assert_eq!(result.unwrap_err(), ErrorTypes::Cancelled.to_string());
+
+    // calls of Postgres functions are aborted the same way
+    let token: CancellationToken = CancellationToken::new();
+    token.cancel();
+    let result = client
+        .rpc("slow", serde_json::json!({}))
+        .abort_signal(token)
+        .execute()
+        .await;
+    assert!(matches!(result, Err(ErrorTypes::Cancelled)));
+
+    // the errors are passed on as they are, a transport error with the same text stays one
+    for message in [
+        ErrorTypes::Cancelled.to_string(),
+        ErrorTypes::Timeout.to_string(),
+    ] {
+        let client: SupabaseClient =
+            SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+                .unwrap()
+                .with_transport(FailingTransport(message.clone()));
+        let result = client
+            .rpc("slow", serde_json::json!({}))
+            .abort_signal(CancellationToken::new())
+            .execute()
+            .await;
+        assert!(matches!(result, Err(ErrorTypes::RequestFailed(error)) if error == message));
+    }
+
+    drop(listener);
+}
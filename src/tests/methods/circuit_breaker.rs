@@ -0,0 +1,46 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitPermit, CircuitState};
+use std::time::Duration;
+
+pub async fn circuit_breaker() {
+    let breaker: CircuitBreaker = CircuitBreaker::new(CircuitBreakerConfig {
+        failure_threshold: 2,
+        open_duration: Duration::from_millis(50),
+        half_open_max_probes: 1,
+    });
+    let host: &str = "example.supabase.co";
+
+    // failures below the threshold keep the circuit closed
+    breaker.record_failure(host);
+    assert_eq!(breaker.state(host), CircuitState::Closed);
+    assert!(breaker.try_acquire(host).is_ok());
+
+    // reaching the threshold opens it and requests fail fast
+    breaker.record_failure(host);
+    assert_eq!(breaker.state(host), CircuitState::Open);
+    assert!(breaker.try_acquire(host).is_err());
+
+    // other hosts are unaffected
+    assert!(breaker.try_acquire("other.supabase.co").is_ok());
+
+    // after the open duration a single probe is let through
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(breaker.state(host), CircuitState::HalfOpen);
+    let probe: CircuitPermit = breaker.try_acquire(host).unwrap();
+    assert!(breaker.try_acquire(host).is_err());
+
+    // a probe dropped before its response, e.g. on a timeout, frees its slot
+    drop(probe);
+    assert_eq!(breaker.state(host), CircuitState::HalfOpen);
+    let probe: CircuitPermit = breaker.try_acquire(host).unwrap();
+
+    // a failed probe re-opens the circuit
+    probe.failure();
+    assert_eq!(breaker.state(host), CircuitState::Open);
+    assert!(breaker.try_acquire(host).is_err());
+
+    // a successful probe closes it again
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    breaker.try_acquire(host).unwrap().success();
+    assert_eq!(breaker.state(host), CircuitState::Closed);
+    assert!(breaker.try_acquire(host).is_ok());
+}
@@ -0,0 +1,29 @@
+use crate::stats::{column_stats_query, parse_column_stats, ColumnStats};
+use serde_json::{json, Value};
+
+pub async fn column_stats() {
+    assert_eq!(
+        column_stats_query("price"),
+        "select=min:price.min(),max:price.max(),count:price.count(),rows:count()"
+    );
+
+    let stats: ColumnStats =
+        parse_column_stats(&json!({"min": 1.5, "max": 99, "count": 8, "rows": 10})).unwrap();
+    assert_eq!(
+        stats,
+        ColumnStats {
+            min: json!(1.5),
+            max: json!(99),
+            count: 8,
+            null_count: 2,
+        }
+    );
+
+    // an empty table has no min or max
+    let stats: ColumnStats =
+        parse_column_stats(&json!({"min": null, "max": null, "count": 0, "rows": 0})).unwrap();
+    assert_eq!(stats.min, Value::Null);
+    assert_eq!(stats.null_count, 0);
+
+    assert!(parse_column_stats(&json!({"min": 1})).is_err());
+}
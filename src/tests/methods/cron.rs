@@ -0,0 +1,42 @@
+use crate::cron::{parse_jobs, CronJob};
+use crate::errors::ErrorTypes;
+use serde_json::{json, Value};
+
+pub async fn cron() {
+    let rows: Value = json!([
+        {
+            "jobid": 1,
+            "jobname": "nightly-cleanup",
+            "schedule": "0 3 * * *",
+            "command": "delete from sessions where expired",
+            "nodename": "localhost",
+            "nodeport": 5432,
+            "database": "postgres",
+            "username": "postgres",
+            "active": true
+        },
+        {
+            "jobid": 2,
+            "jobname": null,
+            "schedule": "30 seconds",
+            "command": "select 1",
+            "nodename": "localhost",
+            "nodeport": 5432,
+            "database": "postgres",
+            "username": "postgres",
+            "active": false
+        }
+    ]);
+
+    let jobs: Vec<CronJob> = parse_jobs(rows).unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].jobname.as_deref(), Some("nightly-cleanup"));
+    assert!(jobs[0].active);
+    assert_eq!(jobs[1].jobname, None);
+
+    // rows that aren't jobs are an error naming what was wrong
+    assert!(matches!(
+        parse_jobs(json!([{"jobid": "one"}])),
+        Err(ErrorTypes::UnexpectedResponse(message)) if message.contains("invalid type")
+    ));
+}
@@ -0,0 +1,25 @@
+use crate::SupabaseClient;
+
+pub async fn delete_builder() {
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "key".to_string()).unwrap();
+
+    let query_string: Result<String, String> = client
+        .delete_from("sessions")
+        .eq("user_id", "42")
+        .lt("expires_at", "2024-01-01")
+        .in_("device", &["ios", "android, tv"])
+        .build();
+    assert_eq!(
+        query_string.unwrap(),
+        r#"user_id=eq.42&expires_at=lt.2024-01-01&device=in.("android, tv","ios")"#
+    );
+
+    // a delete without filters is refused before anything is sent
+    let result = client.delete_from("sessions").execute().await;
+    assert!(result.unwrap_err().contains("allow_unfiltered"));
+
+    let query_string: Result<String, String> =
+        client.delete_from("sessions").allow_unfiltered().build();
+    assert_eq!(query_string.unwrap(), "");
+}
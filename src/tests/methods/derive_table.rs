@@ -0,0 +1,53 @@
+use crate::table::{Column, SupabaseTable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, crate::SupabaseTable)]
+#[supabase(table = "users")]
+pub struct User {
+    #[supabase(primary_key)]
+    pub user_id: i64,
+    pub email: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    #[serde(skip)]
+    pub cached: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, crate::SupabaseTable)]
+pub struct AuditLog {
+    pub id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, crate::SupabaseTable)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Profile {
+    pub user_id: i64,
+    #[serde(rename(serialize = "user_id", deserialize = "user_id"))]
+    pub legacy_user_id: i64,
+    #[serde(rename = "self")]
+    pub own: bool,
+    #[serde(rename = "stats.visits", default)]
+    pub visits: i64,
+}
+
+pub async fn derive_table() {
+    assert_eq!(User::table_name(), "users");
+    assert_eq!(User::columns(), &["user_id", "email", "displayName"]);
+    assert_eq!(User::primary_key(), "user_id");
+
+    assert_eq!(UserColumn::Email.name(), "email");
+    assert_eq!(UserColumn::DisplayName.to_string(), "displayName");
+
+    assert_eq!(AuditLog::table_name(), "audit_log");
+    assert_eq!(AuditLog::primary_key(), "id");
+
+    // serde renames, with distinct variants for columns of the same PascalCase name
+    assert_eq!(
+        Profile::columns(),
+        &["userId", "user_id", "self", "stats.visits"]
+    );
+    assert_eq!(ProfileColumn::UserId.name(), "userId");
+    assert_eq!(ProfileColumn::UserId2.name(), "user_id");
+    assert_eq!(ProfileColumn::Self_.name(), "self");
+    assert_eq!(ProfileColumn::StatsVisits.name(), "stats.visits");
+}
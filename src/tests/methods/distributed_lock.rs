@@ -0,0 +1,29 @@
+use crate::errors::ErrorTypes;
+use crate::lock::Lock;
+use crate::testing::SupabaseMock;
+use crate::SupabaseClient;
+
+use serde_json::json;
+use std::time::Duration;
+
+pub async fn distributed_lock() {
+    // every handle gets its own holder token, so instances can't release each other's locks
+    let first: Lock = Lock::new("nightly-report");
+    let second: Lock = Lock::new("nightly-report");
+    assert_eq!(first.holder.len(), 32);
+    assert_ne!(first.holder, second.holder);
+
+    assert_eq!(
+        first.try_lock_params(Duration::from_millis(1500)),
+        json!({"lock_name": "nightly-report", "lock_holder": first.holder, "ttl_seconds": 1.5})
+    );
+
+    // a response that isn't a boolean names what was returned
+    let client: SupabaseClient = SupabaseMock::new()
+        .rpc("supabase_rs_unlock", json!("released"))
+        .client();
+    assert!(matches!(
+        client.unlock(&first).await,
+        Err(ErrorTypes::UnexpectedResponse(message)) if message == "expected a boolean, got \"released\""
+    ));
+}
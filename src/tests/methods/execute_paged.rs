@@ -0,0 +1,90 @@
+use crate::interop::PostgrestCompat;
+use crate::query::OrderOptions;
+use crate::query_builder::paginate::Page;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn execute_paged() {
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            "/rest/v1/animals",
+            MockResponse::json(206, &json!([{"id": 26}, {"id": 27}]))
+                .with_header("content-range", "25-26/27"),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/animals",
+            MockResponse::json(206, &json!([{"id": 1}, {"id": 2}]))
+                .with_header("content-range", "0-1/27"),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/animals",
+            MockResponse::new(416).with_header("content-range", "*/27"),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let last: Page<Value> = client
+        .select("animals")
+        .order_with(OrderOptions::asc("id"))
+        .execute_paged(2, 25)
+        .await
+        .unwrap();
+    assert_eq!(last.items, vec![json!({"id": 26}), json!({"id": 27})]);
+    assert_eq!(last.total, 27);
+    assert_eq!(last.range, 25..27);
+    assert!(!last.has_more);
+    assert_eq!(last.total_pages(), 2);
+    assert_eq!(last.next_page(), None);
+    assert_eq!(last.previous_page(), Some(1));
+
+    let first: Page<Value> = client.select("animals").execute_paged(1, 2).await.unwrap();
+    assert_eq!(first.range, 0..2);
+    assert!(first.has_more);
+    assert_eq!(first.total_pages(), 14);
+    assert_eq!(first.next_page(), Some(2));
+    assert_eq!(first.previous_page(), None);
+
+    let past_the_end: Page<Value> = client.select("animals").execute_paged(5, 25).await.unwrap();
+    assert!(past_the_end.items.is_empty());
+    assert_eq!(past_the_end.total, 27);
+    assert_eq!(past_the_end.range, 100..100);
+    assert!(!past_the_end.has_more);
+
+    assert!(client.select("animals").execute_paged(0, 25).await.is_err());
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].headers["range"], "25-49");
+    assert_eq!(requests[0].headers["range-unit"], "items");
+    assert!(requests[0].headers["prefer"]
+        .to_str()
+        .unwrap()
+        .contains("count=exact"));
+    assert_eq!(requests[1].headers["range"], "0-1");
+
+    // pages replace a limit or offset of the query instead of sending it twice
+    let transport: MockTransport = MockTransport::new().on(
+        Method::GET,
+        "/rest/v1/animals",
+        MockResponse::json(200, &json!([{"id": 1}])).with_header("content-range", "0-0/*"),
+    );
+    let rows: Vec<Value> = client
+        .with_transport(transport.clone())
+        .select("animals")
+        .limit(10)
+        .paginate(2)
+        .collect_all()
+        .await
+        .unwrap();
+    assert_eq!(rows, vec![json!({"id": 1})]);
+    let query: String = transport.requests()[0].url.query().unwrap().to_string();
+    assert_eq!(query, "limit=2&offset=0");
+}
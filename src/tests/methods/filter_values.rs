@@ -0,0 +1,122 @@
+use crate::errors::{ErrorTypes, RestError};
+use crate::query::FilterValue;
+use crate::transport::MockTransport;
+use crate::SupabaseClient;
+
+pub async fn filter_values() {
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+
+    let query: String = client
+        .select("users")
+        .eq("name", "scooby")
+        .eq("age", 30)
+        .gte("score", 1.5)
+        .lt("balance", f64::NEG_INFINITY)
+        .eq("active", true)
+        .eq("deleted_at", None::<&str>)
+        .neq("email", Option::<String>::None)
+        .neq("plan", Some("free"))
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "name=eq.scooby&age=eq.30&score=gte.1.5&balance=lt.-Infinity&active=eq.true\
+         &deleted_at=is.null&email=not.is.null&plan=neq.free"
+    );
+
+    // owned strings keep working like before
+    let name: String = "scooby".to_string();
+    let query: String = client
+        .delete_from("users")
+        .eq("name", &name)
+        .lte("age", 7_u8)
+        .build()
+        .unwrap();
+    assert_eq!(query, "name=eq.scooby&age=lte.7");
+
+    // NULL matches with `eq`, `is` and `neq` only
+    assert_eq!(FilterValue::Null.with_operator("eq").unwrap(), "is.null");
+    assert_eq!(FilterValue::Null.with_operator("is").unwrap(), "is.null");
+    assert_eq!(
+        FilterValue::Null.with_operator("neq").unwrap(),
+        "not.is.null"
+    );
+    for operator in ["gt", "lt", "gte", "lte", "like"] {
+        assert!(matches!(
+            FilterValue::Null.with_operator(operator),
+            Err(ErrorTypes::InvalidParameters(message)) if message.contains(operator)
+        ));
+    }
+
+    // and the builders fail before a request is sent
+    let transport: MockTransport = MockTransport::new();
+    let client: SupabaseClient = client.with_transport(transport.clone());
+    let error: RestError = client
+        .select("users")
+        .gt("age", None::<i64>)
+        .fetch()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::InvalidParameters(message)) if message.starts_with("age: ")
+    ));
+    let error: RestError = client
+        .delete_from("users")
+        .lte("age", None::<i64>)
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::InvalidParameters(_))
+    ));
+    let error: RestError = client
+        .update_table("users")
+        .gte("age", None::<i64>)
+        .set(serde_json::json!({"name": "scooby"}))
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::InvalidParameters(_))
+    ));
+    assert!(transport.requests().is_empty());
+
+    assert_eq!(
+        FilterValue::from(Some(2_i64)),
+        FilterValue::Text("2".to_string())
+    );
+    assert_eq!(FilterValue::from(None::<bool>), FilterValue::Null);
+
+    #[cfg(feature = "uuid")]
+    assert_eq!(
+        FilterValue::from(uuid::Uuid::nil()),
+        FilterValue::Text("00000000-0000-0000-0000-000000000000".to_string())
+    );
+
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+        let timestamp = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 5, 1, 12, 30, 0)
+            .unwrap();
+        assert_eq!(
+            FilterValue::from(timestamp),
+            FilterValue::Text("2024-05-01T10:30:00Z".to_string())
+        );
+        let date: NaiveDate = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(
+            FilterValue::from(date),
+            FilterValue::Text("2024-05-01".to_string())
+        );
+        assert_eq!(
+            FilterValue::from(date.and_hms_opt(8, 0, 0).unwrap()),
+            FilterValue::Text("2024-05-01T08:00:00".to_string())
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::table::{Column, SupabaseTable};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: String,
+    email: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UserColumn {
+    Email,
+}
+
+impl Column for UserColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            UserColumn::Email => "email",
+        }
+    }
+}
+
+impl SupabaseTable for User {
+    type Column = UserColumn;
+
+    fn table_name() -> &'static str {
+        "users"
+    }
+
+    fn columns() -> &'static [&'static str] {
+        &["id", "email"]
+    }
+}
+
+pub async fn find_by() {
+    let user: Value = json!({"id": "8c5b", "email": "a@b.c"});
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([user])),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([])),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([{"id": "8c5b"}])),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(200, &json!([{"id": 42}])),
+        )
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([user])),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let found: Option<Value> = client.find_one_by("users", "email", "a@b.c").await.unwrap();
+    assert_eq!(found, Some(user));
+    let missing: Option<Value> = client.find_one_by("users", "email", "x@y.z").await.unwrap();
+    assert_eq!(missing, None);
+
+    // text ids come back without their JSON quotes
+    let id: Option<String> = client.find_id_by("users", "email", "a@b.c").await.unwrap();
+    assert_eq!(id.as_deref(), Some("8c5b"));
+    let id: Option<String> = client.find_id_by("orders", "number", 1001).await.unwrap();
+    assert_eq!(id.as_deref(), Some("42"));
+
+    let row: Option<User> = client
+        .find_row_by::<User>(UserColumn::Email, "a@b.c")
+        .await
+        .unwrap();
+    assert_eq!(row.unwrap().email, "a@b.c");
+
+    // lookups on several columns
+    let member: Option<User> = client
+        .select("users")
+        .eq("org_id", 7)
+        .eq("email", "a@b.c")
+        .first_typed::<User>()
+        .await
+        .unwrap();
+    assert!(member.is_some());
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests.len(), 6);
+    assert!(requests
+        .iter()
+        .all(|request| request.query("limit").as_deref() == Some("1")));
+    assert_eq!(requests[0].query("email").as_deref(), Some("eq.a@b.c"));
+    assert_eq!(requests[2].query("select").as_deref(), Some("id"));
+    assert_eq!(requests[3].query("number").as_deref(), Some("eq.1001"));
+    assert_eq!(requests[5].query("org_id").as_deref(), Some("eq.7"));
+}
@@ -0,0 +1,63 @@
+use crate::errors::{Error, ErrorKind};
+use crate::fixtures::Fixtures;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn fixtures() {
+    let remote: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    let error: Error = remote.fixtures().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidRequest);
+
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/rest/v1/rpc/supabase_rs_truncate",
+            MockResponse::new(204),
+        )
+        .on(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(201, &json!([{"id": 1, "email": "jane@example.com"}])),
+        )
+        .on(
+            Method::DELETE,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([{"id": 1}, {"id": 2}])),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("http://127.0.0.1:54321".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let fixtures: Fixtures = client.fixtures().unwrap();
+
+    fixtures
+        .truncate_tables(&["orders", "users"])
+        .await
+        .unwrap();
+    let users: Vec<Value> = fixtures
+        .seed_table("users", vec![json!({"email": "jane@example.com"})])
+        .await
+        .unwrap();
+    assert_eq!(users[0]["id"], 1);
+    assert_eq!(fixtures.delete_all_rows("users", "id").await.unwrap(), 2);
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].json().unwrap(),
+        json!({"table_names": ["orders", "users"], "schema_name": "public", "restart_identity": true})
+    );
+    assert_eq!(requests[1].query("columns").as_deref(), Some("email"));
+    assert_eq!(requests[2].query("id").as_deref(), Some("not.is.null"));
+
+    // seeding nothing sends nothing
+    assert!(fixtures
+        .seed_table::<Value>("users", Vec::new())
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(transport.requests().len(), 3);
+}
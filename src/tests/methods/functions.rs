@@ -0,0 +1,25 @@
+use crate::functions::InvokeOptions;
+use crate::SupabaseClient;
+
+pub async fn functions() {
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "anon-key".to_string()).unwrap();
+
+    let headers = client
+        .functions()
+        .headers(&InvokeOptions::new())
+        .get_headers();
+    assert_eq!(headers["Authorization"], "Bearer anon-key");
+    assert_eq!(headers["apikey"], "anon-key");
+
+    // a user token replaces the client key as bearer, the api key stays the same
+    let options: InvokeOptions = InvokeOptions::new()
+        .access_token("user-jwt")
+        .region("eu-central-1")
+        .header("x-report", "monthly");
+    let headers = client.functions().headers(&options).get_headers();
+    assert_eq!(headers["Authorization"], "Bearer user-jwt");
+    assert_eq!(headers["apikey"], "anon-key");
+    assert_eq!(headers["x-region"], "eu-central-1");
+    assert_eq!(headers["x-report"], "monthly");
+}
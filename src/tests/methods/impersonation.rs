@@ -0,0 +1,84 @@
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+/// The `Authorization` header of a recorded request.
+fn bearer(request: &RecordedRequest) -> &str {
+    request.headers["authorization"].to_str().unwrap()
+}
+
+pub async fn impersonation() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/notes",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(
+            Method::POST,
+            "/rest/v1/rpc/my_notes",
+            MockResponse::new(200),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let jane: SupabaseClient = client.impersonate_user("jane-token");
+    jane.select("notes").execute().await.unwrap();
+    jane.rpc("my_notes", json!({})).execute().await.unwrap();
+    client.select("notes").execute().await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(bearer(&requests[0]), "Bearer jane-token");
+    assert_eq!(bearer(&requests[1]), "Bearer jane-token");
+    assert_eq!(requests[0].headers["apikey"], "key");
+    // the client it was made from keeps its API key
+    assert_eq!(bearer(&requests[2]), "Bearer key");
+
+    #[cfg(feature = "auth")]
+    {
+        use jsonwebtoken::{decode, DecodingKey, Validation};
+        use serde_json::Value;
+
+        assert!(client.as_role("anon").is_err());
+
+        let client: SupabaseClient = client.with_jwt_secret("super-secret");
+        client
+            .as_user("7d2b5a4e")
+            .unwrap()
+            .select("notes")
+            .execute()
+            .await
+            .unwrap();
+        client
+            .as_role("anon")
+            .unwrap()
+            .select("notes")
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        let claims = |request: &RecordedRequest| -> Value {
+            let token: &str = bearer(request).trim_start_matches("Bearer ");
+            let mut validation: Validation = Validation::default();
+            validation.validate_aud = false;
+            decode::<Value>(
+                token,
+                &DecodingKey::from_secret(b"super-secret"),
+                &validation,
+            )
+            .unwrap()
+            .claims
+        };
+        let user: Value = claims(&requests[3]);
+        assert_eq!(user["sub"], "7d2b5a4e");
+        assert_eq!(user["role"], "authenticated");
+        let anon: Value = claims(&requests[4]);
+        assert_eq!(anon["role"], "anon");
+        assert!(anon.get("sub").is_none());
+    }
+}
@@ -4,6 +4,7 @@ use serde_json::json;
 
 pub async fn insert_if_unique_numeric() {
     /// Performs an insert_if_unique operation in an isolated scope.
+    #[allow(deprecated)]
     async fn insert_if_unique_inner(supabase_client: SupabaseClient) -> Result<(), String> {
         // Usage example
         let random_number: u64 = rand::random::<u64>();
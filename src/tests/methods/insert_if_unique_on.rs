@@ -0,0 +1,85 @@
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+#[allow(deprecated)]
+pub async fn insert_if_unique_on() {
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(201, &json!([{"id": 1, "email": "jane@example.com"}])),
+        )
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(201, &json!([])),
+        )
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(409, &json!({"code": "23505", "message": "duplicate key"})),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([{"id": 1, "email": "jane@example.com"}])),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([])),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let row = json!({"email": "jane@example.com"});
+    assert_eq!(
+        client.insert_if_unique_on("users", &row, &["email"]).await,
+        Ok(true)
+    );
+    assert_eq!(
+        client.insert_if_unique_on("users", &row, &["email"]).await,
+        Ok(false)
+    );
+    assert!(client
+        .insert_if_unique_on("users", &row, &[])
+        .await
+        .is_err());
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests
+        .iter()
+        .all(|request| request.method == Method::POST));
+
+    // insert_if_unique looks the row up first and doesn't insert an existing one
+    let error: String = client
+        .insert_if_unique("users", row.clone())
+        .await
+        .unwrap_err();
+    assert!(error.contains("Duplicate entry"));
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[2].method, Method::GET);
+    assert_eq!(
+        requests[2].query("email").as_deref(),
+        Some("eq.jane@example.com")
+    );
+
+    // a duplicate inserted between the lookup and the insert is still rejected
+    let error: String = client.insert_if_unique("users", row).await.unwrap_err();
+    assert!(error.contains("Duplicate entry"));
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 5);
+    assert_eq!(requests[4].method, Method::POST);
+    assert_eq!(requests[0].query("on_conflict").as_deref(), Some("email"));
+    let prefer: &str = requests[0].headers["prefer"].to_str().unwrap();
+    assert!(prefer.contains("resolution=ignore-duplicates"));
+    assert!(prefer.contains("return=representation"));
+    assert_eq!(requests[4].query("on_conflict"), None);
+}
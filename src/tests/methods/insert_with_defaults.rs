@@ -0,0 +1,51 @@
+use crate::query::Returning;
+use crate::request::prefer::PreferBuilder;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn insert_with_defaults() {
+    assert_eq!(
+        PreferBuilder::new()
+            .returning(Returning::Minimal)
+            .missing_default()
+            .build(),
+        "return=minimal,missing=default"
+    );
+
+    let transport: MockTransport =
+        MockTransport::new().on(Method::POST, "/rest/v1/users", MockResponse::new(201));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    // the second row leaves `status` to its default, the third sets `plan` only it has
+    let rows = vec![
+        json!({"name": "John", "status": "active"}),
+        json!({"name": "Jane"}),
+        json!({"name": "Jim", "plan": "pro"}),
+    ];
+    client
+        .bulk_insert_with_defaults("users", rows.clone())
+        .await
+        .unwrap();
+    client.bulk_insert("users", rows).await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].query("columns").as_deref(),
+        Some("name,status,plan")
+    );
+    assert_eq!(
+        requests[0].headers["prefer"],
+        "return=minimal,missing=default"
+    );
+    assert_eq!(requests[0].json().unwrap()[1], json!({"name": "Jane"}));
+
+    // plain bulk inserts keep relying on the columns of the first row
+    assert_eq!(requests[1].query("columns"), None);
+    assert_eq!(requests[1].headers["prefer"], "return=minimal");
+}
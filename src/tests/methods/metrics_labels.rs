@@ -0,0 +1,191 @@
+use crate::metrics::{RATE_LIMIT_IN_FLIGHT, RATE_LIMIT_QUEUED, REQUESTS_TOTAL};
+use crate::rate_limit::RateLimitConfig;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use reqwest::Method;
+use serde_json::json;
+use std::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Keeps the label values of every request counted, in the order they were recorded, and the
+/// labels of the gauges of the rate limiter.
+#[derive(Debug, Default)]
+struct CountedRequests(Mutex<Vec<Vec<String>>>, Mutex<Vec<Vec<String>>>);
+
+impl Recorder for CountedRequests {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        if key.name() == REQUESTS_TOTAL {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| label.value().to_string())
+                .collect();
+            self.0.lock().unwrap().push(labels);
+        }
+        Counter::noop()
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        if key.name() == RATE_LIMIT_QUEUED || key.name() == RATE_LIMIT_IN_FLIGHT {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            self.1.lock().unwrap().push(labels);
+        }
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+/// Answers every request with `status` and the JSON `body`, for the requests sent without the
+/// transport of the client.
+#[cfg_attr(not(any(feature = "nightly", feature = "storage")), allow(dead_code))]
+async fn serve(listener: TcpListener, status: u16, body: &'static str) {
+    loop {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        // read the headers and the body they announce
+        let mut request: Vec<u8> = Vec::new();
+        let mut buffer: [u8; 4096] = [0; 4096];
+        loop {
+            let read: usize = stream.read(&mut buffer).await.unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+            let text: String = String::from_utf8_lossy(&request).to_lowercase();
+            let Some(end) = text.find("\r\n\r\n") else {
+                continue;
+            };
+            let length: usize = text
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|length| length.trim().parse().ok())
+                .unwrap_or(0);
+            if request.len() >= end + 4 + length {
+                break;
+            }
+        }
+
+        let response: String = format!(
+            "HTTP/1.1 {} OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    }
+}
+
+pub async fn metrics_labels() {
+    let recorder: CountedRequests = CountedRequests::default();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    // the requests sent by the transport are labelled with their status class
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(Method::GET, "/rest/v1/teams", MockResponse::new(404))
+        .on(Method::PATCH, "/rest/v1/users", MockResponse::new(503))
+        .on(Method::GET, "/rest/v1/logs", MockResponse::new(600));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport)
+            .with_rate_limit(RateLimitConfig {
+                max_in_flight: Some(1),
+                requests_per_second: None,
+            });
+    client.select("users").execute().await.unwrap();
+    assert!(client.select("teams").execute().await.is_err());
+    assert!(client
+        .update("users", "1", json!({"name": "Jane"}))
+        .await
+        .is_err());
+    // codes outside of the classes are not mistaken for server errors
+    let _ = client.select("logs").execute().await;
+
+    // the gauges of the rate limiter are labelled with the host of the project
+    let gauges = recorder.1.lock().unwrap().clone();
+    assert!(!gauges.is_empty());
+    assert!(gauges
+        .iter()
+        .all(|labels| labels == &["host=project.supabase.co"]));
+
+    // GraphQL queries are labelled with their collection, and requests without response as errors
+    #[cfg(feature = "nightly")]
+    {
+        use crate::graphql::request::Request;
+        use crate::graphql::RootTypes;
+
+        let query = json!({ "query": "{ usersCollection { edges { node { id } } } }" });
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url: String = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(
+            listener,
+            200,
+            r#"{"data":{"usersCollection":{"edges":[]}}}"#,
+        ));
+        let client: SupabaseClient = SupabaseClient::new(url, "key".to_string()).unwrap();
+        Request::new(client, query.clone(), RootTypes::Query)
+            .send()
+            .await
+            .unwrap();
+
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url: String = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let client: SupabaseClient = SupabaseClient::new(url, "key".to_string()).unwrap();
+        assert!(Request::new(client, query, RootTypes::Query)
+            .send()
+            .await
+            .is_err());
+    }
+
+    // downloads of public objects
+    #[cfg(feature = "storage")]
+    {
+        use crate::storage::SupabaseStorage;
+
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url: String = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, 200, "{}"));
+        let storage: SupabaseStorage = SupabaseStorage {
+            supabase_url: url,
+            bucket_name: "docs".to_string(),
+            filename: "hello.json".to_string(),
+        };
+        assert_eq!(storage.download().await.unwrap(), b"{}");
+    }
+
+    #[cfg_attr(not(any(feature = "nightly", feature = "storage")), allow(unused_mut))]
+    let mut expected: Vec<Vec<&str>> = vec![
+        vec!["users", "select", "200", "2xx"],
+        vec!["teams", "select", "404", "4xx"],
+        vec!["users", "update", "503", "5xx"],
+        vec!["logs", "select", "600", "other"],
+    ];
+    #[cfg(feature = "nightly")]
+    expected.extend([
+        vec!["usersCollection", "graphql", "200", "2xx"],
+        vec!["usersCollection", "graphql", "error", "error"],
+    ]);
+    #[cfg(feature = "storage")]
+    expected.push(vec!["", "download", "200", "2xx"]);
+    assert_eq!(*recorder.0.lock().unwrap(), expected);
+}
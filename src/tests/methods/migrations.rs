@@ -0,0 +1,66 @@
+use crate::batch::BATCH_MIGRATION_SQL;
+use crate::cron::CRON_MIGRATION_SQL;
+use crate::fixtures::FIXTURES_MIGRATION_SQL;
+use crate::idempotency::{IDEMPOTENCY_MIGRATION_SQL, REPLAY_HINT};
+use crate::lock::LOCK_MIGRATION_SQL;
+
+/// The functions a migration creates, `public.<name>(`.
+fn created_functions(sql: &str) -> Vec<&str> {
+    sql.split("create or replace function public.")
+        .skip(1)
+        .filter_map(|rest| rest.split_once('('))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+pub async fn migrations() {
+    #[cfg_attr(not(feature = "admin"), allow(unused_mut))]
+    let mut migrations: Vec<(&str, Vec<&str>)> = vec![
+        (BATCH_MIGRATION_SQL, vec!["supabase_rs_batch"]),
+        (
+            CRON_MIGRATION_SQL,
+            vec![
+                "supabase_rs_cron_schedule",
+                "supabase_rs_cron_unschedule",
+                "supabase_rs_cron_jobs",
+            ],
+        ),
+        (FIXTURES_MIGRATION_SQL, vec!["supabase_rs_truncate"]),
+        (IDEMPOTENCY_MIGRATION_SQL, Vec::new()),
+        (
+            LOCK_MIGRATION_SQL,
+            vec!["supabase_rs_try_lock", "supabase_rs_unlock"],
+        ),
+    ];
+    #[cfg(feature = "admin")]
+    migrations.push((
+        crate::vault::VAULT_MIGRATION_SQL,
+        vec![
+            "supabase_rs_vault_create_secret",
+            "supabase_rs_vault_update_secret",
+            "supabase_rs_vault_read_secret",
+            "supabase_rs_vault_delete_secret",
+        ],
+    ));
+
+    // every migration creates the functions its client calls, and takes them away from `public`
+    // before granting them to the roles that may call them
+    for (sql, called) in migrations {
+        let created: Vec<&str> = created_functions(sql);
+        assert!(!created.is_empty());
+        for function in called {
+            assert!(created.contains(&function), "{} isn't created", function);
+        }
+        for function in created {
+            let revoke: String = format!("revoke execute on function public.{}(", function);
+            let revoked: bool = sql
+                .lines()
+                .any(|line| line.starts_with(&revoke) && line.contains(") from public"));
+            assert!(revoked, "{} stays executable by public", function);
+        }
+        assert_eq!(sql.matches("$$").count() % 2, 0, "unclosed function body");
+    }
+
+    // the trigger raises replays with the hint the client looks for
+    assert!(IDEMPOTENCY_MIGRATION_SQL.contains(&format!("hint = '{}'", REPLAY_HINT)));
+}
@@ -0,0 +1,56 @@
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn mock_transport() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([{"id": 1, "email": "jane@example.com"}])),
+        )
+        .on(
+            Method::HEAD,
+            "/rest/v1/users",
+            MockResponse::new(200).with_header("Content-Range", "*/42"),
+        )
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(409, &json!({"code": "23505", "message": "duplicate key"})),
+        )
+        .on(Method::POST, "/rest/v1/users", MockResponse::new(201));
+
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let users: Vec<Value> = client
+        .select("users")
+        .eq("id", "1")
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(users[0]["email"], "jane@example.com");
+    assert_eq!(client.select("users").count_only().await, Ok(42));
+
+    // the first insert hits the conflict, the second one goes through
+    let row: Value = json!({"email": "john@example.com"});
+    assert!(client.insert("users", row.clone()).await.is_err());
+    assert!(client.insert("users", row).await.is_ok());
+
+    assert_eq!(
+        client.select("orders").execute().await.unwrap_err(),
+        "No mock response for GET /rest/v1/orders"
+    );
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 5);
+    assert_eq!(requests[0].query("id").as_deref(), Some("eq.1"));
+    assert_eq!(requests[1].method, Method::HEAD);
+    assert_eq!(requests[2].headers["apikey"], "key");
+    assert_eq!(requests[3].json().unwrap()["email"], "john@example.com");
+}
@@ -0,0 +1,81 @@
+use crate::errors::ErrorTypes;
+use crate::payload::{split_into_batches, OversizedPayload, PayloadLimit};
+use crate::query::Returning;
+use crate::transport::{MockResponse, MockTransport};
+use crate::update::DuplicateResolution;
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn payload() {
+    let rows: Vec<Value> = vec![
+        json!({"dog": "a"}),
+        json!({"dog": "b"}),
+        json!({"dog": "c"}),
+    ];
+
+    // every row is 11 bytes, so two rows plus brackets and a comma fit in 25 bytes
+    let batches: Vec<String> = split_into_batches(&rows, 25).unwrap();
+    assert_eq!(
+        batches,
+        vec![r#"[{"dog":"a"},{"dog":"b"}]"#, r#"[{"dog":"c"}]"#]
+    );
+
+    // a large enough limit keeps everything in one batch
+    let batches: Vec<String> = split_into_batches(&rows, 1024).unwrap();
+    assert_eq!(batches.len(), 1);
+
+    // a single row above the limit can never be sent
+    let result = split_into_batches(&rows, 10);
+    assert!(matches!(
+        result,
+        Err(ErrorTypes::PayloadTooLarge {
+            size: 13,
+            limit: 10
+        })
+    ));
+
+    // a single row above the limit isn't dropped, it fails without being sent
+    let transport: MockTransport =
+        MockTransport::new().on(Method::POST, "/rest/v1/dogs", MockResponse::new(201));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone())
+            .with_payload_limit(PayloadLimit {
+                max_bytes: 10,
+                on_oversized: OversizedPayload::Split,
+            });
+    let error = client
+        .insert_with_returning("dogs", json!({"dog": "a long name"}), Returning::Minimal)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::PayloadTooLarge { limit: 10, .. })
+    ));
+    let error = client
+        .insert_if_unique_on("dogs", json!({"dog": "a long name"}), &["dog"])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::PayloadTooLarge { .. })
+    ));
+    let error = client
+        .upsert_with_returning(
+            "dogs",
+            json!({"dog": "a long name"}),
+            &["dog"],
+            DuplicateResolution::MergeDuplicates,
+            Returning::Minimal,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::PayloadTooLarge { .. })
+    ));
+    assert!(transport.requests().is_empty());
+}
@@ -0,0 +1,102 @@
+use crate::errors::{ErrorTypes, PostgrestErrorKind, RestError, DUPLICATE_ENTRY};
+use crate::insert::InsertVerdict;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn postgrest_errors() {
+    let invalid_uuid = json!({
+        "code": "22P02",
+        "message": "invalid input syntax for type uuid: \"abc\"",
+        "details": null,
+        "hint": "pass a valid uuid",
+    });
+    let foreign_key = json!({
+        "code": "23503",
+        "message": "insert or update on table \"orders\" violates foreign key constraint",
+        "details": "Key (user_id)=(7) is not present in table \"users\".",
+    });
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(400, &invalid_uuid),
+        )
+        .on(
+            Method::DELETE,
+            "/rest/v1/orders",
+            MockResponse::json(400, &invalid_uuid),
+        )
+        .on(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(200, &json!([])),
+        )
+        .once(
+            Method::POST,
+            "/rest/v1/orders",
+            MockResponse::json(409, &foreign_key),
+        )
+        .once(
+            Method::POST,
+            "/rest/v1/orders",
+            MockResponse::json(409, &json!({"code": "23505", "message": "duplicate key"})),
+        )
+        .on(
+            Method::POST,
+            "/rest/v1/orders",
+            MockResponse::json(409, &foreign_key),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    // the code, message and hint reach the caller instead of the status alone
+    let error: RestError = client.select("orders").fetch().await.unwrap_err();
+    assert!(
+        error.starts_with(
+            "PostgREST error 400: [22P02] invalid input syntax for type uuid: \"abc\", hint: pass a valid uuid (request id: "
+        ),
+        "{}",
+        error
+    );
+    let error: RestError = client
+        .delete_from("orders")
+        .eq("id", "abc")
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("PostgREST error 400: [22P02]"));
+
+    // a foreign key violation is a 409 too, but not a duplicate
+    let error: String = client
+        .insert("orders", json!({"user_id": 7}))
+        .await
+        .unwrap_err();
+    assert!(error.contains("[23503]"), "{}", error);
+    assert!(!error.contains("Duplicate entry"));
+    let error: String = client
+        .insert("orders", json!({"user_id": 1}))
+        .await
+        .unwrap_err();
+    assert!(error.starts_with(DUPLICATE_ENTRY), "{}", error);
+    let verdicts: Vec<InsertVerdict> = client
+        .bulk_insert_if_unique("orders", vec![json!({"id": 1, "user_id": 7})], &["id"])
+        .await
+        .unwrap();
+    assert!(matches!(&verdicts[0], InsertVerdict::Failed(error) if error.contains("[23503]")));
+
+    let error: ErrorTypes = ErrorTypes::from_postgrest_body(
+        409,
+        json!({"code": "23505", "message": "duplicate key value violates unique constraint \"users_email_key\""})
+            .to_string(),
+    );
+    assert!(matches!(error, ErrorTypes::UniqueViolation(_)));
+    assert_eq!(
+        error.postgrest_error().map(|(_, kind)| kind),
+        Some(PostgrestErrorKind::ConstraintViolation)
+    );
+}
@@ -0,0 +1,49 @@
+use crate::errors::ErrorTypes;
+use crate::interop::{project_url, PostgrestCompat};
+use crate::SupabaseClient;
+
+pub async fn postgrest_interop() {
+    assert_eq!(
+        project_url("https://project.supabase.co/rest/v1/"),
+        "https://project.supabase.co"
+    );
+    assert_eq!(
+        project_url("http://localhost:3000"),
+        "http://localhost:3000"
+    );
+
+    // the key is taken from `apikey`, or from a bearer token
+    assert!(SupabaseClient::from_postgrest_config(
+        "https://project.supabase.co/rest/v1",
+        &[("apikey", "anon-key")]
+    )
+    .is_ok());
+    assert!(SupabaseClient::from_postgrest_config(
+        "https://project.supabase.co/rest/v1",
+        &[("Authorization", "Bearer anon-key")]
+    )
+    .is_ok());
+    assert!(matches!(
+        SupabaseClient::from_postgrest_config("https://project.supabase.co/rest/v1", &[]),
+        Err(ErrorTypes::ApiKeyMissing)
+    ));
+
+    let client: SupabaseClient = SupabaseClient::from_postgrest_config(
+        "https://project.supabase.co/rest/v1",
+        &[("apikey", "anon-key")],
+    )
+    .unwrap();
+    let query: String = client
+        .from("users")
+        .select("id, email")
+        .eq("plan", "pro")
+        .in_("country", &["NL", "BE"])
+        .order("created_at.desc")
+        .limit(10)
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "select=id,email&plan=eq.pro&country=in.(\"BE\",\"NL\")&order=created_at.desc&limit=10"
+    );
+}
@@ -0,0 +1,67 @@
+use crate::query::{CountMode, Returning};
+use crate::request::prefer::PreferBuilder;
+use crate::transport::{MockResponse, MockTransport};
+use crate::update::DuplicateResolution;
+use crate::SupabaseClient;
+
+use reqwest::header::HeaderValue;
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn prefer_header() {
+    assert!(PreferBuilder::new().is_empty());
+    assert_eq!(PreferBuilder::new().build(), "");
+    assert_eq!(
+        PreferBuilder::new()
+            .resolution(DuplicateResolution::MergeDuplicates)
+            .returning(Returning::Representation)
+            .count(CountMode::Exact)
+            .build(),
+        "resolution=merge-duplicates,return=representation,count=exact"
+    );
+
+    // setting a preference again replaces it in place
+    assert_eq!(
+        PreferBuilder::new()
+            .returning(Returning::Representation)
+            .timezone("UTC")
+            .returning(Returning::Minimal)
+            .build(),
+        "return=minimal,timezone=UTC"
+    );
+
+    let transport: MockTransport = MockTransport::new()
+        .on(Method::POST, "/rest/v1/users", MockResponse::new(201))
+        .on(
+            Method::PATCH,
+            "/rest/v1/users",
+            MockResponse::new(204).with_header("Content-Range", "*/1"),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    client
+        .upsert_without_defined_key("users", json!({"id": 1, "name": "Jane"}))
+        .await
+        .unwrap();
+    let updated: u64 = client
+        .update_table("users")
+        .eq("id", "1")
+        .set(json!({"name": "Janet"}))
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(updated, 1);
+
+    // every write sends its preferences in a single header
+    let requests = transport.requests();
+    let upsert: Vec<&HeaderValue> = requests[0].headers.get_all("prefer").iter().collect();
+    assert_eq!(
+        upsert,
+        vec!["resolution=merge-duplicates,return=representation"]
+    );
+    let update: Vec<&HeaderValue> = requests[1].headers.get_all("prefer").iter().collect();
+    assert_eq!(update, vec!["return=minimal,count=exact"]);
+}
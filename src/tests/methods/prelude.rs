@@ -0,0 +1,21 @@
+use crate::prelude::*;
+
+pub async fn prelude() {
+    // the client, builders and options are all reachable through the prelude
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "anon-key".to_string())
+            .unwrap()
+            .with_payload_limit(PayloadLimit {
+                max_bytes: 1024,
+                on_oversized: OversizedPayload::Split,
+            });
+
+    let query: QueryBuilder = client.select("users").count_mode(CountMode::Exact);
+    assert_eq!(query.count_mode, Some(CountMode::Exact));
+
+    let delete: DeleteBuilder = client.delete_from("users");
+    assert!(delete.build().is_err());
+
+    let error: ErrorTypes = ErrorTypes::Cancelled;
+    assert!(!error.is_retryable());
+}
@@ -0,0 +1,49 @@
+use crate::query::QueryBuilder;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn query_to_url() {
+    let transport: MockTransport = MockTransport::new().on(
+        Method::GET,
+        "/rest/v1/animals",
+        MockResponse::json(200, &json!([])),
+    );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let query: QueryBuilder = client
+        .select("animals")
+        .columns(vec!["id", "dog"])
+        .eq("dog", "scooby")
+        .gte("age", 3);
+    assert_eq!(
+        query.to_url(),
+        "https://project.supabase.co/rest/v1/animals?select=id,dog&dog=eq.scooby&age=gte.3"
+    );
+    assert_eq!(
+        query.to_string(),
+        "GET https://project.supabase.co/rest/v1/animals?select=id,dog&dog=eq.scooby&age=gte.3"
+    );
+
+    // the URL is the one that is sent
+    let url: String = query.to_url();
+    query.execute().await.unwrap();
+    assert_eq!(transport.requests()[0].url.as_str(), url);
+
+    let query: QueryBuilder = client
+        .with_timezone("Europe/Amsterdam")
+        .select("animals")
+        .schema("archive")
+        .eq("dog", "scooby");
+    assert_eq!(
+        query.to_string(),
+        "GET https://project.supabase.co/rest/v1/animals?dog=eq.scooby\n\
+         Accept-Profile: archive\n\
+         prefer: timezone=Europe/Amsterdam"
+    );
+}
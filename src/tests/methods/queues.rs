@@ -0,0 +1,31 @@
+use crate::errors::ErrorTypes;
+use crate::queues::{parse_messages, QueueMessage};
+use serde_json::{json, Value};
+
+pub async fn queues() {
+    let rows: Value = json!([
+        {
+            "msg_id": 7,
+            "read_ct": 1,
+            "enqueued_at": "2024-08-01T10:00:00.000000+00:00",
+            "vt": "2024-08-01T10:00:30.000000+00:00",
+            "message": {"user_id": 1}
+        }
+    ]);
+
+    let messages: Vec<QueueMessage<Value>> = parse_messages(rows).unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].msg_id, 7);
+    assert_eq!(messages[0].read_ct, 1);
+    assert_eq!(messages[0].message, json!({"user_id": 1}));
+
+    // an empty queue returns no rows at all
+    let messages: Vec<QueueMessage<Value>> = parse_messages(Value::Null).unwrap();
+    assert!(messages.is_empty());
+
+    // a payload that doesn't match the expected type is an error naming what was wrong
+    assert!(matches!(
+        parse_messages::<i64>(json!([{"msg_id": 1}])),
+        Err(ErrorTypes::UnexpectedResponse(message)) if message.contains("missing field")
+    ));
+}
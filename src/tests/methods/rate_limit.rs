@@ -0,0 +1,85 @@
+use crate::rate_limit::{RateLimitConfig, RateLimitStats};
+use crate::transport::{HttpTransport, TransportFuture};
+use crate::SupabaseClient;
+
+use reqwest::Request;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Answers after a while, remembering how many requests it handled at once.
+#[derive(Debug, Default)]
+struct SlowTransport {
+    current: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+impl HttpTransport for SlowTransport {
+    fn execute(&self, _request: Request) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let current: usize = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+
+            let response = http::Response::builder().status(200).body("[]".to_string());
+            Ok(response.unwrap().into())
+        })
+    }
+}
+
+pub async fn rate_limit() {
+    let transport: SlowTransport = SlowTransport::default();
+    let peak: Arc<AtomicUsize> = transport.peak.clone();
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport)
+            .with_rate_limit(RateLimitConfig {
+                max_in_flight: Some(2),
+                requests_per_second: None,
+            });
+
+    let selects: Vec<_> = (0..6)
+        .map(|_| {
+            let client: SupabaseClient = client.clone();
+            tokio::spawn(async move { client.select("events").execute().await })
+        })
+        .collect();
+    for select in selects {
+        assert!(select.await.unwrap().is_ok());
+    }
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    assert_eq!(client.rate_limit_stats(), Some(RateLimitStats::default()));
+
+    // a request dropped while it waits leaves the queue
+    let single: SupabaseClient = client.clone().with_rate_limit(RateLimitConfig {
+        max_in_flight: Some(1),
+        requests_per_second: None,
+    });
+    let first = tokio::spawn({
+        let single: SupabaseClient = single.clone();
+        async move { single.select("events").execute().await }
+    });
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let waiting = tokio::time::timeout(Duration::from_millis(5), single.select("events").execute());
+    assert!(waiting.await.is_err());
+    assert_eq!(single.rate_limit_stats().map(|stats| stats.queued), Some(0));
+    assert!(first.await.unwrap().is_ok());
+    assert_eq!(single.rate_limit_stats(), Some(RateLimitStats::default()));
+
+    // requests are spread over the second
+    let client: SupabaseClient = client.with_rate_limit(RateLimitConfig {
+        max_in_flight: None,
+        requests_per_second: Some(50),
+    });
+    let started: Instant = Instant::now();
+    for _ in 0..4 {
+        client.select("events").execute().await.unwrap();
+    }
+    assert!(started.elapsed() >= Duration::from_millis(60));
+
+    let unlimited: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    assert_eq!(unlimited.rate_limit_stats(), None);
+}
@@ -0,0 +1,73 @@
+use crate::realtime::protocol::PhoenixMessage;
+use crate::realtime::{RealtimeChannel, RealtimeClient, RealtimeOptions};
+use crate::tests::realtime::{reply, FakeRealtime};
+
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub async fn realtime_auth() {
+    // acknowledges every push
+    let mut server: FakeRealtime =
+        FakeRealtime::start(|_, message| vec![reply(message, "ok", json!({}))]).await;
+    let url: String = server.url.clone();
+    let options: RealtimeOptions = RealtimeOptions {
+        heartbeat_interval: Duration::from_millis(50),
+        ..RealtimeOptions::default()
+    };
+
+    // a token source is sent with the join, and again once it changes
+    let token: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(Some("t1".to_string())));
+    let realtime: RealtimeClient = RealtimeClient::with_options(&url, "key", options)
+        .with_access_token({
+            let token = token.clone();
+            move || token.lock().unwrap().clone()
+        });
+    let room: RealtimeChannel = realtime.channel("room:1").private();
+    room.subscribe().await.unwrap();
+
+    let join: PhoenixMessage = server.next_event("phx_join").await;
+    assert_eq!(join.payload["access_token"], json!("t1"));
+    assert_eq!(join.payload["config"]["private"], json!(true));
+
+    *token.lock().unwrap() = Some("t2".to_string());
+    let refreshed: PhoenixMessage = server.next_event("access_token").await;
+    assert_eq!(refreshed.topic, "realtime:room:1");
+    assert_eq!(refreshed.payload, json!({"access_token": "t2"}));
+    assert_eq!(refreshed.join_ref, join.join_ref);
+
+    // without a token the channels fall back to the API key
+    *token.lock().unwrap() = None;
+    let signed_out: PhoenixMessage = server.next_event("access_token").await;
+    assert_eq!(signed_out.payload, json!({"access_token": "key"}));
+    assert_eq!(realtime.access_token(), None);
+    realtime.disconnect();
+
+    // a token set by hand is sent right away
+    let realtime: RealtimeClient = RealtimeClient::new(&url, "key");
+    let room: RealtimeChannel = realtime.channel("room:2");
+    room.subscribe().await.unwrap();
+    let join: PhoenixMessage = server.next_event("phx_join").await;
+    assert!(join.payload.get("access_token").is_none());
+
+    realtime.set_auth(Some("t3"));
+    let updated: PhoenixMessage = server.next_event("access_token").await;
+    assert_eq!(updated.topic, "realtime:room:2");
+    assert_eq!(updated.payload, json!({"access_token": "t3"}));
+    realtime.disconnect();
+
+    #[cfg(feature = "auth")]
+    {
+        use crate::auth::{AuthClient, Session};
+
+        let auth: AuthClient = AuthClient::new(url, "key".to_string()).unwrap();
+        assert_eq!(auth.realtime().access_token(), None);
+
+        auth.set_session(Session {
+            access_token: "user-jwt".to_string(),
+            ..Session::default()
+        })
+        .unwrap();
+        assert_eq!(auth.realtime().access_token().as_deref(), Some("user-jwt"));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::realtime::broadcast::{broadcast_body, BroadcastMessage};
+use serde_json::json;
+
+pub async fn realtime_broadcast() {
+    let messages: Vec<BroadcastMessage> = vec![
+        BroadcastMessage::new("room:1", "new_message", json!({"text": "hello"})),
+        BroadcastMessage::new("room:2", "typing", json!({})).private(),
+    ];
+
+    assert_eq!(
+        broadcast_body(&messages),
+        json!({
+            "messages": [
+                {"topic": "room:1", "event": "new_message", "payload": {"text": "hello"}, "private": false},
+                {"topic": "room:2", "event": "typing", "payload": {}, "private": true}
+            ]
+        })
+    );
+}
@@ -0,0 +1,141 @@
+use crate::realtime::protocol::PhoenixMessage;
+use crate::realtime::{ChangeEvent, ChangeKind, ChangesOptions, RealtimeClient};
+
+use crate::tests::realtime::{event, reply, FakeRealtime};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Order {
+    id: i64,
+    status: String,
+}
+
+fn change(topic: &str, ids: &[u64], kind: &str, record: Value, old_record: Value) -> Value {
+    let payload: Value = json!({
+        "ids": ids,
+        "data": {
+            "schema": "public",
+            "table": "orders",
+            "commit_timestamp": "2024-05-09T16:05:33.915+02:00",
+            "type": kind,
+            "record": record,
+            "old_record": old_record,
+            "columns": [{"name": "id", "type": "int8"}, {"name": "status", "type": "text"}],
+            "errors": null,
+        },
+    });
+    event(topic, "postgres_changes", payload)
+}
+
+/// Numbers the change subscription 42 and sends a few row changes once joined.
+fn answer(message: &PhoenixMessage) -> Vec<Value> {
+    if message.event != "phx_join" {
+        return vec![reply(message, "ok", json!({}))];
+    }
+
+    let response: Value = json!({"postgres_changes": [{"id": 42, "event": "*"}]});
+    vec![
+        reply(message, "ok", response),
+        change(
+            &message.topic,
+            &[42],
+            "INSERT",
+            json!({"id": 1, "status": "open"}),
+            json!({}),
+        ),
+        // another subscription of the connection
+        change(
+            &message.topic,
+            &[7],
+            "INSERT",
+            json!({"id": 9, "status": "open"}),
+            json!({}),
+        ),
+        // a row which doesn't decode is skipped
+        change(
+            &message.topic,
+            &[42],
+            "INSERT",
+            json!({"id": "two"}),
+            json!({}),
+        ),
+        change(
+            &message.topic,
+            &[42],
+            "UPDATE",
+            json!({"id": 1, "status": "paid"}),
+            json!({"id": 1, "status": "open"}),
+        ),
+        // without replica identity full only the primary key of the old row is sent
+        change(&message.topic, &[42], "DELETE", json!({}), json!({"id": 1})),
+    ]
+}
+
+pub async fn realtime_changes() {
+    let mut server: FakeRealtime = FakeRealtime::start(|_, message| answer(message)).await;
+
+    let realtime: RealtimeClient = RealtimeClient::new(&server.url, "key");
+    let mut changes: mpsc::Receiver<ChangeEvent<Order>> = realtime
+        .table_changes_with(
+            "public",
+            "orders",
+            ChangesOptions::new().filter("status=neq.void").capacity(1),
+        )
+        .await
+        .unwrap();
+
+    let join: PhoenixMessage = server.next().await;
+    assert_eq!(join.event, "phx_join");
+    assert!(join.topic.starts_with("realtime:changes:public:orders:"));
+    assert_eq!(
+        join.payload["config"]["postgres_changes"],
+        json!([{"event": "*", "schema": "public", "table": "orders", "filter": "status=neq.void"}])
+    );
+
+    // the receiver falls behind its capacity without losing changes
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let insert: ChangeEvent<Order> = changes.recv().await.unwrap();
+    assert_eq!(insert.kind, ChangeKind::Insert);
+    assert_eq!(
+        (insert.schema.as_str(), insert.table.as_str()),
+        ("public", "orders")
+    );
+    assert_eq!(
+        insert.new,
+        Some(Order {
+            id: 1,
+            status: "open".to_string()
+        })
+    );
+    assert_eq!((insert.old, insert.old_record), (None, Value::Null));
+    assert_eq!(
+        insert.commit_timestamp.as_deref(),
+        Some("2024-05-09T16:05:33.915+02:00")
+    );
+    assert!(insert.lag.unwrap() > Duration::from_secs(86_400));
+
+    let update: ChangeEvent<Order> = changes.recv().await.unwrap();
+    assert_eq!(update.kind, ChangeKind::Update);
+    assert_eq!(update.new.unwrap().status, "paid");
+    assert_eq!(update.old.unwrap().status, "open");
+
+    let delete: ChangeEvent<Order> = changes.recv().await.unwrap();
+    assert_eq!(delete.kind, ChangeKind::Delete);
+    assert_eq!((delete.new, delete.old), (None, None));
+    assert_eq!(delete.old_record, json!({"id": 1}));
+
+    // dropping the receiver leaves the channel
+    drop(changes);
+    let leave: PhoenixMessage = server.next().await;
+    assert_eq!(leave.event, "phx_leave");
+    assert_eq!(leave.topic, join.topic);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(realtime.channels().is_empty());
+    realtime.disconnect();
+}
@@ -0,0 +1,145 @@
+use crate::realtime::protocol::PhoenixMessage;
+use crate::realtime::{RealtimeChannel, RealtimeClient};
+
+use crate::tests::realtime::{event, reply, FakeRealtime};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Cursor {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Visitor {
+    name: String,
+}
+
+/// Acknowledges every push, echoes broadcasts and shares the tracked presences.
+fn answer(message: &PhoenixMessage) -> Vec<Value> {
+    match message.event.as_str() {
+        "phx_join" if message.topic == "realtime:forbidden" => {
+            vec![reply(message, "error", json!({}))]
+        }
+        "phx_join" => {
+            let state: Value = json!({"john": {"metas": [{"phx_ref": "1", "name": "John"}]}});
+            vec![
+                reply(message, "ok", json!({})),
+                event(&message.topic, "presence_state", state),
+            ]
+        }
+        "broadcast" => vec![event(&message.topic, "broadcast", message.payload.clone())],
+        "presence" => {
+            let presence: Value = json!({"jane": {"metas": [{"phx_ref": "2", "name": "Jane"}]}});
+            let diff: Value = match message.payload["event"].as_str() {
+                Some("track") => json!({"joins": presence, "leaves": {}}),
+                _ => json!({"joins": {}, "leaves": presence}),
+            };
+            vec![
+                reply(message, "ok", json!({})),
+                event(&message.topic, "presence_diff", diff),
+            ]
+        }
+        _ => vec![reply(message, "ok", json!({}))],
+    }
+}
+
+pub async fn realtime_channel() {
+    let mut server: FakeRealtime = FakeRealtime::start(|_, message| answer(message)).await;
+
+    let realtime: RealtimeClient = RealtimeClient::new(&server.url, "key");
+    assert!(realtime.socket_url().starts_with("ws://127.0.0.1:"));
+    assert!(realtime
+        .socket_url()
+        .ends_with("/realtime/v1/websocket?apikey=key&vsn=1.0.0"));
+
+    let cursors: Arc<Mutex<Vec<Cursor>>> = Arc::default();
+    let joins: Arc<Mutex<Vec<String>>> = Arc::default();
+    let leaves: Arc<Mutex<Vec<String>>> = Arc::default();
+    let syncs: Arc<Mutex<Vec<usize>>> = Arc::default();
+
+    let room: RealtimeChannel = realtime
+        .channel("room:1")
+        .broadcast_self(true)
+        .presence_key("jane")
+        .on_broadcast("cursor", {
+            let cursors = cursors.clone();
+            move |cursor: Cursor| cursors.lock().unwrap().push(cursor)
+        })
+        .on_presence_join({
+            let joins = joins.clone();
+            move |key: &str, visitors: Vec<Visitor>| {
+                for visitor in visitors {
+                    joins
+                        .lock()
+                        .unwrap()
+                        .push(format!("{}: {}", key, visitor.name));
+                }
+            }
+        })
+        .on_presence_leave({
+            let leaves = leaves.clone();
+            move |key: &str, _: Vec<Visitor>| leaves.lock().unwrap().push(key.to_string())
+        })
+        .on_presence_sync({
+            let syncs = syncs.clone();
+            move |state| syncs.lock().unwrap().push(state.len())
+        });
+
+    room.subscribe().await.unwrap();
+    assert!(realtime.is_connected());
+    assert_eq!(realtime.channels(), vec!["realtime:room:1"]);
+
+    let join: PhoenixMessage = server.next().await;
+    assert_eq!(join.event, "phx_join");
+    assert_eq!(join.topic, "realtime:room:1");
+    assert_eq!(join.payload["config"]["broadcast"]["self"], json!(true));
+    assert_eq!(join.payload["config"]["presence"]["key"], json!("jane"));
+
+    room.track(&Visitor {
+        name: "Jane".to_string(),
+    })
+    .await
+    .unwrap();
+    room.send("cursor", &Cursor { x: 1, y: 2 }).await.unwrap();
+    // a payload of another shape is skipped
+    room.send("cursor", &json!({"x": "left"})).await.unwrap();
+    room.untrack().await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(*cursors.lock().unwrap(), vec![Cursor { x: 1, y: 2 }]);
+    assert_eq!(*joins.lock().unwrap(), vec!["john: John", "jane: Jane"]);
+    assert_eq!(*leaves.lock().unwrap(), vec!["jane"]);
+    assert_eq!(*syncs.lock().unwrap(), vec![1, 2, 1]);
+    assert_eq!(room.presence_state().keys(), vec!["john"]);
+
+    let track: PhoenixMessage = server.next().await;
+    assert_eq!(
+        track.payload,
+        json!({"type": "presence", "event": "track", "payload": {"name": "Jane"}})
+    );
+    assert_eq!(track.join_ref, join.join_ref);
+    let broadcast: PhoenixMessage = server.next().await;
+    assert_eq!(
+        broadcast.payload,
+        json!({"type": "broadcast", "event": "cursor", "payload": {"x": 1, "y": 2}})
+    );
+
+    // joins refused by Realtime fail
+    let error = realtime.channel("forbidden").subscribe().await.unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("Realtime error: phx_join failed"));
+    assert_eq!(realtime.channels(), vec!["realtime:room:1"]);
+
+    room.unsubscribe().await.unwrap();
+    assert!(realtime.channels().is_empty());
+
+    realtime.disconnect();
+    assert!(!realtime.is_connected());
+    assert!(room.send("cursor", &Cursor { x: 0, y: 0 }).await.is_err());
+}
@@ -0,0 +1,64 @@
+use crate::realtime::presence::{PresenceChanges, PresenceState};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Visitor {
+    name: String,
+}
+
+pub async fn realtime_presence() {
+    let mut state: PresenceState = PresenceState::default();
+
+    let changes: PresenceChanges = state.sync_state(&json!({
+        "jane": {"metas": [{"phx_ref": "1", "name": "Jane"}]},
+        "john": {"metas": [{"phx_ref": "2", "name": "John"}]}
+    }));
+    assert_eq!(changes.joins.len(), 2);
+    assert!(changes.leaves.is_empty());
+    assert_eq!(state.keys(), vec!["jane", "john"]);
+
+    // jane connects a second time and john leaves
+    let changes: PresenceChanges = state.sync_diff(&json!({
+        "joins": {"jane": {"metas": [{"phx_ref": "3", "name": "Jane"}]}},
+        "leaves": {"john": {"metas": [{"phx_ref": "2", "name": "John"}]}}
+    }));
+    assert_eq!(
+        changes.joins,
+        vec![(
+            "jane".to_string(),
+            vec![json!({"phx_ref": "3", "name": "Jane"})]
+        )]
+    );
+    assert_eq!(changes.leaves[0].0, "john");
+    assert_eq!(state.keys(), vec!["jane"]);
+    assert_eq!(state.get("jane").map(<[_]>::len), Some(2));
+    assert_eq!(
+        state.list::<Visitor>(),
+        vec![(
+            "jane".to_string(),
+            vec![
+                Visitor {
+                    name: "Jane".to_string()
+                },
+                Visitor {
+                    name: "Jane".to_string()
+                }
+            ]
+        )]
+    );
+
+    // a new state drops the presences missing from it
+    let changes: PresenceChanges = state.sync_state(&json!({
+        "jane": {"metas": [{"phx_ref": "3", "name": "Jane"}]}
+    }));
+    assert!(changes.joins.is_empty());
+    assert_eq!(
+        changes.leaves,
+        vec![(
+            "jane".to_string(),
+            vec![json!({"phx_ref": "1", "name": "Jane"})]
+        )]
+    );
+    assert_eq!(state.len(), 1);
+}
@@ -0,0 +1,92 @@
+use crate::realtime::protocol::PhoenixMessage;
+use crate::realtime::{
+    ConnectionState, RealtimeChannel, RealtimeClient, RealtimeOptions, ReconnectConfig,
+};
+
+use crate::tests::realtime::{reply, FakeRealtime};
+
+use futures_util::StreamExt;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub async fn realtime_reconnect() {
+    let config: ReconnectConfig = ReconnectConfig {
+        initial_delay: Duration::from_millis(20),
+        max_delay: Duration::from_millis(50),
+        ..ReconnectConfig::default()
+    };
+    assert_eq!(config.delay(1), Duration::from_millis(20));
+    assert_eq!(config.delay(2), Duration::from_millis(40));
+    assert_eq!(config.delay(3), Duration::from_millis(50));
+    assert_eq!(config.delay(100), Duration::from_millis(50));
+
+    // acknowledges every push, except the heartbeats of the first connection so the client drops it
+    let mut server: FakeRealtime = FakeRealtime::start(|connection, message| {
+        if message.event == "heartbeat" && connection == 1 {
+            return Vec::new();
+        }
+        vec![reply(message, "ok", json!({}))]
+    })
+    .await;
+
+    let realtime: RealtimeClient = RealtimeClient::with_options(
+        &server.url,
+        "key",
+        RealtimeOptions {
+            heartbeat_interval: Duration::from_millis(50),
+            timeout: Duration::from_secs(1),
+            reconnect: config,
+        },
+    );
+    let states: Arc<Mutex<Vec<ConnectionState>>> = Arc::default();
+    tokio::spawn({
+        let states = states.clone();
+        let mut changes = realtime.connection_states();
+        async move {
+            while let Some(state) = changes.next().await {
+                states.lock().unwrap().push(state);
+            }
+        }
+    });
+
+    let room: RealtimeChannel = realtime.channel("room:1");
+    room.subscribe().await.unwrap();
+    room.track(&json!({"name": "Jane"})).await.unwrap();
+
+    // the first connection doesn't answer its heartbeat, the second one joins and tracks again
+    let mut second: Vec<PhoenixMessage> = Vec::new();
+    while second.len() < 2 {
+        let (connection, message) = server.next_with_connection().await;
+        if connection == 2 && message.event != "heartbeat" {
+            second.push(message);
+        }
+    }
+    assert_eq!(second[0].event, "phx_join");
+    assert_eq!(second[0].topic, "realtime:room:1");
+    assert_eq!(second[1].event, "presence");
+    assert_eq!(second[1].payload["payload"], json!({"name": "Jane"}));
+    assert_eq!(second[1].join_ref, second[0].join_ref);
+
+    // answered heartbeats keep the connection open
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(realtime.is_connected());
+    assert_eq!(realtime.connection_state(), ConnectionState::Connected);
+
+    realtime.disconnect();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!realtime.is_connected());
+    assert_eq!(
+        *states.lock().unwrap(),
+        vec![
+            ConnectionState::Connecting,
+            ConnectionState::Connected,
+            ConnectionState::Reconnecting {
+                attempt: 1,
+                retry_in: Duration::from_millis(20)
+            },
+            ConnectionState::Connected,
+            ConnectionState::Disconnected,
+        ]
+    );
+}
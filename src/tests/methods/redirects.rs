@@ -0,0 +1,79 @@
+use crate::request::dispatch::may_follow;
+use reqwest::{Method, StatusCode, Url};
+
+pub async fn redirects() {
+    let http: Url = Url::parse("http://project.supabase.co/rest/v1/test").unwrap();
+    let https: Url = Url::parse("https://project.supabase.co/rest/v1/test").unwrap();
+    let other_host: Url = Url::parse("https://evil.example.com/rest/v1/test").unwrap();
+
+    // method preserving redirects are always followed on the same host
+    assert!(may_follow(
+        StatusCode::PERMANENT_REDIRECT,
+        &Method::POST,
+        &http,
+        &https
+    ));
+    assert!(may_follow(
+        StatusCode::TEMPORARY_REDIRECT,
+        &Method::PATCH,
+        &http,
+        &https
+    ));
+
+    // 301 would turn a POST into a GET, so only reads follow it
+    assert!(may_follow(
+        StatusCode::MOVED_PERMANENTLY,
+        &Method::GET,
+        &http,
+        &https
+    ));
+    assert!(!may_follow(
+        StatusCode::MOVED_PERMANENTLY,
+        &Method::POST,
+        &http,
+        &https
+    ));
+    assert!(!may_follow(
+        StatusCode::SEE_OTHER,
+        &Method::DELETE,
+        &http,
+        &https
+    ));
+
+    // the api key is never sent to another host
+    assert!(!may_follow(
+        StatusCode::PERMANENT_REDIRECT,
+        &Method::GET,
+        &http,
+        &other_host
+    ));
+
+    // nor downgraded to http or sent to another port of the host
+    assert!(!may_follow(
+        StatusCode::PERMANENT_REDIRECT,
+        &Method::GET,
+        &https,
+        &http
+    ));
+    let other_port: Url = Url::parse("https://project.supabase.co:8443/rest/v1/test").unwrap();
+    assert!(!may_follow(
+        StatusCode::PERMANENT_REDIRECT,
+        &Method::GET,
+        &https,
+        &other_port
+    ));
+    let http_port: Url = Url::parse("http://project.supabase.co:8080/rest/v1/test").unwrap();
+    assert!(!may_follow(
+        StatusCode::PERMANENT_REDIRECT,
+        &Method::GET,
+        &http_port,
+        &https
+    ));
+    let trailing_slash: Url = Url::parse("https://project.supabase.co/rest/v1/test/").unwrap();
+    assert!(may_follow(
+        StatusCode::PERMANENT_REDIRECT,
+        &Method::POST,
+        &https,
+        &trailing_slash
+    ));
+}
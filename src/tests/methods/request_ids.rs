@@ -0,0 +1,100 @@
+use crate::errors::{request_id, Error, ErrorKind, ErrorTypes, RestError};
+use crate::idempotency::{is_replay, REPLAY_HINT};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn request_ids() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::new(401).with_header("x-request-id", "from-the-gateway"),
+        )
+        .once(
+            Method::PATCH,
+            "/rest/v1/orders",
+            MockResponse::new(204).with_header("content-range", "0-0/1"),
+        )
+        .on(
+            Method::PATCH,
+            "/rest/v1/orders",
+            MockResponse::json(
+                400,
+                &json!({
+                    "code": "P0001",
+                    "message": "Idempotency key already used: job-7",
+                    "hint": REPLAY_HINT
+                }),
+            ),
+        )
+        .on(Method::DELETE, "/rest/v1/orders", MockResponse::new(500));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    // the id echoed by the server is the one named in the error
+    let error: RestError = client.select("users").fetch().await.unwrap_err();
+    assert_eq!(error.request_id(), Some("from-the-gateway"));
+    assert_eq!(request_id(&error), Some("from-the-gateway"));
+
+    // a new message keeps the id, and a message alone names no request
+    let renamed: RestError = error.clone().with_message("Batch 2 of 2 failed");
+    assert_eq!(renamed.request_id(), Some("from-the-gateway"));
+    assert_eq!(
+        renamed,
+        "Batch 2 of 2 failed (request id: from-the-gateway)"
+    );
+    let quoted: String = format!("Batch 2 of 2 failed: {}", error);
+    assert_eq!(error.clone().with_message(quoted.as_str()), quoted);
+    assert_eq!(
+        RestError::from("quoted (request id: abc)").request_id(),
+        None
+    );
+    let error: Error = error.into();
+    assert_eq!(error.kind(), ErrorKind::Unauthorized);
+    assert_eq!(error.request_id(), Some("from-the-gateway"));
+
+    let update = client
+        .update_table("orders")
+        .eq("id", 42)
+        .set(json!({"status": "shipped"}))
+        .idempotency_key("job-7");
+    assert_eq!(update.clone().execute().await.unwrap(), 1);
+    let error: RestError = update.execute().await.unwrap_err();
+    assert!(is_replay(&error), "{}", error);
+    assert!(!error.is_unique_violation());
+
+    // a unique violation of the write, or another exception, isn't a replay
+    for body in [
+        json!({"code": "23505", "message": "Idempotency key already used: job-7"}),
+        json!({"code": "P0001", "message": "Idempotency key already used: job-7"}),
+    ] {
+        let error: RestError = ErrorTypes::from_postgrest_body(400, body.to_string()).into();
+        assert!(!is_replay(&error));
+    }
+
+    // otherwise the id sent with the request
+    let error: RestError = client
+        .delete_from("orders")
+        .eq("id", 42)
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(!is_replay(&error));
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    let sent: Vec<&str> = requests
+        .iter()
+        .map(|request| request.headers["x-request-id"].to_str().unwrap())
+        .collect();
+    assert_eq!(sent[3].len(), 36);
+    assert_eq!(error.request_id(), Some(sent[3]));
+    assert_eq!(request_id(&error), Some(sent[3]));
+    assert_ne!(sent[1], sent[2]);
+    assert_eq!(requests[1].headers["idempotency-key"], "job-7");
+    assert!(requests[0].headers.get("idempotency-key").is_none());
+}
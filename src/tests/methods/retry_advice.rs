@@ -0,0 +1,40 @@
+use crate::errors::{parse_retry_after, ErrorTypes};
+use std::time::Duration;
+
+pub async fn retry_advice() {
+    assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+    assert_eq!(parse_retry_after(" 3 "), Some(Duration::from_secs(3)));
+    assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+
+    let rate_limited: ErrorTypes = ErrorTypes::RateLimited {
+        retry_after: Some(Duration::from_secs(30)),
+        message: "Too many requests".to_string(),
+    };
+    assert!(rate_limited.is_retryable());
+    assert_eq!(rate_limited.retry_after(), Some(Duration::from_secs(30)));
+
+    let unavailable: ErrorTypes = ErrorTypes::ServiceUnavailable {
+        retry_after: None,
+        message: String::new(),
+    };
+    assert!(unavailable.is_retryable());
+    assert_eq!(unavailable.retry_after(), None);
+
+    // sending the same oversized body again can't succeed
+    let too_large: ErrorTypes = ErrorTypes::RequestTooLarge {
+        message: "Payload too large".to_string(),
+    };
+    assert!(!too_large.is_retryable());
+
+    let bad_gateway: ErrorTypes = ErrorTypes::ApiError {
+        status: 502,
+        message: String::new(),
+    };
+    assert!(bad_gateway.is_retryable());
+
+    let not_found: ErrorTypes = ErrorTypes::ApiError {
+        status: 404,
+        message: String::new(),
+    };
+    assert!(!not_found.is_retryable());
+}
@@ -0,0 +1,61 @@
+use crate::errors::{ErrorTypes, PostgrestErrorKind};
+
+pub async fn rpc_errors() {
+    let error: ErrorTypes = ErrorTypes::from_postgrest_body(
+        404,
+        r#"{"code":"PGRST202","details":"Searched for the function public.sum with parameter a","hint":"Perhaps you meant to call the function public.add","message":"Could not find the function public.sum(a) in the schema cache"}"#.to_string(),
+    );
+    let (body, kind) = error.postgrest_error().unwrap();
+    assert_eq!(kind, PostgrestErrorKind::NotFound);
+    assert_eq!(body.code.as_deref(), Some("PGRST202"));
+    assert_eq!(
+        body.hint.as_deref(),
+        Some("Perhaps you meant to call the function public.add")
+    );
+    assert!(error.to_string().starts_with(
+        "PostgREST error 404: [PGRST202] Could not find the function public.sum(a) in the schema cache"
+    ));
+
+    let kind = |status: u16, body: &str| {
+        ErrorTypes::from_postgrest_body(status, body.to_string())
+            .postgrest_error()
+            .map(|(_, kind)| kind)
+    };
+    assert_eq!(
+        kind(
+            406,
+            r#"{"code":"PGRST116","details":"The result contains 0 rows","message":"JSON object requested, multiple (or no) rows returned"}"#
+        ),
+        Some(PostgrestErrorKind::NoRows)
+    );
+    assert_eq!(
+        kind(
+            406,
+            r#"{"code":"PGRST116","details":"The result contains 3 rows","message":"JSON object requested, multiple (or no) rows returned"}"#
+        ),
+        Some(PostgrestErrorKind::MultipleRows)
+    );
+    assert_eq!(
+        kind(
+            403,
+            r#"{"code":"42501","message":"permission denied for function transfer"}"#
+        ),
+        Some(PostgrestErrorKind::PermissionDenied)
+    );
+    assert_eq!(
+        kind(
+            400,
+            r#"{"code":"22P02","message":"invalid input syntax for type integer: \"abc\""}"#
+        ),
+        Some(PostgrestErrorKind::InvalidParameters)
+    );
+    assert_eq!(
+        kind(400, r#"{"code":"P0001","message":"insufficient funds"}"#),
+        Some(PostgrestErrorKind::RaisedException)
+    );
+
+    // bodies that aren't PostgREST errors are kept as they are
+    let error: ErrorTypes = ErrorTypes::from_postgrest_body(502, "Bad Gateway".to_string());
+    assert!(error.postgrest_error().is_none());
+    assert!(error.is_retryable());
+}
@@ -0,0 +1,59 @@
+use crate::SupabaseClient;
+
+use serde_json::json;
+
+pub async fn rpc_read_only() {
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "anon-key".to_string()).unwrap();
+
+    // strings are sent as is, arrays as Postgres array literals, everything else as JSON text
+    let params = client
+        .rpc(
+            "nearby_stores",
+            json!({"city": "Amsterdam", "radius": 2.5, "tags": ["open", "bio"]}),
+        )
+        .read_only()
+        .query_params();
+    assert_eq!(
+        params,
+        vec![
+            ("city".to_string(), "Amsterdam".to_string()),
+            ("radius".to_string(), "2.5".to_string()),
+            ("tags".to_string(), "{\"open\",\"bio\"}".to_string()),
+        ]
+    );
+
+    let params = client
+        .rpc(
+            "nearby_stores",
+            json!({
+                "ids": [1, 2],
+                "grid": [[1, null], [3, 4]],
+                "names": ["a,b", "say \"hi\"", "back\\slash", "NULL", ""],
+                "filters": [{"open": true}]
+            }),
+        )
+        .read_only()
+        .query_params();
+    assert_eq!(
+        params,
+        vec![
+            (
+                "filters".to_string(),
+                "{\"{\\\"open\\\":true}\"}".to_string()
+            ),
+            ("grid".to_string(), "{{1,NULL},{3,4}}".to_string()),
+            ("ids".to_string(), "{1,2}".to_string()),
+            (
+                "names".to_string(),
+                "{\"a,b\",\"say \\\"hi\\\"\",\"back\\\\slash\",\"NULL\",\"\"}".to_string()
+            ),
+        ]
+    );
+
+    assert!(client
+        .rpc("now", json!({}))
+        .read_only()
+        .query_params()
+        .is_empty());
+}
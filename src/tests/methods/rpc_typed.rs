@@ -0,0 +1,31 @@
+use crate::errors::ErrorTypes;
+use crate::rpc::serialize_params;
+use crate::SupabaseClient;
+
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+struct Range {
+    from: i64,
+    to: i64,
+}
+
+pub async fn rpc_typed() {
+    assert_eq!(
+        serialize_params(Range { from: 1, to: 10 }).unwrap(),
+        json!({"from": 1, "to": 10})
+    );
+    assert_eq!(serialize_params(()).unwrap(), json!({}));
+
+    // maps with non-string keys can't be serialized to JSON, which is reported instead of sending `{}`
+    let mut invalid: HashMap<(i32, i32), i32> = HashMap::new();
+    invalid.insert((1, 2), 3);
+    assert!(serialize_params(&invalid).is_err());
+
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost:1".to_string(), "anon-key".to_string()).unwrap();
+    let result = client.rpc("sum", &invalid).execute_typed::<i64>().await;
+    assert!(matches!(result, Err(ErrorTypes::InvalidParameters(_))));
+}
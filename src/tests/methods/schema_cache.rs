@@ -0,0 +1,47 @@
+use crate::schema_cache::SchemaCache;
+
+use serde_json::json;
+
+pub async fn schema_cache() {
+    let cache: SchemaCache = SchemaCache::from_openapi(&json!({
+        "definitions": {
+            "authors": {
+                "properties": {
+                    "id": {"description": "Note:\nThis is a Primary Key.<pk/>", "format": "bigint", "type": "integer"}
+                }
+            },
+            "books": {
+                "properties": {
+                    "id": {"description": "Note:\nThis is a Primary Key.<pk/>", "format": "bigint", "type": "integer"},
+                    "author_id": {
+                        "description": "Note:\nThis is a Foreign Key to `authors.id`.<fk table='authors' column='id'/>",
+                        "format": "bigint",
+                        "type": "integer"
+                    }
+                }
+            },
+            "tags": {"properties": {"name": {"format": "text", "type": "string"}}}
+        }
+    }));
+
+    let relationships = cache.relationships("books");
+    assert_eq!(relationships.len(), 1);
+    assert_eq!(relationships[0].column, "author_id");
+    assert_eq!(relationships[0].foreign_table, "authors");
+    assert_eq!(relationships[0].foreign_column, "id");
+
+    // embeds work in both directions, and through the foreign key column
+    assert!(cache.validate_relation("books", "authors").is_ok());
+    assert!(cache.validate_relation("books", "author_id").is_ok());
+    assert!(cache.validate_relation("authors", "books").is_ok());
+
+    assert_eq!(
+        cache.validate_relation("books", "authorz").unwrap_err(),
+        "`books` has no relationship named `authorz`, expected one of: author_id, authors"
+    );
+    assert!(cache
+        .validate_relation("tags", "books")
+        .unwrap_err()
+        .contains("no relationships at all"));
+    assert!(cache.validate_relation("missing", "books").is_err());
+}
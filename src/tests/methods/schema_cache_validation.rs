@@ -0,0 +1,143 @@
+use crate::errors::RestError;
+use crate::query::OrderOptions;
+use crate::schema_cache::SchemaCache;
+use crate::table::{Column, Relationship, SupabaseTable};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Book {
+    id: i64,
+    author_id: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BookColumn;
+
+impl Column for BookColumn {
+    fn name(&self) -> &'static str {
+        "id"
+    }
+}
+
+impl SupabaseTable for Book {
+    type Column = BookColumn;
+
+    fn table_name() -> &'static str {
+        "books"
+    }
+
+    fn columns() -> &'static [&'static str] {
+        &["id", "author_id"]
+    }
+
+    fn belongs_to() -> &'static [Relationship] {
+        &[Relationship {
+            column: "author_id",
+            table: "authors",
+            foreign_column: "id",
+        }]
+    }
+}
+
+pub async fn schema_cache_validation() {
+    let cache: SchemaCache = SchemaCache::from_openapi(&json!({
+        "definitions": {
+            "users": {
+                "properties": {
+                    "id": {"format": "bigint", "type": "integer"},
+                    "email": {"format": "text", "type": "string"},
+                    "address": {"format": "jsonb"}
+                }
+            }
+        }
+    }));
+
+    assert_eq!(
+        cache.validate_table("userz").unwrap_err(),
+        "Unknown table `userz`, did you mean `users`?"
+    );
+    assert_eq!(
+        cache.validate_table("invoices").unwrap_err(),
+        "Unknown table `invoices`"
+    );
+    assert_eq!(
+        cache
+            .validate_columns("users", ["id", "emial"])
+            .unwrap_err(),
+        "Unknown column `emial` on `users`, did you mean `email`?"
+    );
+
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(Method::PATCH, "/rest/v1/users", MockResponse::new(204));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone())
+            .with_schema_cache_from(cache);
+
+    // unknown names fail before anything is sent
+    let error: RestError = client
+        .select("users")
+        .eq("emial", "a@b.c")
+        .fetch()
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        "Unknown column `emial` on `users`, did you mean `email`?"
+    );
+    assert!(client.select("userz").execute().await.is_err());
+    assert!(client
+        .select("users")
+        .columns(vec!["id", "nmae:email"])
+        .order_with(OrderOptions::asc("created_at"))
+        .execute()
+        .await
+        .unwrap_err()
+        .contains("`created_at`"));
+    assert!(client
+        .update_table("users")
+        .set(json!({"emial": "a@b.c"}))
+        .eq("id", "1")
+        .execute()
+        .await
+        .is_err());
+    assert!(client
+        .delete_from("users")
+        .eq("mail", "a@b.c")
+        .execute()
+        .await
+        .is_err());
+    assert!(transport.requests().is_empty());
+
+    // aliases, casts and JSON paths resolve to their column
+    client
+        .select("users")
+        .columns(vec!["id", "mail:email::text", "address->>city"])
+        .eq("address->>city", "Paris")
+        .execute()
+        .await
+        .unwrap();
+    // queries on another schema are left to PostgREST
+    client
+        .select("ledger")
+        .schema("billing")
+        .execute()
+        .await
+        .unwrap_err();
+    assert_eq!(transport.requests().len(), 2);
+
+    let cache: SchemaCache = SchemaCache::new().with_table::<Book>();
+    assert!(cache.validate_columns("books", ["id", "author_id"]).is_ok());
+    assert!(cache.validate_relation("books", "authors").is_ok());
+}
@@ -0,0 +1,93 @@
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+fn profile(request: &RecordedRequest, header: &str) -> Option<String> {
+    request
+        .headers
+        .get(header)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+pub async fn schema_profile() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/events",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(Method::POST, "/rest/v1/events", MockResponse::new(201))
+        .on(
+            Method::POST,
+            "/rest/v1/rpc/daily_totals",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(
+            Method::POST,
+            "/rest/v1/rpc/supabase_rs_batch",
+            MockResponse::json(200, &json!([1])),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    // without a schema the default exposed schema is used
+    client.select("events").execute().await.unwrap();
+
+    let analytics: SupabaseClient = client.clone().with_schema("analytics");
+    assert_eq!(analytics.schema(), Some("analytics"));
+    analytics.select("events").execute().await.unwrap();
+    analytics
+        .select("events")
+        .schema("archive")
+        .execute()
+        .await
+        .unwrap();
+    analytics
+        .insert_without_defined_key("events", json!({"name": "signup"}))
+        .await
+        .unwrap();
+    analytics
+        .rpc("daily_totals", json!({}))
+        .execute()
+        .await
+        .unwrap();
+    analytics
+        .batch()
+        .insert("events", json!({"name": "login"}))
+        .execute()
+        .await
+        .unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(profile(&requests[0], "Accept-Profile"), None);
+    assert_eq!(
+        profile(&requests[1], "Accept-Profile").as_deref(),
+        Some("analytics")
+    );
+    assert_eq!(
+        profile(&requests[2], "Accept-Profile").as_deref(),
+        Some("archive")
+    );
+    assert_eq!(
+        profile(&requests[3], "Content-Profile").as_deref(),
+        Some("analytics")
+    );
+    assert_eq!(
+        profile(&requests[4], "Content-Profile").as_deref(),
+        Some("analytics")
+    );
+
+    // the batch function lives in `public` and writes to the tables of the client schema
+    assert_eq!(
+        profile(&requests[5], "Content-Profile").as_deref(),
+        Some("public")
+    );
+    assert_eq!(
+        requests[5].json().unwrap()["operations"][0]["schema"],
+        "analytics"
+    );
+}
@@ -0,0 +1,144 @@
+use crate::cache::{CacheConfig, ResponseCache};
+use crate::testing::SupabaseMock;
+use crate::transport::{HttpTransport, TransportFuture};
+use crate::SupabaseClient;
+
+use reqwest::{Method, Request};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Holds writes until they are released, and counts the selects it answered.
+#[derive(Debug, Default)]
+struct HeldWrites {
+    sent: Arc<Notify>,
+    released: Arc<Notify>,
+    selects: Arc<AtomicUsize>,
+}
+
+impl HttpTransport for HeldWrites {
+    fn execute(&self, request: Request) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let response = if request.method() == Method::GET {
+                self.selects.fetch_add(1, Ordering::SeqCst);
+                http::Response::builder()
+                    .status(200)
+                    .body(json!([{"code": "NL"}]).to_string())
+            } else {
+                self.sent.notify_one();
+                self.released.notified().await;
+                http::Response::builder().status(201).body(String::new())
+            };
+            Ok(response.unwrap().into())
+        })
+    }
+}
+
+pub async fn select_cache() {
+    let mock: SupabaseMock = SupabaseMock::new()
+        .select("countries", json!([{"code": "NL"}]))
+        .insert("countries");
+    let client: SupabaseClient = mock.client().with_cache(CacheConfig::default());
+
+    // identical selects are served from the cache
+    for _ in 0..3 {
+        let rows = client.select("countries").execute().await.unwrap();
+        assert_eq!(rows, vec![json!({"code": "NL"})]);
+    }
+    assert_eq!(mock.requests().len(), 1);
+
+    // other filters, bypassing queries and writes reach the database
+    client
+        .select("countries")
+        .eq("code", "NL")
+        .execute()
+        .await
+        .unwrap();
+    client
+        .select("countries")
+        .no_cache()
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(mock.requests().len(), 3);
+
+    client
+        .insert("countries", json!({"code": "BE"}))
+        .await
+        .unwrap();
+    client.select("countries").execute().await.unwrap();
+    assert_eq!(mock.requests().len(), 5);
+
+    client.clear_cache();
+    client
+        .select("countries")
+        .cache_ttl(Duration::ZERO)
+        .execute()
+        .await
+        .unwrap();
+    client
+        .select("countries")
+        .cache_ttl(Duration::ZERO)
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(mock.requests().len(), 7);
+
+    // clones sent as other users or roles don't share cached rows
+    client.clear_cache();
+    let jane: SupabaseClient = client.impersonate_user("jane-token");
+    let john: SupabaseClient = client.impersonate_user("john-token");
+    jane.select("countries").execute().await.unwrap();
+    john.select("countries").execute().await.unwrap();
+    client.select("countries").execute().await.unwrap();
+    assert_eq!(mock.requests().len(), 10);
+    jane.select("countries").execute().await.unwrap();
+    john.select("countries").execute().await.unwrap();
+    assert_eq!(mock.requests().len(), 10);
+
+    // a select answered while a write is in flight doesn't outlive the write
+    let transport: HeldWrites = HeldWrites::default();
+    let (sent, released, selects) = (
+        transport.sent.clone(),
+        transport.released.clone(),
+        transport.selects.clone(),
+    );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport)
+            .with_cache(CacheConfig::default());
+    let write = tokio::spawn({
+        let client: SupabaseClient = client.clone();
+        async move {
+            client
+                .insert_without_defined_key("countries", json!({"code": "BE"}))
+                .await
+        }
+    });
+    sent.notified().await;
+    client.select("countries").execute().await.unwrap();
+    assert_eq!(selects.load(Ordering::SeqCst), 1);
+    released.notify_one();
+    write.await.unwrap().unwrap();
+    client.select("countries").execute().await.unwrap();
+    assert_eq!(selects.load(Ordering::SeqCst), 2);
+
+    // the entries expiring first make room for new ones
+    let cache: ResponseCache = ResponseCache::new(CacheConfig {
+        ttl: Duration::from_secs(60),
+        max_entries: 2,
+    });
+    cache.insert("a".to_string(), "t", vec![], Some(Duration::from_secs(10)));
+    cache.insert("b".to_string(), "t", vec![], None);
+    cache.insert("c".to_string(), "u", vec![], None);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("a").is_none());
+    assert!(cache.get("b").is_some());
+
+    cache.invalidate("t");
+    assert!(cache.get("b").is_none());
+    assert!(cache.get("c").is_some());
+}
@@ -0,0 +1,35 @@
+use crate::tests::methods::init::init;
+use crate::SupabaseClient;
+
+pub async fn select_csv() {
+    /// Performs a select_csv operation in an isolated scope.
+    async fn select_csv_inner(supabase_client: SupabaseClient) -> Result<(), String> {
+        // Usage example
+
+        let csv: String = supabase_client
+            .select("test")
+            .columns(vec!["id", "dog"])
+            .execute_csv()
+            .await?;
+
+        // the first line is always the header
+        match csv.lines().next() {
+            Some("id,dog") => Ok(()),
+            header => Err(format!("Unexpected CSV header: {:?}", header)),
+        }
+    }
+
+    let supabase_client: SupabaseClient = match init().await {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!(
+                "\x1b[31mFailed to initialize Supabase client: {:?}\x1b[0m",
+                e
+            );
+            return;
+        }
+    };
+    let response: Result<(), String> = select_csv_inner(supabase_client).await;
+
+    assert!(response.is_ok());
+}
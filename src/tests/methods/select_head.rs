@@ -0,0 +1,33 @@
+use crate::tests::methods::init::init;
+use crate::SupabaseClient;
+
+pub async fn select_head() {
+    /// Counts and checks rows without fetching them in an isolated scope.
+    async fn select_head_inner(supabase_client: SupabaseClient) -> Result<(), String> {
+        let total: u64 = supabase_client.select("test").count_only().await?;
+
+        let exists: bool = supabase_client
+            .select("test")
+            .eq("dog", "what da dog doing")
+            .exists()
+            .await?;
+
+        // a matching row means the table isn't empty
+        assert!(!exists || total > 0);
+        Ok(())
+    }
+
+    let supabase_client: SupabaseClient = match init().await {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!(
+                "\x1b[31mFailed to initialize Supabase client: {:?}\x1b[0m",
+                e
+            );
+            return;
+        }
+    };
+    let response: Result<(), String> = select_head_inner(supabase_client).await;
+
+    assert!(response.is_ok());
+}
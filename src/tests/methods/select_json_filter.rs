@@ -0,0 +1,28 @@
+use crate::query_builder::json::json_path;
+use crate::SupabaseClient;
+
+use serde_json::json;
+
+pub async fn select_json_filter() {
+    assert_eq!(json_path("metadata", &[]), "metadata");
+    assert_eq!(json_path("metadata", &["kind"]), "metadata->>kind");
+    assert_eq!(
+        json_path("metadata", &["address", "lines", "0"]),
+        "metadata->address->lines->>0"
+    );
+
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+
+    let query: String = client
+        .select("events")
+        .contains("metadata", &json!({"source": "webhook"}))
+        .contained_by("tags", &json!(["a", "b"]))
+        .eq(&json_path("metadata", &["kind"]), "signup")
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "metadata=cs.{\"source\":\"webhook\"}&tags=cd.[\"a\",\"b\"]&metadata->>kind=eq.signup"
+    );
+}
@@ -0,0 +1,32 @@
+use crate::interop::PostgrestCompat;
+use crate::query::OrderOptions;
+use crate::SupabaseClient;
+
+pub async fn select_order() {
+    assert_eq!(OrderOptions::asc("name").to_string(), "name.asc");
+    assert_eq!(
+        OrderOptions::desc("starts_at").nulls_last().to_string(),
+        "starts_at.desc.nullslast"
+    );
+    assert_eq!(
+        OrderOptions::asc("rank").nulls_first().to_string(),
+        "rank.asc.nullsfirst"
+    );
+
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+
+    // every ordering is kept, in the order they were added
+    let query: String = client
+        .select("events")
+        .order_with(OrderOptions::desc("starts_at").nulls_last())
+        .eq("kind", "talk")
+        .order_with(OrderOptions::asc("name"))
+        .order("id.asc")
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "order=starts_at.desc.nullslast,name.asc,id.asc&kind=eq.talk"
+    );
+}
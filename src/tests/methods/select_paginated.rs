@@ -0,0 +1,41 @@
+use crate::tests::methods::init::init;
+use crate::SupabaseClient;
+use serde_json::Value;
+
+pub async fn select_paginated() {
+    /// Performs a paginated select operation in an isolated scope.
+    async fn select_paginated_inner(supabase_client: SupabaseClient) -> Result<(), String> {
+        // Usage example
+
+        let all_rows: Vec<Value> = supabase_client.select("test").execute().await?;
+
+        let paginated_rows: Vec<Value> = supabase_client
+            .select("test")
+            .paginate(2)
+            .collect_all()
+            .await?;
+
+        if paginated_rows.len() < all_rows.len() {
+            return Err(format!(
+                "Paginated {} rows, expected at least {}",
+                paginated_rows.len(),
+                all_rows.len()
+            ));
+        }
+        Ok(())
+    }
+
+    let supabase_client: SupabaseClient = match init().await {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!(
+                "\x1b[31mFailed to initialize Supabase client: {:?}\x1b[0m",
+                e
+            );
+            return;
+        }
+    };
+    let response: Result<(), String> = select_paginated_inner(supabase_client).await;
+
+    assert!(response.is_ok());
+}
@@ -0,0 +1,20 @@
+use crate::SupabaseClient;
+
+pub async fn select_range_filter() {
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+
+    let query: String = client
+        .select("bookings")
+        .overlaps("during", "[2024-01-01,2024-02-01)")
+        .strictly_left("seats", "[10,20)")
+        .strictly_right("floors", "[1,3)")
+        .adjacent("window", "(5,6]")
+        .overlaps("tags", "{rust,sql}")
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "during=ov.[2024-01-01,2024-02-01)&seats=sl.[10,20)&floors=sr.[1,3)&window=adj.(5,6]&tags=ov.{rust,sql}"
+    );
+}
@@ -0,0 +1,121 @@
+use crate::errors::RestError;
+use crate::query_builder::embed::{embed_relation, split_top_level};
+use crate::schema_cache::SchemaCache;
+use crate::SupabaseClient;
+
+use serde_json::json;
+use std::sync::Arc;
+
+pub async fn select_related() {
+    assert_eq!(
+        split_top_level("id, name,orders(id,items(sku,quantity)),author:users(*)"),
+        vec![
+            "id",
+            "name",
+            "orders(id,items(sku,quantity))",
+            "author:users(*)"
+        ]
+    );
+    assert_eq!(
+        embed_relation("author:users!author_id(name)"),
+        Some("users")
+    );
+    assert_eq!(embed_relation("books!inner(*)"), Some("books"));
+    assert_eq!(embed_relation("name"), None);
+    assert_eq!(embed_relation("count()"), None);
+    assert_eq!(embed_relation("total:amount.sum()::int"), None);
+
+    let mut client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+
+    // all columns unless `columns` picks some, before or after the embeds
+    let query: String = client
+        .select("customers")
+        .select_related("orders", &["id", "total"])
+        .select_related("addresses", &[])
+        .query
+        .build();
+    assert_eq!(query, "select=*,orders(id,total),addresses(*)");
+
+    let query: String = client
+        .select("customers")
+        .select_related("orders", &["id", "items(sku)"])
+        .columns(vec!["id", "name"])
+        .eq("id", "1")
+        .query
+        .build();
+    assert_eq!(query, "select=id,name,orders(id,items(sku))&id=eq.1");
+
+    client.schema_cache = Some(Arc::new(SchemaCache::from_openapi(&json!({
+        "definitions": {
+            "authors": {"properties": {"id": {"format": "bigint", "type": "integer"}}},
+            "books": {
+                "properties": {
+                    "author_id": {
+                        "description": "<fk table='authors' column='id'/>",
+                        "format": "bigint",
+                        "type": "integer"
+                    }
+                }
+            },
+            "tags": {"properties": {"id": {"format": "bigint", "type": "integer"}}},
+            "book_tags": {
+                "properties": {
+                    "book_id": {
+                        "description": "<fk table='books' column='id'/>",
+                        "format": "bigint",
+                        "type": "integer"
+                    },
+                    "tag_id": {
+                        "description": "<fk table='tags' column='id'/>",
+                        "format": "bigint",
+                        "type": "integer"
+                    }
+                }
+            },
+            "book_stats": {"properties": {"book_id": {"format": "bigint", "type": "integer"}}}
+        }
+    }))));
+
+    assert!(client
+        .select("books")
+        .select_related("writer:authors!author_id", &["id"])
+        .validate_embeds()
+        .is_ok());
+
+    // aggregates aren't relations
+    assert!(client
+        .select("books")
+        .columns(vec!["author_id", "count()", "total:author_id.sum()"])
+        .validate_embeds()
+        .is_ok());
+
+    // many-to-many through a join table, and the relations of a view the cache can't see
+    assert!(client
+        .select("books")
+        .select_related("tags", &["id"])
+        .validate_embeds()
+        .is_ok());
+    assert!(client
+        .select("books")
+        .select_related("book_stats", &["*"])
+        .validate_embeds()
+        .is_ok());
+    assert!(client
+        .select("book_stats")
+        .select_related("books", &["*"])
+        .validate_embeds()
+        .is_ok());
+
+    // unknown relations fail before a request is sent
+    let error: RestError = client
+        .select("books")
+        .select_related("authorz", &["id"])
+        .fetch()
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        "`books` has no relationship named `authorz`, expected one of: author_id, authors, book_tags, tags"
+    );
+}
@@ -0,0 +1,43 @@
+use crate::SupabaseClient;
+
+pub async fn select_related_filter() {
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+
+    let query: String = client
+        .select("customers")
+        .select_related("orders", &["id", "total"])
+        .inner_join("orders")
+        .inner_join("orders")
+        .eq_related("orders", "status", "paid")
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "select=*,orders!inner(id,total)&orders.status=eq.paid"
+    );
+
+    // aliased embeds are joined and filtered by their alias
+    let query: String = client
+        .select("books")
+        .columns(vec!["title"])
+        .select_related("writer:authors!author_id", &["name"])
+        .inner_join("writer")
+        .eq_related("writer", "country", "NL")
+        .query
+        .build();
+    assert_eq!(
+        query,
+        "select=title,writer:authors!author_id!inner(name)&writer.country=eq.NL"
+    );
+
+    // a relation that is only filtered on is embedded without columns
+    let query: String = client
+        .select("customers")
+        .columns(vec!["id"])
+        .inner_join("orders")
+        .eq_related("orders", "status", "paid")
+        .query
+        .build();
+    assert_eq!(query, "select=id,orders!inner()&orders.status=eq.paid");
+}
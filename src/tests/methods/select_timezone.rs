@@ -0,0 +1,39 @@
+use crate::query::CountMode;
+use crate::SupabaseClient;
+
+pub async fn select_timezone() {
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "key".to_string()).unwrap();
+
+    // without a timezone or count no preference is sent
+    let headers = client.select("events").preferences(None).get_headers();
+    assert!(headers.is_empty());
+
+    let headers = client
+        .select("events")
+        .timezone("Europe/Amsterdam")
+        .preferences(Some(CountMode::Exact))
+        .get_headers();
+    assert_eq!(
+        headers.get("prefer").map(String::as_str),
+        Some("count=exact,timezone=Europe/Amsterdam")
+    );
+
+    // the timezone of the query overrides the one of the client
+    let client: SupabaseClient = client.with_timezone("America/New_York");
+    let headers = client.select("events").preferences(None).get_headers();
+    assert_eq!(
+        headers.get("prefer").map(String::as_str),
+        Some("timezone=America/New_York")
+    );
+
+    let headers = client
+        .select("events")
+        .timezone("Asia/Tokyo")
+        .preferences(None)
+        .get_headers();
+    assert_eq!(
+        headers.get("prefer").map(String::as_str),
+        Some("timezone=Asia/Tokyo")
+    );
+}
@@ -0,0 +1,15 @@
+use crate::query::CountMode;
+use crate::success::parse_content_range_total;
+
+pub async fn select_with_count_header() {
+    assert_eq!(parse_content_range_total("0-24/3573"), Some(3573));
+    assert_eq!(parse_content_range_total("*/0"), Some(0));
+
+    // no count was requested, so the total is unknown
+    assert_eq!(parse_content_range_total("0-24/*"), None);
+    assert_eq!(parse_content_range_total("0-24"), None);
+
+    assert_eq!(CountMode::Exact.as_str(), "exact");
+    assert_eq!(CountMode::Planned.as_str(), "planned");
+    assert_eq!(CountMode::Estimated.as_str(), "estimated");
+}
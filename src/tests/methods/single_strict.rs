@@ -0,0 +1,74 @@
+use crate::errors::RestError;
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Order {
+    id: i64,
+}
+
+pub async fn single_strict() {
+    let multiple_rows = json!({
+        "code": "PGRST116",
+        "message": "JSON object requested, multiple (or no) rows returned",
+        "details": "The result contains 2 rows",
+        "hint": null,
+    });
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(200, &json!({"id": 42})),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(406, &multiple_rows),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/orders",
+            MockResponse::json(200, &json!({"id": 7})),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let order: Value = client
+        .select("orders")
+        .eq("id", 42)
+        .single_strict()
+        .await
+        .unwrap();
+    assert_eq!(order, json!({"id": 42}));
+
+    let error: RestError = client
+        .select("orders")
+        .eq("status", "open")
+        .single_strict()
+        .await
+        .unwrap_err();
+    assert!(error.contains("[PGRST116]"), "{}", error);
+    assert!(error.contains("2 rows"));
+
+    let order: Order = client
+        .select("orders")
+        .eq("id", 7)
+        .single_strict_typed()
+        .await
+        .unwrap();
+    assert_eq!(order, Order { id: 7 });
+
+    let requests = transport.requests();
+    assert_eq!(
+        requests[0].headers["accept"],
+        "application/vnd.pgrst.object+json"
+    );
+    // the cardinality is left to the database, no limit is added
+    assert_eq!(requests[0].query("limit"), None);
+}
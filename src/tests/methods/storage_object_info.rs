@@ -0,0 +1,70 @@
+use crate::errors::ErrorTypes;
+use crate::storage::{ObjectInfo, StorageBucket};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn storage_object_info() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/storage/v1/object/info/avatars/users/jane%20doe.png",
+            MockResponse::json(
+                200,
+                &json!({
+                    "id": "obj-1",
+                    "name": "users/jane doe.png",
+                    "bucket_id": "avatars",
+                    "version": "v1",
+                    "size": 5120,
+                    "content_type": "image/png",
+                    "cache_control": "max-age=3600",
+                    "etag": "\"abc\"",
+                    "last_modified": "2024-05-09T14:05:33Z",
+                    "created_at": "2024-05-09T14:05:33Z",
+                    "metadata": {"owner": "jane"},
+                }),
+            ),
+        )
+        .on(
+            Method::HEAD,
+            "/storage/v1/object/avatars/users/jane%20doe.png",
+            MockResponse::new(200),
+        )
+        .on(
+            Method::HEAD,
+            "/storage/v1/object/avatars/users/missing.png",
+            MockResponse::new(400),
+        )
+        .on(
+            Method::HEAD,
+            "/storage/v1/object/avatars/private.png",
+            MockResponse::new(403),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let avatars: StorageBucket = client.storage().from("avatars");
+
+    let info: ObjectInfo = avatars.info("users/jane doe.png").await.unwrap();
+    assert_eq!(info.size, Some(5120));
+    assert_eq!(info.content_type.as_deref(), Some("image/png"));
+    assert_eq!(info.cache_control.as_deref(), Some("max-age=3600"));
+    assert_eq!(info.last_modified.as_deref(), Some("2024-05-09T14:05:33Z"));
+    assert_eq!(info.metadata, json!({"owner": "jane"}));
+
+    assert!(avatars.exists("users/jane doe.png").await.unwrap());
+    assert!(!avatars.exists("users/missing.png").await.unwrap());
+    // objects the user may not read aren't reported as missing
+    assert!(matches!(
+        avatars.exists("private.png").await,
+        Err(ErrorTypes::ApiError { status: 403, .. })
+    ));
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[0].headers["authorization"], "Bearer key");
+    assert_eq!(requests[0].headers["apikey"], "key");
+}
@@ -0,0 +1,33 @@
+use crate::storage::{ImageFormat, ResizeMode, StorageBucket, TransformOptions};
+use crate::SupabaseClient;
+
+pub async fn storage_public_url() {
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string()).unwrap();
+    let avatars: StorageBucket = client.storage().from("avatars");
+
+    assert_eq!(
+        avatars.get_public_url("users/jane.png"),
+        "https://project.supabase.co/storage/v1/object/public/avatars/users/jane.png"
+    );
+    // segments are percent-encoded
+    assert_eq!(
+        avatars.get_public_url("/summer 2024/#1.png"),
+        "https://project.supabase.co/storage/v1/object/public/avatars/summer%202024/%231.png"
+    );
+
+    let transform: TransformOptions = TransformOptions::new()
+        .width(64)
+        .height(48)
+        .resize(ResizeMode::Contain)
+        .quality(75)
+        .format(ImageFormat::Origin);
+    assert_eq!(
+        avatars.get_public_url_transformed("users/jane.png", &transform),
+        "https://project.supabase.co/storage/v1/render/image/public/avatars/users/jane.png?width=64&height=48&resize=contain&quality=75&format=origin"
+    );
+    assert_eq!(
+        avatars.get_public_url_transformed("jane.png", &TransformOptions::new()),
+        "https://project.supabase.co/storage/v1/render/image/public/avatars/jane.png"
+    );
+}
@@ -0,0 +1,158 @@
+use crate::errors::ErrorTypes;
+use crate::storage::StorageBucket;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+
+const OBJECT_PATH: &str = "/storage/v1/object/videos/clips/intro.mp4";
+const INFO_PATH: &str = "/storage/v1/object/info/videos/clips/intro.mp4";
+
+fn header(request: &RecordedRequest, name: &str) -> String {
+    request.headers[name].to_str().unwrap().to_string()
+}
+
+pub async fn storage_range_download() {
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(206)
+                .with_header("Content-Range", "bytes 2-5/10")
+                .with_body("cdef"),
+        )
+        // a server ignoring the range
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(200).with_body("abcdefghij"),
+        )
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(206)
+                .with_header("Content-Range", "bytes 0-9/10")
+                .with_body("cdef"),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let videos: StorageBucket = client.storage().from("videos");
+
+    let bytes: Vec<u8> = videos
+        .download_range("clips/intro.mp4", 2, Some(5))
+        .await
+        .unwrap();
+    assert_eq!(bytes, b"cdef");
+    let bytes: Vec<u8> = videos
+        .download_range("clips/intro.mp4", 7, None)
+        .await
+        .unwrap();
+    assert_eq!(bytes, b"hij");
+    // a range other than the one asked for isn't taken for it
+    assert!(matches!(
+        videos.download_range("clips/intro.mp4", 2, Some(5)).await,
+        Err(ErrorTypes::UnexpectedResponse(_))
+    ));
+    assert!(matches!(
+        videos.download_range("clips/intro.mp4", 5, Some(2)).await,
+        Err(ErrorTypes::InvalidParameters(_))
+    ));
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(header(&requests[0], "range"), "bytes=2-5");
+    assert_eq!(header(&requests[1], "range"), "bytes=7-");
+    assert_eq!(requests.len(), 3);
+
+    // continuing a file holding the first 4 bytes of the same object
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(206)
+                .with_header("Content-Range", "bytes 4-9/10")
+                .with_header("ETag", "\"v1\"")
+                .with_body("efghij"),
+        )
+        // the object changed since, the server sends all of it
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(200)
+                .with_header("ETag", "\"v2\"")
+                .with_body("ABCDEFGHIJ"),
+        )
+        // a file without an ETag starts over
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(206)
+                .with_header("Content-Range", "bytes 0-9/10")
+                .with_header("ETag", "\"v2\"")
+                .with_body("ABCDEFGHIJ"),
+        )
+        // nothing after the end of the file, which is complete
+        .once(Method::GET, OBJECT_PATH, MockResponse::new(416))
+        .once(
+            Method::GET,
+            INFO_PATH,
+            MockResponse::json(200, &serde_json::json!({ "size": 10 })),
+        )
+        // nothing after the end of a file longer than the object
+        .once(Method::GET, OBJECT_PATH, MockResponse::new(416))
+        .once(
+            Method::GET,
+            INFO_PATH,
+            MockResponse::json(200, &serde_json::json!({ "size": 10 })),
+        )
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(206)
+                .with_header("Content-Range", "bytes 0-9/10")
+                .with_body("ABCDEFGHIJ"),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let videos: StorageBucket = client.storage().from("videos");
+
+    let file_path =
+        std::env::temp_dir().join(format!("supabase_rs_range_{}.mp4", rand::random::<u64>()));
+    let file: &str = file_path.to_str().unwrap();
+    let etag_file: String = format!("{}.etag", file);
+    std::fs::write(file, "abcd").unwrap();
+    std::fs::write(&etag_file, "\"v1\"").unwrap();
+    assert_eq!(videos.save("clips/intro.mp4", file).await.unwrap(), 10);
+    assert_eq!(std::fs::read(file).unwrap(), b"abcdefghij");
+    assert!(!std::path::Path::new(&etag_file).exists());
+    let request: RecordedRequest = transport.requests().pop().unwrap();
+    assert_eq!(header(&request, "range"), "bytes=4-");
+    assert_eq!(header(&request, "if-range"), "\"v1\"");
+
+    std::fs::write(file, "abcd").unwrap();
+    std::fs::write(&etag_file, "\"v1\"").unwrap();
+    assert_eq!(videos.save("clips/intro.mp4", file).await.unwrap(), 10);
+    assert_eq!(std::fs::read(file).unwrap(), b"ABCDEFGHIJ");
+
+    std::fs::write(file, "abcd").unwrap();
+    assert_eq!(videos.save("clips/intro.mp4", file).await.unwrap(), 10);
+    assert_eq!(std::fs::read(file).unwrap(), b"ABCDEFGHIJ");
+    let request: RecordedRequest = transport.requests().pop().unwrap();
+    assert_eq!(header(&request, "range"), "bytes=0-");
+    assert!(request.headers.get("if-range").is_none());
+
+    std::fs::write(&etag_file, "\"v2\"").unwrap();
+    assert_eq!(videos.save("clips/intro.mp4", file).await.unwrap(), 10);
+    assert_eq!(std::fs::read(file).unwrap(), b"ABCDEFGHIJ");
+    assert!(!std::path::Path::new(&etag_file).exists());
+
+    std::fs::write(file, "ABCDEFGHIJKL").unwrap();
+    std::fs::write(&etag_file, "\"v2\"").unwrap();
+    assert_eq!(videos.save("clips/intro.mp4", file).await.unwrap(), 10);
+    assert_eq!(std::fs::read(file).unwrap(), b"ABCDEFGHIJ");
+    assert_eq!(transport.requests().len(), 8);
+    std::fs::remove_file(file).unwrap();
+}
@@ -0,0 +1,172 @@
+use crate::storage::resumable::{ResumableUpload, ResumableUploadOptions, CHUNK_SIZE};
+use crate::storage::StorageBucket;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+const UPLOAD_PATH: &str = "/storage/v1/upload/resumable/upload-1";
+
+fn header(request: &RecordedRequest, name: &str) -> String {
+    request.headers[name].to_str().unwrap().to_string()
+}
+
+pub async fn storage_resumable_upload() {
+    let size: usize = CHUNK_SIZE + 100;
+    let data: Vec<u8> = (0..size).map(|index| b'a' + (index % 26) as u8).collect();
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/storage/v1/upload/resumable",
+            MockResponse::new(201).with_header("Location", UPLOAD_PATH),
+        )
+        .once(
+            Method::PATCH,
+            UPLOAD_PATH,
+            MockResponse::new(204).with_header("Upload-Offset", &CHUNK_SIZE.to_string()),
+        )
+        .once(Method::PATCH, UPLOAD_PATH, MockResponse::new(503))
+        .on(
+            Method::HEAD,
+            UPLOAD_PATH,
+            MockResponse::new(200).with_header("Upload-Offset", &CHUNK_SIZE.to_string()),
+        )
+        .once(
+            Method::PATCH,
+            UPLOAD_PATH,
+            MockResponse::new(204).with_header("Upload-Offset", &size.to_string()),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let videos: StorageBucket = client.storage().from("videos");
+
+    let progress: Arc<Mutex<Vec<(u64, u64)>>> = Arc::default();
+    let reported: Arc<Mutex<Vec<(u64, u64)>>> = progress.clone();
+    let mut upload: ResumableUpload = videos
+        .create_resumable_upload(
+            "2024/holiday.mp4",
+            size as u64,
+            ResumableUploadOptions::new()
+                .content_type("video/mp4")
+                .upsert(true)
+                .access_token("user-jwt")
+                .on_progress(move |progress| {
+                    reported
+                        .lock()
+                        .unwrap()
+                        .push((progress.bytes_uploaded, progress.total_bytes))
+                }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        upload.url(),
+        "https://project.supabase.co/storage/v1/upload/resumable/upload-1"
+    );
+
+    let create: RecordedRequest = transport.requests()[0].clone();
+    assert_eq!(header(&create, "Tus-Resumable"), "1.0.0");
+    assert_eq!(header(&create, "Upload-Length"), size.to_string());
+    assert_eq!(header(&create, "x-upsert"), "true");
+    assert_eq!(header(&create, "Authorization"), "Bearer user-jwt");
+    // base64 of "videos", "2024/holiday.mp4" and "video/mp4"
+    assert_eq!(
+        header(&create, "Upload-Metadata"),
+        "bucketName dmlkZW9z,objectName MjAyNC9ob2xpZGF5Lm1wNA==,contentType dmlkZW8vbXA0"
+    );
+
+    // the second chunk fails, the first one stays uploaded
+    assert!(upload.upload_from(Cursor::new(&data)).await.is_err());
+    assert_eq!(upload.offset(), CHUNK_SIZE as u64);
+    assert!(!upload.is_complete());
+
+    assert_eq!(upload.sync_offset().await.unwrap(), CHUNK_SIZE as u64);
+    upload.upload_from(Cursor::new(&data)).await.unwrap();
+    assert!(upload.is_complete());
+    // every chunk the server received is reported
+    assert_eq!(
+        *progress.lock().unwrap(),
+        vec![(CHUNK_SIZE as u64, size as u64), (size as u64, size as u64)]
+    );
+
+    let patches: Vec<RecordedRequest> = transport
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == Method::PATCH)
+        .collect();
+    assert_eq!(patches.len(), 3);
+    assert_eq!(header(&patches[0], "Upload-Offset"), "0");
+    assert_eq!(
+        header(&patches[0], "Content-Type"),
+        "application/offset+octet-stream"
+    );
+    assert_eq!(
+        patches[0].body.as_deref().unwrap().as_bytes(),
+        &data[..CHUNK_SIZE]
+    );
+    assert_eq!(header(&patches[2], "Upload-Offset"), CHUNK_SIZE.to_string());
+    assert_eq!(
+        patches[2].body.as_deref().unwrap().as_bytes(),
+        &data[CHUNK_SIZE..]
+    );
+
+    // an upload can be continued from its URL
+    let resumed: ResumableUpload = ResumableUpload::resume(
+        &videos,
+        upload.url(),
+        size as u64,
+        ResumableUploadOptions::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resumed.offset(), CHUNK_SIZE as u64);
+
+    // larger chunks are multiples of the chunk size Storage requires
+    assert_eq!(ResumableUploadOptions::new().chunk_size, CHUNK_SIZE);
+    assert_eq!(
+        ResumableUploadOptions::new().chunk_size(1).chunk_size,
+        CHUNK_SIZE
+    );
+    assert_eq!(
+        ResumableUploadOptions::new()
+            .chunk_size(CHUNK_SIZE + 1)
+            .chunk_size,
+        2 * CHUNK_SIZE
+    );
+
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::POST,
+            "/storage/v1/upload/resumable",
+            MockResponse::new(201).with_header("Location", UPLOAD_PATH),
+        )
+        .on(
+            Method::PATCH,
+            UPLOAD_PATH,
+            MockResponse::new(204).with_header("Upload-Offset", &size.to_string()),
+        );
+    let videos: StorageBucket = client
+        .clone()
+        .with_transport(transport.clone())
+        .storage()
+        .from("videos");
+    videos
+        .upload_resumable(
+            "2024/holiday.mp4",
+            &data,
+            ResumableUploadOptions::new().chunk_size(2 * CHUNK_SIZE),
+        )
+        .await
+        .unwrap();
+    let patches: Vec<RecordedRequest> = transport
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == Method::PATCH)
+        .collect();
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0].body.as_deref().unwrap().len(), size);
+}
@@ -0,0 +1,192 @@
+use crate::errors::{ErrorKind, ErrorTypes};
+use crate::storage::{Checksum, StorageBucket, StorageRetry};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+use std::time::Duration;
+
+const OBJECT_PATH: &str = "/storage/v1/object/docs/notes/hello.txt";
+const INFO_PATH: &str = "/storage/v1/object/info/docs/notes/hello.txt";
+const HELLO_MD5: &str = "5d41402abc4b2a76b9719d911017c592";
+const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+fn bucket(transport: &MockTransport) -> StorageBucket {
+    SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+        .unwrap()
+        .with_transport(transport.clone())
+        .storage()
+        .from("docs")
+        .with_retry(StorageRetry::new().max_retries(2).backoff(Duration::ZERO))
+}
+
+pub async fn storage_retry_checksum() {
+    // transient failures are sent again, the Retry-After is waited for
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            INFO_PATH,
+            MockResponse::new(503).with_header("Retry-After", "0"),
+        )
+        .once(Method::GET, INFO_PATH, MockResponse::new(502))
+        .once(
+            Method::GET,
+            INFO_PATH,
+            MockResponse::json(200, &json!({ "name": "notes/hello.txt", "size": 5 })),
+        );
+    let info = bucket(&transport).info("notes/hello.txt").await.unwrap();
+    assert_eq!(info.size, Some(5));
+    assert_eq!(transport.requests().len(), 3);
+
+    // other errors and exhausted retries are returned
+    let transport: MockTransport =
+        MockTransport::new().on(Method::GET, INFO_PATH, MockResponse::new(503));
+    let error = bucket(&transport)
+        .info("notes/hello.txt")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::ServiceUnavailable { .. }));
+    assert_eq!(transport.requests().len(), 3);
+
+    let transport: MockTransport =
+        MockTransport::new().on(Method::GET, INFO_PATH, MockResponse::new(403));
+    let error = bucket(&transport)
+        .info("notes/hello.txt")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::ApiError { status: 403, .. }));
+    assert_eq!(transport.requests().len(), 1);
+
+    let transport: MockTransport =
+        MockTransport::new().on(Method::GET, INFO_PATH, MockResponse::new(503));
+    let single = bucket(&transport).with_retry(StorageRetry::none());
+    assert!(single.info("notes/hello.txt").await.is_err());
+    assert_eq!(transport.requests().len(), 1);
+
+    // only requests that can be sent twice are retried
+    let transport: MockTransport = MockTransport::new()
+        .on(Method::POST, OBJECT_PATH, MockResponse::new(503))
+        .on(Method::PUT, OBJECT_PATH, MockResponse::new(503));
+    let docs: StorageBucket = bucket(&transport);
+    let url: String = docs.url(&["object"], "notes/hello.txt", &[]);
+    let request = docs.request(Method::POST, &url).body("hello");
+    assert!(docs.send(request, "upload").await.is_err());
+    assert_eq!(transport.requests().len(), 1);
+    let request = docs.request(Method::PUT, &url).body("hello");
+    assert!(docs.send(request, "upload").await.is_err());
+    assert_eq!(transport.requests().len(), 2);
+    let request = docs
+        .request(Method::PUT, &url)
+        .header("x-upsert", "true")
+        .body("hello");
+    assert!(docs.send(request, "upload").await.is_err());
+    assert_eq!(transport.requests().len(), 5);
+
+    // downloads are verified, a mismatch isn't downloaded again
+    let etag: String = format!("\"{}\"", HELLO_MD5);
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(200)
+                .with_header("ETag", &etag)
+                .with_body("hellx"),
+        )
+        .on(
+            Method::GET,
+            OBJECT_PATH,
+            MockResponse::new(200)
+                .with_header("ETag", &etag)
+                .with_body("hello"),
+        );
+    let docs: StorageBucket = bucket(&transport);
+    let error = docs
+        .download_verified("notes/hello.txt", Checksum::ETag)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::ChecksumMismatch { .. }));
+    assert_eq!(error.kind(), ErrorKind::Integrity);
+    assert!(!error.is_retryable());
+    assert_eq!(transport.requests().len(), 1);
+
+    let bytes: Vec<u8> = docs
+        .download_verified("notes/hello.txt", Checksum::ETag)
+        .await
+        .unwrap();
+    assert_eq!(bytes, b"hello");
+    let bytes: Vec<u8> = docs
+        .download_verified(
+            "notes/hello.txt",
+            Checksum::Sha256(HELLO_SHA256.to_uppercase()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(bytes, b"hello");
+
+    let error = docs
+        .download_verified("notes/hello.txt", Checksum::Md5("0".repeat(32)))
+        .await
+        .unwrap_err();
+    match error {
+        ErrorTypes::ChecksumMismatch { expected, actual } => {
+            assert_eq!(expected, "0".repeat(32));
+            assert_eq!(actual, HELLO_MD5);
+        }
+        error => panic!("expected a checksum mismatch, got {:?}", error),
+    }
+    assert_eq!(transport.requests().len(), 4);
+
+    // the ETag of an object uploaded in parts isn't its MD5
+    let transport: MockTransport = MockTransport::new().on(
+        Method::GET,
+        OBJECT_PATH,
+        MockResponse::new(200)
+            .with_header("ETag", "\"d41d8cd98f00b204e9800998ecf8427e-2\"")
+            .with_body("hello"),
+    );
+    let error = bucket(&transport)
+        .download_verified("notes/hello.txt", Checksum::ETag)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::UnexpectedResponse(_)));
+    assert_eq!(transport.requests().len(), 1);
+
+    // uploads are verified against the size, ETag and sha256 metadata of the object
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            INFO_PATH,
+            MockResponse::json(
+                200,
+                &json!({
+                    "name": "notes/hello.txt",
+                    "size": 5,
+                    "etag": etag,
+                    "metadata": { "sha256": HELLO_SHA256 }
+                }),
+            ),
+        )
+        .on(
+            Method::GET,
+            INFO_PATH,
+            MockResponse::json(
+                200,
+                &json!({ "name": "notes/hello.txt", "size": 5, "etag": "\"abc-2\"" }),
+            ),
+        );
+    let docs: StorageBucket = bucket(&transport);
+    let info = docs
+        .verify_upload("notes/hello.txt", b"hello")
+        .await
+        .unwrap();
+    assert_eq!(info.etag, Some(etag));
+    docs.verify_upload("notes/hello.txt", b"hellx")
+        .await
+        .unwrap();
+    let error = docs
+        .verify_upload("notes/hello.txt", b"hello!")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ErrorTypes::ChecksumMismatch { .. }));
+}
@@ -0,0 +1,173 @@
+use crate::realtime::protocol::PhoenixMessage;
+use crate::realtime::{RealtimeClient, RealtimeOptions, ReconnectConfig, SyncOptions};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use crate::tests::realtime::{event, reply, FakeRealtime};
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Country {
+    code: String,
+    name: String,
+}
+
+fn change(kind: &str, record: Value, old_record: Value) -> Value {
+    json!({
+        "schema": "reference",
+        "table": "countries",
+        "commit_timestamp": "2024-05-09T14:05:33Z",
+        "type": kind,
+        "record": record,
+        "old_record": old_record,
+    })
+}
+
+fn names(countries: &watch::Receiver<HashMap<String, Country>>) -> Vec<String> {
+    let mut names: Vec<String> = countries
+        .borrow()
+        .values()
+        .map(|country| country.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+pub async fn sync_table() {
+    // numbers the change subscription 1
+    let mut server: FakeRealtime = FakeRealtime::start(|_, message| {
+        vec![reply(
+            message,
+            "ok",
+            json!({"postgres_changes": [{"id": 1}]}),
+        )]
+    })
+    .await;
+
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::GET,
+            "/rest/v1/countries",
+            MockResponse::json(
+                200,
+                &json!([{"code": "be", "name": "Belgium"}, {"code": "de", "name": "Germany"}]),
+            )
+            .with_header("content-range", "0-1/*"),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/countries",
+            // a row which doesn't decode is skipped
+            MockResponse::json(200, &json!([{"code": "nl", "name": null}]))
+                .with_header("content-range", "0-0/*"),
+        )
+        .once(
+            Method::GET,
+            "/rest/v1/countries",
+            MockResponse::json(200, &json!([{"code": "fr", "name": "France"}]))
+                .with_header("content-range", "0-0/*"),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+    let realtime: RealtimeClient = RealtimeClient::with_options(
+        &server.url,
+        "key",
+        RealtimeOptions {
+            reconnect: ReconnectConfig {
+                initial_delay: Duration::from_millis(20),
+                ..ReconnectConfig::default()
+            },
+            ..RealtimeOptions::default()
+        },
+    );
+
+    let mut countries: watch::Receiver<HashMap<String, Country>> = client
+        .sync_table_with(
+            &realtime,
+            "countries",
+            SyncOptions::new()
+                .schema("reference")
+                .primary_key("code")
+                .page_size(2),
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&countries), vec!["Belgium", "Germany"]);
+
+    // the subscription is joined first, then the table is read page by page
+    let join: PhoenixMessage = server.next().await;
+    assert_eq!(join.event, "phx_join");
+    assert_eq!(
+        join.payload["config"]["postgres_changes"],
+        json!([{"event": "*", "schema": "reference", "table": "countries"}])
+    );
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].query("order").as_deref(), Some("code.asc"));
+    assert_eq!(requests[0].query("limit").as_deref(), Some("2"));
+    assert_eq!(requests[0].query("code"), None);
+    assert_eq!(requests[0].headers["accept-profile"], "reference");
+    // pages start after the last key, rows deleted meanwhile don't shift them
+    assert_eq!(requests[1].query("code").as_deref(), Some("gt.de"));
+    assert!(requests
+        .iter()
+        .all(|request| request.headers.get("range").is_none()
+            && request.headers.get("prefer").is_none()));
+
+    let changes: Vec<Value> = vec![
+        change(
+            "INSERT",
+            json!({"code": "lu", "name": "Luxembourg"}),
+            json!({}),
+        ),
+        change(
+            "UPDATE",
+            json!({"code": "de", "name": "Deutschland"}),
+            json!({"code": "de"}),
+        ),
+        change("DELETE", json!({}), json!({"code": "be"})),
+    ];
+    for change in changes {
+        server.push(event(
+            &join.topic,
+            "postgres_changes",
+            json!({"ids": [1], "data": change}),
+        ));
+        tokio::time::timeout(Duration::from_secs(5), countries.changed())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(names(&countries), vec!["Deutschland", "Luxembourg"]);
+
+    // the table is read again after reconnecting, as changes could have been missed
+    server.close_connection();
+    tokio::time::timeout(Duration::from_secs(5), countries.changed())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(names(&countries), vec!["France"]);
+    assert_eq!(transport.requests().len(), 3);
+    let rejoin: PhoenixMessage = server.next().await;
+    assert_eq!(
+        (rejoin.event.as_str(), rejoin.topic),
+        ("phx_join", join.topic.clone())
+    );
+
+    // dropping the receiver leaves the channel
+    drop(countries);
+    let leave: PhoenixMessage = server.next().await;
+    assert_eq!(
+        (leave.event.as_str(), leave.topic),
+        ("phx_leave", join.topic)
+    );
+    realtime.disconnect();
+}
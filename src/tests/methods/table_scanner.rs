@@ -0,0 +1,49 @@
+use crate::interop::PostgrestCompat;
+use crate::query::OrderOptions;
+use crate::query_builder::scan::ScanCheckpoint;
+use crate::SupabaseClient;
+use serde_json::json;
+
+pub async fn table_scanner() {
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "key".to_string()).unwrap();
+
+    let scanner = client.select("events").eq("kind", "click").scan("id", 100);
+    assert_eq!(
+        scanner.page_builder().query.build(),
+        "kind=eq.click&order=id.asc&limit=100"
+    );
+
+    let checkpoint: ScanCheckpoint = ScanCheckpoint {
+        table: "events".to_string(),
+        key_column: "id".to_string(),
+        last_key: Some(json!(1337)),
+        rows_scanned: 500,
+    };
+
+    // checkpoints survive a round trip through storage
+    let stored: String = serde_json::to_string(&checkpoint).unwrap();
+    let restored: ScanCheckpoint = serde_json::from_str(&stored).unwrap();
+    assert_eq!(restored, checkpoint);
+
+    let scanner = scanner.resume_from(restored).unwrap();
+    assert_eq!(
+        scanner.page_builder().query.build(),
+        "kind=eq.click&id=gt.1337&order=id.asc&limit=100"
+    );
+
+    // an order, limit or offset of the query is replaced by the ones of the scan
+    let scanner = client
+        .select("events")
+        .order_with(OrderOptions::desc("created_at"))
+        .limit(10)
+        .scan("id", 100);
+    assert_eq!(
+        scanner.page_builder().query.build(),
+        "order=id.asc&limit=100"
+    );
+
+    // a checkpoint of another scan is rejected
+    let other = client.select("users").scan("id", 100);
+    assert!(other.resume_from(checkpoint).is_err());
+}
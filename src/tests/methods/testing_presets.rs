@@ -0,0 +1,65 @@
+use crate::testing::SupabaseMock;
+use crate::SupabaseClient;
+
+use serde_json::{json, Value};
+
+pub async fn testing_presets() {
+    let mock: SupabaseMock = SupabaseMock::new()
+        .select("users", json!([{"id": 1}, {"id": 2}]))
+        .select("orders", json!([]))
+        .count("users", 2)
+        .insert_conflict("users")
+        .insert("users")
+        .rpc("add_one", json!(3));
+    let client: SupabaseClient = mock.client();
+
+    let users: Vec<Value> = client.select("users").execute().await.unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(client.select("orders").execute().await, Ok(Vec::new()));
+    assert_eq!(client.select("users").count_only().await, Ok(2));
+    assert_eq!(client.select("users").exists().await, Ok(true));
+
+    let row: Value = json!({"email": "jane@example.com"});
+    assert!(client.insert("users", row.clone()).await.is_err());
+    assert!(client.insert("users", row).await.is_ok());
+
+    let result: Value = client
+        .rpc("add_one", json!({"value": 2}))
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(result, json!(3));
+    assert_eq!(mock.requests().len(), 7);
+
+    #[cfg(feature = "auth")]
+    {
+        use crate::auth::{Session, User};
+        use crate::errors::ErrorTypes;
+
+        let session: Session = Session {
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_in: 3600,
+            user: User {
+                id: "user-1".to_string(),
+                ..User::default()
+            },
+            ..Session::default()
+        };
+        let auth = SupabaseMock::new().auth_session(&session).auth_client();
+        let signed_in: Session = auth
+            .sign_in_with_password("jane@example.com", "secret")
+            .await
+            .unwrap();
+        assert_eq!(signed_in.access_token, "access");
+        assert_eq!(auth.get_user().await.unwrap().id, "user-1");
+        assert!(auth.sign_out().await.is_ok());
+
+        let auth = SupabaseMock::new().auth_invalid_credentials().auth_client();
+        assert!(matches!(
+            auth.sign_in_with_password("jane@example.com", "wrong")
+                .await,
+            Err(ErrorTypes::Auth { status: 400, .. })
+        ));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::errors::ErrorTypes;
+use crate::SupabaseClient;
+
+use serde_json::json;
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+pub async fn timeout() {
+    // a server that accepts connections but never responds
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url: String = format!("http://{}", listener.local_addr().unwrap());
+    let client: SupabaseClient = SupabaseClient::new(url, "key".to_string()).unwrap();
+
+    let started: Instant = Instant::now();
+    let result = client
+        .select("test")
+        .timeout(Duration::from_millis(50))
+        .execute()
+        .await;
+    assert_eq!(result.unwrap_err(), ErrorTypes::Timeout.to_string());
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let result = client
+        .rpc("slow", json!({}))
+        .timeout(Duration::from_millis(50))
+        .execute()
+        .await;
+    assert!(matches!(result, Err(ErrorTypes::Timeout)));
+
+    // the timeout of the client applies to every request
+    let client: SupabaseClient = client.with_timeout(Duration::from_millis(50));
+    let result = client.select("test").count_only().await;
+    assert_eq!(result.unwrap_err(), ErrorTypes::Timeout.to_string());
+    assert!(ErrorTypes::Timeout.is_retryable());
+
+    drop(listener);
+}
@@ -0,0 +1,54 @@
+use crate::trace_context::{NewTracePerRequest, TraceParent};
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+
+pub async fn trace_context() {
+    let header: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let parent: TraceParent = TraceParent::parse(header).unwrap();
+    assert_eq!(parent.trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+    assert!(parent.sampled);
+    assert_eq!(parent.to_header(), header);
+    assert!(
+        TraceParent::parse("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+    );
+    assert!(TraceParent::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7").is_none());
+
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::GET,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([])),
+        )
+        .on(Method::POST, "/rest/v1/users", MockResponse::new(201));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    client.select("users").execute().await.unwrap();
+    client
+        .clone()
+        .with_trace_propagator(parent)
+        .insert("users", json!({"name": "Jane"}))
+        .await
+        .unwrap();
+    let per_request: SupabaseClient = client.with_trace_propagator(NewTracePerRequest);
+    per_request.select("users").execute().await.unwrap();
+    per_request.select("users").execute().await.unwrap();
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert!(requests[0].headers.get("traceparent").is_none());
+    assert_eq!(requests[1].headers["traceparent"], header);
+
+    let started: Vec<TraceParent> = requests[2..]
+        .iter()
+        .map(|request| {
+            TraceParent::parse(request.headers["traceparent"].to_str().unwrap()).unwrap()
+        })
+        .collect();
+    assert!(started.iter().all(|parent| parent.sampled));
+    assert_ne!(started[0].trace_id, started[1].trace_id);
+}
@@ -0,0 +1,187 @@
+use crate::introspection::{
+    parse_function_definitions, parse_table_definitions, FunctionDefinition, TableDefinition,
+};
+use crate::type_gen::render::{
+    render_schema_for_include, render_schema_with_options, render_tables,
+    render_tables_with_options,
+};
+use crate::type_gen::TypeGenOptions;
+use serde_json::{json, Value};
+
+pub async fn type_gen() {
+    let openapi: Value = json!({
+        "definitions": {
+            "user_profiles": {
+                "required": ["id", "email"],
+                "properties": {
+                    "id": {
+                        "description": "Note:\nThis is a Primary Key.<pk/>",
+                        "format": "bigint",
+                        "type": "integer"
+                    },
+                    "email": {"format": "text", "type": "string"},
+                    "displayName": {"format": "character varying", "type": "string"},
+                    "type": {"format": "text", "type": "string"},
+                    "tags": {"format": "text[]", "items": {"type": "string"}, "type": "array"},
+                    "mood": {"enum": ["happy", "very-sad"], "format": "public.mood", "type": "string"}
+                }
+            },
+            "posts": {
+                "required": ["id", "author_id"],
+                "properties": {
+                    "id": {"format": "bigint", "type": "integer"},
+                    "author_id": {
+                        "description": "Note:\nThis is a Foreign Key to `user_profiles.id`.<fk table='user_profiles' column='id'/>",
+                        "format": "bigint",
+                        "type": "integer"
+                    }
+                }
+            }
+        },
+        "paths": {
+            "/user_profiles": {"get": {}},
+            "/rpc/get_active_users": {
+                "post": {
+                    "summary": "Users seen in the last days",
+                    "parameters": [
+                        {
+                            "in": "body",
+                            "name": "args",
+                            "required": true,
+                            "schema": {
+                                "required": ["min_age"],
+                                "properties": {
+                                    "min_age": {"format": "integer", "type": "integer"},
+                                    "country": {"format": "text", "type": "string"}
+                                },
+                                "type": "object"
+                            }
+                        },
+                        {"$ref": "#/parameters/preferParams"}
+                    ]
+                }
+            },
+            "/rpc/refresh_stats": {
+                "post": {
+                    "parameters": [
+                        {
+                            "in": "body",
+                            "name": "args",
+                            "required": true,
+                            "schema": {"properties": {}, "type": "object"}
+                        }
+                    ]
+                }
+            }
+        }
+    });
+
+    let tables: Vec<TableDefinition> = parse_table_definitions(&openapi);
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[1].primary_key(), Some("id"));
+    let mood = tables[1]
+        .columns
+        .iter()
+        .find(|column| column.name == "mood")
+        .unwrap();
+    assert_eq!(mood.enum_values, vec!["happy", "very-sad"]);
+
+    let source: String = render_tables(&tables);
+    assert!(source.contains("pub struct UserProfiles {"));
+    assert!(source.contains("    pub id: i64,"));
+    assert!(source.contains("    pub email: String,"));
+    assert!(source
+        .contains("    #[serde(rename = \"displayName\")]\n    pub display_name: Option<String>,"));
+    assert!(source.contains("    pub r#type: Option<String>,"));
+    assert!(source.contains("    pub tags: Option<Vec<String>>,"));
+    assert!(source.contains("pub enum UserProfilesColumn {"));
+    assert!(source.contains("UserProfilesColumn::DisplayName => \"displayName\","));
+    assert!(source.contains("fn select_user_profiles(&self) -> TypedQueryBuilder<UserProfiles>;"));
+
+    assert!(source.contains("pub enum Mood {\n    #[serde(rename = \"happy\")]\n    Happy,\n    #[serde(rename = \"very-sad\")]\n    VerySad,\n}"));
+    assert!(source.contains("    pub mood: Option<Mood>,"));
+    assert!(source.contains("Mood::VerySad => \"very-sad\","));
+
+    assert!(source.contains(
+        "use supabase_rs::table::{Column, Relationship, SupabaseTable, TypedQueryBuilder};"
+    ));
+    let relationship: &str = "            Relationship {\n                column: \"author_id\",\n                table: \"user_profiles\",\n                foreign_column: \"id\",\n            },";
+    assert!(source.contains(&format!(
+        "    fn belongs_to() -> &'static [Relationship] {{\n        &[\n{}",
+        relationship
+    )));
+    assert!(source.contains("    fn has_many() -> &'static [Relationship] {\n        &[\n            Relationship {\n                column: \"id\",\n                table: \"posts\",\n                foreign_column: \"author_id\","));
+
+    assert!(!source.contains("ToSchema"));
+
+    let source: String =
+        render_tables_with_options(&tables, &TypeGenOptions::new().derive_to_schema(true));
+    assert!(source.contains("use utoipa::ToSchema;\n"));
+    assert!(source.contains(
+        "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]\npub struct UserProfiles {"
+    ));
+
+    let functions: Vec<FunctionDefinition> = parse_function_definitions(&openapi);
+    assert_eq!(functions.len(), 2);
+    assert_eq!(functions[0].name, "get_active_users");
+    assert_eq!(functions[0].arguments.len(), 2);
+    assert!(functions[1].arguments.is_empty());
+
+    let source: String = render_schema_with_options(&tables, &functions, &TypeGenOptions::new());
+    assert!(source.contains("use supabase_rs::rpc::RpcBuilder;\n"));
+    assert!(source.contains("pub struct GetActiveUsersArgs {"));
+    assert!(source.contains("    pub min_age: i32,"));
+    assert!(source.contains(
+        "    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub country: Option<String>,"
+    ));
+    assert!(!source.contains("RefreshStatsArgs"));
+    assert!(source.contains("    /// Users seen in the last days\n    fn rpc_get_active_users(&self, args: GetActiveUsersArgs) -> RpcBuilder;"));
+    assert!(source.contains(
+        "    fn rpc_refresh_stats(&self) -> RpcBuilder {\n        self.rpc(\"refresh_stats\", ())\n    }"
+    ));
+
+    assert!(source.contains("#![allow(dead_code)]"));
+
+    let included: String = render_schema_for_include(&tables, &functions, &TypeGenOptions::new());
+    assert!(!included.contains("#!["));
+    assert!(included.contains("pub struct GetActiveUsersArgs {"));
+
+    // enum values and names which collide once PascalCased, and values needing escapes
+    let tables: Vec<TableDefinition> = parse_table_definitions(&json!({
+        "definitions": {
+            "status": {
+                "properties": {
+                    "state": {
+                        "enum": ["in-progress", "in_progress", "A", "a", "self", "say \"hi\""],
+                        "format": "public.status",
+                        "type": "string"
+                    }
+                }
+            }
+        }
+    }));
+    let source: String = render_tables(&tables);
+    assert!(source.contains("pub struct Status {\n    pub state: Option<StatusEnum>,\n}"));
+    assert!(source.contains(
+        "pub enum StatusEnum {\n    #[serde(rename = \"in-progress\")]\n    InProgress,\n    #[serde(rename = \"in_progress\")]\n    InProgress2,\n    #[serde(rename = \"A\")]\n    A,\n    #[serde(rename = \"a\")]\n    A2,\n    #[serde(rename = \"self\")]\n    Self_,\n    #[serde(rename = \"say \\\"hi\\\"\")]\n    SayHi,\n}"
+    ));
+    assert!(source.contains("StatusEnum::SayHi => \"say \\\"hi\\\"\","));
+
+    // columns with the same field name, e.g. after a camelCase migration
+    let tables: Vec<TableDefinition> = parse_table_definitions(&json!({
+        "definitions": {
+            "users": {
+                "required": ["userId", "user_id"],
+                "properties": {
+                    "self": {"format": "text", "type": "string"},
+                    "userId": {"format": "bigint", "type": "integer"},
+                    "user_id": {"format": "bigint", "type": "integer"}
+                }
+            }
+        }
+    }));
+    let source: String = render_tables(&tables);
+    assert!(source.contains("    #[serde(rename = \"self\")]\n    pub self_: Option<String>,\n    #[serde(rename = \"userId\")]\n    pub user_id: i64,\n    #[serde(rename = \"user_id\")]\n    pub user_id_2: i64,"));
+    assert!(source.contains("pub enum UsersColumn {\n    Self_,\n    UserId,\n    UserId2,\n}"));
+    assert!(source.contains("UsersColumn::UserId2 => \"user_id\","));
+}
@@ -0,0 +1,55 @@
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::type_gen::{generate_supabase_types_with_options, TypeGenOptions};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::json;
+use std::path::PathBuf;
+
+pub async fn type_gen_schema() {
+    let openapi = json!({
+        "definitions": {
+            "events": {
+                "required": ["id"],
+                "properties": {
+                    "id": {"format": "bigint", "type": "integer"},
+                    "name": {"format": "text", "type": "string"}
+                }
+            }
+        },
+        "paths": {
+            "/events": {"get": {}},
+            "/rpc/daily_totals": {"post": {"parameters": []}}
+        }
+    });
+    let transport: MockTransport =
+        MockTransport::new().on(Method::GET, "/rest/v1/", MockResponse::json(200, &openapi));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let output: PathBuf = std::env::temp_dir().join(format!(
+        "supabase_rs_type_gen_schema_{}.rs",
+        std::process::id()
+    ));
+    generate_supabase_types_with_options(
+        &client,
+        &output,
+        &TypeGenOptions::new().schema("analytics"),
+    )
+    .await
+    .unwrap();
+    let source: String = std::fs::read_to_string(&output).unwrap();
+    let _ = std::fs::remove_file(&output);
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].headers.get("accept-profile").unwrap(),
+        "analytics"
+    );
+
+    assert!(source.contains("self.select_typed::<Events>().schema(\"analytics\")"));
+    assert!(source.contains("self.rpc(\"daily_totals\", ()).schema(\"analytics\")"));
+}
@@ -0,0 +1,82 @@
+use crate::errors::{Error, ErrorKind, ErrorTypes, PostgrestError, RestError};
+use crate::transport::{MockResponse, MockTransport};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::Value;
+
+/// Runs a REST call and an `ErrorTypes` call behind one `?`.
+async fn select_then_fail(client: &SupabaseClient, fail: bool) -> Result<Vec<Value>, Error> {
+    let rows: Vec<Value> = client.select("users").fetch().await?;
+    if fail {
+        Err(ErrorTypes::MissingSession)?;
+    }
+    Ok(rows)
+}
+
+pub async fn unified_error() {
+    let transport: MockTransport =
+        MockTransport::new().once(Method::GET, "/rest/v1/users", MockResponse::new(401));
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let error: Error = select_then_fail(&client, false).await.unwrap_err();
+    assert!(matches!(error, Error::Rest(_)));
+    assert_eq!(error.kind(), ErrorKind::Unauthorized);
+
+    let transport: MockTransport = MockTransport::new().on(
+        Method::GET,
+        "/rest/v1/users",
+        MockResponse::json(200, &serde_json::json!([])),
+    );
+    let client: SupabaseClient = client.with_transport(transport);
+    let error: Error = select_then_fail(&client, true).await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Unauthorized);
+    assert!(matches!(
+        error.as_error_types(),
+        Some(ErrorTypes::MissingSession)
+    ));
+
+    // anyhow errors wrapping `ErrorTypes` keep their kind
+    let error: Error = anyhow::Error::new(ErrorTypes::Timeout).into();
+    assert_eq!(error.kind(), ErrorKind::Timeout);
+    let error: Error = anyhow::anyhow!("unexpected GraphQL response").into();
+    assert_eq!(error.kind(), ErrorKind::Other);
+    assert_eq!(error.to_string(), "unexpected GraphQL response");
+
+    let error: Error = ErrorTypes::Postgrest {
+        status: 409,
+        error: PostgrestError {
+            code: Some("23505".to_string()),
+            message: "duplicate key value".to_string(),
+            ..Default::default()
+        },
+    }
+    .into();
+    assert_eq!(error.kind(), ErrorKind::Api);
+    assert_eq!(
+        Error::from(ErrorTypes::ApiError {
+            status: 403,
+            message: String::new()
+        })
+        .kind(),
+        ErrorKind::Unauthorized
+    );
+    // the kind of a REST error is carried with it, not read from its message
+    let error: Error = RestError::from(ErrorTypes::RateLimited {
+        retry_after: None,
+        message: String::new(),
+    })
+    .into();
+    assert_eq!(error.kind(), ErrorKind::RateLimited);
+    assert_eq!(
+        Error::from(RestError::from(ErrorTypes::Cancelled)).kind(),
+        ErrorKind::Cancelled
+    );
+    assert_eq!(
+        Error::from(ErrorTypes::Cancelled.to_string()).kind(),
+        ErrorKind::Other
+    );
+}
@@ -0,0 +1,42 @@
+use crate::SupabaseClient;
+use serde_json::json;
+
+pub async fn update_builder() {
+    let client: SupabaseClient =
+        SupabaseClient::new("http://localhost".to_string(), "key".to_string()).unwrap();
+
+    let query_string: Result<String, String> = client
+        .update_table("orders")
+        .eq("status", "pending")
+        .lt("created_at", "2024-01-01")
+        .set(json!({"status": "expired"}))
+        .build();
+    assert_eq!(
+        query_string.unwrap(),
+        "status=eq.pending&created_at=lt.2024-01-01"
+    );
+
+    // the values of `in_` are quoted, so a comma doesn't split them
+    let query_string: Result<String, String> = client
+        .update_table("orders")
+        .in_("region", &["eu", "us, east"])
+        .set(json!({"status": "expired"}))
+        .build();
+    assert_eq!(query_string.unwrap(), r#"region=in.("eu","us, east")"#);
+
+    // an update without changes is refused before anything is sent
+    let result = client
+        .update_table("orders")
+        .eq("status", "pending")
+        .execute()
+        .await;
+    assert!(result.unwrap_err().contains("set()"));
+
+    // so is an update without filters
+    let result = client
+        .update_table("orders")
+        .set(json!({"status": "expired"}))
+        .execute()
+        .await;
+    assert!(result.unwrap_err().contains("allow_unfiltered"));
+}
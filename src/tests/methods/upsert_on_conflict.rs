@@ -0,0 +1,47 @@
+use crate::errors::RestError;
+use crate::tests::methods::init::init;
+use crate::update::DuplicateResolution;
+use crate::SupabaseClient;
+use serde_json::json;
+
+pub async fn upsert_on_conflict() {
+    assert_eq!(
+        DuplicateResolution::MergeDuplicates.as_str(),
+        "resolution=merge-duplicates"
+    );
+    assert_eq!(
+        DuplicateResolution::IgnoreDuplicates.as_str(),
+        "resolution=ignore-duplicates"
+    );
+
+    /// Performs an upsert_on_conflict operation in an isolated scope.
+    async fn upsert_on_conflict_inner(supabase_client: SupabaseClient) -> Result<(), RestError> {
+        // Usage example
+
+        let rows = json!([
+            {"id": 8826759220049045588_i64, "email": "floris@xylex.ai"},
+            {"id": 8826759220049045589_i64, "email": "floris+1@xylex.ai"}
+        ]);
+
+        supabase_client
+            .upsert_on_conflict("test", &rows, &["id"])
+            .await?;
+        supabase_client
+            .upsert_ignore_duplicates("test", &rows, &["id"])
+            .await
+    }
+
+    let supabase_client: SupabaseClient = match init().await {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!(
+                "\x1b[31mFailed to initialize Supabase client: {:?}\x1b[0m",
+                e
+            );
+            return;
+        }
+    };
+    let response: Result<(), RestError> = upsert_on_conflict_inner(supabase_client).await;
+
+    assert!(response.is_ok());
+}
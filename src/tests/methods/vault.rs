@@ -0,0 +1,33 @@
+use crate::errors::ErrorTypes;
+use crate::testing::SupabaseMock;
+use crate::vault::Vault;
+
+use serde_json::{json, Value};
+
+pub async fn vault() {
+    let mock: SupabaseMock = SupabaseMock::new()
+        .rpc(
+            "supabase_rs_vault_create_secret",
+            json!("5c2b4e1a-0000-4000-8000-000000000000"),
+        )
+        .rpc("supabase_rs_vault_read_secret", Value::Null)
+        .rpc("supabase_rs_vault_update_secret", json!(true))
+        .rpc("supabase_rs_vault_delete_secret", json!(1));
+    let vault: Vault = mock.client().vault();
+
+    assert_eq!(
+        vault
+            .create_secret("stripe", "sk_test", None)
+            .await
+            .unwrap(),
+        "5c2b4e1a-0000-4000-8000-000000000000"
+    );
+    assert_eq!(vault.read_secret("stripe").await.unwrap(), None);
+    assert!(vault.update_secret("stripe", "sk_live").await.unwrap());
+
+    // a response of another type names what was returned
+    assert!(matches!(
+        vault.delete_secret("stripe").await,
+        Err(ErrorTypes::UnexpectedResponse(message)) if message == "expected a boolean, got 1"
+    ));
+}
@@ -0,0 +1,97 @@
+use crate::query::Returning;
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::update::DuplicateResolution;
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn write_returning() {
+    assert_eq!(Returning::default(), Returning::Minimal);
+    assert_eq!(Returning::HeadersOnly.as_str(), "headers-only");
+
+    let transport: MockTransport = MockTransport::new()
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::json(201, &json!([{"id": 7, "name": "Jane"}])),
+        )
+        .once(
+            Method::POST,
+            "/rest/v1/users",
+            MockResponse::new(201).with_header("Location", "/users?id=eq.8&team=eq.red%20sox"),
+        )
+        .on(Method::POST, "/rest/v1/users", MockResponse::new(201))
+        .on(
+            Method::PATCH,
+            "/rest/v1/users",
+            MockResponse::json(200, &json!([{"id": 7, "name": "Janet"}])),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let inserted: Vec<Value> = client
+        .insert_with_returning("users", json!({"name": "Jane"}), Returning::Representation)
+        .await
+        .unwrap();
+    assert_eq!(inserted, vec![json!({"id": 7, "name": "Jane"})]);
+
+    // the primary key named by the Location header, and nothing without the header
+    let keys: Vec<Value> = client
+        .insert_with_returning("users", json!([{"name": "John"}]), Returning::HeadersOnly)
+        .await
+        .unwrap();
+    assert_eq!(keys, vec![json!({"id": "8", "team": "red sox"})]);
+    let keys: Vec<Value> = client
+        .insert_with_returning("users", json!([{"name": "Jill"}]), Returning::HeadersOnly)
+        .await
+        .unwrap();
+    assert!(keys.is_empty());
+
+    client
+        .bulk_insert("users", vec![json!({"name": "Jack"})])
+        .await
+        .unwrap();
+
+    let updated: Vec<Value> = client
+        .update_with_returning(
+            "users",
+            "id",
+            "7",
+            json!({"name": "Janet"}),
+            Returning::Representation,
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated[0]["name"], "Janet");
+
+    client
+        .upsert_with_returning(
+            "users",
+            json!([{"id": 7, "name": "Jane"}]),
+            &["id"],
+            DuplicateResolution::MergeDuplicates,
+            Returning::Minimal,
+        )
+        .await
+        .unwrap();
+
+    let prefer: Vec<String> = transport
+        .requests()
+        .iter()
+        .map(|request: &RecordedRequest| request.headers["prefer"].to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        prefer,
+        vec![
+            "return=representation",
+            "return=headers-only",
+            "return=headers-only",
+            "return=minimal",
+            "return=representation",
+            "resolution=merge-duplicates,return=minimal",
+        ]
+    );
+}
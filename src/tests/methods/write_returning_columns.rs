@@ -0,0 +1,63 @@
+use crate::transport::{MockResponse, MockTransport, RecordedRequest};
+use crate::SupabaseClient;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub async fn write_returning_columns() {
+    let transport: MockTransport = MockTransport::new()
+        .on(
+            Method::PATCH,
+            "/rest/v1/invoices",
+            MockResponse::json(200, &json!([{"id": 1, "status": "paid"}])),
+        )
+        .on(
+            Method::DELETE,
+            "/rest/v1/invoices",
+            MockResponse::json(200, &json!([{"id": 2, "number": "INV-2"}])),
+        );
+    let client: SupabaseClient =
+        SupabaseClient::new("https://project.supabase.co".to_string(), "key".to_string())
+            .unwrap()
+            .with_transport(transport.clone());
+
+    let updated: Vec<Value> = client
+        .update_table("invoices")
+        .eq("customer_id", 42)
+        .set(json!({"status": "paid"}))
+        .returning_columns(&["id", "status"])
+        .execute_returning()
+        .await
+        .unwrap();
+    assert_eq!(updated, vec![json!({"id": 1, "status": "paid"})]);
+
+    let deleted: Vec<Value> = client
+        .delete_from("invoices")
+        .eq("status", "draft")
+        .returning_columns(&["id", "number"])
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(deleted, vec![json!({"id": 2, "number": "INV-2"})]);
+
+    // the columns to return don't count as a filter
+    let unfiltered: Result<String, String> = client
+        .delete_from("invoices")
+        .returning_columns(&["id"])
+        .build();
+    assert!(unfiltered.is_err());
+
+    let requests: Vec<RecordedRequest> = transport.requests();
+    assert_eq!(requests[0].query("select").as_deref(), Some("id,status"));
+    assert_eq!(requests[0].query("customer_id").as_deref(), Some("eq.42"));
+    assert!(requests[0].headers["prefer"]
+        .to_str()
+        .unwrap()
+        .contains("return=representation"));
+    assert_eq!(requests[1].query("select").as_deref(), Some("id,number"));
+    assert_eq!(requests[1].query("status").as_deref(), Some("eq.draft"));
+    assert!(requests[1].headers["prefer"]
+        .to_str()
+        .unwrap()
+        .contains("return=representation"));
+}
@@ -1,23 +1,289 @@
 pub mod base;
 
+/// A fake Realtime server for the tests of the realtime client, answering with the replies of the
+/// test.
+#[cfg(feature = "realtime")]
+pub mod realtime {
+    use crate::realtime::protocol::PhoenixMessage;
+
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// A Realtime server accepting one websocket connection after the other, numbered from 1.
+    ///
+    /// Every message it receives is answered with the messages `reply` returns for it, and kept to
+    /// be read with [`next`](FakeRealtime::next).
+    pub struct FakeRealtime {
+        /// The URL of the server, to create the `RealtimeClient` with.
+        pub url: String,
+        received: mpsc::UnboundedReceiver<(usize, PhoenixMessage)>,
+        pushed: mpsc::UnboundedSender<Option<Value>>,
+    }
+
+    impl FakeRealtime {
+        /// Starts the server, `reply` gets the number of the connection and the received message.
+        pub async fn start<F>(reply: F) -> FakeRealtime
+        where
+            F: Fn(usize, &PhoenixMessage) -> Vec<Value> + Send + 'static,
+        {
+            let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url: String = format!("http://{}", listener.local_addr().unwrap());
+            let (received_sender, received) = mpsc::unbounded_channel();
+            let (pushed, pushed_receiver) = mpsc::unbounded_channel();
+            tokio::spawn(serve(listener, reply, received_sender, pushed_receiver));
+
+            FakeRealtime {
+                url,
+                received,
+                pushed,
+            }
+        }
+
+        /// The next message received, with the number of its connection.
+        pub async fn next_with_connection(&mut self) -> (usize, PhoenixMessage) {
+            tokio::time::timeout(Duration::from_secs(5), self.received.recv())
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        /// The next message received.
+        pub async fn next(&mut self) -> PhoenixMessage {
+            self.next_with_connection().await.1
+        }
+
+        /// The next message of `event`, skipping the others.
+        pub async fn next_event(&mut self, event: &str) -> PhoenixMessage {
+            loop {
+                let message: PhoenixMessage = self.next().await;
+                if message.event == event {
+                    return message;
+                }
+            }
+        }
+
+        /// Sends `message` on the open connection.
+        pub fn push(&self, message: Value) {
+            self.pushed.send(Some(message)).unwrap();
+        }
+
+        /// Closes the open connection, the next one is accepted afterwards.
+        pub fn close_connection(&self) {
+            self.pushed.send(None).unwrap();
+        }
+    }
+
+    /// The reply to `message`, acknowledging it with `status` and `response`.
+    pub fn reply(message: &PhoenixMessage, status: &str, response: Value) -> Value {
+        json!({
+            "topic": message.topic,
+            "event": "phx_reply",
+            "payload": {"status": status, "response": response},
+            "ref": message.reference,
+            "join_ref": message.join_ref,
+        })
+    }
+
+    /// A message of the server to the channel of `topic`, which isn't a reply.
+    pub fn event(topic: &str, event: &str, payload: Value) -> Value {
+        json!({"topic": topic, "event": event, "payload": payload, "ref": null})
+    }
+
+    async fn serve<F>(
+        listener: TcpListener,
+        reply: F,
+        received: mpsc::UnboundedSender<(usize, PhoenixMessage)>,
+        mut pushed: mpsc::UnboundedReceiver<Option<Value>>,
+    ) where
+        F: Fn(usize, &PhoenixMessage) -> Vec<Value>,
+    {
+        for connection in 1.. {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            loop {
+                tokio::select! {
+                    incoming = socket.next() => {
+                        let Some(Ok(Message::Text(text))) = incoming else {
+                            break;
+                        };
+                        let message: PhoenixMessage = serde_json::from_str(&text).unwrap();
+                        let _ = received.send((connection, message.clone()));
+                        for answer in reply(connection, &message) {
+                            let _ = socket.send(Message::Text(answer.to_string())).await;
+                        }
+                    }
+                    Some(message) = pushed.recv() => {
+                        let Some(message) = message else {
+                            let _ = socket.close(None).await;
+                            break;
+                        };
+                        let _ = socket.send(Message::Text(message.to_string())).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub mod methods {
+    #[cfg(feature = "audit")]
+    pub mod audit_sink;
+    #[cfg(feature = "auth")]
+    pub mod auth_admin;
+    #[cfg(feature = "auth")]
+    pub mod auth_captcha;
+    #[cfg(feature = "auth")]
+    pub mod auth_error_kinds;
+    #[cfg(feature = "auth")]
+    pub mod auth_errors;
+    #[cfg(feature = "auth")]
+    pub mod auth_events;
+    #[cfg(feature = "auth")]
+    pub mod auth_generate_link;
+    #[cfg(feature = "auth")]
+    pub mod auth_id_token;
+    #[cfg(feature = "auth")]
+    pub mod auth_identities;
+    #[cfg(feature = "auth")]
+    pub mod auth_invite;
+    #[cfg(feature = "auth")]
+    pub mod auth_mfa;
+    #[cfg(feature = "auth")]
+    pub mod auth_phone;
+    #[cfg(feature = "auth")]
+    pub mod auth_pkce;
+    #[cfg(feature = "auth")]
+    pub mod auth_redirects;
+    #[cfg(feature = "auth")]
+    pub mod auth_session_store;
+    #[cfg(feature = "auth")]
+    pub mod auth_shared_session;
+    #[cfg(feature = "auth")]
+    pub mod auth_sign_out_scope;
+    #[cfg(feature = "auth")]
+    pub mod auth_sso;
+    #[cfg(feature = "auth")]
+    pub mod auth_verify_jwt;
+    pub mod batch_execute;
+    pub mod bulk_chunked;
+    #[cfg(feature = "import")]
+    pub mod bulk_import;
+    pub mod bulk_insert_if_unique;
+    pub mod bulk_upsert;
+    #[cfg(feature = "cancellation")]
+    pub mod cancellation;
+    pub mod circuit_breaker;
+    pub mod column_stats;
+    pub mod cron;
     pub mod delete;
+    pub mod delete_builder;
+    #[cfg(all(test, feature = "derive"))]
+    pub mod derive_table;
+    pub mod distributed_lock;
+    pub mod execute_paged;
+    pub mod filter_values;
+    pub mod find_by;
+    pub mod fixtures;
+    pub mod functions;
+    pub mod impersonation;
     pub mod init;
     pub mod insert;
     pub mod insert_if_unique_numeric;
+    pub mod insert_if_unique_on;
     pub mod insert_if_unique_string;
     pub mod insert_numeric;
     pub mod insert_string;
+    pub mod insert_with_defaults;
+    #[cfg(feature = "metrics")]
+    pub mod metrics_labels;
+    pub mod migrations;
+    pub mod mock_transport;
+    pub mod payload;
+    pub mod postgrest_errors;
+    pub mod postgrest_interop;
+    pub mod prefer_header;
+    pub mod prelude;
     pub mod query;
+    pub mod query_to_url;
+    pub mod queues;
+    pub mod rate_limit;
+    #[cfg(feature = "realtime")]
+    pub mod realtime_auth;
+    #[cfg(feature = "realtime")]
+    pub mod realtime_broadcast;
+    #[cfg(feature = "realtime")]
+    pub mod realtime_changes;
+    #[cfg(feature = "realtime")]
+    pub mod realtime_channel;
+    #[cfg(feature = "realtime")]
+    pub mod realtime_presence;
+    #[cfg(feature = "realtime")]
+    pub mod realtime_reconnect;
+    pub mod redirects;
+    pub mod request_ids;
+    pub mod retry_advice;
+    pub mod rpc_errors;
+    pub mod rpc_read_only;
+    pub mod rpc_typed;
+    pub mod schema_cache;
+    pub mod schema_cache_validation;
+    pub mod schema_profile;
     pub mod select;
+    #[cfg(feature = "cache")]
+    pub mod select_cache;
+    pub mod select_csv;
     pub mod select_filter;
+    pub mod select_head;
+    pub mod select_json_filter;
+    pub mod select_order;
+    pub mod select_paginated;
+    pub mod select_range_filter;
+    pub mod select_related;
+    pub mod select_related_filter;
     pub mod select_stacked_queries;
+    pub mod select_timezone;
     pub mod select_with_columns;
     pub mod select_with_count;
     pub mod select_with_count_and_filter;
+    pub mod select_with_count_header;
+    pub mod single_strict;
+    #[cfg(feature = "storage")]
+    pub mod storage_object_info;
+    #[cfg(feature = "storage")]
+    pub mod storage_public_url;
+    #[cfg(feature = "storage")]
+    pub mod storage_range_download;
+    #[cfg(feature = "storage")]
+    pub mod storage_resumable_upload;
+    #[cfg(feature = "storage")]
+    pub mod storage_retry_checksum;
+    #[cfg(feature = "storage_s3")]
+    pub mod storage_s3_multipart;
+    #[cfg(feature = "realtime")]
+    pub mod sync_table;
+    pub mod table_scanner;
+    pub mod testing_presets;
+    pub mod timeout;
+    pub mod trace_context;
+    #[cfg(feature = "type_gen")]
+    pub mod type_gen;
+    #[cfg(feature = "type_gen")]
+    pub mod type_gen_schema;
+    pub mod unified_error;
+    pub mod update_builder;
     pub mod update_with_column;
     pub mod upsert_numeric;
+    pub mod upsert_on_conflict;
     pub mod upsert_string;
+    #[cfg(feature = "admin")]
+    pub mod vault;
+    pub mod write_returning;
+    pub mod write_returning_columns;
 }
 
 #[cfg(test)]
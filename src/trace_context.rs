@@ -0,0 +1,157 @@
+//! ## Trace context propagation
+//!
+//! A client with a [`TracePropagator`] adds the trace context of the caller, the W3C `traceparent`
+//! and `tracestate` headers, to every REST, auth, storage and edge function request, so the API logs
+//! of Supabase can be joined with the distributed traces of the calling service. Requests which
+//! already carry a `traceparent` keep it.
+//!
+//! With OpenTelemetry the global propagator can inject the context of the current span, here with
+//! the `HeaderInjector` of `opentelemetry-http`:
+//! ```rust,ignore
+//! use opentelemetry::global;
+//! use opentelemetry_http::HeaderInjector;
+//! use supabase_rs::trace_context::TracePropagator;
+//!
+//! #[derive(Debug)]
+//! struct OtelPropagator;
+//!
+//! impl TracePropagator for OtelPropagator {
+//!     fn inject(&self, headers: &mut HeaderMap) {
+//!         let context = opentelemetry::Context::current();
+//!         global::get_text_map_propagator(|propagator| {
+//!             propagator.inject_context(&context, &mut HeaderInjector(headers))
+//!         });
+//!     }
+//! }
+//!
+//! let client = SupabaseClient::new(url, key)?.with_trace_propagator(OtelPropagator);
+//! ```
+//!
+//! Without OpenTelemetry, a [`TraceParent`] sends the same context with every request, e.g. the one
+//! of the incoming request a clone of the client serves, and [`NewTracePerRequest`] starts a
+//! sampled trace for every request, which still finds a request across the logs of the API gateway
+//! and Postgres.
+//! ```rust,ignore
+//! let client = match TraceParent::parse(incoming_traceparent) {
+//!     Some(parent) => client.clone().with_trace_propagator(parent),
+//!     None => client.clone().with_trace_propagator(NewTracePerRequest),
+//! };
+//! ```
+//!
+//! With the `tracing` feature every request also runs in a `supabase_rs.request` span, see
+//! [dispatching](crate::request::dispatch).
+
+use crate::SupabaseClient;
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// The header carrying the trace and parent span of a request.
+pub const TRACEPARENT: &str = "traceparent";
+
+/// The header carrying vendor specific trace state.
+pub const TRACESTATE: &str = "tracestate";
+
+/// Adds the trace context of the caller to the requests of a `SupabaseClient`.
+pub trait TracePropagator: Debug + Send + Sync {
+    /// Inserts the `traceparent`, and optionally `tracestate`, header of the current trace.
+    fn inject(&self, headers: &mut HeaderMap);
+}
+
+/// The `traceparent` of a request, `00-{trace id}-{parent id}-{flags}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceParent {
+    /// Identifies the whole trace, not all zeros.
+    pub trace_id: u128,
+    /// Identifies the span the request was sent from, not all zeros.
+    pub parent_id: u64,
+    /// Whether the caller records the trace.
+    pub sampled: bool,
+}
+
+impl TraceParent {
+    /// A sampled trace with random ids.
+    pub fn random() -> Self {
+        TraceParent {
+            trace_id: rand::random::<u128>().max(1),
+            parent_id: rand::random::<u64>().max(1),
+            sampled: true,
+        }
+    }
+
+    /// Parses a `traceparent` header of version `00`.
+    ///
+    /// # Returns
+    /// `None` when the header is malformed or one of its ids is all zeros.
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let (version, trace_id, parent_id, flags) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if version != "00" || parts.next().is_some() {
+            return None;
+        }
+        if trace_id.len() != 32 || parent_id.len() != 16 || flags.len() != 2 {
+            return None;
+        }
+
+        let trace_id: u128 = u128::from_str_radix(trace_id, 16).ok()?;
+        let parent_id: u64 = u64::from_str_radix(parent_id, 16).ok()?;
+        let flags: u8 = u8::from_str_radix(flags, 16).ok()?;
+        if trace_id == 0 || parent_id == 0 {
+            return None;
+        }
+
+        Some(TraceParent {
+            trace_id,
+            parent_id,
+            sampled: flags & 1 == 1,
+        })
+    }
+
+    /// The value of the `traceparent` header.
+    pub fn to_header(&self) -> String {
+        format!(
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id, self.parent_id, self.sampled as u8
+        )
+    }
+}
+
+impl TracePropagator for TraceParent {
+    fn inject(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.to_header()) {
+            headers.insert(TRACEPARENT, value);
+        }
+    }
+}
+
+/// Starts a new sampled trace for every request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NewTracePerRequest;
+
+impl TracePropagator for NewTracePerRequest {
+    fn inject(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&TraceParent::random().to_header()) {
+            headers.insert(TRACEPARENT, value);
+        }
+    }
+}
+
+impl SupabaseClient {
+    /// Adds the trace context `propagator` injects to every request of the client and its clones.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the trace propagator.
+    pub fn with_trace_propagator(mut self, propagator: impl TracePropagator + 'static) -> Self {
+        self.trace_propagator = Some(Arc::new(propagator));
+        self
+    }
+}
+
+/// Injects the trace context into the headers of a request, unless it has a `traceparent` already.
+pub(crate) fn inject(propagator: Option<&Arc<dyn TracePropagator>>, headers: &mut HeaderMap) {
+    if let Some(propagator) = propagator.filter(|_| !headers.contains_key(TRACEPARENT)) {
+        propagator.inject(headers);
+    }
+}
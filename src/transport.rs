@@ -0,0 +1,218 @@
+//! ## Transports
+//!
+//! Every REST request of a [`SupabaseClient`] is sent by its transport. By default that is `reqwest`,
+//! [`SupabaseClient::with_transport`] replaces it, e.g. with the in-memory [`MockTransport`] so unit
+//! tests run without a Supabase project:
+//!
+//! ```rust,ignore
+//! use reqwest::Method;
+//! use serde_json::json;
+//! use supabase_rs::transport::{MockResponse, MockTransport};
+//!
+//! let transport = MockTransport::new().on(
+//!     Method::GET,
+//!     "/rest/v1/users",
+//!     MockResponse::json(200, &json!([{ "id": 1, "email": "jane@example.com" }])),
+//! );
+//! let client = SupabaseClient::new(url, key)?.with_transport(transport.clone());
+//!
+//! let users = client.select("users").eq("id", "1").execute().await?;
+//! assert_eq!(transport.requests()[0].query("id").as_deref(), Some("eq.1"));
+//! ```
+//!
+//! Redirects are only followed by the default transport, the circuit breaker, metrics and tracing
+//! apply to every transport.
+
+use crate::SupabaseClient;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Request, Response, Url};
+use serde_json::Value;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The future returned by [`HttpTransport::execute`].
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<Response, String>> + Send + 'a>>;
+
+/// Sends the requests of a `SupabaseClient`.
+pub trait HttpTransport: Debug + Send + Sync {
+    /// Sends a request and returns its response, or the transport error as a `String`.
+    fn execute(&self, request: Request) -> TransportFuture<'_>;
+}
+
+impl SupabaseClient {
+    /// Sends every REST request through `transport` instead of `reqwest`.
+    ///
+    /// # Returns
+    /// The `SupabaseClient` with the transport applied.
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+}
+
+/// A canned response of a [`MockTransport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    /// A response with an empty body.
+    pub fn new(status: u16) -> Self {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// A response with a JSON body.
+    pub fn json(status: u16, body: &Value) -> Self {
+        MockResponse::new(status)
+            .with_header("Content-Type", "application/json")
+            .with_body(&body.to_string())
+    }
+
+    /// Adds a header, e.g. `Content-Range` for counts.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Replaces the body.
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = body.to_string();
+        self
+    }
+
+    fn to_response(&self) -> Result<Response, String> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        builder
+            .body(self.body.clone())
+            .map(Response::from)
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<String>,
+}
+
+impl RecordedRequest {
+    /// Returns the value of a query parameter, e.g. `eq.1` for the filter `id=eq.1`.
+    pub fn query(&self, key: &str) -> Option<String> {
+        self.url
+            .query_pairs()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    /// Returns the body parsed as JSON.
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_str(self.body.as_deref()?).ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockRoute {
+    method: Method,
+    path: String,
+    response: MockResponse,
+    once: bool,
+}
+
+/// An in-memory transport answering requests with canned responses, and recording them.
+///
+/// Requests are answered by the first route registered for their method and URL path. Clones share
+/// their routes and recorded requests, so keep one to inspect what the client sent.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    routes: Arc<Mutex<Vec<MockRoute>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every request to `path`, e.g. `/rest/v1/users`, with `response`.
+    pub fn on(self, method: Method, path: &str, response: MockResponse) -> Self {
+        self.route(method, path, response, false)
+    }
+
+    /// Answers the next request to `path` with `response`, later requests fall through to the next route.
+    pub fn once(self, method: Method, path: &str, response: MockResponse) -> Self {
+        self.route(method, path, response, true)
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.requests).clone()
+    }
+
+    fn route(self, method: Method, path: &str, response: MockResponse, once: bool) -> Self {
+        lock(&self.routes).push(MockRoute {
+            method,
+            path: path.to_string(),
+            response,
+            once,
+        });
+        self
+    }
+
+    /// Returns the response of the first matching route, consuming it when it only answers once.
+    fn respond(&self, method: &Method, path: &str) -> Option<MockResponse> {
+        let mut routes = lock(&self.routes);
+        let index: usize = routes
+            .iter()
+            .position(|route| route.method == *method && route.path == path)?;
+
+        match routes[index].once {
+            true => Some(routes.remove(index).response),
+            false => Some(routes[index].response.clone()),
+        }
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn execute(&self, request: Request) -> TransportFuture<'_> {
+        let recorded: RecordedRequest = RecordedRequest {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|body| String::from_utf8_lossy(body).into_owned()),
+        };
+        let response: Option<MockResponse> = self.respond(&recorded.method, recorded.url.path());
+        let error: String = format!(
+            "No mock response for {} {}",
+            recorded.method,
+            recorded.url.path()
+        );
+        lock(&self.requests).push(recorded);
+
+        Box::pin(async move { response.ok_or(error)?.to_response() })
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,243 @@
+//! ## Type generation
+//!
+//! Generates Rust types for every table exposed by PostgREST, using the OpenAPI document served on
+//! the root of the REST endpoint. Enabled with the `type_gen` feature.
+//!
+//! For every table the generated file contains:
+//! - A struct deriving `Serialize` and `Deserialize`, with nullable columns wrapped in `Option`.
+//! - A `{Table}Column` enum implementing [`Column`](crate::table::Column), so filters on a typo'd
+//!   column fail to compile.
+//! - An implementation of [`SupabaseTable`](crate::table::SupabaseTable), whose `belongs_to()` and
+//!   `has_many()` list the foreign keys from and to the table as
+//!   [`Relationship`](crate::table::Relationship)s.
+//! - A `select_{table}()` accessor on the `SupabaseTables` trait, implemented for `SupabaseClient`.
+//!
+//! Every Postgres enum used by a column or function argument becomes a Rust enum, serialized as its
+//! original values and implementing `Display` so it can be passed to filters. An enum named like
+//! another generated type gets an `Enum` suffix, e.g. `StatusEnum` next to a `status` table, and
+//! values with the same `PascalCase` name get numbered variants.
+//!
+//! For every function exposed on `/rpc` it contains a `{Function}Args` struct of its arguments, and
+//! an `rpc_{function}(args)` wrapper on the `SupabaseFunctions` trait returning an
+//! [`RpcBuilder`](crate::rpc::RpcBuilder). Arguments with a default are optional.
+//!
+//! With [`TypeGenOptions::derive_to_schema`] the structs also derive `utoipa::ToSchema`, so APIs proxying
+//! the tables can document them in their OpenAPI spec. The generating crate then needs `utoipa` as a
+//! dependency, column comments become the schema descriptions.
+//!
+//! The document is fetched through the REST endpoint of the client, so no database connection,
+//! pooler host or TLS setup is involved: whatever project and region the client points at is the
+//! one introspected. Only the exposed schemas can be generated, pick one with
+//! [`TypeGenOptions::schema`].
+//!
+//! ### Usage
+//! ```rust,ignore
+//! use supabase_rs::type_gen::{
+//!     generate_supabase_types, generate_supabase_types_with_options, TypeGenOptions,
+//! };
+//!
+//! generate_supabase_types(&client, "src/supabase_types.rs").await?;
+//!
+//! // the tables and functions of the `analytics` schema
+//! generate_supabase_types_with_options(
+//!     &client,
+//!     "src/analytics_types.rs",
+//!     &TypeGenOptions::new().schema("analytics"),
+//! )
+//! .await?;
+//! ```
+//!
+//! Then, in your own crate:
+//! ```rust,ignore
+//! mod supabase_types;
+//! use supabase_types::{SupabaseTables, UsersColumn};
+//!
+//! let users = client
+//!     .select_users()
+//!     .eq(UsersColumn::Email, "a@b.c")
+//!     .execute()
+//!     .await?;
+//!
+//! use supabase_types::{GetActiveUsersArgs, SupabaseFunctions};
+//!
+//! let active: Value = client
+//!     .rpc_get_active_users(GetActiveUsersArgs { min_age: 18, country: None })
+//!     .execute()
+//!     .await?;
+//! ```
+//!
+//! ### From a build script
+//! [`generate_supabase_types_in_out_dir`] blocks, and writes into `OUT_DIR` so the source tree is
+//! never touched. Add `supabase_rs` with the `type_gen` feature to `[build-dependencies]`, then:
+//! ```rust,ignore
+//! // build.rs
+//! fn main() {
+//!     println!("cargo:rerun-if-env-changed=SUPABASE_URL");
+//!     let client = SupabaseClient::new(
+//!         std::env::var("SUPABASE_URL").unwrap(),
+//!         std::env::var("SUPABASE_KEY").unwrap(),
+//!     )
+//!     .unwrap();
+//!     generate_supabase_types_in_out_dir(&client, "supabase_types.rs", &TypeGenOptions::new())
+//!         .unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! #[allow(dead_code)]
+//! mod supabase_types {
+//!     include!(concat!(env!("OUT_DIR"), "/supabase_types.rs"));
+//! }
+//! ```
+//!
+//! ### From the command line
+//! Installing the crate with the `type_gen` feature adds a `cargo supabase-types` subcommand, see
+//! `cargo supabase-types --help`:
+//! ```text
+//! cargo supabase-types --schema public --output src/supabase_types.rs
+//! ```
+#![cfg(feature = "type_gen")]
+
+pub mod render;
+
+use crate::errors::RestError;
+use crate::introspection::{
+    parse_function_definitions, parse_table_definitions, FunctionDefinition, TableDefinition,
+};
+use crate::SupabaseClient;
+
+use serde_json::Value;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Options of the generated source.
+#[derive(Debug, Clone, Default)]
+pub struct TypeGenOptions {
+    /// Derives `utoipa::ToSchema` on the generated structs.
+    pub derive_to_schema: bool,
+    /// The schema to introspect, the one of the client when `None`.
+    pub schema: Option<String>,
+}
+
+impl TypeGenOptions {
+    /// Creates the default options, generating plain `serde` structs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the generated structs derive `utoipa::ToSchema`.
+    pub fn derive_to_schema(mut self, derive: bool) -> Self {
+        self.derive_to_schema = derive;
+        self
+    }
+
+    /// Introspects `schema` instead of the schema of the client, the generated accessors and RPC
+    /// wrappers then target it as well.
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+}
+
+/// Introspects the exposed schema and writes the generated Rust types to `output_path`.
+///
+/// # Arguments
+/// * `client` - The client used to fetch the OpenAPI document.
+/// * `output_path` - The file the generated source is written to, it is overwritten if it exists.
+///
+/// # Returns
+/// A `Result` which is `Ok(())` once the file is written, or a `RestError`.
+pub async fn generate_supabase_types(
+    client: &SupabaseClient,
+    output_path: impl AsRef<Path>,
+) -> Result<(), RestError> {
+    generate_supabase_types_with_options(client, output_path, &TypeGenOptions::default()).await
+}
+
+/// Introspects the exposed schema and writes the generated Rust types to `output_path`, see [`TypeGenOptions`].
+///
+/// # Arguments
+/// * `client` - The client used to fetch the OpenAPI document.
+/// * `output_path` - The file the generated source is written to, it is overwritten if it exists.
+/// * `options` - The options of the generated source.
+///
+/// # Returns
+/// A `Result` which is `Ok(())` once the file is written, or a `RestError`.
+pub async fn generate_supabase_types_with_options(
+    client: &SupabaseClient,
+    output_path: impl AsRef<Path>,
+    options: &TypeGenOptions,
+) -> Result<(), RestError> {
+    let source: String = generate_supabase_types_source(client, options).await?;
+
+    std::fs::write(output_path, source).map_err(|e| e.to_string().into())
+}
+
+/// Introspects the exposed schema and returns the generated Rust types, without writing them anywhere.
+///
+/// # Arguments
+/// * `client` - The client used to fetch the OpenAPI document.
+/// * `options` - The options of the generated source.
+///
+/// # Returns
+/// A `Result` containing the generated source, or a `RestError`.
+pub async fn generate_supabase_types_source(
+    client: &SupabaseClient,
+    options: &TypeGenOptions,
+) -> Result<String, RestError> {
+    let (tables, functions) = introspect(client, options).await?;
+
+    Ok(render::render_schema_with_options(
+        &tables, &functions, options,
+    ))
+}
+
+/// Introspects the exposed schema from a build script, and writes the generated Rust types to
+/// `file_name` in `OUT_DIR`, ready to be pulled in with `include!`.
+///
+/// Blocks on a runtime of its own, so it must not be called from async code.
+///
+/// # Arguments
+/// * `client` - The client used to fetch the OpenAPI document.
+/// * `file_name` - The name of the file in `OUT_DIR`, it is overwritten if it exists.
+/// * `options` - The options of the generated source.
+///
+/// # Returns
+/// A `Result` containing the path of the written file, or a `RestError`.
+pub fn generate_supabase_types_in_out_dir(
+    client: &SupabaseClient,
+    file_name: &str,
+    options: &TypeGenOptions,
+) -> Result<PathBuf, RestError> {
+    let out_dir: OsString = std::env::var_os("OUT_DIR")
+        .ok_or("OUT_DIR is not set, type generation into it only works in a build script")?;
+    let output_path: PathBuf = PathBuf::from(out_dir).join(file_name);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+    let (tables, functions) = runtime.block_on(introspect(client, options))?;
+
+    std::fs::write(
+        &output_path,
+        render::render_schema_for_include(&tables, &functions, options),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(output_path)
+}
+
+/// Fetches the OpenAPI document of the schema selected by `options`, and parses its tables and functions.
+async fn introspect(
+    client: &SupabaseClient,
+    options: &TypeGenOptions,
+) -> Result<(Vec<TableDefinition>, Vec<FunctionDefinition>), RestError> {
+    let openapi: Value = match &options.schema {
+        Some(schema) => client.clone().with_schema(schema).fetch_openapi().await?,
+        None => client.fetch_openapi().await?,
+    };
+
+    Ok((
+        parse_table_definitions(&openapi),
+        parse_function_definitions(&openapi),
+    ))
+}
@@ -0,0 +1,656 @@
+//! Renders [`TableDefinition`]s into Rust source code.
+
+use crate::introspection::{ColumnDefinition, FunctionDefinition, TableDefinition};
+use crate::type_gen::TypeGenOptions;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Words that can't be used as field names without escaping.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+/// Type names the generated source uses besides its structs, column enums and argument structs.
+const IMPORTED_TYPES: &[&str] = &[
+    "Column",
+    "Deserialize",
+    "Option",
+    "Relationship",
+    "RpcBuilder",
+    "Self",
+    "Serialize",
+    "String",
+    "SupabaseClient",
+    "SupabaseFunctions",
+    "SupabaseTable",
+    "SupabaseTables",
+    "ToSchema",
+    "TypedQueryBuilder",
+    "Vec",
+];
+
+/// The Rust names of the Postgres enums of a schema, by their type without the array suffix.
+///
+/// Enums whose names collide with another type, e.g. a `status` enum of a `status` table, get an
+/// `Enum` suffix, and a number when that is taken too.
+struct EnumNames(BTreeMap<String, String>);
+
+impl EnumNames {
+    fn new(tables: &[TableDefinition], functions: &[FunctionDefinition]) -> Self {
+        let pg_types: BTreeSet<&str> = tables
+            .iter()
+            .flat_map(|table| &table.columns)
+            .chain(functions.iter().flat_map(|function| &function.arguments))
+            .filter(|column| !column.enum_values.is_empty())
+            .map(|column| column.pg_type.trim_end_matches("[]"))
+            .collect();
+
+        let mut taken: Vec<String> = IMPORTED_TYPES.iter().map(|name| name.to_string()).collect();
+        for table in tables {
+            taken.push(to_pascal_case(&table.name));
+            taken.push(format!("{}Column", to_pascal_case(&table.name)));
+        }
+        for function in functions
+            .iter()
+            .filter(|function| !function.arguments.is_empty())
+        {
+            taken.push(format!("{}Args", to_pascal_case(&function.name)));
+        }
+
+        let mut names: BTreeMap<String, String> = BTreeMap::new();
+        for pg_type in pg_types {
+            let base: String = enum_name(pg_type);
+            let name: String = match taken.contains(&base) {
+                true => dedupe(format!("{}Enum", base), &taken, ""),
+                false => base,
+            };
+            taken.push(name.clone());
+            names.insert(pg_type.to_string(), name);
+        }
+        EnumNames(names)
+    }
+
+    /// The name of the enum of `pg_type`, which may have an array suffix.
+    fn name(&self, pg_type: &str) -> String {
+        let pg_type: &str = pg_type.trim_end_matches("[]");
+        self.0
+            .get(pg_type)
+            .cloned()
+            .unwrap_or_else(|| enum_name(pg_type))
+    }
+}
+
+/// Renders the complete generated file for the given tables.
+pub fn render_tables(tables: &[TableDefinition]) -> String {
+    render_tables_with_options(tables, &TypeGenOptions::default())
+}
+
+/// Renders the complete generated file for the given tables, with the given options.
+pub fn render_tables_with_options(tables: &[TableDefinition], options: &TypeGenOptions) -> String {
+    render_schema_with_options(tables, &[], options)
+}
+
+/// Renders the complete generated file for the given tables and functions, with the given options.
+pub fn render_schema_with_options(
+    tables: &[TableDefinition],
+    functions: &[FunctionDefinition],
+    options: &TypeGenOptions,
+) -> String {
+    render_source(tables, functions, options, true)
+}
+
+/// Renders the generated source for the given tables and functions without inner attributes, so
+/// it can be pulled in with `include!`. Put `#[allow(dead_code)]` on the enclosing module instead.
+pub fn render_schema_for_include(
+    tables: &[TableDefinition],
+    functions: &[FunctionDefinition],
+    options: &TypeGenOptions,
+) -> String {
+    render_source(tables, functions, options, false)
+}
+
+fn render_source(
+    tables: &[TableDefinition],
+    functions: &[FunctionDefinition],
+    options: &TypeGenOptions,
+    module_file: bool,
+) -> String {
+    let mut source: String = String::new();
+
+    source.push_str("// @generated by supabase_rs::type_gen, do not edit by hand\n\n");
+    if module_file {
+        source.push_str("#![allow(dead_code)]\n\n");
+    }
+    source.push_str("use serde::{Deserialize, Serialize};\n");
+    if !functions.is_empty() {
+        source.push_str("use supabase_rs::rpc::RpcBuilder;\n");
+    }
+    let has_relationships: bool = tables
+        .iter()
+        .flat_map(|table| &table.columns)
+        .any(|column| column.foreign_key.is_some());
+    if has_relationships {
+        source.push_str(
+            "use supabase_rs::table::{Column, Relationship, SupabaseTable, TypedQueryBuilder};\n",
+        );
+    } else {
+        source.push_str("use supabase_rs::table::{Column, SupabaseTable, TypedQueryBuilder};\n");
+    }
+    source.push_str("use supabase_rs::SupabaseClient;\n");
+    if options.derive_to_schema {
+        source.push_str("use utoipa::ToSchema;\n");
+    }
+
+    let enum_names: EnumNames = EnumNames::new(tables, functions);
+    let enums: String = render_enums(tables, functions, options);
+    if !enums.is_empty() {
+        source.push('\n');
+        source.push_str(&enums);
+    }
+
+    for table in tables {
+        source.push('\n');
+        source.push_str(&render_table_in(table, tables, &enum_names, options));
+    }
+
+    source.push('\n');
+    source.push_str(&render_accessors(tables, options));
+
+    if !functions.is_empty() {
+        source.push('\n');
+        source.push_str(&render_functions_in(functions, &enum_names, options));
+    }
+    source
+}
+
+/// Renders the struct, column enum and trait implementations of a single table.
+pub fn render_table(table: &TableDefinition) -> String {
+    render_table_with_options(table, &TypeGenOptions::default())
+}
+
+/// Renders the struct, column enum and trait implementations of a single table, with the given options.
+///
+/// Only the foreign keys of the table itself are known here, so `has_many()` lists self references
+/// at most. [`render_schema_with_options`] also lists the tables referencing it.
+pub fn render_table_with_options(table: &TableDefinition, options: &TypeGenOptions) -> String {
+    let tables: &[TableDefinition] = std::slice::from_ref(table);
+    render_table_in(table, tables, &EnumNames::new(tables, &[]), options)
+}
+
+/// Renders a single table, looking up the tables referencing it in `tables`.
+fn render_table_in(
+    table: &TableDefinition,
+    tables: &[TableDefinition],
+    enum_names: &EnumNames,
+    options: &TypeGenOptions,
+) -> String {
+    let struct_name: String = to_pascal_case(&table.name);
+    let column_enum: String = format!("{}Column", struct_name);
+    let mut source: String = String::new();
+
+    let _ = writeln!(source, "/// A row of the `{}` table.", table.name);
+    if options.derive_to_schema {
+        source.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]\n");
+    } else {
+        source.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    }
+    let fields: Vec<String> = field_names(table.columns.iter().map(|column| column.name.as_str()));
+    let variants: Vec<String> =
+        variant_names(table.columns.iter().map(|column| column.name.as_str()));
+    let _ = writeln!(source, "pub struct {} {{", struct_name);
+    for (column, field) in table.columns.iter().zip(&fields) {
+        if let Some(description) = &column.description {
+            let comment: &str = description.lines().next().unwrap_or_default();
+            if !comment.is_empty() && !comment.starts_with("Note:") {
+                let _ = writeln!(source, "    /// {}", comment);
+            }
+        }
+        if field.trim_start_matches("r#") != column.name {
+            let _ = writeln!(source, "    #[serde(rename = {:?})]", column.name);
+        }
+        let _ = writeln!(
+            source,
+            "    pub {}: {},",
+            field,
+            rust_type(column, enum_names)
+        );
+    }
+    source.push_str("}\n\n");
+
+    let _ = writeln!(source, "/// Columns of the `{}` table.", table.name);
+    source.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n");
+    let _ = writeln!(source, "pub enum {} {{", column_enum);
+    for variant in &variants {
+        let _ = writeln!(source, "    {},", variant);
+    }
+    source.push_str("}\n\n");
+
+    let _ = writeln!(source, "impl Column for {} {{", column_enum);
+    source.push_str("    fn name(&self) -> &'static str {\n");
+    source.push_str("        match self {\n");
+    for (column, variant) in table.columns.iter().zip(&variants) {
+        let _ = writeln!(
+            source,
+            "            {}::{} => {:?},",
+            column_enum, variant, column.name
+        );
+    }
+    source.push_str("        }\n    }\n}\n\n");
+
+    let _ = writeln!(source, "impl std::fmt::Display for {} {{", column_enum);
+    source.push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+    source.push_str("        f.write_str(self.name())\n    }\n}\n\n");
+
+    let _ = writeln!(source, "impl SupabaseTable for {} {{", struct_name);
+    let _ = writeln!(source, "    type Column = {};\n", column_enum);
+    let _ = writeln!(
+        source,
+        "    fn table_name() -> &'static str {{\n        {:?}\n    }}\n",
+        table.name
+    );
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| format!("{:?}", column.name))
+        .collect();
+    let _ = writeln!(
+        source,
+        "    fn columns() -> &'static [&'static str] {{\n        &[{}]\n    }}",
+        columns.join(", ")
+    );
+    if let Some(primary_key) = table.primary_key() {
+        let _ = writeln!(
+            source,
+            "\n    fn primary_key() -> &'static str {{\n        {:?}\n    }}",
+            primary_key
+        );
+    }
+
+    let belongs_to: Vec<String> = table
+        .columns
+        .iter()
+        .filter_map(|column| {
+            let foreign_key = column.foreign_key.as_ref()?;
+            Some(render_relationship(
+                &column.name,
+                &foreign_key.table,
+                &foreign_key.column,
+            ))
+        })
+        .collect();
+    let has_many: Vec<String> = tables
+        .iter()
+        .flat_map(|other| other.columns.iter().map(move |column| (other, column)))
+        .filter_map(|(other, column)| {
+            let foreign_key = column.foreign_key.as_ref()?;
+            (foreign_key.table == table.name)
+                .then(|| render_relationship(&foreign_key.column, &other.name, &column.name))
+        })
+        .collect();
+    for (name, relationships) in [("belongs_to", belongs_to), ("has_many", has_many)] {
+        if relationships.is_empty() {
+            continue;
+        }
+        let _ = writeln!(
+            source,
+            "\n    fn {}() -> &'static [Relationship] {{\n        &[\n{}        ]\n    }}",
+            name,
+            relationships.concat()
+        );
+    }
+    source.push_str("}\n");
+
+    source
+}
+
+/// Renders one `Relationship` literal, as a line of a slice.
+fn render_relationship(column: &str, table: &str, foreign_column: &str) -> String {
+    format!(
+        "            Relationship {{\n                column: {:?},\n                table: {:?},\n                foreign_column: {:?},\n            }},\n",
+        column, table, foreign_column
+    )
+}
+
+/// Renders a Rust enum per Postgres enum used by a column or function argument, with a variant per
+/// value. The values are kept as they are on the wire through `serde(rename)`, values with the same
+/// `PascalCase` name get numbered variants.
+pub fn render_enums(
+    tables: &[TableDefinition],
+    functions: &[FunctionDefinition],
+    options: &TypeGenOptions,
+) -> String {
+    let enum_names: EnumNames = EnumNames::new(tables, functions);
+    let enums: BTreeMap<String, (&str, &[String])> = tables
+        .iter()
+        .flat_map(|table| &table.columns)
+        .chain(functions.iter().flat_map(|function| &function.arguments))
+        .filter(|column| !column.enum_values.is_empty())
+        .map(|column| {
+            let pg_type: &str = column.pg_type.trim_end_matches("[]");
+            (
+                enum_names.name(pg_type),
+                (pg_type, column.enum_values.as_slice()),
+            )
+        })
+        .collect();
+    let mut source: String = String::new();
+
+    for (name, (pg_type, values)) in enums {
+        let variants: Vec<String> = variant_names(values.iter().map(String::as_str));
+        let _ = writeln!(source, "/// The values of the `{}` enum.", pg_type);
+        if options.derive_to_schema {
+            source.push_str(
+                "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]\n",
+            );
+        } else {
+            source.push_str(
+                "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n",
+            );
+        }
+        let _ = writeln!(source, "pub enum {} {{", name);
+        for (value, variant) in values.iter().zip(&variants) {
+            let _ = writeln!(
+                source,
+                "    #[serde(rename = {:?})]\n    {},",
+                value, variant
+            );
+        }
+        source.push_str("}\n\n");
+
+        let _ = writeln!(source, "impl std::fmt::Display for {} {{", name);
+        source
+            .push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+        source.push_str("        f.write_str(match self {\n");
+        for (value, variant) in values.iter().zip(&variants) {
+            let _ = writeln!(source, "            {}::{} => {:?},", name, variant, value);
+        }
+        source.push_str("        })\n    }\n}\n\n");
+    }
+
+    source
+}
+
+/// The name of the Rust enum of a Postgres enum type, without its schema or array suffix.
+fn enum_name(pg_type: &str) -> String {
+    let pg_type: &str = pg_type.trim_end_matches("[]");
+    let name: &str = pg_type.rsplit('.').next().unwrap_or(pg_type);
+    to_pascal_case(name.trim_matches('"'))
+}
+
+/// The distinct `PascalCase` variants of `names`, e.g. `InProgress` and `InProgress2` for
+/// `in-progress` and `in_progress`, and `Self_` for `self`.
+fn variant_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut variants: Vec<String> = Vec::new();
+    for name in names {
+        let variant: String = match to_pascal_case(name).as_str() {
+            "Self" => "Self_".to_string(),
+            pascal => pascal.to_string(),
+        };
+        variants.push(dedupe(variant, &variants, ""));
+    }
+    variants
+}
+
+/// Returns `name`, or `name` with the first number from 2 up, joined by `separator`, which isn't
+/// `taken` yet.
+fn dedupe(name: String, taken: &[String], separator: &str) -> String {
+    if !taken.contains(&name) {
+        return name;
+    }
+    (2..)
+        .map(|number| format!("{}{}{}", name, separator, number))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("a free number")
+}
+
+/// Renders the `SupabaseTables` trait with a `select_{table}()` accessor per table.
+fn render_accessors(tables: &[TableDefinition], options: &TypeGenOptions) -> String {
+    let mut source: String = String::new();
+    let schema: String = schema_call(options);
+
+    source.push_str("/// Typed query builders for every generated table.\n");
+    source.push_str("pub trait SupabaseTables {\n");
+    for table in tables {
+        let _ = writeln!(
+            source,
+            "    fn select_{}(&self) -> TypedQueryBuilder<{}>;",
+            to_snake_case(&table.name),
+            to_pascal_case(&table.name)
+        );
+    }
+    source.push_str("}\n\n");
+
+    source.push_str("impl SupabaseTables for SupabaseClient {\n");
+    for table in tables {
+        let struct_name: String = to_pascal_case(&table.name);
+        let _ = writeln!(
+            source,
+            "    fn select_{}(&self) -> TypedQueryBuilder<{}> {{\n        self.select_typed::<{}>(){}\n    }}",
+            to_snake_case(&table.name),
+            struct_name,
+            struct_name,
+            schema
+        );
+    }
+    source.push_str("}\n");
+
+    source
+}
+
+/// Renders an argument struct per function, and the `SupabaseFunctions` trait with a typed
+/// `rpc_{function}()` wrapper per function.
+///
+/// Arguments with a default become `Option`s which are left out when `None`, so the default applies.
+pub fn render_functions(functions: &[FunctionDefinition], options: &TypeGenOptions) -> String {
+    render_functions_in(functions, &EnumNames::new(&[], functions), options)
+}
+
+fn render_functions_in(
+    functions: &[FunctionDefinition],
+    enum_names: &EnumNames,
+    options: &TypeGenOptions,
+) -> String {
+    let mut source: String = String::new();
+
+    for function in functions
+        .iter()
+        .filter(|function| !function.arguments.is_empty())
+    {
+        let args: String = format!("{}Args", to_pascal_case(&function.name));
+
+        let _ = writeln!(
+            source,
+            "/// The arguments of the `{}` function.",
+            function.name
+        );
+        if options.derive_to_schema {
+            source
+                .push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]\n");
+        } else {
+            source.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+        }
+        let _ = writeln!(source, "pub struct {} {{", args);
+        let fields: Vec<String> = field_names(
+            function
+                .arguments
+                .iter()
+                .map(|argument| argument.name.as_str()),
+        );
+        for (argument, field) in function.arguments.iter().zip(&fields) {
+            if field.trim_start_matches("r#") != argument.name {
+                let _ = writeln!(source, "    #[serde(rename = {:?})]", argument.name);
+            }
+            if !argument.required {
+                source.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+            }
+            let _ = writeln!(
+                source,
+                "    pub {}: {},",
+                field,
+                rust_type(argument, enum_names)
+            );
+        }
+        source.push_str("}\n\n");
+    }
+
+    source.push_str("/// Typed RPC calls for every generated function.\n");
+    source.push_str("pub trait SupabaseFunctions {\n");
+    for function in functions {
+        if let Some(description) = &function.description {
+            let _ = writeln!(
+                source,
+                "    /// {}",
+                description.lines().next().unwrap_or_default()
+            );
+        }
+        let _ = writeln!(source, "    {};", rpc_signature(function));
+    }
+    source.push_str("}\n\n");
+
+    let schema: String = schema_call(options);
+    source.push_str("impl SupabaseFunctions for SupabaseClient {\n");
+    for function in functions {
+        let params: &str = match function.arguments.is_empty() {
+            true => "()",
+            false => "args",
+        };
+        let _ = writeln!(
+            source,
+            "    {} {{\n        self.rpc({:?}, {}){}\n    }}",
+            rpc_signature(function),
+            function.name,
+            params,
+            schema
+        );
+    }
+    source.push_str("}\n");
+
+    source
+}
+
+/// The `.schema(...)` call pinning generated builders to the introspected schema, if one was chosen.
+fn schema_call(options: &TypeGenOptions) -> String {
+    match &options.schema {
+        Some(schema) => format!(".schema({:?})", schema),
+        None => String::new(),
+    }
+}
+
+/// The signature of the `rpc_{function}()` wrapper of a function.
+fn rpc_signature(function: &FunctionDefinition) -> String {
+    let name: String = to_snake_case(&function.name);
+    match function.arguments.is_empty() {
+        true => format!("fn rpc_{}(&self) -> RpcBuilder", name),
+        false => format!(
+            "fn rpc_{}(&self, args: {}Args) -> RpcBuilder",
+            name,
+            to_pascal_case(&function.name)
+        ),
+    }
+}
+
+/// Maps a column onto the Rust type used for its field.
+fn rust_type(column: &ColumnDefinition, enum_names: &EnumNames) -> String {
+    let scalar: String = match column.enum_values.is_empty() {
+        true => scalar_type(column.pg_type.trim_end_matches("[]")).to_string(),
+        false => enum_names.name(&column.pg_type),
+    };
+    let rust_type: String = if column.json_type == "array" || column.pg_type.ends_with("[]") {
+        format!("Vec<{}>", scalar)
+    } else {
+        scalar
+    };
+
+    if column.required {
+        rust_type
+    } else {
+        format!("Option<{}>", rust_type)
+    }
+}
+
+/// Maps a Postgres type onto a Rust type, falling back to `String` for text-like types.
+fn scalar_type(pg_type: &str) -> &'static str {
+    match pg_type {
+        "smallint" => "i16",
+        "integer" => "i32",
+        "bigint" => "i64",
+        "real" => "f32",
+        "double precision" | "numeric" => "f64",
+        "boolean" => "bool",
+        "json" | "jsonb" => "serde_json::Value",
+        _ => "String",
+    }
+}
+
+/// Turns a table or column name into a `PascalCase` identifier.
+pub fn to_pascal_case(name: &str) -> String {
+    let pascal: String = name
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut characters = part.chars();
+            match characters.next() {
+                Some(first) => first.to_uppercase().chain(characters).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    match pascal.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("_{}", pascal),
+        Some(_) => pascal,
+        None => "Unnamed".to_string(),
+    }
+}
+
+/// Turns a table or column name into a `snake_case` identifier.
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake: String = String::new();
+    let mut previous_lowercase: bool = false;
+
+    for character in name.chars() {
+        if character.is_alphanumeric() {
+            if character.is_uppercase() && previous_lowercase {
+                snake.push('_');
+            }
+            snake.extend(character.to_lowercase());
+            previous_lowercase = character.is_lowercase() || character.is_ascii_digit();
+        } else {
+            if !snake.ends_with('_') {
+                snake.push('_');
+            }
+            previous_lowercase = false;
+        }
+    }
+
+    let snake: String = snake.trim_matches('_').to_string();
+    match snake.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("_{}", snake),
+        Some(_) => snake,
+        None => "unnamed".to_string(),
+    }
+}
+
+/// The distinct field names of columns, e.g. `user_id` and `user_id_2` for `userId` and `user_id`,
+/// with Rust keywords escaped.
+fn field_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    for name in names {
+        fields.push(dedupe(to_snake_case(name), &fields, "_"));
+    }
+    fields.into_iter().map(escape_field_name).collect()
+}
+
+/// Escapes a `snake_case` field name which is a Rust keyword.
+fn escape_field_name(field: String) -> String {
+    match field.as_str() {
+        "self" | "super" | "crate" | "Self" => format!("{}_", field),
+        _ if RUST_KEYWORDS.contains(&field.as_str()) => format!("r#{}", field),
+        _ => field,
+    }
+}
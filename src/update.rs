@@ -7,6 +7,7 @@
 //!
 //! - **Update**: Modify existing rows in a table based on a unique identifier.
 //! - **Upsert**: Insert a new row into a table if it does not exist, or update it if it does.
+//! - **Bulk Upsert**: Insert or merge a list of rows on a conflict target in one request.
 //!
 //! ## Usage
 //!
@@ -47,10 +48,36 @@
 //!
 //! Both `update` and `upsert` methods return a `Result<(), String>`, where `Ok(())` indicates a successful operation,
 //! and `Err(String)` contains an error message in case of failure.
+use crate::errors::RestError;
+use crate::query::Returning;
+use crate::request::headers::HeadersTypes;
+use crate::request::prefer::PreferBuilder;
+use crate::request::Headers;
+use crate::success::{location_row, response_rows, write_error};
 use crate::SupabaseClient;
 use reqwest::Response;
+use serde::Serialize;
 use serde_json::{json, Value};
 
+/// How an upsert treats rows that conflict with an existing row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateResolution {
+    /// Updates the existing row with the new values.
+    MergeDuplicates,
+    /// Keeps the existing row and skips the new one.
+    IgnoreDuplicates,
+}
+
+impl DuplicateResolution {
+    /// Returns the value used in the `Prefer` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateResolution::MergeDuplicates => "resolution=merge-duplicates",
+            DuplicateResolution::IgnoreDuplicates => "resolution=ignore-duplicates",
+        }
+    }
+}
+
 impl SupabaseClient {
     /// Updates a row in the table, based on the id
     pub async fn update(&self, table_name: &str, id: &str, body: Value) -> Result<String, String> {
@@ -65,31 +92,57 @@ impl SupabaseClient {
         id: &str,
         body: Value,
     ) -> Result<String, String> {
+        self.update_with_returning(table_name, column_name, id, body, Returning::Minimal)
+            .await?;
+        Ok(id.to_string())
+    }
+
+    /// Updates the rows where `column_name` equals `id`, and returns what `returning` asks the server for.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `column_name` - The column to match, e.g. `id`.
+    /// * `id` - The value of the column.
+    /// * `body` - The columns to change, as a JSON object.
+    /// * `returning` - What the server sends back, [`Returning::Minimal`] skips the response body.
+    ///
+    /// # Returns
+    /// The updated rows with [`Returning::Representation`], an empty vector otherwise, or the
+    /// error.
+    pub async fn update_with_returning(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        id: &str,
+        body: Value,
+        returning: Returning,
+    ) -> Result<Vec<Value>, RestError> {
         // endpoint and client construction
         let endpoint: String = format!(
             "{}/rest/v1/{}?{}=eq.{}",
             self.url, table_name, column_name, id
         );
 
-        let response: Response = match self
-            .client
-            .patch(&endpoint)
-            .header("apikey", &self.api_key)
-            .header("Authorization", &format!("Bearer {}", &self.api_key))
-            .header("Content-Type", "application/json")
-            .body(body.to_string())
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(error) => return Err(error.to_string()),
-        };
+        let response: Response = self
+            .dispatch(
+                table_name,
+                "update",
+                self.content_profile(
+                    self.client
+                        .patch(&endpoint)
+                        .header("apikey", &self.api_key)
+                        .header("Authorization", &format!("Bearer {}", &self.api_key))
+                        .header("Content-Type", "application/json")
+                        .header(
+                            HeadersTypes::Prefer.as_str(),
+                            PreferBuilder::new().returning(returning).build(),
+                        )
+                        .body(body.to_string()),
+                ),
+            )
+            .await?;
 
-        if response.status().is_success() {
-            Ok(id.to_string())
-        } else {
-            Err(response.status().to_string())
-        }
+        returned_rows(response, returning).await
     }
 
     /// Creates a row in the table, or updates if the id already exists
@@ -121,27 +174,229 @@ impl SupabaseClient {
         #[cfg(feature = "nightly")]
         print_nightly_warning();
 
-        let response: Response = match self
-            .client
-            .post(&endpoint)
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", &self.api_key))
-            .header("Content-Type", "application/json")
-            .header("x_client_info", "supabase-rs/0.3.1")
-            .header("Prefer", "resolution=merge-duplicates")
-            .header("Prefer", "return=representation")
-            .body(body.to_string())
-            .send()
+        let response: Response = self
+            .dispatch(
+                table_name,
+                "upsert",
+                self.content_profile(
+                    self.client
+                        .post(&endpoint)
+                        .header("apikey", &self.api_key)
+                        .header("Authorization", format!("Bearer {}", &self.api_key))
+                        .header("Content-Type", "application/json")
+                        .header("x_client_info", "supabase-rs/0.3.1")
+                        .header(
+                            HeadersTypes::Prefer.as_str(),
+                            PreferBuilder::new()
+                                .resolution(DuplicateResolution::MergeDuplicates)
+                                .returning(Returning::Representation)
+                                .build(),
+                        )
+                        .body(body.to_string()),
+                ),
+            )
             .await
-        {
-            Ok(response) => response,
-            Err(e) => return Err(e.to_string()),
-        };
+            .map_err(RestError::from)?;
 
         if response.status().is_success() {
             Ok(())
         } else {
-            Err(response.status().to_string())
+            Err(write_error(response).await.into())
+        }
+    }
+
+    /// Creates rows in the table, or updates them when they conflict on the given columns.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `rows` - A single row or a list of rows, serialized as JSON.
+    /// * `on_conflict` - The columns of a unique constraint to detect conflicts on, e.g. `&["email"]`.
+    ///
+    /// # Returns
+    /// This method returns a `Result<(), RestError>`. On success, it returns `Ok(())`,
+    /// and on failure, it returns `Err(RestError)` with an error message.
+    pub async fn upsert_on_conflict<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: T,
+        on_conflict: &[&str],
+    ) -> Result<(), RestError> {
+        self.upsert_with_resolution(
+            table_name,
+            rows,
+            on_conflict,
+            DuplicateResolution::MergeDuplicates,
+        )
+        .await
+    }
+
+    /// Creates or updates a list of rows in a single request, merging the rows that conflict on the
+    /// given columns into the existing ones.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `rows` - The rows, each serializing to a JSON object with the same columns.
+    /// * `on_conflict` - The columns of a unique constraint to detect conflicts on, e.g. `&["email"]`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let users: Vec<Value> = client
+    ///     .bulk_upsert("users", vec![jane, john], &["email"])
+    ///     .await?;
+    /// ```
+    ///
+    /// # Returns
+    /// The inserted and merged rows as stored, or a `RestError`. Rows above the
+    /// [`PayloadLimit`](crate::payload::PayloadLimit) of the client are split over several requests.
+    pub async fn bulk_upsert<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: Vec<T>,
+        on_conflict: &[&str],
+    ) -> Result<Vec<Value>, RestError> {
+        if on_conflict.is_empty() {
+            return Err("At least one conflict column is required".into());
+        }
+        if rows.is_empty() {
+            return Ok(Vec::new());
         }
+
+        self.upsert_with_returning(
+            table_name,
+            rows,
+            on_conflict,
+            DuplicateResolution::MergeDuplicates,
+            Returning::Representation,
+        )
+        .await
+    }
+
+    /// Creates rows in the table, skipping the rows that conflict on the given columns.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `rows` - A single row or a list of rows, serialized as JSON.
+    /// * `on_conflict` - The columns of a unique constraint to detect conflicts on, e.g. `&["email"]`.
+    ///
+    /// # Returns
+    /// This method returns a `Result<(), RestError>`. On success, it returns `Ok(())`,
+    /// and on failure, it returns `Err(RestError)` with an error message.
+    pub async fn upsert_ignore_duplicates<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: T,
+        on_conflict: &[&str],
+    ) -> Result<(), RestError> {
+        self.upsert_with_resolution(
+            table_name,
+            rows,
+            on_conflict,
+            DuplicateResolution::IgnoreDuplicates,
+        )
+        .await
+    }
+
+    /// Upserts rows with an explicit conflict target, splitting bulk payloads according to the payload limit.
+    pub async fn upsert_with_resolution<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: T,
+        on_conflict: &[&str],
+        resolution: DuplicateResolution,
+    ) -> Result<(), RestError> {
+        self.upsert_with_returning(
+            table_name,
+            rows,
+            on_conflict,
+            resolution,
+            Returning::Minimal,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Upserts rows with an explicit conflict target, and returns what `returning` asks the server for.
+    ///
+    /// # Arguments
+    /// * `table_name` - The name of the table.
+    /// * `rows` - A single row or a list of rows, serialized as JSON.
+    /// * `on_conflict` - The columns of a unique constraint to detect conflicts on, e.g. `&["email"]`.
+    /// * `resolution` - Whether conflicting rows are updated or skipped.
+    /// * `returning` - What the server sends back, [`Returning::Minimal`] skips the response body.
+    ///
+    /// # Returns
+    /// The inserted and updated rows with [`Returning::Representation`] (skipped rows are left out),
+    /// the primary key of an inserted row with [`Returning::HeadersOnly`], an empty vector
+    /// otherwise, or a `RestError`.
+    pub async fn upsert_with_returning<T: Serialize>(
+        &self,
+        table_name: &str,
+        rows: T,
+        on_conflict: &[&str],
+        resolution: DuplicateResolution,
+        returning: Returning,
+    ) -> Result<Vec<Value>, RestError> {
+        let body: Value = serde_json::to_value(rows).map_err(|e| e.to_string())?;
+        let endpoint: String = format!(
+            "{}/rest/v1/{}?on_conflict={}",
+            self.url,
+            table_name,
+            on_conflict.join(",")
+        );
+
+        #[cfg(feature = "nightly")]
+        use crate::nightly::print_nightly_warning;
+        #[cfg(feature = "nightly")]
+        print_nightly_warning();
+
+        let mut headers: Headers = Headers::with_defaults(&self.api_key, &self.api_key);
+        PreferBuilder::new()
+            .resolution(resolution)
+            .returning(returning)
+            .apply(&mut headers);
+
+        let batches: Vec<String> = self.payload_batches(&body)?;
+        let batch_count: usize = batches.len();
+
+        // send the batches in order and stop at the first failure
+        let mut upserted: Vec<Value> = Vec::new();
+        for (index, batch) in batches.into_iter().enumerate() {
+            let mut request = self.client.post(&endpoint).body(batch);
+            for (key, value) in headers.get_headers() {
+                request = request.header(key, value);
+            }
+
+            let response: Response = self
+                .dispatch(table_name, "upsert", self.content_profile(request))
+                .await?;
+            match returned_rows(response, returning).await {
+                Ok(rows) => upserted.extend(rows),
+                Err(error) if batch_count == 1 => return Err(error),
+                Err(error) => {
+                    let message: String = format!(
+                        "Batch {} of {} failed, earlier batches were upserted: {}",
+                        index + 1,
+                        batch_count,
+                        error
+                    );
+                    return Err(error.with_message(message));
+                }
+            }
+        }
+
+        Ok(upserted)
+    }
+}
+
+/// Reads the rows of a write response, which only has a body with [`Returning::Representation`]
+/// and a `Location` with [`Returning::HeadersOnly`].
+async fn returned_rows(response: Response, returning: Returning) -> Result<Vec<Value>, RestError> {
+    if !response.status().is_success() {
+        return Err(write_error(response).await);
+    }
+    match returning {
+        Returning::Representation => response_rows(response).await,
+        Returning::HeadersOnly => Ok(location_row(&response).into_iter().collect()),
+        Returning::Minimal => Ok(Vec::new()),
     }
 }
@@ -0,0 +1,191 @@
+//! ## Vault
+//!
+//! Service role helpers for secrets stored in Supabase Vault. Enabled with the `admin` feature.
+//!
+//! The `vault` schema isn't exposed through PostgREST, so the helpers call wrapper functions which
+//! have to be created once, see [`VAULT_MIGRATION_SQL`]. Only the service role can execute them,
+//! never use the service role key in a client application.
+//!
+//! ### Usage
+//! ```rust,ignore
+//! let id: String = client
+//!     .vault()
+//!     .create_secret("stripe_key", "sk_live_...", Some("Stripe API key"))
+//!     .await?;
+//!
+//! let secret: Option<String> = client.vault().read_secret("stripe_key").await?;
+//! ```
+#![cfg(feature = "admin")]
+
+use crate::errors::{ErrorTypes, Result};
+use crate::SupabaseClient;
+
+use serde_json::{json, Value};
+
+/// Creates the wrapper functions the [`Vault`] helpers call, run it once as a migration.
+pub const VAULT_MIGRATION_SQL: &str = r#"
+create extension if not exists supabase_vault with schema vault;
+
+create or replace function public.supabase_rs_vault_create_secret(secret text, name text, description text default '')
+returns uuid
+language sql
+security definer
+set search_path = ''
+as $$ select vault.create_secret(secret, name, description) $$;
+
+create or replace function public.supabase_rs_vault_update_secret(name text, secret text)
+returns boolean
+language plpgsql
+security definer
+set search_path = ''
+as $$
+declare
+  secret_id uuid;
+begin
+  select id into secret_id from vault.secrets where secrets.name = supabase_rs_vault_update_secret.name;
+  if secret_id is null then
+    return false;
+  end if;
+  perform vault.update_secret(secret_id, secret);
+  return true;
+end;
+$$;
+
+create or replace function public.supabase_rs_vault_read_secret(name text)
+returns text
+language sql
+security definer
+set search_path = ''
+as $$ select decrypted_secret from vault.decrypted_secrets where decrypted_secrets.name = supabase_rs_vault_read_secret.name $$;
+
+create or replace function public.supabase_rs_vault_delete_secret(name text)
+returns boolean
+language sql
+security definer
+set search_path = ''
+as $$
+  with deleted as (delete from vault.secrets where secrets.name = supabase_rs_vault_delete_secret.name returning 1)
+  select exists (select 1 from deleted)
+$$;
+
+revoke execute on function public.supabase_rs_vault_create_secret(text, text, text) from public, anon, authenticated;
+revoke execute on function public.supabase_rs_vault_update_secret(text, text) from public, anon, authenticated;
+revoke execute on function public.supabase_rs_vault_read_secret(text) from public, anon, authenticated;
+revoke execute on function public.supabase_rs_vault_delete_secret(text) from public, anon, authenticated;
+grant execute on function public.supabase_rs_vault_create_secret(text, text, text) to service_role;
+grant execute on function public.supabase_rs_vault_update_secret(text, text) to service_role;
+grant execute on function public.supabase_rs_vault_read_secret(text) to service_role;
+grant execute on function public.supabase_rs_vault_delete_secret(text) to service_role;
+"#;
+
+/// A client for Supabase Vault, created by [`SupabaseClient::vault`].
+#[derive(Debug, Clone)]
+pub struct Vault {
+    client: SupabaseClient,
+}
+
+impl SupabaseClient {
+    /// Returns a client for Supabase Vault secrets.
+    ///
+    /// Requires the service role key and the functions of [`VAULT_MIGRATION_SQL`].
+    pub fn vault(&self) -> Vault {
+        Vault {
+            client: self.clone(),
+        }
+    }
+}
+
+impl Vault {
+    /// Stores a new encrypted secret.
+    ///
+    /// # Arguments
+    /// * `name` - The unique name of the secret.
+    /// * `secret` - The value to encrypt.
+    /// * `description` - An optional description of the secret.
+    ///
+    /// # Returns
+    /// The id of the new secret.
+    pub async fn create_secret(
+        &self,
+        name: &str,
+        secret: &str,
+        description: Option<&str>,
+    ) -> Result<String> {
+        let result: Value = self
+            .client
+            .rpc(
+                "supabase_rs_vault_create_secret",
+                json!({
+                    "name": name,
+                    "secret": secret,
+                    "description": description.unwrap_or_default(),
+                }),
+            )
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_str().map(str::to_string).ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a secret id, got {}", result))
+        })
+    }
+
+    /// Replaces the value of an existing secret.
+    ///
+    /// # Returns
+    /// Whether a secret with `name` existed and was updated.
+    pub async fn update_secret(&self, name: &str, secret: &str) -> Result<bool> {
+        let result: Value = self
+            .client
+            .rpc(
+                "supabase_rs_vault_update_secret",
+                json!({ "name": name, "secret": secret }),
+            )
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_bool().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a boolean, got {}", result))
+        })
+    }
+
+    /// Reads the decrypted value of a secret.
+    ///
+    /// # Returns
+    /// The decrypted secret, or `None` when no secret with `name` exists.
+    pub async fn read_secret(&self, name: &str) -> Result<Option<String>> {
+        let result: Value = self
+            .client
+            .rpc("supabase_rs_vault_read_secret", json!({ "name": name }))
+            .schema("public")
+            .execute()
+            .await?;
+
+        match result {
+            Value::Null => Ok(None),
+            Value::String(secret) => Ok(Some(secret)),
+            result => Err(ErrorTypes::UnexpectedResponse(format!(
+                "expected a secret, got {}",
+                result
+            ))),
+        }
+    }
+
+    /// Deletes a secret.
+    ///
+    /// # Returns
+    /// Whether a secret with `name` existed and was deleted.
+    pub async fn delete_secret(&self, name: &str) -> Result<bool> {
+        let result: Value = self
+            .client
+            .rpc("supabase_rs_vault_delete_secret", json!({ "name": name }))
+            .schema("public")
+            .execute()
+            .await?;
+
+        result.as_bool().ok_or_else(|| {
+            ErrorTypes::UnexpectedResponse(format!("expected a boolean, got {}", result))
+        })
+    }
+}
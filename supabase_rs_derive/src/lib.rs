@@ -0,0 +1,337 @@
+//! # Derive macros for supabase_rs
+//!
+//! This crate provides `#[derive(SupabaseTable)]`, re-exported by `supabase_rs` behind the `derive` feature.
+//!
+//! ## Attributes
+//! - `#[supabase(table = "users")]` on the struct overrides the table name, which defaults to the
+//!   struct name in `snake_case`.
+//! - `#[supabase(primary_key)]` on a field marks it as the key used by `update_row`, which defaults to `id`.
+//! - `#[serde(rename = "...")]`, `#[serde(skip)]` and `#[serde(skip_serializing)]` on fields, and
+//!   `#[serde(rename_all = "...")]` on the struct, are honoured when listing columns. A column has a
+//!   single name, so renames serializing and deserializing differently fail to compile.
+//!
+//! ## Generated items
+//! - An implementation of `supabase_rs::table::SupabaseTable`.
+//! - A `{Struct}Column` enum with one variant per column, implementing `supabase_rs::table::Column`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr, Token};
+
+/// Derives `supabase_rs::table::SupabaseTable` and a typed column enum for a struct.
+#[proc_macro_derive(SupabaseTable, attributes(supabase))]
+pub fn derive_supabase_table(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+struct TableColumn {
+    name: String,
+    variant: Ident,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name: &Ident = &input.ident;
+    let visibility = &input.vis;
+
+    let mut table_name: String = to_snake_case(&struct_name.to_string());
+    let mut rename_all: Option<RenameRule> = None;
+    for attr in &input.attrs {
+        if attr.path().is_ident("supabase") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("table") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    table_name = value.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported supabase attribute, expected `table = \"...\"`"))
+                }
+            })?;
+        } else if attr.path().is_ident("serde") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    let rule: LitStr = single_name(&meta, "rename_all")?;
+                    rename_all = Some(RenameRule::parse(&rule)?);
+                    Ok(())
+                } else {
+                    skip_meta(&meta)
+                }
+            })?;
+        }
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    struct_name,
+                    "SupabaseTable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "SupabaseTable can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns: Vec<TableColumn> = Vec::new();
+    let mut primary_key: Option<String> = None;
+
+    for field in fields {
+        let ident: &Ident = field.ident.as_ref().expect("named field");
+        let field_name: String = ident.to_string().trim_start_matches("r#").to_string();
+        let mut name: String = match rename_all {
+            Some(rule) => rule.apply(&field_name),
+            None => field_name,
+        };
+        let mut skipped: bool = false;
+        let mut is_primary_key: bool = false;
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        name = single_name(&meta, "rename")?.value();
+                        Ok(())
+                    } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                        skipped = true;
+                        Ok(())
+                    } else {
+                        skip_meta(&meta)
+                    }
+                })?;
+            } else if attr.path().is_ident("supabase") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("primary_key") {
+                        is_primary_key = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported supabase attribute, expected `primary_key`"))
+                    }
+                })?;
+            }
+        }
+
+        if skipped {
+            continue;
+        }
+        if is_primary_key {
+            primary_key = Some(name.clone());
+        }
+
+        let variant: Ident = column_variant(&name, &columns).ok_or_else(|| {
+            syn::Error::new_spanned(
+                ident,
+                format!("no enum variant can be named after the column `{}`", name),
+            )
+        })?;
+        columns.push(TableColumn { variant, name });
+    }
+
+    let primary_key: String = primary_key.unwrap_or_else(|| "id".to_string());
+    let column_enum: Ident = Ident::new(&format!("{}Column", struct_name), Span::call_site());
+    let column_names: Vec<&String> = columns.iter().map(|column| &column.name).collect();
+    let variants: Vec<&Ident> = columns.iter().map(|column| &column.variant).collect();
+    let enum_doc: String = format!("Columns of the `{}` table.", table_name);
+
+    Ok(quote! {
+        #[doc = #enum_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #visibility enum #column_enum {
+            #( #variants, )*
+        }
+
+        impl ::supabase_rs::table::Column for #column_enum {
+            fn name(&self) -> &'static str {
+                match self {
+                    #( #column_enum::#variants => #column_names, )*
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for #column_enum {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(::supabase_rs::table::Column::name(self))
+            }
+        }
+
+        impl ::supabase_rs::table::SupabaseTable for #struct_name {
+            type Column = #column_enum;
+
+            fn table_name() -> &'static str {
+                #table_name
+            }
+
+            fn columns() -> &'static [&'static str] {
+                &[ #( #column_names ),* ]
+            }
+
+            fn primary_key() -> &'static str {
+                #primary_key
+            }
+        }
+    })
+}
+
+/// Reads the name of `rename = "..."` or `rename_all = "..."`, or of its
+/// `(serialize = "...", deserialize = "...")` form when both are the same.
+fn single_name(meta: &ParseNestedMeta, attribute: &str) -> syn::Result<LitStr> {
+    if meta.input.peek(Token![=]) {
+        return meta.value()?.parse();
+    }
+
+    let mut serialize: Option<LitStr> = None;
+    let mut deserialize: Option<LitStr> = None;
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("serialize") {
+            serialize = Some(nested.value()?.parse()?);
+        } else if nested.path.is_ident("deserialize") {
+            deserialize = Some(nested.value()?.parse()?);
+        } else {
+            return Err(nested.error("expected `serialize` or `deserialize`"));
+        }
+        Ok(())
+    })?;
+
+    match (serialize, deserialize) {
+        (Some(serialize), Some(deserialize)) if serialize.value() == deserialize.value() => {
+            Ok(serialize)
+        }
+        _ => Err(meta.error(format!(
+            "a column has a single name, `{}` must serialize and deserialize the same",
+            attribute
+        ))),
+    }
+}
+
+/// Skips a serde attribute which doesn't change the columns, e.g. `default` or `with = "..."`.
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        let _: syn::Expr = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_meta(&nested))?;
+    }
+    Ok(())
+}
+
+/// The `rename_all` rules of serde, applied to the `snake_case` field names.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &LitStr) -> syn::Result<Self> {
+        Ok(match rule.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            other => {
+                return Err(syn::Error::new_spanned(
+                    rule,
+                    format!("unknown rename rule `{}`", other),
+                ))
+            }
+        })
+    }
+
+    fn apply(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => field
+                .split('_')
+                .map(|part| {
+                    let mut characters = part.chars();
+                    match characters.next() {
+                        Some(first) => first.to_uppercase().chain(characters).collect::<String>(),
+                        None => String::new(),
+                    }
+                })
+                .collect(),
+            RenameRule::Camel => {
+                let pascal: String = RenameRule::Pascal.apply(field);
+                let mut characters = pascal.chars();
+                match characters.next() {
+                    Some(first) => first.to_lowercase().chain(characters).collect(),
+                    None => pascal,
+                }
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
+
+/// The variant of a column, suffixed with a number when another column has the same one, e.g.
+/// `UserId` and `UserId2` for `user_id` and `userId`. `None` when no identifier can be made of it.
+fn column_variant(name: &str, columns: &[TableColumn]) -> Option<Ident> {
+    let base: String = match to_pascal_case(name).as_str() {
+        "Self" => "Self_".to_string(),
+        pascal => pascal.to_string(),
+    };
+    let mut variant: String = base.clone();
+    let mut suffix: usize = 2;
+    while columns.iter().any(|column| column.variant == variant) {
+        variant = format!("{}{}", base, suffix);
+        suffix += 1;
+    }
+    syn::parse_str::<Ident>(&variant).ok()
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake: String = String::new();
+    for (index, character) in name.chars().enumerate() {
+        if character.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(character.to_lowercase());
+        } else {
+            snake.push(character);
+        }
+    }
+    snake
+}
+
+fn to_pascal_case(name: &str) -> String {
+    let pascal: String = name
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut characters = part.chars();
+            match characters.next() {
+                Some(first) => first.to_uppercase().chain(characters).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    match pascal.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("_{}", pascal),
+        Some(_) => pascal,
+        None => "Column".to_string(),
+    }
+}